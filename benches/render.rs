@@ -0,0 +1,116 @@
+//! Benchmarks of the print & render paths, on a 20x4 display connected through a simulated I2C
+//! backpack (see `NullBus`).
+//!
+//! Each scenario is measured twice:
+//!
+//! - `cpu/*` - time spent by the driver itself (the bus does not wait at all),
+//! - `bus/*` - time a real display would require (the transfers and the delays, as accounted by
+//!   the bus' stats) - which is what batching, busy-flag polling etc. should bring down.
+//!
+//! ```text
+//! cargo bench --bench render
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use pwr_hd44780::{BufferedLcd, DirectLcd, Hd44780, NullBus, Point};
+use std::time::Duration;
+
+const WIDTH: usize = 20;
+const HEIGHT: usize = 4;
+
+const LINES: [&str; HEIGHT] = [
+    "Temperature:   23.4C",
+    "Humidity:        41%",
+    "Pressure:   1013 hPa",
+    "Uptime:     12:34:56",
+];
+
+fn direct() -> DirectLcd {
+    let mut bus = NullBus::new();
+    bus.set_transfer_cost_us(NullBus::I2C_TRANSFER_US);
+
+    DirectLcd::new(Box::new(bus), WIDTH, HEIGHT).unwrap()
+}
+
+fn buffered() -> BufferedLcd {
+    let mut lcd = BufferedLcd::from_lcd(direct());
+
+    print_lines(&mut lcd);
+    lcd.render().unwrap();
+    lcd
+}
+
+fn print_lines<L: Hd44780>(lcd: &mut L) {
+    for (y, line) in LINES.iter().enumerate() {
+        lcd.print_at(y, 0, *line).unwrap();
+    }
+}
+
+/// Runs given scenario `iters` times, returning the bus time it would take on a real display.
+fn bus_time<F>(lcd: &mut BufferedLcd, iters: u64, mut scenario: F) -> Duration where F: FnMut(&mut BufferedLcd) {
+    let started = lcd.inner().stats().delay;
+
+    for _ in 0..iters {
+        scenario(lcd);
+    }
+
+    lcd.inner().stats().delay - started
+}
+
+/// Prints the whole screen into the buffer (no bus traffic).
+fn print_buffered(lcd: &mut BufferedLcd) {
+    print_lines(black_box(lcd));
+}
+
+/// Renders the whole screen from scratch (eg. after switching pages).
+fn render_full(lcd: &mut BufferedLcd) {
+    lcd.invalidate();
+    black_box(lcd.render().unwrap());
+}
+
+/// Renders a ticking clock - a few characters changing between the renders.
+fn render_diff(lcd: &mut BufferedLcd) {
+    let second = lcd.char_at(Point::new(3, 19)).unwrap_or(b'0');
+    let next = if second == b'9' { b'0' } else { second + 1 };
+
+    lcd.print_char_at(3, 19, next).unwrap();
+    black_box(lcd.render().unwrap());
+}
+
+/// Name, the scenario itself and whether it touches the bus.
+type Scenario = (&'static str, fn(&mut BufferedLcd), bool);
+
+fn benchmarks(c: &mut Criterion) {
+    let scenarios: [Scenario; 3] = [
+        ("print_buffered", print_buffered, false),
+        ("render_full", render_full, true),
+        ("render_diff", render_diff, true),
+    ];
+
+    for &(name, scenario, uses_bus) in &scenarios {
+        c.bench_function(&format!("cpu/{}", name), |b| {
+            let mut lcd = buffered();
+
+            b.iter(|| scenario(&mut lcd));
+        });
+
+        if !uses_bus {
+            continue;
+        }
+
+        c.bench_function(&format!("bus/{}", name), |b| {
+            let mut lcd = buffered();
+
+            b.iter_custom(|iters| bus_time(&mut lcd, iters, scenario));
+        });
+    }
+
+    c.bench_function("cpu/print_direct", |b| {
+        let mut lcd = direct();
+
+        b.iter(|| print_lines(black_box(&mut lcd)));
+    });
+}
+
+criterion_group!(benches, benchmarks);
+criterion_main!(benches);
@@ -0,0 +1,147 @@
+//! Generates the code pages' lookup tables (see `codepages/README.md`).
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::{env, fs};
+
+fn main() {
+    println!("cargo:rerun-if-changed=codepages");
+
+    let mut files: Vec<_> = fs::read_dir("codepages")
+        .expect("cannot read codepages/")
+        .map(|entry| entry.expect("cannot read codepages/").path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .collect();
+
+    files.sort();
+
+    let mut out = String::new();
+
+    for file in files {
+        let name = file
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .expect("invalid code page's name")
+            .to_uppercase();
+
+        let (encode, decode) = parse(&file);
+
+        writeln!(out, "/// Characters of the `{}` code page along with their codes, sorted by the characters.", name).unwrap();
+        writeln!(out, "static {}_ENCODE: [(char, u8); {}] = [", name, encode.len()).unwrap();
+
+        for (ch, code) in &encode {
+            writeln!(out, "    ('\\u{{{:X}}}', {:#04X}),", *ch as u32, code).unwrap();
+        }
+
+        writeln!(out, "];\n").unwrap();
+        writeln!(out, "/// Characters shown by each code of the `{}` code page.", name).unwrap();
+        writeln!(out, "static {}_DECODE: [Option<char>; 256] = [", name).unwrap();
+
+        for ch in decode.iter() {
+            match ch {
+                Some(ch) => writeln!(out, "    Some('\\u{{{:X}}}'),", *ch as u32).unwrap(),
+                None => writeln!(out, "    None,").unwrap(),
+            }
+        }
+
+        writeln!(out, "];\n").unwrap();
+    }
+
+    let path = Path::new(&env::var("OUT_DIR").unwrap()).join("code_pages.rs");
+
+    fs::write(path, out).expect("cannot write the code pages");
+}
+
+/// Parses given code page, returning its characters (with their codes) and the character each
+/// code gets decoded into.
+fn parse(file: &Path) -> (BTreeMap<char, u8>, [Option<char>; 256]) {
+    let source = fs::read_to_string(file).expect("cannot read a code page");
+
+    let mut encode = BTreeMap::new();
+    let mut decode = [None; 256];
+
+    for (idx, line) in source.lines().enumerate() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fail = |reason: &str| -> ! {
+            panic!("{}:{}: {}", file.display(), idx + 1, reason);
+        };
+
+        let mut tokens = line.split_whitespace();
+        let codes = tokens.next().unwrap();
+
+        let (first, last) = match codes.split_once('-') {
+            Some((first, last)) => (parse_code(first), parse_code(last)),
+            None => (parse_code(codes), parse_code(codes)),
+        };
+
+        let (first, last) = match (first, last) {
+            (Some(first), Some(last)) if first <= last => (first, last),
+            _ => fail("invalid code(s)"),
+        };
+
+        let mut map = |code: u8, ch: char| {
+            if let Some(other) = encode.insert(ch, code) {
+                if other != code {
+                    fail(&format!("U+{:04X} is mapped onto both {:#04X} and {:#04X}", ch as u32, other, code));
+                }
+            }
+
+            decode[code as usize].get_or_insert(ch);
+        };
+
+        if first == last {
+            for token in tokens {
+                map(first, parse_char(token).unwrap_or_else(|| fail("invalid character")));
+            }
+
+            continue;
+        }
+
+        let token = tokens.next().unwrap_or_else(|| fail("missing character"));
+        let len = (last - first) as usize + 1;
+
+        if tokens.next().is_some() {
+            fail("a range accepts a single character or a string");
+        }
+
+        if let Some(ch) = parse_char(token) {
+            // consecutive characters
+            for offset in 0..len {
+                let ch = char::from_u32(ch as u32 + offset as u32).unwrap_or_else(|| fail("invalid character"));
+                map(first + offset as u8, ch);
+            }
+        } else if token.chars().count() == len {
+            // one character per code
+            for (offset, ch) in token.chars().enumerate() {
+                map(first + offset as u8, ch);
+            }
+        } else {
+            fail(&format!("expected {} characters", len));
+        }
+    }
+
+    (encode, decode)
+}
+
+fn parse_code(code: &str) -> Option<u8> {
+    u8::from_str_radix(code, 16).ok()
+}
+
+fn parse_char(token: &str) -> Option<char> {
+    if let Some(hex) = token.strip_prefix("U+") {
+        return u32::from_str_radix(hex, 16).ok().and_then(char::from_u32);
+    }
+
+    let mut chars = token.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) => Some(ch),
+        _ => None,
+    }
+}
@@ -0,0 +1,413 @@
+//! Cycles through everything this crate has to offer on a real display.
+//!
+//! Can be used as a burn-in test or as a quick sanity check after wiring up a new display.
+//!
+//! Usage:
+//!     gallery i2c <device> <address> [width] [height]
+//!     gallery gpio <d4,d5,d6,d7> <rs> <en> [width] [height]
+//!
+//! Examples:
+//!     gallery i2c /dev/i2c-1 0x27 20 4
+//!     gallery gpio 26,6,5,16 23 24 16 2
+//!
+//! BCM pin numbering is used for the GPIO bus (as it is natively in the rppal crate).
+
+extern crate pwr_hd44780;
+
+use pwr_hd44780::{Backlight, CursorStyle, CustomChars, DisplayControl, Hd44780, Point};
+use pwr_hd44780::widgets::{BarGraph, BigDigits, BigDigitsSize, Clock, Frame, LevelMeter, Marquee, ProgressBar, Sparkline, Spinner, SpinnerStyle};
+use std::{env, thread, time};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+type Lcd = pwr_hd44780::BufferedLcd;
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+type Section = fn(&mut Lcd) -> Result<()>;
+
+fn main() {
+    run().unwrap();
+}
+
+fn run() -> Result<()> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let mut lcd = create_lcd(&args)?;
+
+    let sections: Vec<(&str, Section)> = vec![
+        ("Text", show_text),
+        ("Character ROM", show_character_rom),
+        ("Custom chars", show_custom_chars),
+        ("Big digits", show_big_digits),
+        ("Bar graph", show_bar_graph),
+        ("Progress bar", show_progress_bar),
+        ("Level meters", show_level_meters),
+        ("Sparkline", show_sparkline),
+        ("Marquee", show_marquee),
+        ("Spinners", show_spinners),
+        ("Clock", show_clock),
+        ("Frame", show_frame),
+        ("Cursor", show_cursor),
+        ("Backlight", show_backlight),
+    ];
+
+    loop {
+        for &(title, section) in &sections {
+            show_title(&mut lcd, title)?;
+            section(&mut lcd)?;
+        }
+    }
+}
+
+/// Creates the LCD basing on the command-line arguments.
+fn create_lcd(args: &[String]) -> Result<Lcd> {
+    let arg = |idx: usize| -> Result<&str> {
+        args.get(idx)
+            .map(|arg| arg.as_str())
+            .ok_or_else(|| "Not enough arguments - see the example's documentation for usage.".into())
+    };
+
+    let (bus, dims_idx): (Box<dyn pwr_hd44780::buses::Bus>, usize) = match arg(0)? {
+        "i2c" => {
+            let address = u16::from_str_radix(arg(2)?.trim_start_matches("0x"), 16)?;
+
+            (Box::new(pwr_hd44780::I2CBus::new(arg(1)?, address)?), 3)
+        }
+
+        "gpio" => {
+            let data: Vec<u8> = arg(1)?
+                .split(',')
+                .map(|pin| pin.parse())
+                .collect::<std::result::Result<_, _>>()?;
+
+            if data.len() != 4 {
+                return Err("Exactly four data pins are required.".into());
+            }
+
+            let pins = pwr_hd44780::buses::gpio4::Pins {
+                data: [data[0], data[1], data[2], data[3]],
+                rs: arg(2)?.parse()?,
+                en: arg(3)?.parse()?,
+                rw: None,
+                backlight: None,
+                backlight_rgb: None,
+                contrast: None,
+                extra_en: vec![],
+            };
+
+            (Box::new(pwr_hd44780::Gpio4Bus::new(pins)?), 4)
+        }
+
+        bus => {
+            return Err(format!("Unknown bus: {} (expected `i2c` or `gpio`).", bus).into());
+        }
+    };
+
+    let width = args.get(dims_idx).map_or(Ok(20), |width| width.parse())?;
+    let height = args.get(dims_idx + 1).map_or(Ok(4), |height| height.parse())?;
+
+    let lcd = pwr_hd44780::DirectLcd::new(bus, width, height)?;
+
+    pwr_hd44780::BufferedLcd::new(Box::new(lcd))
+}
+
+/// Shows the section's title for a moment.
+fn show_title(lcd: &mut Lcd, title: &str) -> Result<()> {
+    lcd.clear()?;
+    lcd.println("-- gallery --")?;
+    lcd.print(title)?;
+    lcd.render()?;
+
+    sleep(1500);
+
+    Ok(())
+}
+
+/// Fills the screen with text, line by line.
+fn show_text(lcd: &mut Lcd) -> Result<()> {
+    lcd.clear()?;
+
+    for y in 0..lcd.height() {
+        let line: String = (0..lcd.width())
+            .map(|x| (b'A' + ((y * lcd.width() + x) % 26) as u8) as char)
+            .collect();
+
+        lcd.print_at(y, 0, line)?;
+        lcd.render()?;
+
+        sleep(500);
+    }
+
+    sleep(1000);
+
+    Ok(())
+}
+
+/// Shows the whole character ROM, page by page.
+fn show_character_rom(lcd: &mut Lcd) -> Result<()> {
+    let page_size = lcd.width() * lcd.height();
+
+    for page in (0x20..0x100).step_by(page_size) {
+        lcd.clear()?;
+
+        for code in page..(page + page_size).min(0x100) {
+            lcd.print_char(code as u8)?;
+        }
+
+        lcd.render()?;
+
+        sleep(2000);
+    }
+
+    Ok(())
+}
+
+/// Uploads a few custom characters and shows them.
+fn show_custom_chars(lcd: &mut Lcd) -> Result<()> {
+    for idx in 0..8 {
+        let mut lines = [0u8; 8];
+
+        for (y, line) in lines.iter_mut().enumerate() {
+            *line = if y >= 7 - idx as usize { 0b11111 } else { 0 };
+        }
+
+        lcd.create_char(idx, lines)?;
+    }
+
+    lcd.clear()?;
+
+    for idx in 0..8 {
+        lcd.print_char(idx)?;
+    }
+
+    lcd.render()?;
+
+    sleep(3000);
+
+    Ok(())
+}
+
+/// Counts using the big digits.
+fn show_big_digits(lcd: &mut Lcd) -> Result<()> {
+    let size = if lcd.height() >= 4 { BigDigitsSize::FourLines } else { BigDigitsSize::TwoLines };
+    let digits = BigDigits::new(size);
+    let max = 10u64.pow(((lcd.width() + 1) / (digits.digit_width() + 1)).min(4) as u32);
+
+    digits.load(lcd)?;
+
+    for value in (0..max).step_by((max / 20).max(1) as usize) {
+        lcd.clear()?;
+        digits.print_big_number(lcd, 0, 0, value)?;
+        lcd.render()?;
+
+        sleep(250);
+    }
+
+    Ok(())
+}
+
+/// Fills and empties a bar graph.
+fn show_bar_graph(lcd: &mut Lcd) -> Result<()> {
+    let graph = BarGraph::new(lcd.height() - 1, 0, lcd.width());
+    let steps = graph.resolution();
+
+    graph.load(lcd)?;
+    lcd.clear()?;
+
+    for step in (0..=steps).chain((0..steps).rev()) {
+        lcd.print_at(0, 0, format!("{:>3}%", 100 * step / steps))?;
+        graph.draw(lcd, step as f32 / steps as f32)?;
+        lcd.render()?;
+
+        sleep(50);
+    }
+
+    Ok(())
+}
+
+/// Fills a framed, labeled progress bar.
+fn show_progress_bar(lcd: &mut Lcd) -> Result<()> {
+    let mut bar = ProgressBar::new(lcd.height() - 1, 0, lcd.width());
+
+    bar.set_frame(Some(('[', ']')));
+    bar.set_label(true);
+    bar.load(lcd)?;
+
+    lcd.clear()?;
+    lcd.print("Installing...")?;
+
+    for step in 0..=100 {
+        bar.draw(lcd, step as f32 / 100.0)?;
+        lcd.render()?;
+
+        sleep(50);
+    }
+
+    Ok(())
+}
+
+/// Animates a row of level meters.
+fn show_level_meters(lcd: &mut Lcd) -> Result<()> {
+    let meters: Vec<LevelMeter> = (0..lcd.width())
+        .map(|x| LevelMeter::new(0, x, lcd.height()))
+        .collect();
+
+    meters[0].load(lcd)?;
+
+    for frame in 0..100 {
+        for (x, meter) in meters.iter().enumerate() {
+            let phase = (frame + 3 * x) as f32 / 10.0;
+
+            meter.draw(lcd, (phase.sin() + 1.0) / 2.0)?;
+        }
+
+        lcd.render()?;
+
+        sleep(50);
+    }
+
+    Ok(())
+}
+
+/// Feeds a sparkline with a noisy sine wave.
+fn show_sparkline(lcd: &mut Lcd) -> Result<()> {
+    let mut sparkline = Sparkline::new(lcd.height() - 1, 0, lcd.width());
+
+    sparkline.load(lcd)?;
+
+    lcd.clear()?;
+    lcd.print("Temperature")?;
+
+    for sample in 0..60 {
+        let phase = sample as f32 / 5.0;
+
+        sparkline.push(20.0 + 5.0 * phase.sin() + (sample % 3) as f32 / 2.0);
+        sparkline.draw(lcd)?;
+        lcd.render()?;
+
+        sleep(150);
+    }
+
+    Ok(())
+}
+
+/// Scrolls a long text.
+fn show_marquee(lcd: &mut Lcd) -> Result<()> {
+    let mut marquee = Marquee::new(1, 0, lcd.width(), "The quick brown fox jumps over the lazy dog");
+
+    lcd.clear()?;
+    lcd.print("Now playing:")?;
+
+    for _ in 0..60 {
+        marquee.tick(lcd)?;
+        lcd.render()?;
+
+        sleep(200);
+    }
+
+    Ok(())
+}
+
+/// Spins all the spinners' styles at once.
+fn show_spinners(lcd: &mut Lcd) -> Result<()> {
+    let mut spinners = [
+        Spinner::new(0, 0, SpinnerStyle::Line),
+        Spinner::with_slot(0, 2, SpinnerStyle::Dots, 1)?,
+        Spinner::new(0, 4, SpinnerStyle::Chars(".oOo".chars().collect())),
+    ];
+
+    lcd.clear()?;
+    lcd.print_at(1, 0, "Loading...")?;
+
+    for _ in 0..50 {
+        for spinner in &mut spinners {
+            spinner.tick(lcd)?;
+        }
+
+        lcd.render()?;
+
+        sleep(100);
+    }
+
+    for spinner in &spinners {
+        spinner.clear(lcd)?;
+    }
+
+    lcd.render()?;
+
+    Ok(())
+}
+
+/// Shows the current time (UTC), blinking the colons.
+fn show_clock(lcd: &mut Lcd) -> Result<()> {
+    let mut clock = Clock::new(0, 0, "%H:%M:%S");
+
+    clock.set_blinking_colons(true);
+    lcd.clear()?;
+
+    for _ in 0..10 {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+
+        clock.draw(lcd, now)?;
+        lcd.render()?;
+
+        thread::sleep(clock.until_next_change(now));
+    }
+
+    Ok(())
+}
+
+/// Draws a titled frame around the screen, with the text inside it (when there's room for any).
+fn show_frame(lcd: &mut Lcd) -> Result<()> {
+    let mut frame = Frame::with_first_slot(Point::new(0, 0), Point::new(lcd.height() - 1, lcd.width() - 1), 0)?;
+
+    frame.set_title("Status");
+    frame.load(lcd)?;
+
+    lcd.clear()?;
+    frame.draw(lcd)?;
+
+    if let Some((from, _)) = frame.interior() {
+        lcd.print_at(from.y, from.x, "All systems go")?;
+    }
+
+    lcd.render()?;
+
+    sleep(3000);
+
+    Ok(())
+}
+
+/// Toggles the hardware cursor.
+fn show_cursor(lcd: &mut Lcd) -> Result<()> {
+    let styles = [
+        ("Underline", CursorStyle::Underline),
+        ("Blinking", CursorStyle::Blinking),
+        ("Both", CursorStyle::BlinkingUnderline),
+    ];
+
+    for &(name, style) in &styles {
+        lcd.clear()?;
+        lcd.print(name)?;
+        lcd.render()?;
+        lcd.set_cursor_style(style)?;
+
+        sleep(2000);
+    }
+
+    lcd.set_cursor_style(CursorStyle::Hidden)
+}
+
+/// Blinks the backlight.
+fn show_backlight(lcd: &mut Lcd) -> Result<()> {
+    for _ in 0..3 {
+        lcd.set_backlight(false)?;
+        sleep(500);
+
+        lcd.set_backlight(true)?;
+        sleep(500);
+    }
+
+    Ok(())
+}
+
+fn sleep(millis: u64) {
+    thread::sleep(time::Duration::from_millis(millis));
+}
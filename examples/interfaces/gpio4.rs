@@ -27,6 +27,11 @@ fn run() -> Result<(), Box<std::error::Error>> {
             data: [26, 6, 5, 16],
             rs: 23,
             en: 24,
+            rw: None,
+            backlight: None,
+            backlight_rgb: None,
+            contrast: None,
+            extra_en: vec![],
         },
     )?;
 
@@ -0,0 +1,40 @@
+//! Exposes an LCD connected through the I2C backpack to the other machines on the network (see the
+//! `remote` module).
+//!
+//! Usage:
+//!     remote_agent <device> <address> [listen address]
+//!
+//! Examples:
+//!     remote_agent /dev/i2c-1 0x27
+//!     remote_agent /dev/i2c-1 0x27 0.0.0.0:4444
+//!
+//! The clients connect using `RemoteBus::connect("<host>:4444")`.
+
+extern crate pwr_hd44780;
+
+use pwr_hd44780::{I2CBus, RemoteAgent};
+use std::env;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+fn main() {
+    run().unwrap();
+}
+
+fn run() -> Result<()> {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    if args.len() < 2 {
+        return Err("Usage: remote_agent <device> <address> [listen address]".into());
+    }
+
+    let address = args[1].trim_start_matches("0x");
+    let address = u16::from_str_radix(address, 16)?;
+    let listen = args.get(2).map_or("0.0.0.0:4444", |listen| listen.as_str());
+
+    let bus = I2CBus::new(&args[0], address)?;
+
+    println!("Listening on {}", listen);
+
+    RemoteAgent::new(bus).listen(listen)
+}
@@ -0,0 +1,187 @@
+//! Provides a direct, asynchronous access to the HD44780 (see `frontends::Direct`).
+
+use super::super::*;
+use super::super::buses::command::*;
+use super::{AsyncBus, AsyncLcd};
+
+pub struct Direct<B: AsyncBus> {
+    bus: B,
+    properties: Properties,
+    state: State,
+}
+
+struct State {
+    /// current DDRAM address (the cursor's position)
+    address: u8,
+
+    cursor_blinking: bool,
+    cursor_visible: bool,
+    text_visible: bool,
+}
+
+impl<B: AsyncBus> Direct<B> {
+    /// Creates a new direct HD44780 on given bus.
+    pub async fn new(bus: B, width: usize, height: usize) -> Result<Direct<B>> {
+        Direct::new_ex(bus, Properties::new(width, height)).await
+    }
+
+    /// Creates a new direct HD44780 on given bus.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when given properties are invalid (see
+    /// `Properties::validate`).
+    pub async fn new_ex(bus: B, properties: Properties) -> Result<Direct<B>> {
+        properties.validate()?;
+
+        let mut lcd = Direct {
+            bus,
+            properties,
+
+            state: State {
+                address: 0,
+                cursor_blinking: false,
+                cursor_visible: false,
+                text_visible: true,
+            },
+        };
+
+        lcd.initialize().await?;
+
+        Ok(lcd)
+    }
+
+    /// Initializes the screen.
+    async fn initialize(&mut self) -> UnitResult {
+        self.bus.initialize().await?;
+
+        let commands = self.properties.controller
+            .profile()
+            .init_sequence(&self.properties, self.bus.width() == 8);
+
+        for command in commands {
+            self.bus.execute(command).await?;
+        }
+
+        self.bus.execute(Command::SetEntryMode {
+            enable_shift: false,
+            increment_counter: true,
+        }).await?;
+
+        self.refresh_display_flags().await
+    }
+
+    /// Issues the "set display flags" command with current LCD's state.
+    async fn refresh_display_flags(&mut self) -> UnitResult {
+        self.bus.execute(Command::SetDisplayFlags {
+            cursor_blinking: self.state.cursor_blinking,
+            cursor_visible: self.state.cursor_visible,
+            text_visible: self.state.text_visible,
+        }).await
+    }
+}
+
+impl<B: AsyncBus> AsyncLcd for Direct<B> {
+    async fn clear(&mut self) -> UnitResult {
+        self.bus.execute(Command::Clear {}).await?;
+        self.state.address = 0;
+
+        Ok(())
+    }
+
+    async fn home(&mut self) -> UnitResult {
+        self.bus.execute(Command::Home {}).await?;
+        self.state.address = 0;
+
+        Ok(())
+    }
+
+    async fn move_at(&mut self, y: usize, x: usize) -> UnitResult {
+        if y >= self.height() || x >= self.width() {
+            return Err(
+                format!("Tried to move the cursor outside the screen (at y={}, x={}).", y, x).into()
+            );
+        }
+
+        let address = self.properties.address_of(y, x);
+
+        self.bus.execute(Command::SetDDRamAddress {
+            address,
+        }).await?;
+
+        self.state.address = address;
+
+        Ok(())
+    }
+
+    fn cursor(&self) -> Point {
+        self.properties.position_of(self.state.address)
+    }
+
+    async fn print_char(&mut self, ch: u8) -> UnitResult {
+        self.bus.write_data(ch).await?;
+        self.state.address = self.properties.next_address(self.state.address, true);
+
+        // on split displays the cursor has to be moved manually when crossing the middle of the
+        // screen
+        if self.properties.is_split() {
+            let half = self.width() / 2;
+
+            if self.state.address == half as u8 {
+                self.move_at(0, half).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn set_backlight(&mut self, enabled: bool) -> UnitResult {
+        self.bus.set_backlight(enabled).await
+    }
+
+    async fn set_cursor_blinking(&mut self, enabled: bool) -> UnitResult {
+        self.state.cursor_blinking = enabled;
+        self.refresh_display_flags().await
+    }
+
+    async fn set_cursor_visible(&mut self, enabled: bool) -> UnitResult {
+        self.state.cursor_visible = enabled;
+        self.refresh_display_flags().await
+    }
+
+    async fn set_text_visible(&mut self, enabled: bool) -> UnitResult {
+        self.state.text_visible = enabled;
+        self.refresh_display_flags().await
+    }
+
+    async fn create_char(&mut self, idx: u8, lines: [u8; 8]) -> UnitResult {
+        Error::check_char_idx(idx, 7)?;
+
+        self.bus.execute(Command::SetCGRamAddress {
+            address: idx << 3,
+        }).await?;
+
+        for line in lines.iter() {
+            self.bus.write_data(*line).await?;
+        }
+
+        // go back to the DDRAM, so that the next character gets printed where the cursor was
+        let address = self.state.address;
+
+        self.bus.execute(Command::SetDDRamAddress {
+            address,
+        }).await
+    }
+
+    fn rom(&self) -> Rom {
+        self.properties.rom
+    }
+
+    fn height(&self) -> usize {
+        self.properties.height
+    }
+
+    fn width(&self) -> usize {
+        self.properties.width
+    }
+}
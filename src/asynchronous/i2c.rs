@@ -0,0 +1,138 @@
+//! Defines an asynchronous version of the I2C bus (see `buses::i2c` for the protocol's details).
+//!
+//! The transfers themselves are still regular (blocking) `i2cdev` calls - they take a fraction of
+//! the time spent waiting for the LCD though, and that's the part being awaited.
+
+use i2cdev::core::I2CDevice;
+use i2cdev::linux::LinuxI2CDevice;
+use std::path;
+use super::super::{Error, Result, UnitResult};
+use super::super::buses::I2C as BlockingI2C;
+use super::super::buses::BusLock;
+use super::super::buses::Timings;
+use super::{delay_ns, AsyncBus};
+
+pub struct I2C {
+    dev: LinuxI2CDevice,
+
+    /// held for as long as the bus lives (see the `lock` module)
+    _lock: BusLock,
+
+    backlight_enabled: bool,
+    timings: Timings,
+}
+
+impl I2C {
+    /// Constructs a new asynchronous HD44780 I2C bus.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when given address is not a valid 7-bit I2C address, or
+    /// `Error::BusLocked` when another process is driving the LCD (see the `buses::lock` module).
+    pub fn new<P: AsRef<path::Path>>(i2c_device: P, i2c_address: u16) -> Result<I2C> {
+        I2C::new_ex(i2c_device, i2c_address, Timings::default())
+    }
+
+    /// Constructs a new asynchronous HD44780 I2C bus, using given delays instead of the default
+    /// ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when given address is not a valid 7-bit I2C address, or
+    /// `Error::BusLocked` when another process is driving the LCD (see the `buses::lock` module).
+    pub fn new_ex<P: AsRef<path::Path>>(i2c_device: P, i2c_address: u16, timings: Timings) -> Result<I2C> {
+        if !(BlockingI2C::MIN_ADDRESS..=BlockingI2C::MAX_ADDRESS).contains(&i2c_address) {
+            return Err(Error::invalid_config(
+                "i2c_address",
+                format!("address {:#04x} is out of range <{:#04x}, {:#04x}>", i2c_address, BlockingI2C::MIN_ADDRESS, BlockingI2C::MAX_ADDRESS),
+            ).into());
+        }
+
+        let lock = BusLock::i2c(i2c_device.as_ref(), i2c_address, timings.lock_wait())?;
+
+        Ok(
+            I2C {
+                dev: LinuxI2CDevice::new(i2c_device, i2c_address)?,
+                _lock: lock,
+                backlight_enabled: true,
+                timings,
+            }
+        )
+    }
+
+    /// Returns the control bits (`Bl` and `Rs`) for given transfer.
+    fn control_mask(&self, as_data: bool) -> u8 {
+        let mut mask = 0u8;
+
+        mask |= 0b00001000 * (self.backlight_enabled as u8);
+        mask |= as_data as u8;
+
+        mask
+    }
+
+    /// Sends a single nibble, latching the `Enable` pin.
+    async fn write_nibble(&mut self, value: u8) -> UnitResult {
+        // write value, pull up the `enable` pin & wait ~450ns (enable pulse must be >450ns)
+        self.dev.smbus_write_byte(value | 0b00000100)?;
+        delay_ns(self.timings.enable_pulse_ns as u64).await;
+
+        // write value again, this time pulling the `Enable` pin down & wait ~37us (commands need 37us to settle)
+        self.dev.smbus_write_byte(value & !0b00000100)?;
+        delay_ns(self.timings.settle_us as u64 * 1000).await;
+
+        Ok(())
+    }
+}
+
+impl AsyncBus for I2C {
+    async fn initialize(&mut self) -> UnitResult {
+        let commands = [
+            // try to put LCD in 8-bit mode three times;
+            // required for initialization when LCD has not been previously restarted
+            0x03,
+            0x03,
+            0x03,
+
+            // put LCD in proper 4-bit mode
+            0x02,
+        ];
+
+        // wait for the LCD to power up
+        delay_ns(self.timings.power_on_us as u64 * 1000).await;
+
+        for (step, &c) in commands.iter().enumerate() {
+            self.write_nibble(c << 4).await?;
+            delay_ns(self.timings.init_step_us(step) as u64 * 1000).await;
+        }
+
+        Ok(())
+    }
+
+    async fn set_backlight(&mut self, enabled: bool) -> UnitResult {
+        self.backlight_enabled = enabled;
+
+        // write a dummy byte to update the backlight state
+        self.write_byte(0, false).await
+    }
+
+    async fn write_byte(&mut self, value: u8, as_data: bool) -> UnitResult {
+        let mask = self.control_mask(as_data);
+
+        self.write_nibble(value & 0xF0 | mask).await?;
+        self.write_nibble((value << 4) & 0xF0 | mask).await?;
+
+        Ok(())
+    }
+
+    fn width(&self) -> usize {
+        4
+    }
+
+    fn timings(&self) -> Timings {
+        self.timings
+    }
+
+    async fn delay_us(&mut self, us: u32) {
+        delay_ns(us as u64 * 1000).await;
+    }
+}
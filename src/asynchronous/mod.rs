@@ -0,0 +1,189 @@
+//! Provides an asynchronous version of the driver, built on top of `tokio`.
+//!
+//! The API mirrors the blocking one (`AsyncBus` ~ `Bus`, `AsyncLcd` ~ `Hd44780`), but all the
+//! delays HD44780 requires (37us after each transfer, 1ms after `clear` / `home`) are awaited
+//! instead of putting the whole thread to sleep - so that a single-threaded runtime can keep
+//! handling other tasks while the LCD is being refreshed.
+//!
+//! Requires the `async` feature.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let bus = pwr_hd44780::asynchronous::I2C::new("/dev/i2c-1", 0x27)?;
+//! let mut lcd = pwr_hd44780::asynchronous::Direct::new(bus, 20, 4).await?;
+//!
+//! lcd.clear().await?;
+//! lcd.print("Hello World! :-)").await?;
+//! ```
+//!
+//! # Caveats
+//!
+//! Tokio's timers have a millisecond resolution, so the shorter delays are implemented by
+//! yielding back to the runtime until the deadline passes - it keeps the other tasks going, but
+//! (unlike a timer) it keeps the CPU busy too.
+
+pub use self::direct::Direct;
+#[cfg(feature = "i2cdev")]
+pub use self::i2c::I2C;
+use super::{Point, Rom, UnitResult};
+use super::buses::Timings;
+use super::buses::command::Command;
+use alloc::string::String;
+use core::future::Future;
+use std::time::{Duration, Instant};
+
+mod direct;
+#[cfg(feature = "i2cdev")]
+mod i2c;
+
+/// An asynchronous counterpart of the `Bus` trait.
+pub trait AsyncBus: Send {
+    /// Initializes the bus (eg. puts LCD in appropriate 4/8-bit mode).
+    fn initialize(&mut self) -> impl Future<Output = UnitResult> + Send;
+
+    /// Enables / disables the backlight.
+    fn set_backlight(&mut self, enabled: bool) -> impl Future<Output = UnitResult> + Send;
+
+    /// Sends a single byte to the device.
+    /// When `as_data` is `true`, the `RS` register is pulled up and byte is sent as `data`.
+    fn write_byte(&mut self, value: u8, as_data: bool) -> impl Future<Output = UnitResult> + Send;
+
+    /// Sends a raw command to the device.
+    fn write_command(&mut self, value: u8) -> impl Future<Output = UnitResult> + Send {
+        self.write_byte(value, false)
+    }
+
+    /// Sends a raw data to the device.
+    fn write_data(&mut self, value: u8) -> impl Future<Output = UnitResult> + Send {
+        self.write_byte(value, true)
+    }
+
+    /// Executes given command.
+    fn execute(&mut self, command: Command) -> impl Future<Output = UnitResult> + Send {
+        async move {
+            self.write_command(command.encode()).await?;
+
+            let delay = command.extra_delay_us(&self.timings());
+
+            if delay > 0 {
+                self.delay_us(delay).await;
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Returns bus width (4 / 8 bit).
+    fn width(&self) -> usize;
+
+    /// Waits given number of microseconds (eg. for the LCD to process a slow command).
+    fn delay_us(&mut self, us: u32) -> impl Future<Output = ()> + Send;
+
+    /// Returns the delays used by the bus.
+    fn timings(&self) -> Timings {
+        Timings::default()
+    }
+}
+
+/// An asynchronous counterpart of the `Hd44780` trait.
+pub trait AsyncLcd: Send {
+    /// Clears the screen and moves cursor at (0, 0).
+    fn clear(&mut self) -> impl Future<Output = UnitResult> + Send;
+
+    /// Moves the cursor at (0, 0).
+    fn home(&mut self) -> impl Future<Output = UnitResult> + Send;
+
+    /// Moves the cursor at given position.
+    ///
+    /// # Errors
+    ///
+    /// When passed an invalid coordinates (eg. beyond the screen), returns an error and does not
+    /// update the cursor position.
+    fn move_at(&mut self, y: usize, x: usize) -> impl Future<Output = UnitResult> + Send;
+
+    /// Returns the cursor's position.
+    fn cursor(&self) -> Point;
+
+    /// Prints a single ASCII character at current cursor's position and moves the cursor.
+    fn print_char(&mut self, ch: u8) -> impl Future<Output = UnitResult> + Send;
+
+    /// Prints a single ASCII character at given position.
+    fn print_char_at(&mut self, y: usize, x: usize, ch: u8) -> impl Future<Output = UnitResult> + Send {
+        async move {
+            self.move_at(y, x).await?;
+            self.print_char(ch).await
+        }
+    }
+
+    /// Prints a string at current cursor's position and moves the cursor.
+    ///
+    /// Characters are translated into the codes of LCD's character ROM (see `rom`); the ones not
+    /// present in the ROM are printed as a question mark.
+    fn print<T: Into<String>>(&mut self, str: T) -> impl Future<Output = UnitResult> + Send {
+        let rom = self.rom();
+        let str = str.into();
+
+        async move {
+            for ch in str.chars() {
+                self.print_char(rom.encode(ch)).await?;
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Prints a string at given position.
+    fn print_at<T: Into<String>>(&mut self, y: usize, x: usize, str: T) -> impl Future<Output = UnitResult> + Send {
+        let str = str.into();
+
+        async move {
+            self.move_at(y, x).await?;
+            self.print(str).await
+        }
+    }
+
+    /// Enables / disables the backlight.
+    fn set_backlight(&mut self, enabled: bool) -> impl Future<Output = UnitResult> + Send;
+
+    /// Enables / disables blinking the cursor.
+    fn set_cursor_blinking(&mut self, enabled: bool) -> impl Future<Output = UnitResult> + Send;
+
+    /// Enables / disables the cursor.
+    fn set_cursor_visible(&mut self, enabled: bool) -> impl Future<Output = UnitResult> + Send;
+
+    /// Shows / hides the text.
+    fn set_text_visible(&mut self, enabled: bool) -> impl Future<Output = UnitResult> + Send;
+
+    /// Creates a custom character (see `Hd44780::create_char`).
+    fn create_char(&mut self, idx: u8, lines: [u8; 8]) -> impl Future<Output = UnitResult> + Send;
+
+    /// Returns the character ROM (code page) the LCD has been manufactured with.
+    fn rom(&self) -> Rom {
+        Rom::A00
+    }
+
+    /// Returns screen's height (number of lines).
+    fn height(&self) -> usize;
+
+    /// Returns screen's width (number of characters per line).
+    fn width(&self) -> usize;
+}
+
+/// Waits given number of nanoseconds without blocking the runtime's thread.
+#[cfg_attr(not(feature = "i2cdev"), allow(dead_code))]
+pub(crate) async fn delay_ns(ns: u64) {
+    let duration = Duration::from_nanos(ns);
+
+    // timers are good enough only for the longer delays
+    if duration >= Duration::from_millis(1) {
+        tokio::time::sleep(duration).await;
+        return;
+    }
+
+    let deadline = Instant::now() + duration;
+
+    while Instant::now() < deadline {
+        tokio::task::yield_now().await;
+    }
+}
@@ -0,0 +1,240 @@
+//! Switches the backlight on schedule - eg. dimmed at night, off during the working hours - or
+//! according to an ambient light sensor, so that the applications do not have to carry their own
+//! cron-like logic.
+//!
+//! The scheduler is polled with the current time (just like the health check), from whatever
+//! loop drives the rendering; it touches the LCD only when the backlight's brightness should
+//! change, so it's fine to poll it every frame.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! const HOUR: u64 = 60 * 60;
+//!
+//! // full brightness by default, dimmed between 22:00 and 7:00
+//! let mut scheduler = BacklightScheduler::new(255);
+//!
+//! scheduler.add_window(Duration::from_secs(22 * HOUR), Duration::from_secs(7 * HOUR), 32);
+//!
+//! loop {
+//!     scheduler.poll(&mut lcd, SystemTime::now().duration_since(UNIX_EPOCH)?)?;
+//!     lcd.render()?;
+//! }
+//! ```
+//!
+//! Buses unable to dim the backlight (eg. the I2C one) just turn it on for any non-zero
+//! brightness - so on those, windows with brightness `0` are the ones that matter.
+//!
+//! # Fading
+//!
+//! `BacklightFader` ramps the brightness smoothly instead of switching it at once; it's driven by
+//! calling `tick` (eg. once per frame) for as long as the fade lasts:
+//!
+//! ```rust,ignore
+//! let mut fader = BacklightFader::new(255);
+//!
+//! fader.fade_backlight(0, Duration::from_millis(500));
+//!
+//! while fader.tick(&mut lcd, started.elapsed())? {
+//!     thread::sleep(Duration::from_millis(20));
+//! }
+//! ```
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::time::Duration;
+use super::{Backlight, Result};
+
+pub struct BacklightScheduler {
+    windows: Vec<Window>,
+
+    /// brightness outside of all the windows
+    default: u8,
+
+    ambient: Option<Box<dyn FnMut() -> Option<u8> + Send>>,
+
+    /// brightness applied the last time (`None` when unknown)
+    applied: Option<u8>,
+}
+
+/// Changes the backlight's brightness gradually (see the module's docs).
+pub struct BacklightFader {
+    /// brightness applied the last time
+    brightness: u8,
+
+    fade: Option<Fade>,
+}
+
+struct Fade {
+    from: u8,
+    to: u8,
+    duration: Duration,
+
+    /// when the fade has started (`None` until the first tick)
+    started: Option<Duration>,
+}
+
+/// A time-of-day range during which the backlight has given brightness.
+struct Window {
+    from: Duration,
+    to: Duration,
+    brightness: u8,
+}
+
+const DAY: u64 = 24 * 60 * 60;
+
+impl BacklightScheduler {
+    /// Creates a scheduler without any windows, keeping the backlight at given brightness (`0` -
+    /// off, `255` - full brightness).
+    pub fn new(default: u8) -> BacklightScheduler {
+        BacklightScheduler {
+            windows: Vec::new(),
+            default,
+            ambient: None,
+            applied: None,
+        }
+    }
+
+    /// Adds a window, during which the backlight has given brightness; `from` and `to` are times
+    /// of day (measured since midnight), with `from` being inclusive and `to` - exclusive.
+    ///
+    /// A window ending before it starts (eg. from 22:00 to 7:00) spans midnight. When windows
+    /// overlap, the one added first wins.
+    pub fn add_window(&mut self, from: Duration, to: Duration, brightness: u8) {
+        self.windows.push(Window {
+            from: time_of_day(from),
+            to: time_of_day(to),
+            brightness,
+        });
+    }
+
+    /// Sets a callback (eg. reading a light sensor) asked for the brightness before the windows
+    /// are; when it returns `None`, the windows are used as usual.
+    pub fn set_ambient<F>(&mut self, ambient: F) where F: FnMut() -> Option<u8> + Send + 'static {
+        self.ambient = Some(Box::new(ambient));
+    }
+
+    /// Removes the ambient callback.
+    pub fn clear_ambient(&mut self) {
+        self.ambient = None;
+    }
+
+    /// Returns the brightness scheduled for given time (without asking the ambient callback).
+    ///
+    /// Time longer than a day wraps around - so the time elapsed since the UNIX epoch yields the
+    /// (UTC) time of day.
+    pub fn scheduled_at(&self, time: Duration) -> u8 {
+        let time = time_of_day(time);
+
+        self.windows
+            .iter()
+            .find(|window| window.contains(time))
+            .map_or(self.default, |window| window.brightness)
+    }
+
+    /// Applies the brightness for given time - unless it's the same as the one applied the last
+    /// time.
+    ///
+    /// Returns the new brightness, if it has changed.
+    pub fn poll<L: Backlight + ?Sized>(&mut self, lcd: &mut L, now: Duration) -> Result<Option<u8>> {
+        let brightness = self.ambient
+            .as_mut()
+            .and_then(|ambient| ambient())
+            .unwrap_or_else(|| self.scheduled_at(now));
+
+        if self.applied == Some(brightness) {
+            return Ok(None);
+        }
+
+        lcd.set_backlight_brightness(brightness)?;
+        self.applied = Some(brightness);
+
+        Ok(Some(brightness))
+    }
+
+    /// Makes the next `poll` apply the brightness even if it has not changed (eg. after the
+    /// backlight has been changed manually).
+    pub fn invalidate(&mut self) {
+        self.applied = None;
+    }
+}
+
+impl BacklightFader {
+    /// Creates a fader for a backlight currently having given brightness.
+    pub fn new(brightness: u8) -> BacklightFader {
+        BacklightFader {
+            brightness,
+            fade: None,
+        }
+    }
+
+    /// Starts fading towards given brightness, so that it's reached after `duration` (measured
+    /// since the next `tick`); a fade already in progress continues from wherever it is.
+    pub fn fade_backlight(&mut self, to: u8, duration: Duration) {
+        self.fade = Some(Fade {
+            from: self.brightness,
+            to,
+            duration,
+            started: None,
+        });
+    }
+
+    /// Applies the brightness for given time; returns whether the fade is still in progress.
+    ///
+    /// LCDs unable to dim the backlight are switched to the target brightness at once (see
+    /// `Backlight::can_dim_backlight`).
+    pub fn tick<L: Backlight + ?Sized>(&mut self, lcd: &mut L, now: Duration) -> Result<bool> {
+        let fade = match self.fade.as_mut() {
+            Some(fade) => fade,
+            None => return Ok(false),
+        };
+
+        let started = *fade.started.get_or_insert(now);
+        let elapsed = now.checked_sub(started).unwrap_or_default();
+
+        let (brightness, done) = if !lcd.can_dim_backlight() || elapsed >= fade.duration {
+            (fade.to, true)
+        } else {
+            let progress = elapsed.as_secs_f32() / fade.duration.as_secs_f32();
+            let delta = fade.to as f32 - fade.from as f32;
+
+            ((fade.from as f32 + delta * progress) as u8, false)
+        };
+
+        if brightness != self.brightness || done {
+            lcd.set_backlight_brightness(brightness)?;
+            self.brightness = brightness;
+        }
+
+        if done {
+            self.fade = None;
+        }
+
+        Ok(!done)
+    }
+
+    /// Returns the brightness applied the last time.
+    pub fn brightness(&self) -> u8 {
+        self.brightness
+    }
+
+    /// Returns whether a fade is in progress.
+    pub fn is_fading(&self) -> bool {
+        self.fade.is_some()
+    }
+}
+
+impl Window {
+    fn contains(&self, time: Duration) -> bool {
+        if self.from <= self.to {
+            self.from <= time && time < self.to
+        } else {
+            self.from <= time || time < self.to
+        }
+    }
+}
+
+/// Returns the time elapsed since the most recent midnight.
+fn time_of_day(time: Duration) -> Duration {
+    Duration::new(time.as_secs() % DAY, time.subsec_nanos())
+}
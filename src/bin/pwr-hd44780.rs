@@ -0,0 +1,226 @@
+//! Drives the HD44780 from the command line - eg. from shell scripts or systemd units.
+//!
+//! Usage:
+//!     pwr-hd44780 <bus> [options] <command> [arguments]
+//!
+//! Buses:
+//!     --i2c <device> <address>
+//!     --gpio <d4,d5,d6,d7> <rs> <en>
+//!
+//! Options:
+//!     --size <width>x<height>     (default: 20x4)
+//!     --no-backlight              keeps the backlight off while executing the command
+//!     --warm                      assumes the LCD has already been initialized (skips the reset
+//!                                 sequence, avoiding the flicker - the interface is only
+//!                                 resynchronized, see `DirectLcd::resync`)
+//!
+//! Commands:
+//!     print <text>
+//!     clear
+//!     backlight <on|off>
+//!     goto <y> <x>
+//!     create-char <idx> <line1,...,line8>     (lines given as binary, eg. 0b01010, or hex)
+//!
+//! Examples:
+//!     pwr-hd44780 --i2c /dev/i2c-1 0x27 clear
+//!     pwr-hd44780 --i2c /dev/i2c-1 0x27 goto 1 0
+//!     pwr-hd44780 --i2c /dev/i2c-1 0x27 print "Hello World!"
+//!
+//! Each invocation initializes the LCD anew, which does not affect the displayed text nor the
+//! cursor's position - so the commands can be chained by running the binary a few times.
+//!
+//! Requires the `cli` feature.
+
+extern crate pwr_hd44780;
+
+use pwr_hd44780::{Backlight, CustomChars, DirectLcd, Hd44780, Properties};
+use pwr_hd44780::buses::Bus;
+use std::convert::TryFrom;
+use std::{env, process};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    if let Err(err) = run(&args) {
+        eprintln!("error: {}", err);
+        eprintln!("(run without arguments to see the usage)");
+        process::exit(1);
+    }
+}
+
+fn run(args: &[String]) -> Result<()> {
+    if args.is_empty() {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    let mut args = args.iter().map(|arg| arg.as_str());
+    let mut next = |what: &str| -> Result<&str> {
+        args.next().ok_or_else(|| format!("Missing {}.", what).into())
+    };
+
+    // -- bus -- //
+    let bus: Box<dyn Bus> = match next("bus")? {
+        "--i2c" => {
+            let device = next("I2C device")?;
+            let address = parse_number(next("I2C address")?)?;
+
+            Box::new(pwr_hd44780::I2CBus::new(device, address)?)
+        }
+
+        "--gpio" => {
+            let data: Vec<u8> = next("data pins")?
+                .split(',')
+                .map(|pin| pin.parse())
+                .collect::<std::result::Result<_, _>>()?;
+
+            if data.len() != 4 {
+                return Err("Exactly four data pins are required.".into());
+            }
+
+            Box::new(pwr_hd44780::Gpio4Bus::new(pwr_hd44780::buses::gpio4::Pins {
+                data: [data[0], data[1], data[2], data[3]],
+                rs: next("RS pin")?.parse()?,
+                en: next("EN pin")?.parse()?,
+                rw: None,
+                backlight: None,
+                backlight_rgb: None,
+                contrast: None,
+                extra_en: vec![],
+            })?)
+        }
+
+        bus => {
+            return Err(format!("Unknown bus: {} (expected `--i2c` or `--gpio`).", bus).into());
+        }
+    };
+
+    // -- options -- //
+    let (mut width, mut height) = (20, 4);
+    let mut backlight = true;
+    let mut warm = false;
+
+    let command = loop {
+        match next("command")? {
+            "--size" => {
+                let size = next("size")?;
+                let (w, h) = size.split_once('x').ok_or("Size must be given as <width>x<height>.")?;
+
+                width = w.parse()?;
+                height = h.parse()?;
+            }
+
+            "--no-backlight" => {
+                backlight = false;
+            }
+
+            "--warm" => {
+                warm = true;
+            }
+
+            command => {
+                break command;
+            }
+        }
+    };
+
+    let properties = Properties::new(width, height);
+
+    let mut lcd = if warm {
+        // the previous invocation might have been killed in the middle of a transfer
+        let mut lcd = DirectLcd::new_warm(bus, properties)?;
+        lcd.resync()?;
+        lcd
+    } else {
+        DirectLcd::new_ex(bus, properties)?
+    };
+
+    lcd.set_backlight(backlight)?;
+
+    // -- command -- //
+    match command {
+        "print" => {
+            lcd.print(next("text")?)?;
+        }
+
+        "clear" => {
+            lcd.clear()?;
+        }
+
+        "backlight" => {
+            match next("backlight's state")? {
+                "on" => lcd.set_backlight(true)?,
+                "off" => lcd.set_backlight(false)?,
+                state => return Err(format!("Unknown backlight's state: {} (expected `on` or `off`).", state).into()),
+            }
+        }
+
+        "goto" => {
+            let y = next("line")?.parse()?;
+            let x = next("column")?.parse()?;
+
+            lcd.move_at(y, x)?;
+        }
+
+        "create-char" => {
+            let idx = next("index")?.parse()?;
+
+            let lines: Vec<u8> = next("bitmap")?
+                .split(',')
+                .map(parse_number)
+                .collect::<Result<_>>()?;
+
+            if lines.len() != 8 {
+                return Err("Exactly eight lines are required.".into());
+            }
+
+            let mut bitmap = [0; 8];
+            bitmap.copy_from_slice(&lines);
+
+            lcd.create_char(idx, bitmap)?;
+        }
+
+        command => {
+            return Err(format!("Unknown command: {}.", command).into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses a decimal, hexadecimal (`0x`) or binary (`0b`) number.
+fn parse_number<T: TryFrom<u32>>(value: &str) -> Result<T> {
+    let number = if let Some(hex) = value.strip_prefix("0x") {
+        u32::from_str_radix(hex, 16)?
+    } else if let Some(bin) = value.strip_prefix("0b") {
+        u32::from_str_radix(bin, 2)?
+    } else {
+        value.parse()?
+    };
+
+    T::try_from(number).map_err(|_| format!("Number out of range: {}.", value).into())
+}
+
+const USAGE: &str = "\
+Usage:
+    pwr-hd44780 <bus> [options] <command> [arguments]
+
+Buses:
+    --i2c <device> <address>
+    --gpio <d4,d5,d6,d7> <rs> <en>
+
+Options:
+    --size <width>x<height>     (default: 20x4)
+    --no-backlight              keeps the backlight off while executing the command
+    --warm                      assumes the LCD has already been initialized (skips the reset
+                                sequence, avoiding the flicker - the interface is only
+                                resynchronized)
+
+Commands:
+    print <text>
+    clear
+    backlight <on|off>
+    goto <y> <x>
+    create-char <idx> <line1,...,line8>     (lines given as binary, eg. 0b01010, or hex)";
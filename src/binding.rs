@@ -0,0 +1,93 @@
+//! Binds values to regions of the buffered frontend's screen, so that the code producing them
+//! (eg. a sensor-reading thread) does not have to know anything about the rendering.
+//!
+//! Each binding is a cheap, cloneable handle - updating it only stores the formatted value and
+//! marks it as changed; the frontend picks it up during the next `render`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let temperature = lcd.bind::<f32>(0, 6, 6, Align::Right)?;
+//!
+//! thread::spawn(move || loop {
+//!     temperature.set(sensor.read());
+//!     thread::sleep(Duration::from_secs(1));
+//! });
+//!
+//! loop {
+//!     lcd.render()?;
+//!     thread::sleep(Duration::from_millis(100));
+//! }
+//! ```
+
+use alloc::string::{String, ToString};
+use core::fmt;
+use core::marker::PhantomData;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+pub struct Binding<T> {
+    slot: Arc<Slot>,
+
+    // `fn(T)`, so that the binding is `Send` & `Sync` regardless of `T`
+    _value: PhantomData<fn(T)>,
+}
+
+/// The most recent value of a binding, shared between its handles and the frontend.
+#[derive(Default)]
+pub(crate) struct Slot {
+    /// formatted value, along with whether it has changed since the frontend has taken it
+    value: Mutex<(String, bool)>,
+}
+
+impl<T: fmt::Display> Binding<T> {
+    pub(crate) fn new() -> Binding<T> {
+        Binding {
+            slot: Arc::new(Slot::default()),
+            _value: PhantomData,
+        }
+    }
+
+    /// Changes the value shown on the screen (during the next render).
+    pub fn set(&self, value: T) {
+        let value = value.to_string();
+        let mut slot = self.slot.lock();
+
+        if slot.0 != value {
+            *slot = (value, true);
+        }
+    }
+
+    pub(crate) fn slot(&self) -> Arc<Slot> {
+        self.slot.clone()
+    }
+}
+
+impl Slot {
+    /// Returns the value, if it has changed since the previous call.
+    pub(crate) fn take(&self) -> Option<String> {
+        let mut slot = self.lock();
+
+        if slot.1 {
+            slot.1 = false;
+            Some(slot.0.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Locks the value, recovering it after a panic in some other thread.
+    fn lock(&self) -> MutexGuard<'_, (String, bool)> {
+        self.value
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl<T> Clone for Binding<T> {
+    fn clone(&self) -> Binding<T> {
+        Binding {
+            slot: self.slot.clone(),
+            _value: PhantomData,
+        }
+    }
+}
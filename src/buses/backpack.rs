@@ -0,0 +1,125 @@
+//! Tells the I2C backpacks apart, so that the right bus can be constructed without knowing the
+//! backpack's type (or even address) up front.
+//!
+//! Two kinds of backpacks are recognized:
+//!
+//! - the PCF8574 ones (the cheap, most popular ones - see the `i2c` module),
+//! - the MCP23008 ones (eg. the Adafruit's - see the `mcp23008` module).
+//!
+//! The backpacks with a controller talking the commands natively (eg. the AiP31068-based ones)
+//! are not supported, so they are not recognized.
+//!
+//! # A word on the detection itself
+//!
+//! MCP23008 is register-based - reading a byte from it returns the register selected by the
+//! preceding write, while PCF8574 simply returns the state of its pins (which, in turn, is set by
+//! that write). So selecting the `IPOL` and `DEFVAL` registers (both zeroed after reset and never
+//! touched by the driver) reads zeros back on the MCP23008 and the registers' addresses on the
+//! PCF8574 - neither of the addresses pulls the `En` pin up, so the LCD is not affected.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let lcd = DirectLcd::new(Backpack::auto("/dev/i2c-1")?, 20, 4)?;
+//! ```
+
+use alloc::boxed::Box;
+use i2cdev::core::I2CDevice;
+use i2cdev::linux::LinuxI2CDevice;
+use std::path;
+use super::super::{Error, Result};
+use super::{Bus, I2C, Mcp23008, Timings};
+use super::mcp23008::REG_IPOL;
+
+/// `DEFVAL` register of the MCP23008.
+const REG_DEFVAL: u8 = 0x03;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Backpack {
+    Pcf8574,
+    Mcp23008,
+}
+
+impl Backpack {
+    /// Addresses scanned by `auto`, the most popular ones first: `0x27` and `0x3F` (the PCF8574's
+    /// and PCF8574A's defaults), `0x20` (the MCP23008's default) and then all the remaining ones.
+    pub const ADDRESSES: [u16; 16] = [
+        0x27, 0x3F, 0x20, 0x21, 0x22, 0x23, 0x24, 0x25,
+        0x26, 0x38, 0x39, 0x3A, 0x3B, 0x3C, 0x3D, 0x3E,
+    ];
+
+    /// Detects the backpack at given address; returns `None` when nothing acknowledges it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the I2C device could not be opened or when the device at given
+    /// address is not a recognized backpack.
+    pub fn detect<P: AsRef<path::Path>>(i2c_device: P, i2c_address: u16) -> Result<Option<Backpack>> {
+        let mut dev = LinuxI2CDevice::new(i2c_device, i2c_address)?;
+
+        if dev.smbus_read_byte().is_err() {
+            return Ok(None);
+        }
+
+        // only the PCF8574A lives at these addresses
+        if i2c_address >= 0x38 {
+            return Ok(Some(Backpack::Pcf8574));
+        }
+
+        let ipol = dev.smbus_read_byte_data(REG_IPOL)?;
+        let defval = dev.smbus_read_byte_data(REG_DEFVAL)?;
+
+        match (ipol, defval) {
+            (REG_IPOL, REG_DEFVAL) => Ok(Some(Backpack::Pcf8574)),
+            (0x00, 0x00) => Ok(Some(Backpack::Mcp23008)),
+
+            _ => Err(Error::invalid_config(
+                "i2c_address",
+                format!("device at {:#04x} is not a recognized backpack", i2c_address),
+            ).into()),
+        }
+    }
+
+    /// Finds the first backpack connected to given I2C device (see `ADDRESSES`) and constructs a
+    /// bus for it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the I2C device could not be opened or when no backpack has been
+    /// found.
+    pub fn auto<P: AsRef<path::Path>>(i2c_device: P) -> Result<Box<dyn Bus>> {
+        Backpack::auto_ex(i2c_device, Timings::default())
+    }
+
+    /// Finds the first backpack connected to given I2C device (see `auto`) and constructs a bus
+    /// for it, using given delays instead of the default ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the I2C device could not be opened or when no backpack has been
+    /// found.
+    pub fn auto_ex<P: AsRef<path::Path>>(i2c_device: P, timings: Timings) -> Result<Box<dyn Bus>> {
+        let i2c_device = i2c_device.as_ref();
+
+        for &address in Backpack::ADDRESSES.iter() {
+            if let Ok(Some(backpack)) = Backpack::detect(i2c_device, address) {
+                return backpack.bus(i2c_device, address, timings);
+            }
+        }
+
+        Err(format!("No LCD backpack has been found on {}.", i2c_device.display()).into())
+    }
+
+    /// Constructs a bus for this kind of backpack.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the I2C device could not be opened or when given address is not
+    /// valid for this kind of backpack.
+    pub fn bus<P: AsRef<path::Path>>(self, i2c_device: P, i2c_address: u16, timings: Timings) -> Result<Box<dyn Bus>> {
+        Ok(match self {
+            Backpack::Pcf8574 => Box::new(I2C::new_ex(i2c_device, i2c_address, timings)?),
+            Backpack::Mcp23008 => Box::new(Mcp23008::new_ex(i2c_device, i2c_address, timings)?),
+        })
+    }
+}
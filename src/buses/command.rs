@@ -1,14 +1,24 @@
+//! Describes the HD44780's instruction set.
+//!
+//! Mostly useful together with `DirectLcd::write_raw_command` - eg. to send a standard command
+//! the frontend does not expose, or to build upon one when playing with the extended instruction
+//! sets of the HD44780's clones.
+
+use super::timing::Timings;
+
+/// Opcodes of the commands (the highest bit set in the encoded command).
 pub enum CommandValue {
     Clear = 0x01,
     Home = 0x02,
     SetEntryMode = 0x04,
     SetDisplayFlags = 0x08,
-    // SetCursorShift = 0x10, // @todo not implemented
+    Shift = 0x10,
     SetFunctions = 0x20,
     SetCGRamAddress = 0x40,
     SetDDRamAddress = 0x80,
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Command {
     Clear,
     Home,
@@ -24,6 +34,11 @@ pub enum Command {
         text_visible: bool,
     },
 
+    Shift {
+        display: bool,
+        right: bool,
+    },
+
     SetFunctions {
         font_5x10: bool,
         height: usize,
@@ -37,4 +52,140 @@ pub enum Command {
     SetDDRamAddress {
         address: u8,
     },
-}
\ No newline at end of file
+
+    /// KS0073 only: the "function set" command with the `RE` bit set, which switches the
+    /// controller to the extended instruction set (until the next regular `SetFunctions`).
+    SetFunctionsExtended {
+        height: usize,
+        eight_bit_bus: bool,
+    },
+
+    /// WS0010 only: the "function set" command, along with the font table to use (`0` - English /
+    /// Japanese, `1` - Western European I, `2` - English / Russian, `3` - Western European II).
+    SetOledFunctions {
+        height: usize,
+        eight_bit_bus: bool,
+        font_table: u8,
+    },
+
+    /// WS0010 only: selects the character / graphics mode and turns the panel's power on / off.
+    SetModePower {
+        graphics: bool,
+        power: bool,
+    },
+
+    /// KS0073 only (requires `SetFunctionsExtended` first): the "extended function set" command.
+    SetExtendedFunctions {
+        /// 6-dot wide characters instead of the 5-dot ones
+        wide_font: bool,
+
+        /// black / white inverting cursor
+        inverted_cursor: bool,
+
+        /// 4-line mode (lines starting at `0x00`, `0x20`, `0x40` and `0x60`)
+        four_lines: bool,
+    },
+}
+
+impl Command {
+    /// Returns the byte representing this command on the wire.
+    pub fn encode(&self) -> u8 {
+        match *self {
+            Command::Clear => CommandValue::Clear as u8,
+            Command::Home => CommandValue::Home as u8,
+
+            Command::SetEntryMode { enable_shift, increment_counter } => {
+                let mut cmd = CommandValue::SetEntryMode as u8;
+
+                cmd |= enable_shift as u8;
+                cmd |= 0x02 * increment_counter as u8;
+
+                cmd
+            }
+
+            Command::SetDisplayFlags { cursor_blinking, cursor_visible, text_visible } => {
+                let mut cmd = CommandValue::SetDisplayFlags as u8;
+
+                cmd |= cursor_blinking as u8;
+                cmd |= 0x02 * cursor_visible as u8;
+                cmd |= 0x04 * text_visible as u8;
+
+                cmd
+            }
+
+            Command::Shift { display, right } => {
+                let mut cmd = CommandValue::Shift as u8;
+
+                cmd |= 0x04 * right as u8;
+                cmd |= 0x08 * display as u8;
+
+                cmd
+            }
+
+            Command::SetFunctions { font_5x10, height, eight_bit_bus } => {
+                let mut cmd = CommandValue::SetFunctions as u8;
+
+                cmd |= 0x04 * font_5x10 as u8;
+                cmd |= 0x08 * (height >= 2) as u8;
+                cmd |= 0x10 * eight_bit_bus as u8;
+
+                cmd
+            }
+
+            Command::SetCGRamAddress { address } => (CommandValue::SetCGRamAddress as u8) | address,
+            Command::SetDDRamAddress { address } => (CommandValue::SetDDRamAddress as u8) | address,
+
+            Command::SetFunctionsExtended { height, eight_bit_bus } => {
+                let mut cmd = CommandValue::SetFunctions as u8;
+
+                cmd |= 0x04; // the `RE` bit
+                cmd |= 0x08 * (height >= 2) as u8;
+                cmd |= 0x10 * eight_bit_bus as u8;
+
+                cmd
+            }
+
+            Command::SetOledFunctions { height, eight_bit_bus, font_table } => {
+                let mut cmd = CommandValue::SetFunctions as u8;
+
+                cmd |= font_table & 0x03;
+                cmd |= 0x08 * (height >= 2) as u8;
+                cmd |= 0x10 * eight_bit_bus as u8;
+
+                cmd
+            }
+
+            // shares the opcode with `Shift` - the two lowest bits set tell them apart
+            Command::SetModePower { graphics, power } => {
+                let mut cmd = CommandValue::Shift as u8;
+
+                cmd |= 0x03;
+                cmd |= 0x04 * power as u8;
+                cmd |= 0x08 * graphics as u8;
+
+                cmd
+            }
+
+            // shares the opcode with `SetDisplayFlags` - it's the `RE` bit that tells them apart
+            Command::SetExtendedFunctions { wide_font, inverted_cursor, four_lines } => {
+                let mut cmd = CommandValue::SetDisplayFlags as u8;
+
+                cmd |= four_lines as u8;
+                cmd |= 0x02 * inverted_cursor as u8;
+                cmd |= 0x04 * wide_font as u8;
+
+                cmd
+            }
+        }
+    }
+
+    /// Returns how many microseconds the LCD needs to process this command on top of the regular
+    /// settle time every transfer waits for anyway.
+    pub fn extra_delay_us(&self, timings: &Timings) -> u32 {
+        match *self {
+            // "clear" and "home" commands require additional delay
+            Command::Clear | Command::Home => timings.clear_home_us,
+            _ => 0,
+        }
+    }
+}
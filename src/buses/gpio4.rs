@@ -1,25 +1,79 @@
-/// Defines an interface (a bus) for controlling the HD44780 via a 4-bit GPIO.
-///
-/// Inspired by:
-///     <https://github.com/arduino-libraries/LiquidCrystal/blob/master/src/LiquidCrystal.cpp>.
-///
-/// Thanks to:
-///     <https://github.com/golemparts/rppal>, for providing a nice crate allowing to control the
-///     GPIOs.
-///
-/// # Caveats
-///
-/// 1. No backlight support yet.
+//! Defines an interface (a bus) for controlling the HD44780 via a 4-bit GPIO.
+//!
+//! Inspired by:
+//!     <https://github.com/arduino-libraries/LiquidCrystal/blob/master/src/LiquidCrystal.cpp>.
+//!
+//! Thanks to:
+//!     <https://github.com/golemparts/rppal>, for providing a nice crate allowing to control the
+//!     GPIOs.
+//!
+//! # Backlight
+//!
+//! When the backlight's pin is given, it's driven directly (so it should go through a transistor)
+//! and can be dimmed using a software PWM - see `set_backlight_brightness`.
+//!
+//! RGB backlights are supported as well, by giving three backlight pins (red, green, blue) -
+//! see the `RgbBacklight` trait.
+//!
+//! # Reading
+//!
+//! When the `R/W` pin is given (instead of being tied to the ground), the busy flag, the address
+//! counter and the DDRAM / CGRAM contents can be read back - see `read_byte`. Since the LCD drives the data
+//! lines with 5V then, a 5V-to-3.3V level shifter is required on them.
+//!
+//! # Contrast
+//!
+//! When the contrast's pin is given, it drives the LCD's `V0` pin using a software PWM - so that
+//! no trimpot is needed. The pin must be connected through a low-pass RC filter (eg. 10k & 10uF),
+//! otherwise the LCD gets the raw square wave.
+//!
+//! # Many displays
+//!
+//! Many LCDs can share the data, `RS` (and `R/W`) lines, each one having its own `enable` pin -
+//! see `Pins::extra_en` and `MultiLcd`. Only the display whose `enable` pin is pulsed latches the
+//! data, so the bus talks to one display at a time (see `select_display`); the backlight and the
+//! contrast pins are shared by all the displays.
 
+use alloc::boxed::Box;
+use alloc::vec::Vec;
 use rppal::gpio::{Gpio, Level, Mode};
-use std::{thread, time};
-use super::super::{Bus, Result, UnitResult};
+use std::time;
+use super::super::{Error, Result, UnitResult};
+use super::{Bus, RawNibbleBus, RgbBacklight};
+use super::lock::BusLock;
+use super::soft_pwm::DimmablePin;
+use super::stats::Stats;
+use super::timing::{Operation, TimingReport, Timings};
+use super::super::delay::{Delay, DelayStrategy};
 
 pub struct Gpio4 {
     gpio: Gpio,
+
+    /// held for as long as the bus lives (see the `lock` module)
+    _lock: BusLock,
+
     pins: Pins,
+    timings: TimingReport,
+    stats: Stats,
+    delay: Box<dyn Delay>,
+    config: Timings,
+
+    /// `enable` pin of the display being talked to
+    en: u8,
+
+    backlight: Backlight,
+    contrast: Option<DimmablePin>,
 }
 
+struct Backlight {
+    pin: Option<DimmablePin>,
+    rgb_pins: Option<[DimmablePin; 3]>,
+    brightness: u8,
+    color: [u8; 3],
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Pins {
     /// four `data` pins
     pub data: [u8; 4],
@@ -29,12 +83,55 @@ pub struct Pins {
 
     /// `enable` pin
     pub en: u8,
+
+    /// `read / write` pin (optional; when not given, it has to be pulled down)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rw: Option<u8>,
+
+    /// `backlight` pin (optional)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub backlight: Option<u8>,
+
+    /// RGB backlight's pins - red, green and blue (optional)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub backlight_rgb: Option<[u8; 3]>,
+
+    /// `V0` (contrast) pin, connected through a low-pass filter (optional)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub contrast: Option<u8>,
+
+    /// `enable` pins of the additional displays sharing the other lines (optional; see
+    /// `MultiLcd`)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub extra_en: Vec<u8>,
 }
 
 impl Gpio4 {
     /// Constructs a new HD44780 GPIO bus.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when given pins are invalid (see `Pins::validate`),
+    /// `Error::Gpio` when the GPIO could not be accessed, or `Error::BusLocked` when another
+    /// process is driving the LCD (see the `lock` module).
     pub fn new(pins: Pins) -> Result<Gpio4> {
-        let mut gpio = Gpio::new().unwrap();
+        Gpio4::new_ex(pins, Timings::default())
+    }
+
+    /// Constructs a new HD44780 GPIO bus, using given delays instead of the default ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when given pins are invalid (see `Pins::validate`),
+    /// `Error::Gpio` when the GPIO could not be accessed, or `Error::BusLocked` when another
+    /// process is driving the LCD (see the `lock` module).
+    pub fn new_ex(pins: Pins, timings: Timings) -> Result<Gpio4> {
+        pins.validate()?;
+
+        // the LCDs are told apart by their `enable` pins (the other ones might be shared)
+        let lock = BusLock::acquire(&format!("gpio-{}", pins.en), timings.lock_wait())?;
+
+        let mut gpio = Gpio::new().map_err(Error::Gpio)?;
 
         gpio.set_mode(pins.data[0], Mode::Output);
         gpio.set_mode(pins.data[1], Mode::Output);
@@ -42,40 +139,239 @@ impl Gpio4 {
         gpio.set_mode(pins.data[3], Mode::Output);
 
         gpio.set_mode(pins.rs, Mode::Output);
-        gpio.set_mode(pins.en, Mode::Output);
+
+        for &en in Some(&pins.en).into_iter().chain(&pins.extra_en) {
+            gpio.set_mode(en, Mode::Output);
+            gpio.write(en, Level::Low);
+        }
+
+        if let Some(rw) = pins.rw {
+            gpio.set_mode(rw, Mode::Output);
+            gpio.write(rw, Level::Low);
+        }
+
+        let backlight_pins = pins.backlight
+            .iter()
+            .chain(pins.backlight_rgb.iter().flatten());
+
+        for &pin in backlight_pins {
+            gpio.set_mode(pin, Mode::Output);
+            gpio.write(pin, Level::High);
+        }
+
+        if let Some(contrast) = pins.contrast {
+            gpio.set_mode(contrast, Mode::Output);
+            gpio.write(contrast, Level::Low);
+        }
+
+        let contrast = pins.contrast.map(DimmablePin::new);
+
+        let en = pins.en;
+
+        let backlight = Backlight {
+            pin: pins.backlight.map(DimmablePin::new),
+            rgb_pins: pins.backlight_rgb.map(|rgb| rgb.map(DimmablePin::new)),
+            brightness: 255,
+            color: [255, 255, 255],
+        };
 
         Ok(
             Gpio4 {
                 gpio,
+                _lock: lock,
                 pins,
+                timings: TimingReport::default(),
+                stats: Stats::default(),
+                delay: Box::new(DelayStrategy::Sleep),
+                config: timings,
+                en,
+                backlight,
+                contrast,
             }
         )
     }
 
+    /// Changes the way the bus waits for the LCD (see `DelayStrategy`).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // ~10x faster refreshes, at the cost of keeping a CPU core busy while refreshing
+    /// bus.set_delay_strategy(DelayStrategy::Hybrid);
+    /// ```
+    pub fn set_delay_strategy(&mut self, strategy: DelayStrategy) {
+        self.set_delay(strategy);
+    }
+
+    /// Makes the bus wait for the LCD using given delay - eg. a timer-based one, or `NoDelay` in
+    /// the tests (see the `delay` module).
+    pub fn set_delay<D: Delay + 'static>(&mut self, delay: D) {
+        self.delay = Box::new(delay);
+    }
+
+    /// Returns number of displays connected to the bus (see `Pins::extra_en`).
+    pub fn displays(&self) -> usize {
+        1 + self.pins.extra_en.len()
+    }
+
+    /// Chooses the display the following transfers are latched into: `0` is the one connected to
+    /// `Pins::en`, the next ones - to the subsequent `Pins::extra_en`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when there's no such display.
+    pub fn select_display(&mut self, idx: usize) -> UnitResult {
+        self.en = match idx {
+            0 => self.pins.en,
+
+            idx => *self.pins.extra_en.get(idx - 1).ok_or_else(|| {
+                format!("Tried to select display #{}, but the bus has only {} of them.", idx, self.displays())
+            })?,
+        };
+
+        Ok(())
+    }
+
     /// Sends a single nibble, latching the `Enable` pin.
     fn write_nibble(&mut self, value: u8, as_data: bool) -> UnitResult {
-        let write_pin = |pin: u8, enabled: bool| {
-            self.gpio.write(pin, if enabled { Level::High } else { Level::Low });
-        };
+        let started = time::Instant::now();
+
+        self.gpio.write(self.en, Level::Low);
+        self.gpio.write(self.pins.rs, level(as_data));
 
-        write_pin(self.pins.en, false);
-        write_pin(self.pins.rs, as_data);
+        self.gpio.write(self.pins.data[0], level(value & 0b0001_0000u8 > 0));
+        self.gpio.write(self.pins.data[1], level(value & 0b0010_0000u8 > 0));
+        self.gpio.write(self.pins.data[2], level(value & 0b0100_0000u8 > 0));
+        self.gpio.write(self.pins.data[3], level(value & 0b1000_0000u8 > 0));
 
-        write_pin(self.pins.data[0], value & 0b0001_0000u8 > 0);
-        write_pin(self.pins.data[1], value & 0b0010_0000u8 > 0);
-        write_pin(self.pins.data[2], value & 0b0100_0000u8 > 0);
-        write_pin(self.pins.data[3], value & 0b1000_0000u8 > 0);
+        // let the pins settle before latching them
+        self.setup_delay();
 
-        // give LCD some time to process GPIO changes
-        thread::sleep(time::Duration::new(0, 1000));
+        self.timings.record_since(Operation::Transfer, started);
 
         // pull up the `enable` pin & wait ~450ns (enable pulse must be >450ns)
-        write_pin(self.pins.en, true);
-        thread::sleep(time::Duration::new(0, 450));
+        let started = time::Instant::now();
+        self.gpio.write(self.en, Level::High);
+        self.stats.record_delay_ns(self.config.enable_pulse_ns);
+        self.delay.delay_ns(self.config.enable_pulse_ns);
+        self.timings.record_since(Operation::EnablePulse, started);
 
         // pull down the `enable` pin & wait ~37us (commands need 37us to settle)
-        write_pin(self.pins.en, false);
-        thread::sleep(time::Duration::new(0, 37 * 1000));
+        let started = time::Instant::now();
+        self.gpio.write(self.en, Level::Low);
+        self.stats.record_delay_us(self.config.settle_us);
+        self.delay.delay_us(self.config.settle_us);
+        self.timings.record_since(Operation::Settle, started);
+
+        Ok(())
+    }
+
+    /// Waits the setup time (see `Timings::setup_ns`) - just long enough for the LCD to notice the
+    /// pins' changes, since with a few of these per byte, anything longer adds up quickly.
+    fn setup_delay(&mut self) {
+        if self.config.setup_ns > 0 {
+            self.stats.record_delay_ns(self.config.setup_ns);
+            self.delay.delay_ns(self.config.setup_ns);
+        }
+    }
+
+    /// Reads a single nibble (into the upper 4 bits), latching the `Enable` pin; the data pins must
+    /// be already switched to inputs.
+    fn read_nibble(&mut self) -> Result<u8> {
+        self.gpio.write(self.en, Level::High);
+
+        // data are available ~360ns after the `enable` pin goes up
+        self.stats.record_delay_ns(self.config.enable_pulse_ns);
+        self.delay.delay_ns(self.config.enable_pulse_ns);
+
+        let mut value = 0;
+
+        for (bit, &pin) in self.pins.data.iter().enumerate() {
+            if self.gpio.read(pin).map_err(Error::Gpio)? == Level::High {
+                value |= 0b0001_0000 << bit;
+            }
+        }
+
+        self.gpio.write(self.en, Level::Low);
+        self.stats.record_delay_us(1);
+        self.delay.delay_us(1);
+
+        Ok(value)
+    }
+
+    /// Drives the backlight's pins according to the current brightness & color.
+    fn refresh_backlight(&mut self) -> UnitResult {
+        let gpio = &self.gpio;
+        let backlight = &mut self.backlight;
+        let brightness = backlight.brightness as u32;
+
+        if let Some(ref mut pin) = backlight.pin {
+            pin.set(gpio, backlight.brightness)?;
+        }
+
+        if let Some(ref mut rgb_pins) = backlight.rgb_pins {
+            for (pin, &level) in rgb_pins.iter_mut().zip(backlight.color.iter()) {
+                pin.set(gpio, (level as u32 * brightness / 255) as u8)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Pins {
+    /// Highest pin number (in the BCM numbering) available on the Raspberry Pi.
+    pub const MAX_PIN: u8 = 53;
+
+    /// Checks whether all the pins exist and none of them is used twice.
+    pub fn validate(&self) -> ::std::result::Result<(), Error> {
+        let mut pins = vec![
+            ("pins.data[0]", self.data[0]),
+            ("pins.data[1]", self.data[1]),
+            ("pins.data[2]", self.data[2]),
+            ("pins.data[3]", self.data[3]),
+            ("pins.rs", self.rs),
+            ("pins.en", self.en),
+        ];
+
+        if let Some(rw) = self.rw {
+            pins.push(("pins.rw", rw));
+        }
+
+        if let Some(backlight) = self.backlight {
+            pins.push(("pins.backlight", backlight));
+        }
+
+        if let Some(rgb) = self.backlight_rgb {
+            pins.push(("pins.backlight_rgb[0]", rgb[0]));
+            pins.push(("pins.backlight_rgb[1]", rgb[1]));
+            pins.push(("pins.backlight_rgb[2]", rgb[2]));
+        }
+
+        if let Some(contrast) = self.contrast {
+            pins.push(("pins.contrast", contrast));
+        }
+
+        for &en in &self.extra_en {
+            pins.push(("pins.extra_en", en));
+        }
+
+        for (idx, &(field, pin)) in pins.iter().enumerate() {
+            if pin > Pins::MAX_PIN {
+                return Err(Error::invalid_config(
+                    field,
+                    format!("pin {} does not exist (pins must be in range <0, {}>)", pin, Pins::MAX_PIN),
+                ));
+            }
+
+            if let Some(&(other_field, _)) = pins[..idx].iter().find(|&&(_, other_pin)| other_pin == pin) {
+                return Err(Error::DuplicatePin {
+                    field,
+                    other_field,
+                    pin,
+                });
+            }
+        }
 
         Ok(())
     }
@@ -83,7 +379,14 @@ impl Gpio4 {
 
 impl Bus for Gpio4 {
     fn initialize(&mut self) -> UnitResult {
-        // initialize the screen
+        // wait for the LCD to power up
+        self.stats.record_delay_us(self.config.power_on_us);
+        self.delay.delay_us(self.config.power_on_us);
+
+        self.resync()
+    }
+
+    fn resync(&mut self) -> UnitResult {
         let commands = vec![
             // try to put LCD in 8-bit mode three times;
             // required for initialization when LCD has not been previously restarted
@@ -95,21 +398,87 @@ impl Bus for Gpio4 {
             0x02,
         ];
 
-        for c in commands {
+        for (step, c) in commands.into_iter().enumerate() {
             self.write_nibble(c << 4, false)?;
-            thread::sleep(time::Duration::new(0, 100 * 1000));
+            self.stats.record_delay_us(self.config.init_step_us(step));
+            self.delay.delay_us(self.config.init_step_us(step));
         }
 
         Ok(())
     }
 
-    fn set_backlight(&mut self, _enabled: bool) -> UnitResult {
-        Ok(()) // @todo
+    fn set_backlight(&mut self, enabled: bool) -> UnitResult {
+        self.set_backlight_brightness(if enabled { 255 } else { 0 })
+    }
+
+    /// Changes the backlight's brightness; does nothing when no backlight pins have been given.
+    fn set_backlight_brightness(&mut self, brightness: u8) -> UnitResult {
+        self.backlight.brightness = brightness;
+        self.refresh_backlight()
+    }
+
+    fn can_dim_backlight(&self) -> bool {
+        self.backlight.pin.is_some() || self.backlight.rgb_pins.is_some()
+    }
+
+    /// Changes the contrast.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ContrastNotSupported` when the contrast's pin has not been given.
+    fn set_contrast(&mut self, level: u8) -> UnitResult {
+        let contrast = self.contrast
+            .as_mut()
+            .ok_or(Error::ContrastNotSupported)?;
+
+        // the lower voltage on `V0`, the higher contrast
+        contrast.set(&self.gpio, 255 - level)
+    }
+
+    fn can_read(&self) -> bool {
+        self.pins.rw.is_some()
+    }
+
+    /// Reads a single byte from the LCD.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ReadingNotSupported` when the `R/W` pin has not been given.
+    fn read_byte(&mut self, as_data: bool) -> Result<u8> {
+        let rw = self.pins.rw.ok_or(Error::ReadingNotSupported)?;
+
+        for &pin in &self.pins.data {
+            self.gpio.set_mode(pin, Mode::Input);
+        }
+
+        self.gpio.write(self.pins.rs, level(as_data));
+        self.gpio.write(rw, Level::High);
+        self.setup_delay();
+
+        let result = self.read_nibble().and_then(|high| {
+            Ok(high | (self.read_nibble()? >> 4))
+        });
+
+        // restore the pins even when reading has failed, so that writing still works
+        self.gpio.write(rw, Level::Low);
+
+        for &pin in &self.pins.data {
+            self.gpio.set_mode(pin, Mode::Output);
+        }
+
+        // reading data moves the address counter, which takes the same time as writing
+        if as_data {
+            self.stats.record_delay_us(self.config.settle_us);
+            self.delay.delay_us(self.config.settle_us);
+        }
+
+        result
     }
 
     fn write_byte(&mut self, value: u8, as_data: bool) -> UnitResult {
-        self.write_nibble(value << 0, as_data)?;
+        self.write_nibble(value, as_data)?;
         self.write_nibble(value << 4, as_data)?;
+        self.stats.record_byte(as_data);
 
         Ok(())
     }
@@ -117,4 +486,53 @@ impl Bus for Gpio4 {
     fn width(&self) -> usize {
         4
     }
-}
\ No newline at end of file
+
+    fn delay_us(&mut self, us: u32) {
+        self.stats.record_delay_us(us);
+        self.delay.delay_us(us);
+    }
+
+    fn timings(&self) -> Timings {
+        self.config
+    }
+
+    fn timing_report(&self) -> TimingReport {
+        self.timings.clone()
+    }
+
+    fn reset_timing_report(&mut self) {
+        self.timings.clear();
+    }
+
+    fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    fn reset_stats(&mut self) {
+        self.stats.clear();
+    }
+
+    fn as_rgb_backlight(&mut self) -> Option<&mut dyn RgbBacklight> {
+        if self.pins.backlight_rgb.is_some() { Some(self) } else { None }
+    }
+}
+
+impl RawNibbleBus for Gpio4 {
+    fn write_raw_nibble(&mut self, nibble: u8, as_data: bool) -> UnitResult {
+        self.write_nibble(nibble << 4, as_data)
+    }
+}
+
+impl RgbBacklight for Gpio4 {
+    /// Changes the RGB backlight's color; does nothing when the RGB backlight's pins have not been
+    /// given.
+    fn set_backlight_color(&mut self, r: u8, g: u8, b: u8) -> UnitResult {
+        self.backlight.color = [r, g, b];
+        self.refresh_backlight()
+    }
+}
+
+/// Returns the level driving a pin high (`true`) or low (`false`).
+fn level(high: bool) -> Level {
+    if high { Level::High } else { Level::Low }
+}
@@ -1,56 +1,286 @@
-/// Defines an interface (a bus) for controlling the HD44780 via the I2C..
-///
-/// Inspired by:
-///     <https://github.com/fdebrabander/Arduino-LiquidCrystal-I2C-library>.
-///
-/// Thanks to:
-///     <https://github.com/rust-embedded/rust-i2cdev> for providing a nice crate allowing to
-///     control the I2C.
-///
-/// # A word on the protocol itself
-///
-/// The PCF8574 family (to which this driver's been written) allows us to send only a nibble
-/// (4 bits) of command or data at once - the rest 4 bits are used as control bits, precisely being:
-///
-/// `U1 U2 U3 U4 | Bl En Rw Rs`
-/// `1  2  3  4  | 5  6  7  8`
-///
-/// `U1..U4` - currently sent part of the command or data
-/// `Bl`     - `backlight` pin (`0` - disabled, `1` - enabled)
-/// `En`     - `enable` pin (as above)
-/// `Rw`     - `read / write` pin (as above)
-/// `Rs`     - `register select` pin (`0` - command, `1` - data)
+//! Defines an interface (a bus) for controlling the HD44780 via the I2C..
+//!
+//! Inspired by:
+//!     <https://github.com/fdebrabander/Arduino-LiquidCrystal-I2C-library>.
+//!
+//! Thanks to:
+//!     <https://github.com/rust-embedded/rust-i2cdev> for providing a nice crate allowing to
+//!     control the I2C.
+//!
+//! # A word on the protocol itself
+//!
+//! The PCF8574 family (to which this driver's been written) allows us to send only a nibble
+//! (4 bits) of command or data at once - the rest 4 bits are used as control bits, precisely being:
+//!
+//! `U1 U2 U3 U4 | Bl En Rw Rs`
+//! `1  2  3  4  | 5  6  7  8`
+//!
+//! `U1..U4` - currently sent part of the command or data
+//! `Bl`     - `backlight` pin (`0` - disabled, `1` - enabled)
+//! `En`     - `enable` pin (as above)
+//! `Rw`     - `read / write` pin (as above)
+//! `Rs`     - `register select` pin (`0` - command, `1` - data)
+//!
+//! # Spare pins
+//!
+//! Some of the backpacks (or hand-made wirings) leave the `Rw` pin (when the LCD's `R/W` is tied
+//! to the ground) or the `Bl` pin (when there's no backlight transistor) unconnected - such pins
+//! can be declared as spare (see `set_spare_pins`) and used eg. for a button or a LED; the bus
+//! keeps their state on every transfer, so they are not affected by the LCD's traffic.
 
+use alloc::boxed::Box;
+use alloc::vec::Vec;
 use i2cdev::core::I2CDevice;
 use i2cdev::linux::LinuxI2CDevice;
-use std::{path, thread, time};
-use super::super::{Bus, Result, UnitResult};
+use std::{path, time};
+use super::super::{Error, Result, UnitResult};
+use super::{Bus, RawNibbleBus};
+use super::i2c_adapter::device_path;
+use super::lock::BusLock;
+use super::stats::Stats;
+use super::timing::{Latency, Operation, TimingReport, Timings};
+use super::super::delay::{Delay, DelayStrategy};
 
 pub struct I2C {
     dev: LinuxI2CDevice,
+
+    /// held for as long as the bus lives (see the `lock` module)
+    _lock: BusLock,
+
     backlight_enabled: bool,
+    timings: TimingReport,
+    stats: Stats,
+    delay: Box<dyn Delay>,
+    config: Timings,
+    latency: Latency,
+
+    /// pins not connected to the LCD and their state
+    spare_pins: u8,
+    spare_state: u8,
 }
 
 impl I2C {
+    /// Lowest non-reserved 7-bit I2C address.
+    pub const MIN_ADDRESS: u16 = 0x08;
+
+    /// Highest non-reserved 7-bit I2C address.
+    pub const MAX_ADDRESS: u16 = 0x77;
+
+    /// The `Rw` pin (`P1`) - see `set_spare_pins`.
+    pub const PIN_RW: u8 = 0b00000010;
+
+    /// The `Bl` pin (`P3`) - see `set_spare_pins`.
+    pub const PIN_BACKLIGHT: u8 = 0b00001000;
+
     /// Constructs a new HD44780 I2C bus.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when given address is not a valid 7-bit I2C address, or
+    /// `Error::BusLocked` when another process is driving the LCD (see the `lock` module).
     pub fn new<P: AsRef<path::Path>>(i2c_device: P, i2c_address: u16) -> Result<I2C> {
+        I2C::new_ex(i2c_device, i2c_address, Timings::default())
+    }
+
+    /// Constructs a new HD44780 I2C bus on the adapter with given index (eg. `1` for
+    /// `/dev/i2c-1`; see `I2CAdapter::list` for the available ones).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when given address is not a valid 7-bit I2C address, or
+    /// `Error::BusLocked` when another process is driving the LCD (see the `lock` module).
+    pub fn on_adapter(adapter: u32, i2c_address: u16) -> Result<I2C> {
+        I2C::new(device_path(adapter), i2c_address)
+    }
+
+    /// Constructs a new HD44780 I2C bus, using given delays instead of the default ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when given address is not a valid 7-bit I2C address, or
+    /// `Error::BusLocked` when another process is driving the LCD (see the `lock` module).
+    pub fn new_ex<P: AsRef<path::Path>>(i2c_device: P, i2c_address: u16, timings: Timings) -> Result<I2C> {
+        if !(I2C::MIN_ADDRESS..=I2C::MAX_ADDRESS).contains(&i2c_address) {
+            return Err(Error::invalid_config(
+                "i2c_address",
+                format!("address {:#04x} is out of range <{:#04x}, {:#04x}>", i2c_address, I2C::MIN_ADDRESS, I2C::MAX_ADDRESS),
+            ).into());
+        }
+
+        let lock = BusLock::i2c(i2c_device.as_ref(), i2c_address, timings.lock_wait())?;
+
         Ok(
             I2C {
                 dev: LinuxI2CDevice::new(i2c_device, i2c_address)?,
+                _lock: lock,
                 backlight_enabled: true,
+                timings: TimingReport::default(),
+                stats: Stats::default(),
+                delay: Box::new(DelayStrategy::Sleep),
+                config: timings,
+                latency: Latency::new(),
+                spare_pins: 0,
+                spare_state: 0,
             }
         )
     }
 
+    /// Declares given expander's pins (a mask of `PIN_RW` and `PIN_BACKLIGHT`) as not connected
+    /// to the LCD, so that they can be used for other purposes (see `write_spare_pins` and
+    /// `read_spare_pins`).
+    ///
+    /// When the `Bl` pin is declared as spare, `set_backlight` does not affect it anymore.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when the mask contains pins that are required to drive the
+    /// LCD (`En`, `Rs` and the data ones).
+    pub fn set_spare_pins(&mut self, pins: u8) -> UnitResult {
+        if pins & !(I2C::PIN_RW | I2C::PIN_BACKLIGHT) != 0 {
+            return Err(Error::invalid_config(
+                "spare_pins",
+                format!("pins {:#010b} are required to drive the LCD", pins & !(I2C::PIN_RW | I2C::PIN_BACKLIGHT)),
+            ).into());
+        }
+
+        self.spare_pins = pins;
+        self.spare_state &= pins;
+
+        self.write_pins()
+    }
+
+    /// Sets the spare pins' state (bits of the other pins are ignored).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// bus.set_spare_pins(I2C::PIN_RW)?;
+    ///
+    /// // turn the LED on
+    /// bus.write_spare_pins(I2C::PIN_RW)?;
+    /// ```
+    pub fn write_spare_pins(&mut self, state: u8) -> UnitResult {
+        self.spare_state = state & self.spare_pins;
+        self.write_pins()
+    }
+
+    /// Reads the spare pins' state (bits of the other pins are zeroed).
+    ///
+    /// PCF8574's pins are quasi-bidirectional - to be used as an input (eg. a button pulling the
+    /// pin down), a pin has to be set high with `write_spare_pins` first.
+    pub fn read_spare_pins(&mut self) -> Result<u8> {
+        Ok(self.dev.smbus_read_byte()? & self.spare_pins)
+    }
+
+    /// Checks whether the backpack is connected, by reading the expander's pins (which does not
+    /// affect the LCD) - returns `false` when the device does not acknowledge its address.
+    pub fn probe(&mut self) -> bool {
+        self.dev.smbus_read_byte().is_ok()
+    }
+
+    /// Changes the way the bus waits for the LCD (see `DelayStrategy`).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // ~10x faster refreshes, at the cost of keeping a CPU core busy while refreshing
+    /// bus.set_delay_strategy(DelayStrategy::Hybrid);
+    /// ```
+    pub fn set_delay_strategy(&mut self, strategy: DelayStrategy) {
+        self.set_delay(strategy);
+    }
+
+    /// Makes the bus wait for the LCD using given delay - eg. a timer-based one, or `NoDelay` in
+    /// the tests (see the `delay` module).
+    pub fn set_delay<D: Delay + 'static>(&mut self, delay: D) {
+        self.delay = Box::new(delay);
+    }
+
+    /// Enables or disables skipping the part of the settle delays covered by the transfers
+    /// themselves (see `Bus::transfer_latency_us`) - enabled by default.
+    pub fn set_delay_elision(&mut self, enabled: bool) {
+        self.latency.set_enabled(enabled);
+    }
+
+    /// Returns the control bits (`Bl`, `Rs` and the spare pins) for given transfer.
+    fn control_mask(&self, as_data: bool) -> u8 {
+        let mut mask = 0u8;
+
+        if self.spare_pins & I2C::PIN_BACKLIGHT == 0 {
+            mask |= I2C::PIN_BACKLIGHT * (self.backlight_enabled as u8);
+        }
+
+        mask |= self.spare_state;
+        mask |= as_data as u8;
+
+        mask
+    }
+
+    /// Appends the I2C bytes latching given byte into the LCD.
+    fn encode(&self, value: u8, as_data: bool, buffer: &mut Vec<u8>) {
+        let mask = self.control_mask(as_data);
+
+        for &nibble in &[value & 0xF0, (value << 4) & 0xF0] {
+            buffer.push(nibble | mask | 0b00000100);
+            buffer.push((nibble | mask) & !0b00000100);
+        }
+    }
+
+    /// Sends given I2C bytes in a single transaction and waits for the LCD to settle.
+    fn transfer(&mut self, buffer: &[u8]) -> UnitResult {
+        let started = time::Instant::now();
+        self.dev.write(buffer)?;
+        self.timings.record_since(Operation::Transfer, started);
+
+        // the next transfer takes a while anyway, so only the rest of the settle time is waited
+        let settle_us = self.latency.remaining_us(self.config.settle_us);
+
+        if settle_us > 0 {
+            let started = time::Instant::now();
+            self.stats.record_delay_us(settle_us);
+            self.delay.delay_us(settle_us);
+            self.timings.record_since(Operation::Settle, started);
+        }
+
+        Ok(())
+    }
+
+    /// Updates the backlight & spare pins, without latching anything into the LCD.
+    fn write_pins(&mut self) -> UnitResult {
+        let started = time::Instant::now();
+        self.dev.smbus_write_byte(self.control_mask(false))?;
+        self.timings.record_since(Operation::Transfer, started);
+
+        Ok(())
+    }
+
     /// Sends a single nibble, latching the `Enable` pin.
     fn write_nibble(&mut self, value: u8) -> UnitResult {
         // write value, pull up the `enable` pin & wait ~450ns (enable pulse must be >450ns)
+        let started = time::Instant::now();
         self.dev.smbus_write_byte(value | 0b00000100)?;
-        thread::sleep(time::Duration::new(0, 450));
+        self.timings.record_since(Operation::Transfer, started);
+
+        let started = time::Instant::now();
+        self.stats.record_delay_ns(self.config.enable_pulse_ns);
+        self.delay.delay_ns(self.config.enable_pulse_ns);
+        self.timings.record_since(Operation::EnablePulse, started);
 
         // write value again, this time pulling the `Enable` pin down & wait ~37us (commands need 37us to settle)
+        let started = time::Instant::now();
         self.dev.smbus_write_byte(value & !0b00000100)?;
-        thread::sleep(time::Duration::new(0, 37 * 1000));
+        self.latency.record(started.elapsed());
+        self.timings.record_since(Operation::Transfer, started);
+
+        // the next transfer takes a while anyway, so only the rest of the settle time is waited
+        let settle_us = self.latency.remaining_us(self.config.settle_us);
+
+        if settle_us > 0 {
+            let started = time::Instant::now();
+            self.stats.record_delay_us(settle_us);
+            self.delay.delay_us(settle_us);
+            self.timings.record_since(Operation::Settle, started);
+        }
 
         Ok(())
     }
@@ -58,6 +288,14 @@ impl I2C {
 
 impl Bus for I2C {
     fn initialize(&mut self) -> UnitResult {
+        // wait for the LCD to power up
+        self.stats.record_delay_us(self.config.power_on_us);
+        self.delay.delay_us(self.config.power_on_us);
+
+        self.resync()
+    }
+
+    fn resync(&mut self) -> UnitResult {
         let commands = vec![
             // try to put LCD in 8-bit mode three times;
             // required for initialization when LCD has not been previously restarted
@@ -69,9 +307,10 @@ impl Bus for I2C {
             0x02,
         ];
 
-        for c in commands {
+        for (step, c) in commands.into_iter().enumerate() {
             self.write_nibble(c << 4)?;
-            thread::sleep(time::Duration::new(0, 100 * 1000));
+            self.stats.record_delay_us(self.config.init_step_us(step));
+            self.delay.delay_us(self.config.init_step_us(step));
         }
 
         Ok(())
@@ -79,19 +318,55 @@ impl Bus for I2C {
 
     fn set_backlight(&mut self, enabled: bool) -> UnitResult {
         self.backlight_enabled = enabled;
-
-        // write a dummy byte to update the backlight state
-        self.write_byte(0, false)
+        self.write_pins()
     }
 
     fn write_byte(&mut self, value: u8, as_data: bool) -> UnitResult {
-        let mut mask = 0u8;
-
-        mask |= 0b00001000 * (self.backlight_enabled as u8);
-        mask |= 0b00000001 * (as_data as u8);
+        let mask = self.control_mask(as_data);
 
-        self.write_nibble((value << 0) & 0xF0 | mask)?;
+        self.write_nibble(value & 0xF0 | mask)?;
         self.write_nibble((value << 4) & 0xF0 | mask)?;
+        self.stats.record_byte(as_data);
+
+        Ok(())
+    }
+
+    /// Sends all the bytes in a single I2C transaction.
+    ///
+    /// No delays are needed in between the nibbles, since the transfers themselves take longer
+    /// than the LCD needs (PCF8574 works at 100kHz, so transferring a single byte takes ~90us).
+    fn write_bytes(&mut self, bytes: &[(u8, bool)]) -> UnitResult {
+        let mut buffer = Vec::with_capacity(bytes.len() * 4);
+
+        for &(value, as_data) in bytes {
+            self.encode(value, as_data, &mut buffer);
+        }
+
+        self.transfer(&buffer)?;
+
+        for &(_, as_data) in bytes {
+            self.stats.record_byte(as_data);
+        }
+
+        Ok(())
+    }
+
+    /// Sends the whole run in a single I2C transaction, encoding the byte just once.
+    fn write_data_repeated(&mut self, value: u8, count: usize) -> UnitResult {
+        let mut encoded = Vec::with_capacity(4);
+        self.encode(value, true, &mut encoded);
+
+        let mut buffer = Vec::with_capacity(count * 4);
+
+        for _ in 0..count {
+            buffer.extend_from_slice(&encoded);
+        }
+
+        self.transfer(&buffer)?;
+
+        for _ in 0..count {
+            self.stats.record_byte(true);
+        }
 
         Ok(())
     }
@@ -99,4 +374,50 @@ impl Bus for I2C {
     fn width(&self) -> usize {
         4
     }
-}
\ No newline at end of file
+
+    fn transfer_latency_us(&self) -> u32 {
+        self.latency.us()
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        self.stats.record_delay_us(us);
+        self.delay.delay_us(us);
+    }
+
+    fn timings(&self) -> Timings {
+        self.config
+    }
+
+    fn timing_report(&self) -> TimingReport {
+        self.timings.clone()
+    }
+
+    fn reset_timing_report(&mut self) {
+        self.timings.clear();
+    }
+
+    fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    fn reset_stats(&mut self) {
+        self.stats.clear();
+    }
+
+    fn probe(&mut self) -> Option<bool> {
+        Some(I2C::probe(self))
+    }
+}
+
+impl RawNibbleBus for I2C {
+    fn write_raw_nibble(&mut self, nibble: u8, as_data: bool) -> UnitResult {
+        let mask = self.control_mask(as_data);
+
+        self.write_nibble((nibble << 4) | mask)
+    }
+
+    fn write_raw_nibble_ex(&mut self, nibble: u8, as_data: bool, backlight: bool) -> UnitResult {
+        self.backlight_enabled = backlight;
+        self.write_raw_nibble(nibble, as_data)
+    }
+}
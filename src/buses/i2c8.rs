@@ -0,0 +1,305 @@
+//! Defines an interface (a bus) for controlling the HD44780 via the I2C, using a PCF8575-based
+//! (16-bit) backpack.
+//!
+//! # A word on the protocol itself
+//!
+//! Unlike the PCF8574 (see the `i2c` module), PCF8575 has enough pins to drive the LCD in the
+//! 8-bit mode - each command or data is sent at once, instead of being split into two nibbles,
+//! which halves the number of transfers.
+//!
+//! Each transfer consists of two bytes - the first one drives the `P0..P7` pins, the second one -
+//! the `P10..P17` ones:
+//!
+//! `D0 D1 D2 D3 D4 D5 D6 D7 | Rs Rw En Bl -  -  -  -`
+//! `P0 P1 P2 P3 P4 P5 P6 P7 | P10 P11 P12 P13 ...`
+//!
+//! `D0..D7` - currently sent command or data
+//! `Rs`     - `register select` pin (`0` - command, `1` - data)
+//! `Rw`     - `read / write` pin (always `0`)
+//! `En`     - `enable` pin
+//! `Bl`     - `backlight` pin (`0` - disabled, `1` - enabled)
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use i2cdev::core::I2CDevice;
+use i2cdev::linux::LinuxI2CDevice;
+use std::{path, time};
+use super::super::{Error, Result, UnitResult};
+use super::Bus;
+use super::lock::BusLock;
+use super::stats::Stats;
+use super::timing::{Latency, Operation, TimingReport, Timings};
+use super::super::delay::{Delay, DelayStrategy};
+
+pub struct I2C8 {
+    dev: LinuxI2CDevice,
+
+    /// held for as long as the bus lives (see the `lock` module)
+    _lock: BusLock,
+
+    backlight_enabled: bool,
+    timings: TimingReport,
+    stats: Stats,
+    delay: Box<dyn Delay>,
+    config: Timings,
+    latency: Latency,
+}
+
+/// Bits of the control byte (the one driving the `P10..P17` pins).
+const RS: u8 = 0b00000001;
+const EN: u8 = 0b00000100;
+const BL: u8 = 0b00001000;
+
+impl I2C8 {
+    /// Lowest 7-bit I2C address of the PCF8575.
+    pub const MIN_ADDRESS: u16 = 0x20;
+
+    /// Highest 7-bit I2C address of the PCF8575.
+    pub const MAX_ADDRESS: u16 = 0x27;
+
+    /// Constructs a new HD44780 I2C bus for the PCF8575-based backpacks.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when given address is not a valid PCF8575's address, or
+    /// `Error::BusLocked` when another process is driving the LCD (see the `lock` module).
+    pub fn new<P: AsRef<path::Path>>(i2c_device: P, i2c_address: u16) -> Result<I2C8> {
+        I2C8::new_ex(i2c_device, i2c_address, Timings::default())
+    }
+
+    /// Constructs a new HD44780 I2C bus for the PCF8575-based backpacks, using given delays
+    /// instead of the default ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when given address is not a valid PCF8575's address, or
+    /// `Error::BusLocked` when another process is driving the LCD (see the `lock` module).
+    pub fn new_ex<P: AsRef<path::Path>>(i2c_device: P, i2c_address: u16, timings: Timings) -> Result<I2C8> {
+        if !(I2C8::MIN_ADDRESS..=I2C8::MAX_ADDRESS).contains(&i2c_address) {
+            return Err(Error::invalid_config(
+                "i2c_address",
+                format!("address {:#04x} is out of range <{:#04x}, {:#04x}>", i2c_address, I2C8::MIN_ADDRESS, I2C8::MAX_ADDRESS),
+            ).into());
+        }
+
+        let lock = BusLock::i2c(i2c_device.as_ref(), i2c_address, timings.lock_wait())?;
+
+        Ok(
+            I2C8 {
+                dev: LinuxI2CDevice::new(i2c_device, i2c_address)?,
+                _lock: lock,
+                backlight_enabled: true,
+                timings: TimingReport::default(),
+                stats: Stats::default(),
+                delay: Box::new(DelayStrategy::Sleep),
+                config: timings,
+                latency: Latency::new(),
+            }
+        )
+    }
+
+    /// Checks whether the backpack is connected, by reading the expander's pins (which does not
+    /// affect the LCD) - returns `false` when the device does not acknowledge its address.
+    pub fn probe(&mut self) -> bool {
+        self.dev.read(&mut [0; 2]).is_ok()
+    }
+
+    /// Changes the way the bus waits for the LCD (see `DelayStrategy`).
+    pub fn set_delay_strategy(&mut self, strategy: DelayStrategy) {
+        self.set_delay(strategy);
+    }
+
+    /// Makes the bus wait for the LCD using given delay - eg. a timer-based one, or `NoDelay` in
+    /// the tests (see the `delay` module).
+    pub fn set_delay<D: Delay + 'static>(&mut self, delay: D) {
+        self.delay = Box::new(delay);
+    }
+
+    /// Enables or disables skipping the part of the settle delays covered by the transfers
+    /// themselves (see `Bus::transfer_latency_us`) - enabled by default.
+    pub fn set_delay_elision(&mut self, enabled: bool) {
+        self.latency.set_enabled(enabled);
+    }
+
+    /// Returns the control byte (`Bl` and `Rs`) for given transfer.
+    fn control_mask(&self, as_data: bool) -> u8 {
+        let mut mask = 0u8;
+
+        mask |= BL * (self.backlight_enabled as u8);
+        mask |= RS * (as_data as u8);
+
+        mask
+    }
+
+    /// Appends the I2C bytes latching given byte into the LCD.
+    fn encode(&self, value: u8, as_data: bool, buffer: &mut Vec<u8>) {
+        let mask = self.control_mask(as_data);
+
+        buffer.extend_from_slice(&[value, mask | EN, value, mask]);
+    }
+
+    /// Sends given I2C bytes in a single transaction and waits for the LCD to settle.
+    fn transfer(&mut self, buffer: &[u8]) -> UnitResult {
+        let started = time::Instant::now();
+        self.dev.write(buffer)?;
+        self.timings.record_since(Operation::Transfer, started);
+
+        // the next transfer takes a while anyway, so only the rest of the settle time is waited
+        let settle_us = self.latency.remaining_us(self.config.settle_us);
+
+        if settle_us > 0 {
+            let started = time::Instant::now();
+            self.stats.record_delay_us(settle_us);
+            self.delay.delay_us(settle_us);
+            self.timings.record_since(Operation::Settle, started);
+        }
+
+        Ok(())
+    }
+
+    /// Sends a single byte, latching the `Enable` pin.
+    fn write_latched(&mut self, value: u8, as_data: bool) -> UnitResult {
+        let mask = self.control_mask(as_data);
+
+        // write value, pull up the `enable` pin & wait ~450ns (enable pulse must be >450ns)
+        let started = time::Instant::now();
+        self.dev.write(&[value, mask | EN])?;
+        self.timings.record_since(Operation::Transfer, started);
+
+        let started = time::Instant::now();
+        self.stats.record_delay_ns(self.config.enable_pulse_ns);
+        self.delay.delay_ns(self.config.enable_pulse_ns);
+        self.timings.record_since(Operation::EnablePulse, started);
+
+        // write value again, this time pulling the `Enable` pin down & wait ~37us (commands need 37us to settle)
+        let started = time::Instant::now();
+        self.dev.write(&[value, mask])?;
+        self.latency.record(started.elapsed());
+        self.timings.record_since(Operation::Transfer, started);
+
+        // the next transfer takes a while anyway, so only the rest of the settle time is waited
+        let settle_us = self.latency.remaining_us(self.config.settle_us);
+
+        if settle_us > 0 {
+            let started = time::Instant::now();
+            self.stats.record_delay_us(settle_us);
+            self.delay.delay_us(settle_us);
+            self.timings.record_since(Operation::Settle, started);
+        }
+
+        Ok(())
+    }
+}
+
+impl Bus for I2C8 {
+    fn initialize(&mut self) -> UnitResult {
+        // wait for the LCD to power up
+        self.stats.record_delay_us(self.config.power_on_us);
+        self.delay.delay_us(self.config.power_on_us);
+
+        self.resync()
+    }
+
+    fn resync(&mut self) -> UnitResult {
+        // put LCD in 8-bit mode three times;
+        // required for initialization when LCD has not been previously restarted
+        for step in 0..3 {
+            self.write_latched(0x30, false)?;
+            self.stats.record_delay_us(self.config.init_step_us(step));
+            self.delay.delay_us(self.config.init_step_us(step));
+        }
+
+        Ok(())
+    }
+
+    fn set_backlight(&mut self, enabled: bool) -> UnitResult {
+        self.backlight_enabled = enabled;
+
+        let started = time::Instant::now();
+        self.dev.write(&[0, self.control_mask(false)])?;
+        self.timings.record_since(Operation::Transfer, started);
+
+        Ok(())
+    }
+
+    fn write_byte(&mut self, value: u8, as_data: bool) -> UnitResult {
+        self.write_latched(value, as_data)?;
+        self.stats.record_byte(as_data);
+
+        Ok(())
+    }
+
+    /// Sends all the bytes in a single I2C transaction (see `I2C::write_bytes`).
+    fn write_bytes(&mut self, bytes: &[(u8, bool)]) -> UnitResult {
+        let mut buffer = Vec::with_capacity(bytes.len() * 4);
+
+        for &(value, as_data) in bytes {
+            self.encode(value, as_data, &mut buffer);
+        }
+
+        self.transfer(&buffer)?;
+
+        for &(_, as_data) in bytes {
+            self.stats.record_byte(as_data);
+        }
+
+        Ok(())
+    }
+
+    /// Sends the whole run in a single I2C transaction, encoding the byte just once.
+    fn write_data_repeated(&mut self, value: u8, count: usize) -> UnitResult {
+        let mut encoded = Vec::with_capacity(4);
+        self.encode(value, true, &mut encoded);
+
+        let mut buffer = Vec::with_capacity(count * 4);
+
+        for _ in 0..count {
+            buffer.extend_from_slice(&encoded);
+        }
+
+        self.transfer(&buffer)?;
+
+        for _ in 0..count {
+            self.stats.record_byte(true);
+        }
+
+        Ok(())
+    }
+
+    fn width(&self) -> usize {
+        8
+    }
+
+    fn transfer_latency_us(&self) -> u32 {
+        self.latency.us()
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        self.stats.record_delay_us(us);
+        self.delay.delay_us(us);
+    }
+
+    fn timings(&self) -> Timings {
+        self.config
+    }
+
+    fn timing_report(&self) -> TimingReport {
+        self.timings.clone()
+    }
+
+    fn reset_timing_report(&mut self) {
+        self.timings.clear();
+    }
+
+    fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    fn reset_stats(&mut self) {
+        self.stats.clear();
+    }
+
+    fn probe(&mut self) -> Option<bool> {
+        Some(I2C8::probe(self))
+    }
+}
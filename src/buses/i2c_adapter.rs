@@ -0,0 +1,144 @@
+//! Finds the I2C buses (adapters) available in the system and tells their clock speed - so that
+//! setting an LCD up on a board with many buses (eg. the Raspberry Pi 5 or a Compute Module's
+//! carrier board) does not come down to trying `/dev/i2c-0`, `/dev/i2c-1`... one after another.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! for adapter in I2CAdapter::list()? {
+//!     // eg. "/dev/i2c-1: bcm2835 (i2c@7e804000), 100000 Hz"
+//!     println!(
+//!         "{}: {}, {} Hz",
+//!         adapter.path().display(),
+//!         adapter.name().unwrap_or("unknown"),
+//!         adapter.speed_hz().map_or("?".into(), |hz| hz.to_string()),
+//!     );
+//! }
+//!
+//! let bus = I2CBus::on_adapter(1, 0x27)?;
+//! ```
+//!
+//! # A word on the clock speed
+//!
+//! Linux does not allow to change the I2C bus' speed from the userspace - it's a property of the
+//! adapter, configured in the device tree (on the Raspberry Pi: `dtparam=i2c_arm_baudrate=400000`
+//! in `config.txt`). The only exception are the adapters whose driver exposes the speed as a
+//! writable module parameter (eg. the legacy `i2c_bcm2708` one) - see `I2CAdapter::set_speed_hz`.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use std::fs;
+use std::path::{Path, PathBuf};
+use super::super::{Result, UnitResult};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct I2CAdapter {
+    index: u32,
+    name: Option<String>,
+}
+
+/// Module parameters of the drivers allowing to change the bus' speed at runtime.
+const SPEED_PARAMETERS: [&str; 1] = [
+    "/sys/module/i2c_bcm2708/parameters/baudrate",
+];
+
+impl I2CAdapter {
+    /// Returns the adapter with given index (eg. `1` for `/dev/i2c-1`), without checking whether
+    /// it exists.
+    pub fn new(index: u32) -> I2CAdapter {
+        I2CAdapter {
+            index,
+            name: fs::read_to_string(sysfs_path(index).join("name"))
+                .ok()
+                .map(|name| name.trim().to_string()),
+        }
+    }
+
+    /// Returns all the adapters available in the system (all the `/dev/i2c-*` devices), sorted
+    /// by their indices.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when `/dev` cannot be read.
+    pub fn list() -> Result<Vec<I2CAdapter>> {
+        let mut indices: Vec<u32> = fs::read_dir("/dev")?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                entry
+                    .file_name()
+                    .to_str()
+                    .and_then(|name| name.strip_prefix("i2c-"))
+                    .and_then(|index| index.parse().ok())
+            })
+            .collect();
+
+        indices.sort_unstable();
+
+        Ok(indices.into_iter().map(I2CAdapter::new).collect())
+    }
+
+    /// Returns the adapter's index.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Returns path to the adapter's device (eg. `/dev/i2c-1`) - to be passed to the buses'
+    /// constructors.
+    pub fn path(&self) -> PathBuf {
+        device_path(self.index)
+    }
+
+    /// Returns the adapter's name, as reported by its driver (eg. `bcm2835 (i2c@7e804000)`).
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the adapter's clock speed, or `None` when the driver does not tell it.
+    pub fn speed_hz(&self) -> Option<u32> {
+        // the device tree's property - a big-endian 32-bit number
+        if let Ok(bytes) = fs::read(sysfs_path(self.index).join("of_node/clock-frequency")) {
+            if let [a, b, c, d] = bytes[..] {
+                return Some(u32::from_be_bytes([a, b, c, d]));
+            }
+        }
+
+        SPEED_PARAMETERS
+            .iter()
+            .find_map(|parameter| fs::read_to_string(parameter).ok())
+            .and_then(|speed| speed.trim().parse().ok())
+    }
+
+    /// Changes the adapter's clock speed (eg. to `400_000` for the fast mode, which makes the
+    /// renders ~4x faster - as long as the backpack and the wiring keep up).
+    ///
+    /// Requires the root privileges and takes effect once the adapter is opened anew.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the adapter's driver does not allow to change the speed at runtime
+    /// (which is the case for almost all of them - see the module's documentation).
+    pub fn set_speed_hz(&self, hz: u32) -> UnitResult {
+        for parameter in &SPEED_PARAMETERS {
+            if Path::new(parameter).exists() {
+                fs::write(parameter, hz.to_string())
+                    .map_err(|err| format!("Cannot change the speed of {}: {}", self.path().display(), err))?;
+
+                return Ok(());
+            }
+        }
+
+        Err(format!(
+            "Cannot change the speed of {} at runtime - configure it in the device tree instead (eg. `dtparam=i2c_arm_baudrate={}` in the Raspberry Pi's `config.txt`).",
+            self.path().display(), hz,
+        ).into())
+    }
+}
+
+/// Returns path to the device of the adapter with given index.
+pub(crate) fn device_path(index: u32) -> PathBuf {
+    PathBuf::from(format!("/dev/i2c-{}", index))
+}
+
+fn sysfs_path(index: u32) -> PathBuf {
+    PathBuf::from(format!("/sys/class/i2c-dev/i2c-{}/device", index))
+}
@@ -0,0 +1,140 @@
+//! Keeps two processes from driving the same LCD at once.
+//!
+//! Nothing stops two processes from opening the same `/dev/i2c-X` device (or the same GPIO pins) -
+//! and when they do, their transfers interleave, the LCD takes one process' nibble for the other
+//! half of the other process' byte and ends up showing garbage (or stuck in a wrong mode, until
+//! it gets reinitialized).
+//!
+//! So the buses' constructors take an advisory lock (`flock`) on a file named after the LCD they
+//! drive (eg. `/run/lock/pwr-hd44780-i2c-1-0x27.lock`) and hold it until the bus gets dropped -
+//! a second process trying to open the same LCD fails with `Error::BusLocked` right away (or
+//! after waiting for the lock a bit - see `Timings::lock_wait_ms`), instead of corrupting it.
+//!
+//! The lock is released by the kernel when the process dies, so a crash does not leave the LCD
+//! locked; the lock files themselves are never removed (removing them would race with the other
+//! processes trying to lock them).
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! match I2CBus::new("/dev/i2c-1", 0x27) {
+//!     Err(err) if matches!(err.downcast_ref(), Some(Error::BusLocked { .. })) => {
+//!         eprintln!("{}", err); // eg. "The LCD is already being driven by process 1234 (...)"
+//!     }
+//!
+//!     // ...
+//! }
+//! ```
+
+use alloc::string::{String, ToString};
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Read, Seek, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::{process, thread};
+use std::time::{Duration, Instant};
+use super::super::{Error, Result};
+
+/// How often a busy lock is retried, while waiting for it.
+const RETRY_EVERY: Duration = Duration::from_millis(10);
+
+/// An advisory lock on an LCD, held until dropped (see the module's documentation).
+#[derive(Debug)]
+pub struct BusLock {
+    /// the locked file (closing it releases the lock)
+    _file: File,
+    path: PathBuf,
+}
+
+impl BusLock {
+    /// Locks the LCD with given name (eg. `i2c-1-0x27`), waiting up to `wait` for another
+    /// process to release it.
+    ///
+    /// Custom buses can use it to play along with the built-in ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::BusLocked` when the LCD has not been released in time, or an I/O error when
+    /// the lock file could not be opened.
+    pub fn acquire(name: &str, wait: Duration) -> Result<BusLock> {
+        let path = lock_dir().join(format!("pwr-hd44780-{}.lock", name));
+        let mut file = open(&path)?;
+        let started = Instant::now();
+
+        loop {
+            match file.try_lock() {
+                Ok(()) => break,
+
+                Err(fs::TryLockError::WouldBlock) if started.elapsed() < wait => {
+                    thread::sleep(RETRY_EVERY);
+                }
+
+                Err(fs::TryLockError::WouldBlock) => {
+                    let mut pid = String::new();
+
+                    // the lock file might be read-only for us, so the process' id is just a hint
+                    let _ = file.read_to_string(&mut pid);
+
+                    return Err(Error::BusLocked {
+                        lock: path.display().to_string(),
+                        pid: pid.trim().parse().ok(),
+                    }.into());
+                }
+
+                Err(fs::TryLockError::Error(err)) => {
+                    return Err(format!("Cannot lock {}: {}", path.display(), err).into());
+                }
+            }
+        }
+
+        // (see above)
+        let _ = file.set_len(0)
+            .and_then(|_| file.rewind())
+            .and_then(|_| write!(file, "{}", process::id()));
+
+        Ok(BusLock { _file: file, path })
+    }
+
+    /// Locks the LCD at given address of given I2C device (see `acquire`).
+    pub(crate) fn i2c(i2c_device: &Path, i2c_address: u16, wait: Duration) -> Result<BusLock> {
+        let device = i2c_device
+            .file_name()
+            .map_or("i2c".into(), |name| name.to_string_lossy());
+
+        BusLock::acquire(&format!("{}-{:#04x}", device, i2c_address), wait)
+    }
+
+    /// Returns path to the lock file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Returns directory the lock files are kept in - `/run/lock` (a tmpfs, writable by everyone on
+/// most distributions) when available, the temporary one otherwise.
+fn lock_dir() -> PathBuf {
+    let run_lock = Path::new("/run/lock");
+
+    if run_lock.is_dir() {
+        run_lock.to_path_buf()
+    } else {
+        std::env::temp_dir()
+    }
+}
+
+/// Opens (or creates) given lock file - read-only when it belongs to another user and we cannot
+/// write it, which is enough to lock it.
+fn open(path: &Path) -> Result<File> {
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .mode(0o666)
+        .open(path);
+
+    match file {
+        Err(err) if err.kind() == ErrorKind::PermissionDenied => File::open(path),
+        file => file,
+    }.map_err(|err| format!("Cannot open {}: {}", path.display(), err).into())
+}
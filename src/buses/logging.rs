@@ -0,0 +1,302 @@
+//! Provides a bus decorator recording every byte sent to (or read from) the LCD - for debugging
+//! the initialization sequences and timing issues of the HD44780 clones without reaching for a
+//! logic analyzer.
+//!
+//! The log is kept behind a shared handle (see `TransferLog`), so it stays accessible after the
+//! bus has been moved into a frontend.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let bus = LoggingBus::printing(I2CBus::new("/dev/i2c-1", 0x27)?);
+//! let log = bus.log();
+//!
+//! let mut lcd = DirectLcd::new(Box::new(bus), 20, 4)?;
+//!
+//! lcd.print("Hello World!")?;
+//!
+//! // prints eg. `  0.000412s  W  RS=1  0x48`
+//! print!("{}", log);
+//! ```
+//!
+//! # Caveats
+//!
+//! 1. Only what goes through the `Bus` trait gets recorded - the nibbles sent by the decorated
+//!    bus' own `initialize` (eg. the "8-bit mode" handshake) are not visible.
+//!
+//! 2. The log grows with each transfer, so it should be cleared from time to time (see
+//!    `TransferLog::clear`) when left enabled for long.
+
+use alloc::vec::Vec;
+use core::{fmt, time};
+use std::sync::{Arc, Mutex, MutexGuard};
+use super::super::{Result, UnitResult};
+use super::{Bus, RawNibbleBus, RgbBacklight, Stats, TimingReport, Timings};
+
+pub struct LoggingBus<B> {
+    bus: B,
+    log: TransferLog,
+    started: ::std::time::Instant,
+
+    /// whether the entries get printed onto stderr too
+    printing: bool,
+}
+
+/// A shared handle to the entries recorded by a `LoggingBus`.
+#[derive(Clone, Default)]
+pub struct TransferLog {
+    entries: Arc<Mutex<Vec<Entry>>>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    /// time elapsed since the bus' creation
+    pub at: time::Duration,
+
+    pub event: Event,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// The bus has been initialized.
+    Initialize,
+
+    /// A byte has been sent (`as_data` is the state of the `RS` pin).
+    Write { value: u8, as_data: bool },
+
+    /// A nibble has been sent (see `RawNibbleBus`).
+    WriteNibble { nibble: u8, as_data: bool },
+
+    /// A byte has been read.
+    Read { value: u8, as_data: bool },
+
+    /// The backlight has been turned on / off.
+    Backlight(bool),
+
+    /// The driver has waited given number of microseconds.
+    Delay(u32),
+}
+
+impl<B: Bus> LoggingBus<B> {
+    /// Decorates given bus, recording its traffic.
+    pub fn new(bus: B) -> LoggingBus<B> {
+        LoggingBus {
+            bus,
+            log: TransferLog::default(),
+            started: ::std::time::Instant::now(),
+            printing: false,
+        }
+    }
+
+    /// Decorates given bus, recording its traffic and printing each entry onto stderr as soon as
+    /// it happens.
+    pub fn printing(bus: B) -> LoggingBus<B> {
+        LoggingBus {
+            printing: true,
+            ..LoggingBus::new(bus)
+        }
+    }
+
+    /// Returns a handle to the recorded entries.
+    pub fn log(&self) -> TransferLog {
+        self.log.clone()
+    }
+
+    /// Returns the decorated bus.
+    pub fn inner(&mut self) -> &mut B {
+        &mut self.bus
+    }
+
+    /// Returns the decorated bus.
+    pub fn into_inner(self) -> B {
+        self.bus
+    }
+
+    fn record(&self, event: Event) {
+        let entry = Entry {
+            at: self.started.elapsed(),
+            event,
+        };
+
+        if self.printing {
+            ::std::eprintln!("{}", entry);
+        }
+
+        self.log.lock().push(entry);
+    }
+}
+
+impl TransferLog {
+    /// Returns all the entries recorded so far.
+    pub fn entries(&self) -> Vec<Entry> {
+        self.lock().clone()
+    }
+
+    /// Returns the bytes written so far (along with the state of the `RS` pin), skipping all the
+    /// other entries.
+    pub fn writes(&self) -> Vec<(u8, bool)> {
+        self.lock()
+            .iter()
+            .filter_map(|entry| match entry.event {
+                Event::Write { value, as_data } => Some((value, as_data)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Forgets all the entries recorded so far.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Locks the entries, recovering them after a panic in some other thread.
+    fn lock(&self) -> MutexGuard<'_, Vec<Entry>> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl fmt::Display for TransferLog {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for entry in self.lock().iter() {
+            writeln!(f, "{}", entry)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:>4}.{:06}s  ", self.at.as_secs(), self.at.subsec_micros())?;
+
+        match self.event {
+            Event::Initialize => write!(f, "initialize"),
+            Event::Write { value, as_data } => write!(f, "W  RS={}  {:#04x}", as_data as u8, value),
+            Event::WriteNibble { nibble, as_data } => write!(f, "N  RS={}  {:#03x}", as_data as u8, nibble),
+            Event::Read { value, as_data } => write!(f, "R  RS={}  {:#04x}", as_data as u8, value),
+            Event::Backlight(enabled) => write!(f, "backlight {}", if enabled { "on" } else { "off" }),
+            Event::Delay(us) => write!(f, "delay {}us", us),
+        }
+    }
+}
+
+impl<B: Bus> Bus for LoggingBus<B> {
+    fn initialize(&mut self) -> UnitResult {
+        self.record(Event::Initialize);
+        self.bus.initialize()
+    }
+
+    fn initialize_warm(&mut self) -> UnitResult {
+        self.bus.initialize_warm()
+    }
+
+    fn resync(&mut self) -> UnitResult {
+        self.bus.resync()
+    }
+
+    fn set_backlight(&mut self, enabled: bool) -> UnitResult {
+        self.record(Event::Backlight(enabled));
+        self.bus.set_backlight(enabled)
+    }
+
+    fn set_backlight_brightness(&mut self, brightness: u8) -> UnitResult {
+        self.record(Event::Backlight(brightness > 0));
+        self.bus.set_backlight_brightness(brightness)
+    }
+
+    fn can_dim_backlight(&self) -> bool {
+        self.bus.can_dim_backlight()
+    }
+
+    fn transfer_latency_us(&self) -> u32 {
+        self.bus.transfer_latency_us()
+    }
+
+    fn set_contrast(&mut self, level: u8) -> UnitResult {
+        self.bus.set_contrast(level)
+    }
+
+    fn can_read(&self) -> bool {
+        self.bus.can_read()
+    }
+
+    fn read_byte(&mut self, as_data: bool) -> Result<u8> {
+        let value = self.bus.read_byte(as_data)?;
+        self.record(Event::Read { value, as_data });
+
+        Ok(value)
+    }
+
+    fn write_byte(&mut self, value: u8, as_data: bool) -> UnitResult {
+        self.record(Event::Write { value, as_data });
+        self.bus.write_byte(value, as_data)
+    }
+
+    fn write_bytes(&mut self, bytes: &[(u8, bool)]) -> UnitResult {
+        for &(value, as_data) in bytes {
+            self.record(Event::Write { value, as_data });
+        }
+
+        self.bus.write_bytes(bytes)
+    }
+
+    fn write_data_repeated(&mut self, value: u8, count: usize) -> UnitResult {
+        for _ in 0..count {
+            self.record(Event::Write { value, as_data: true });
+        }
+
+        self.bus.write_data_repeated(value, count)
+    }
+
+    fn width(&self) -> usize {
+        self.bus.width()
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        self.record(Event::Delay(us));
+        self.bus.delay_us(us);
+    }
+
+    fn timings(&self) -> Timings {
+        self.bus.timings()
+    }
+
+    fn timing_report(&self) -> TimingReport {
+        self.bus.timing_report()
+    }
+
+    fn reset_timing_report(&mut self) {
+        self.bus.reset_timing_report();
+    }
+
+    fn stats(&self) -> Stats {
+        self.bus.stats()
+    }
+
+    fn reset_stats(&mut self) {
+        self.bus.reset_stats();
+    }
+
+    fn probe(&mut self) -> Option<bool> {
+        self.bus.probe()
+    }
+
+    fn as_rgb_backlight(&mut self) -> Option<&mut dyn RgbBacklight> {
+        self.bus.as_rgb_backlight()
+    }
+}
+
+impl<B: RawNibbleBus> RawNibbleBus for LoggingBus<B> {
+    fn write_raw_nibble(&mut self, nibble: u8, as_data: bool) -> UnitResult {
+        self.record(Event::WriteNibble { nibble, as_data });
+        self.bus.write_raw_nibble(nibble, as_data)
+    }
+
+    fn write_raw_nibble_ex(&mut self, nibble: u8, as_data: bool, backlight: bool) -> UnitResult {
+        self.record(Event::Backlight(backlight));
+        self.record(Event::WriteNibble { nibble, as_data });
+        self.bus.write_raw_nibble_ex(nibble, as_data, backlight)
+    }
+}
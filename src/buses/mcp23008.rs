@@ -0,0 +1,352 @@
+//! Defines an interface (a bus) for controlling the HD44780 via the I2C, using an MCP23008-based
+//! backpack (eg. the Adafruit's "I2C / SPI character LCD backpack").
+//!
+//! # A word on the protocol itself
+//!
+//! Unlike the PCF8574 (see the `i2c` module), MCP23008 is register-based - the pins are set by
+//! writing into its `GPIO` register (after configuring them as outputs in the `IODIR` one). The
+//! LCD is driven in the 4-bit mode, with the pins connected as following:
+//!
+//! `Bl D7 D6 D5 D4 En Rs -`
+//! `7  6  5  4  3  2  1  0`
+//!
+//! `D4..D7` - currently sent part of the command or data
+//! `En`     - `enable` pin
+//! `Rs`     - `register select` pin (`0` - command, `1` - data)
+//! `Bl`     - `backlight` pin (`0` - disabled, `1` - enabled)
+//!
+//! The bus disables the MCP23008's address incrementation, so that many values can be written
+//! into the `GPIO` register in a single I2C transaction (see `write_bytes`).
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use i2cdev::core::I2CDevice;
+use i2cdev::linux::LinuxI2CDevice;
+use std::{path, time};
+use super::super::{Error, Result, UnitResult};
+use super::{Bus, RawNibbleBus};
+use super::lock::BusLock;
+use super::stats::Stats;
+use super::timing::{Latency, Operation, TimingReport, Timings};
+use super::super::delay::{Delay, DelayStrategy};
+
+pub struct Mcp23008 {
+    dev: LinuxI2CDevice,
+
+    /// held for as long as the bus lives (see the `lock` module)
+    _lock: BusLock,
+
+    backlight_enabled: bool,
+    timings: TimingReport,
+    stats: Stats,
+    delay: Box<dyn Delay>,
+    config: Timings,
+    latency: Latency,
+}
+
+/// MCP23008's registers.
+pub(crate) const REG_IODIR: u8 = 0x00;
+pub(crate) const REG_IPOL: u8 = 0x01;
+pub(crate) const REG_IOCON: u8 = 0x05;
+pub(crate) const REG_GPIO: u8 = 0x09;
+
+/// `IOCON`'s bit disabling the address incrementation.
+const IOCON_SEQOP: u8 = 0b00100000;
+
+/// Pins of the `GPIO` register.
+const RS: u8 = 0b00000010;
+const EN: u8 = 0b00000100;
+const BL: u8 = 0b10000000;
+
+impl Mcp23008 {
+    /// Lowest 7-bit I2C address of the MCP23008.
+    pub const MIN_ADDRESS: u16 = 0x20;
+
+    /// Highest 7-bit I2C address of the MCP23008.
+    pub const MAX_ADDRESS: u16 = 0x27;
+
+    /// Constructs a new HD44780 I2C bus for the MCP23008-based backpacks.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when given address is not a valid MCP23008's address, or
+    /// `Error::BusLocked` when another process is driving the LCD (see the `lock` module).
+    pub fn new<P: AsRef<path::Path>>(i2c_device: P, i2c_address: u16) -> Result<Mcp23008> {
+        Mcp23008::new_ex(i2c_device, i2c_address, Timings::default())
+    }
+
+    /// Constructs a new HD44780 I2C bus for the MCP23008-based backpacks, using given delays
+    /// instead of the default ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when given address is not a valid MCP23008's address, or
+    /// `Error::BusLocked` when another process is driving the LCD (see the `lock` module).
+    pub fn new_ex<P: AsRef<path::Path>>(i2c_device: P, i2c_address: u16, timings: Timings) -> Result<Mcp23008> {
+        if !(Mcp23008::MIN_ADDRESS..=Mcp23008::MAX_ADDRESS).contains(&i2c_address) {
+            return Err(Error::invalid_config(
+                "i2c_address",
+                format!("address {:#04x} is out of range <{:#04x}, {:#04x}>", i2c_address, Mcp23008::MIN_ADDRESS, Mcp23008::MAX_ADDRESS),
+            ).into());
+        }
+
+        let lock = BusLock::i2c(i2c_device.as_ref(), i2c_address, timings.lock_wait())?;
+
+        Ok(
+            Mcp23008 {
+                dev: LinuxI2CDevice::new(i2c_device, i2c_address)?,
+                _lock: lock,
+                backlight_enabled: true,
+                timings: TimingReport::default(),
+                stats: Stats::default(),
+                delay: Box::new(DelayStrategy::Sleep),
+                config: timings,
+                latency: Latency::new(),
+            }
+        )
+    }
+
+    /// Checks whether the backpack is connected, by reading one of the expander's registers -
+    /// returns `false` when the device does not acknowledge its address.
+    pub fn probe(&mut self) -> bool {
+        self.dev.smbus_read_byte_data(REG_IODIR).is_ok()
+    }
+
+    /// Changes the way the bus waits for the LCD (see `DelayStrategy`).
+    pub fn set_delay_strategy(&mut self, strategy: DelayStrategy) {
+        self.set_delay(strategy);
+    }
+
+    /// Makes the bus wait for the LCD using given delay - eg. a timer-based one, or `NoDelay` in
+    /// the tests (see the `delay` module).
+    pub fn set_delay<D: Delay + 'static>(&mut self, delay: D) {
+        self.delay = Box::new(delay);
+    }
+
+    /// Enables or disables skipping the part of the settle delays covered by the transfers
+    /// themselves (see `Bus::transfer_latency_us`) - enabled by default.
+    pub fn set_delay_elision(&mut self, enabled: bool) {
+        self.latency.set_enabled(enabled);
+    }
+
+    /// Returns the control bits (`Bl` and `Rs`) for given transfer.
+    fn control_mask(&self, as_data: bool) -> u8 {
+        let mut mask = 0u8;
+
+        mask |= BL * (self.backlight_enabled as u8);
+        mask |= RS * (as_data as u8);
+
+        mask
+    }
+
+    /// Appends the I2C bytes latching given byte into the LCD.
+    fn encode(&self, value: u8, as_data: bool, buffer: &mut Vec<u8>) {
+        let mask = self.control_mask(as_data);
+
+        for &nibble in &[value >> 4, value] {
+            let pins = Mcp23008::nibble_pins(nibble, mask);
+
+            buffer.push(pins | EN);
+            buffer.push(pins & !EN);
+        }
+    }
+
+    /// Sends given I2C bytes in a single transaction and waits for the LCD to settle.
+    fn transfer(&mut self, buffer: &[u8]) -> UnitResult {
+        let started = time::Instant::now();
+        self.dev.write(buffer)?;
+        self.timings.record_since(Operation::Transfer, started);
+
+        // the next transfer takes a while anyway, so only the rest of the settle time is waited
+        let settle_us = self.latency.remaining_us(self.config.settle_us);
+
+        if settle_us > 0 {
+            let started = time::Instant::now();
+            self.stats.record_delay_us(settle_us);
+            self.delay.delay_us(settle_us);
+            self.timings.record_since(Operation::Settle, started);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the `GPIO` register's value sending given nibble (the lower 4 bits).
+    fn nibble_pins(nibble: u8, mask: u8) -> u8 {
+        ((nibble & 0x0F) << 3) | mask
+    }
+
+    /// Sends a single nibble (already mapped onto the pins), latching the `Enable` pin.
+    fn write_nibble(&mut self, pins: u8) -> UnitResult {
+        // write value, pull up the `enable` pin & wait ~450ns (enable pulse must be >450ns)
+        let started = time::Instant::now();
+        self.dev.smbus_write_byte_data(REG_GPIO, pins | EN)?;
+        self.timings.record_since(Operation::Transfer, started);
+
+        let started = time::Instant::now();
+        self.stats.record_delay_ns(self.config.enable_pulse_ns);
+        self.delay.delay_ns(self.config.enable_pulse_ns);
+        self.timings.record_since(Operation::EnablePulse, started);
+
+        // write value again, this time pulling the `Enable` pin down & wait ~37us (commands need 37us to settle)
+        let started = time::Instant::now();
+        self.dev.smbus_write_byte_data(REG_GPIO, pins & !EN)?;
+        self.latency.record(started.elapsed());
+        self.timings.record_since(Operation::Transfer, started);
+
+        // the next transfer takes a while anyway, so only the rest of the settle time is waited
+        let settle_us = self.latency.remaining_us(self.config.settle_us);
+
+        if settle_us > 0 {
+            let started = time::Instant::now();
+            self.stats.record_delay_us(settle_us);
+            self.delay.delay_us(settle_us);
+            self.timings.record_since(Operation::Settle, started);
+        }
+
+        Ok(())
+    }
+}
+
+impl Bus for Mcp23008 {
+    fn initialize(&mut self) -> UnitResult {
+        self.initialize_warm()?;
+
+        // wait for the LCD to power up
+        self.stats.record_delay_us(self.config.power_on_us);
+        self.delay.delay_us(self.config.power_on_us);
+
+        self.resync()
+    }
+
+    fn resync(&mut self) -> UnitResult {
+        // try to put LCD in 8-bit mode three times, then put it in proper 4-bit mode
+        for (step, &nibble) in [0x03, 0x03, 0x03, 0x02].iter().enumerate() {
+            let pins = Mcp23008::nibble_pins(nibble, self.control_mask(false));
+
+            self.write_nibble(pins)?;
+            self.stats.record_delay_us(self.config.init_step_us(step));
+            self.delay.delay_us(self.config.init_step_us(step));
+        }
+
+        Ok(())
+    }
+
+    /// Makes all the pins outputs and disables the address incrementation.
+    fn initialize_warm(&mut self) -> UnitResult {
+        self.dev.smbus_write_byte_data(REG_IODIR, 0x00)?;
+        self.dev.smbus_write_byte_data(REG_IOCON, IOCON_SEQOP)?;
+
+        Ok(())
+    }
+
+    fn set_backlight(&mut self, enabled: bool) -> UnitResult {
+        self.backlight_enabled = enabled;
+
+        let started = time::Instant::now();
+        self.dev.smbus_write_byte_data(REG_GPIO, self.control_mask(false))?;
+        self.timings.record_since(Operation::Transfer, started);
+
+        Ok(())
+    }
+
+    fn write_byte(&mut self, value: u8, as_data: bool) -> UnitResult {
+        let mask = self.control_mask(as_data);
+
+        self.write_nibble(Mcp23008::nibble_pins(value >> 4, mask))?;
+        self.write_nibble(Mcp23008::nibble_pins(value, mask))?;
+        self.stats.record_byte(as_data);
+
+        Ok(())
+    }
+
+    /// Sends all the bytes in a single I2C transaction (see `I2C::write_bytes`).
+    fn write_bytes(&mut self, bytes: &[(u8, bool)]) -> UnitResult {
+        let mut buffer = Vec::with_capacity(1 + bytes.len() * 4);
+
+        buffer.push(REG_GPIO);
+
+        for &(value, as_data) in bytes {
+            self.encode(value, as_data, &mut buffer);
+        }
+
+        self.transfer(&buffer)?;
+
+        for &(_, as_data) in bytes {
+            self.stats.record_byte(as_data);
+        }
+
+        Ok(())
+    }
+
+    /// Sends the whole run in a single I2C transaction, encoding the byte just once.
+    fn write_data_repeated(&mut self, value: u8, count: usize) -> UnitResult {
+        let mut encoded = Vec::with_capacity(4);
+        self.encode(value, true, &mut encoded);
+
+        let mut buffer = Vec::with_capacity(1 + count * 4);
+
+        buffer.push(REG_GPIO);
+
+        for _ in 0..count {
+            buffer.extend_from_slice(&encoded);
+        }
+
+        self.transfer(&buffer)?;
+
+        for _ in 0..count {
+            self.stats.record_byte(true);
+        }
+
+        Ok(())
+    }
+
+    fn width(&self) -> usize {
+        4
+    }
+
+    fn transfer_latency_us(&self) -> u32 {
+        self.latency.us()
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        self.stats.record_delay_us(us);
+        self.delay.delay_us(us);
+    }
+
+    fn timings(&self) -> Timings {
+        self.config
+    }
+
+    fn timing_report(&self) -> TimingReport {
+        self.timings.clone()
+    }
+
+    fn reset_timing_report(&mut self) {
+        self.timings.clear();
+    }
+
+    fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    fn reset_stats(&mut self) {
+        self.stats.clear();
+    }
+
+    fn probe(&mut self) -> Option<bool> {
+        Some(Mcp23008::probe(self))
+    }
+}
+
+impl RawNibbleBus for Mcp23008 {
+    fn write_raw_nibble(&mut self, nibble: u8, as_data: bool) -> UnitResult {
+        let mask = self.control_mask(as_data);
+
+        self.write_nibble(Mcp23008::nibble_pins(nibble, mask))
+    }
+
+    fn write_raw_nibble_ex(&mut self, nibble: u8, as_data: bool, backlight: bool) -> UnitResult {
+        self.backlight_enabled = backlight;
+        self.write_raw_nibble(nibble, as_data)
+    }
+}
@@ -1,25 +1,160 @@
 use self::command::*;
+#[cfg(feature = "i2cdev")]
+pub use self::backpack::Backpack;
+#[cfg(feature = "raspberrypi")]
 pub use self::gpio4::Gpio4;
+#[cfg(feature = "i2cdev")]
 pub use self::i2c::I2C;
-use std::{thread, time};
-use super::UnitResult;
+#[cfg(feature = "i2cdev")]
+pub use self::i2c8::I2C8;
+#[cfg(feature = "i2cdev")]
+pub use self::i2c_adapter::I2CAdapter;
+#[cfg(feature = "i2cdev")]
+pub use self::lock::BusLock;
+#[cfg(feature = "std")]
+pub use self::logging::{LoggingBus, TransferLog};
+#[cfg(feature = "i2cdev")]
+pub use self::mcp23008::Mcp23008;
+pub use self::null::NullBus;
+#[cfg(feature = "std")]
+pub use self::simulated::{Registers, SimulatedController};
+pub use self::stats::Stats;
+pub use self::timing::{Operation, OperationStats, TimingReport, Timings};
+use super::{Error, Result, UnitResult};
 
-pub(crate) mod command;
+#[cfg(feature = "i2cdev")]
+pub mod backpack;
+pub mod command;
 
+#[cfg(feature = "i2cdev")]
 pub mod i2c;
+#[cfg(feature = "i2cdev")]
+pub mod i2c8;
+#[cfg(feature = "i2cdev")]
+pub mod i2c_adapter;
+#[cfg(feature = "raspberrypi")]
 pub mod gpio4;
+#[cfg(feature = "i2cdev")]
+pub mod lock;
+#[cfg(feature = "std")]
+pub mod logging;
+#[cfg(feature = "i2cdev")]
+pub mod mcp23008;
+pub mod null;
+#[cfg(feature = "std")]
+pub mod simulated;
+#[cfg(feature = "raspberrypi")]
+mod soft_pwm;
+pub mod stats;
+pub mod timing;
 
-pub trait Bus {
+/// Buses are required to be `Send`, so that the LCDs built on top of them can be moved between
+/// threads (eg. when wrapped in the shared frontend).
+pub trait Bus: Send {
     /// Initializes the bus (eg. puts LCD in appropriate 4/8-bit mode).
     fn initialize(&mut self) -> UnitResult;
 
+    /// Prepares the bus for talking to an LCD that has already been initialized, without resetting
+    /// the LCD (see `DirectLcd::new_warm`).
+    ///
+    /// Buses not requiring any preparation do nothing (which is the default).
+    fn initialize_warm(&mut self) -> UnitResult {
+        Ok(())
+    }
+
+    /// Brings the LCD's interface back in sync with the bus, without waiting for the LCD to power
+    /// up - by sending the "8-bit mode" handshake (and, for the 4-bit buses, switching back into
+    /// the 4-bit mode afterwards).
+    ///
+    /// Needed when a 4-bit transfer has been interrupted in the middle of a byte (eg. the process
+    /// has been killed between the nibbles): the LCD then takes each next nibble for the other
+    /// half of a byte and shows garbage - the handshake works regardless of which half the LCD
+    /// expects (see `DirectLcd::resync`).
+    ///
+    /// By default, the bus gets initialized from scratch.
+    fn resync(&mut self) -> UnitResult {
+        self.initialize()
+    }
+
     /// Enables / disables the backlight.
     fn set_backlight(&mut self, enabled: bool) -> UnitResult;
 
+    /// Changes the backlight's brightness (`0` - off, `255` - full brightness).
+    ///
+    /// Buses unable to dim the backlight fall back to turning it on / off (on for any non-zero
+    /// brightness).
+    fn set_backlight_brightness(&mut self, brightness: u8) -> UnitResult {
+        self.set_backlight(brightness > 0)
+    }
+
+    /// Returns whether the bus is able to dim the backlight (instead of just turning it on /
+    /// off, which is the default).
+    fn can_dim_backlight(&self) -> bool {
+        false
+    }
+
+    /// Returns how long (in microseconds) a single transfer takes, as measured by the bus - the
+    /// settle delays are shortened by that much, since the LCD cannot be sent anything sooner
+    /// anyway.
+    ///
+    /// Buses not measuring it return zero (which is the default).
+    fn transfer_latency_us(&self) -> u32 {
+        0
+    }
+
+    /// Changes the display's contrast (`0` - lowest, `255` - highest).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ContrastNotSupported` when the bus is not able to control the contrast
+    /// (which is the default).
+    fn set_contrast(&mut self, _level: u8) -> UnitResult {
+        Err(Error::ContrastNotSupported.into())
+    }
+
+    /// Reads a single byte from the device.
+    /// When `as_data` is `true`, the `RS` register is pulled up and byte is read from the DDRAM /
+    /// CGRAM (whichever was addressed last) - otherwise the busy flag & address counter are read.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ReadingNotSupported` when the bus is not able to read from the LCD (which
+    /// is the default).
+    fn read_byte(&mut self, _as_data: bool) -> Result<u8> {
+        Err(Error::ReadingNotSupported.into())
+    }
+
+    /// Returns whether the bus is able to read from the LCD (see `read_byte`; `false` by
+    /// default).
+    fn can_read(&self) -> bool {
+        false
+    }
+
+    /// Reads the busy flag (`true` when the LCD is still processing the previous command) and the
+    /// address counter (the cursor's DDRAM address, or the CGRAM one after `create_char`).
+    fn read_busy_and_address(&mut self) -> Result<(bool, u8)> {
+        let value = self.read_byte(false)?;
+
+        Ok((value & 0x80 != 0, value & 0x7F))
+    }
+
     /// Sends a single byte to the device.
     /// When `as_data` is `true`, the `RS` register is pulled up and byte is sent as `data`.
     fn write_byte(&mut self, value: u8, as_data: bool) -> UnitResult;
 
+    /// Sends a sequence of bytes (each one being either a command or data, depending on the
+    /// `as_data` flag) to the device.
+    ///
+    /// Buses capable of it send them in a single transaction, which is way faster than sending
+    /// the bytes one by one (see `DirectLcd::batch`).
+    fn write_bytes(&mut self, bytes: &[(u8, bool)]) -> UnitResult {
+        for &(value, as_data) in bytes {
+            self.write_byte(value, as_data)?;
+        }
+
+        Ok(())
+    }
+
     /// Sends a raw command to the device.
     fn write_command(&mut self, value: u8) -> UnitResult {
         self.write_byte(value, false)
@@ -30,70 +165,27 @@ pub trait Bus {
         self.write_byte(value, true)
     }
 
+    /// Sends the same data byte `count` times in a row (eg. a run of spaces left by `clear_line`
+    /// or a bar's fill).
+    ///
+    /// Buses capable of it send the whole run in a single transaction; the default falls back to
+    /// `write_data` in a loop.
+    fn write_data_repeated(&mut self, value: u8, count: usize) -> UnitResult {
+        for _ in 0..count {
+            self.write_data(value)?;
+        }
+
+        Ok(())
+    }
+
     /// Executes given command.
     fn execute(&mut self, command: Command) -> UnitResult {
-        match command {
-            // -- clear -- //
-            Command::Clear => {
-                self.write_command(CommandValue::Clear as u8)?;
-
-                // "clear" command requires additional delay
-                thread::sleep(time::Duration::new(0, 1000 * 1000));
-            }
-
-            // -- home -- //
-            Command::Home => {
-                self.write_command(CommandValue::Home as u8)?;
-
-                // "home" command requires additional delay
-                thread::sleep(time::Duration::new(0, 1000 * 1000));
-            }
-
-            // -- set entry mode -- //
-            Command::SetEntryMode { enable_shift, increment_counter } => {
-                let mut cmd = CommandValue::SetEntryMode as u8;
-
-                cmd |= 0x01 * enable_shift as u8;
-                cmd |= 0x02 * increment_counter as u8;
-
-                self.write_command(cmd)?;
-            }
-
-            // -- set display flags -- //
-            Command::SetDisplayFlags { cursor_blinking, cursor_visible, text_visible } => {
-                let mut cmd = CommandValue::SetDisplayFlags as u8;
-
-                cmd |= 0x01 * cursor_blinking as u8;
-                cmd |= 0x02 * cursor_visible as u8;
-                cmd |= 0x04 * text_visible as u8;
-
-                self.write_command(cmd)?;
-            }
-
-            // -- set functions -- //
-            Command::SetFunctions { font_5x10, height, eight_bit_bus } => {
-                let mut cmd = CommandValue::SetFunctions as u8;
-
-                cmd |= 0x04 * font_5x10 as u8;
-                cmd |= 0x08 * (height >= 2) as u8;
-                cmd |= 0x10 * eight_bit_bus as u8;
-
-                self.write_command(cmd)?;
-            }
-
-            // -- set CGRAM address -- //
-            Command::SetCGRamAddress { address } => {
-                self.write_command(
-                    (CommandValue::SetCGRamAddress as u8) | address
-                )?;
-            }
-
-            // -- set DDRAM address -- //
-            Command::SetDDRamAddress { address } => {
-                self.write_command(
-                    (CommandValue::SetDDRamAddress as u8) | address
-                )?;
-            }
+        self.write_command(command.encode())?;
+
+        let delay = command.extra_delay_us(&self.timings());
+
+        if delay > 0 {
+            self.delay_us(delay);
         }
 
         Ok(())
@@ -101,4 +193,109 @@ pub trait Bus {
 
     /// Returns bus width (4 / 8 bit).
     fn width(&self) -> usize;
-}
\ No newline at end of file
+
+    /// Waits given number of microseconds (eg. for the LCD to process a slow command).
+    ///
+    /// Sleeps by default (see `DelayStrategy::Sleep`); without the standard library there is no
+    /// portable way to wait, so the buses have to implement it themselves.
+    #[cfg(feature = "std")]
+    fn delay_us(&mut self, us: u32) {
+        super::Delay::delay_us(&mut super::DelayStrategy::Sleep, us);
+    }
+
+    /// Waits given number of microseconds (eg. for the LCD to process a slow command).
+    #[cfg(not(feature = "std"))]
+    fn delay_us(&mut self, us: u32);
+
+    /// Returns the delays used by the bus.
+    fn timings(&self) -> Timings {
+        Timings::default()
+    }
+
+    /// Returns timing statistics of the bus phases (transfers, enable pulses, settling) gathered
+    /// so far.
+    fn timing_report(&self) -> TimingReport {
+        TimingReport::default()
+    }
+
+    /// Forgets all the timing statistics gathered so far.
+    fn reset_timing_report(&mut self) {}
+
+    /// Returns the traffic statistics (commands, data bytes, delays) gathered so far.
+    ///
+    /// Buses not gathering them (which is the default) return zeros.
+    fn stats(&self) -> Stats {
+        Stats::default()
+    }
+
+    /// Forgets all the traffic statistics gathered so far.
+    fn reset_stats(&mut self) {}
+
+    /// Checks whether the LCD (or its backpack) is connected - `None` when the bus is not able to
+    /// tell (which is the default).
+    ///
+    /// Meant for detecting a disconnected / re-plugged display (see `HealthCheck`).
+    fn probe(&mut self) -> Option<bool> {
+        None
+    }
+
+    /// Returns the bus' RGB backlight, if it has one.
+    fn as_rgb_backlight(&mut self) -> Option<&mut dyn RgbBacklight> {
+        None
+    }
+}
+
+/// Provides a low-level access to the buses transferring data in nibbles (4 bits at once).
+///
+/// Meant for protocol experiments (eg. custom initialization sequences or handshakes required by
+/// some of the HD44780 clones) - each nibble is sent with the very same timings (enable pulse
+/// width, settle time) the bus uses internally, so there's no need to reimplement them.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// // manually put the LCD in the 4-bit mode
+/// for nibble in &[0x03, 0x03, 0x03, 0x02] {
+///     bus.write_raw_nibble(*nibble, false)?;
+/// }
+///
+/// // ... and blink the backlight along with the next command's nibbles
+/// bus.write_raw_nibble_ex(0x00, false, false)?;
+/// bus.write_raw_nibble_ex(0x0C, false, true)?;
+/// ```
+pub trait RawNibbleBus: Bus {
+    /// Sends a single nibble (lower 4 bits of `nibble`), latching the `enable` pin.
+    ///
+    /// When `as_data` is `true`, the `RS` register is pulled up. The backlight (on buses
+    /// supporting it) is kept in the state set by `set_backlight`.
+    fn write_raw_nibble(&mut self, nibble: u8, as_data: bool) -> UnitResult;
+
+    /// Sends a single nibble just like `write_raw_nibble`, turning the backlight on / off at the
+    /// same time.
+    ///
+    /// The buses latching the backlight along with the LCD's pins (eg. the I2C backpacks) do it in
+    /// a single transfer; the rest call `set_backlight` first. Either way, the backlight stays in
+    /// the new state afterwards, just as if `set_backlight` had been called.
+    fn write_raw_nibble_ex(&mut self, nibble: u8, as_data: bool, backlight: bool) -> UnitResult {
+        self.set_backlight(backlight)?;
+        self.write_raw_nibble(nibble, as_data)
+    }
+}
+
+/// Provides control over the RGB backlights (eg. the ones found in Adafruit's RGB LCD shields).
+///
+/// Implemented by the buses having three backlight lines, as well as by the frontends (which
+/// forward the calls to their buses) - monochrome displays are not affected at all.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// lcd.set_backlight_color(255, 128, 0)?;
+/// ```
+pub trait RgbBacklight {
+    /// Changes the backlight's color.
+    ///
+    /// The color gets scaled by the backlight's brightness (see `set_backlight_brightness`), and
+    /// is restored when the backlight is turned on again.
+    fn set_backlight_color(&mut self, r: u8, g: u8, b: u8) -> UnitResult;
+}
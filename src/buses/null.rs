@@ -0,0 +1,168 @@
+//! Provides a bus not connected to anything - for developing the applications on a workstation
+//! (or running them in the CI), where there's no LCD at hand.
+//!
+//! The bytes are discarded, but the bus keeps track of the time a real display would require
+//! (the transfers, the settle delays and the slow commands - see `Bus::stats`), and can actually
+//! wait that long too (see `set_delay`), so that the application can be profiled realistically
+//! before being deployed.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! // behaves like an LCD connected through the I2C backpack
+//! let mut lcd = BufferedLcd::from_lcd(DirectLcd::new(Box::new(NullBus::realistic()), 20, 4)?);
+//!
+//! lcd.print_at(0, 0, "Hello World!")?;
+//! lcd.render()?;
+//!
+//! println!("{}", lcd.inner().stats());
+//! ```
+//!
+//! To inspect what's been printed, see `frontends::InMemory`.
+
+use alloc::boxed::Box;
+use super::super::UnitResult;
+use super::{Bus, Stats, Timings};
+use super::super::delay::{Delay, NoDelay};
+
+pub struct NullBus {
+    /// number of data lines (4 / 8)
+    width: usize,
+
+    config: Timings,
+    delay: Box<dyn Delay>,
+
+    /// how long (in microseconds) a single byte's transfer takes
+    transfer_us: u32,
+
+    backlight_enabled: bool,
+    stats: Stats,
+}
+
+impl NullBus {
+    /// Transfer's cost of the PCF8574-based backpacks (working at 100kHz).
+    pub const I2C_TRANSFER_US: u32 = 90;
+
+    /// Constructs a 4-bit bus, which does not wait at all.
+    pub fn new() -> NullBus {
+        NullBus {
+            width: 4,
+            config: Timings::default(),
+            delay: Box::new(NoDelay),
+            transfer_us: 0,
+            backlight_enabled: true,
+            stats: Stats::default(),
+        }
+    }
+
+    /// Constructs a 4-bit bus waiting as long as an LCD connected through the I2C backpack would
+    /// require (see `I2C_TRANSFER_US`).
+    #[cfg(feature = "std")]
+    pub fn realistic() -> NullBus {
+        let mut bus = NullBus::new();
+
+        bus.set_delay(super::super::delay::DelayStrategy::Sleep);
+        bus.set_transfer_cost_us(NullBus::I2C_TRANSFER_US);
+        bus
+    }
+
+    /// Changes the number of data lines the bus pretends to have (`4` or `8`).
+    ///
+    /// # Panics
+    ///
+    /// Panics when given width is neither `4` nor `8`.
+    pub fn set_width(&mut self, width: usize) {
+        assert!(width == 4 || width == 8, "Bus width must be 4 or 8, got {}.", width);
+
+        self.width = width;
+    }
+
+    /// Changes the delays the bus pretends to require (eg. to emulate one of the HD44780's
+    /// clones - see `ControllerProfile::timings`).
+    pub fn set_timings(&mut self, timings: Timings) {
+        self.config = timings;
+    }
+
+    /// Changes how long (in microseconds) transferring a single byte takes (`0` by default).
+    pub fn set_transfer_cost_us(&mut self, us: u32) {
+        self.transfer_us = us;
+    }
+
+    /// Makes the bus actually wait using given delay (by default, the delays are only accounted
+    /// for in the stats).
+    pub fn set_delay<D: Delay + 'static>(&mut self, delay: D) {
+        self.delay = Box::new(delay);
+    }
+
+    /// Returns whether the backlight is enabled.
+    pub fn backlight(&self) -> bool {
+        self.backlight_enabled
+    }
+}
+
+impl Default for NullBus {
+    fn default() -> NullBus {
+        NullBus::new()
+    }
+}
+
+impl Bus for NullBus {
+    fn initialize(&mut self) -> UnitResult {
+        // wait for the LCD to power up
+        self.delay_us(self.config.power_on_us);
+
+        self.resync()
+    }
+
+    fn resync(&mut self) -> UnitResult {
+        for step in 0..4 {
+            self.delay_us(self.transfer_us);
+            self.delay_us(self.config.init_step_us(step));
+        }
+
+        Ok(())
+    }
+
+    fn set_backlight(&mut self, enabled: bool) -> UnitResult {
+        self.backlight_enabled = enabled;
+        self.delay_us(self.transfer_us);
+
+        Ok(())
+    }
+
+    fn write_byte(&mut self, _: u8, as_data: bool) -> UnitResult {
+        self.stats.record_byte(as_data);
+
+        // the settle time overlaps with the next transfer (just like on the I2C buses)
+        self.delay_us(self.transfer_us.max(self.config.settle_us));
+
+        Ok(())
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        if us > 0 {
+            self.stats.record_delay_us(us);
+            self.delay.delay_us(us);
+        }
+    }
+
+    fn timings(&self) -> Timings {
+        self.config
+    }
+
+    fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    fn reset_stats(&mut self) {
+        self.stats.clear();
+    }
+
+    fn probe(&mut self) -> Option<bool> {
+        Some(true)
+    }
+}
@@ -0,0 +1,543 @@
+//! Provides a bus connected to a simulated HD44780 - a state machine interpreting the raw bytes
+//! (and, on the 4-bit bus, the nibbles) just like the real controller does, for the end-to-end
+//! tests catching the protocol bugs (eg. a wrong initialization order, a desynchronized 4-bit
+//! interface or a mishandled display shift) without any hardware.
+//!
+//! Unlike `FrameCapture` (which records what the frontend has *meant* to show), the simulator
+//! shows what a real display would: the DDRAM as seen through the display shift, the function
+//! set, the display flags and the entry mode the controller has actually received.
+//!
+//! The controller is kept behind a shared handle - all the clones of a `SimulatedController` talk
+//! to the same controller, so it stays accessible after the bus has been moved into a frontend.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let sim = SimulatedController::new(20, 4);
+//! let mut lcd = DirectLcd::new(Box::new(sim.clone()), 20, 4)?;
+//!
+//! lcd.print_at(1, 0, "Hello World!")?;
+//!
+//! assert_eq!(sim.lines()[1], "Hello World!        ");
+//! assert!(sim.registers().two_lines);
+//! ```
+//!
+//! # Caveats
+//!
+//! 1. Only the HD44780's own instruction set is simulated - the clones' extensions (eg. KS0073's
+//!    extended function set or WS0010's mode & power command) get interpreted the way HD44780
+//!    would interpret them.
+//!
+//! 2. The timing is not simulated - the delays are only accounted for in the stats, and the busy
+//!    flag always reads as clear.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use std::sync::{Arc, Mutex, MutexGuard};
+use super::super::frontends::decode_char;
+use super::super::{Point, Properties, Result, UnitResult};
+use super::{Bus, RawNibbleBus, Stats};
+
+#[derive(Clone)]
+pub struct SimulatedController {
+    state: Arc<Mutex<State>>,
+}
+
+/// The controller's registers, as set by the commands it has received.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Registers {
+    /// interface's width: `true` for 8 bits, `false` for 4 bits (`DL`)
+    pub eight_bit: bool,
+
+    /// 2-line mode (`N`)
+    pub two_lines: bool,
+
+    /// 5x10 font (`F`)
+    pub font_5x10: bool,
+
+    /// whether the address counter gets incremented (`I/D`)
+    pub increment: bool,
+
+    /// whether the display shifts on each write (`S`)
+    pub autoscroll: bool,
+
+    /// display on (`D`)
+    pub text_visible: bool,
+
+    /// cursor on (`C`)
+    pub cursor_visible: bool,
+
+    /// cursor blinking (`B`)
+    pub cursor_blinking: bool,
+
+    /// address counter
+    pub address: u8,
+
+    /// whether the address counter points into the CGRAM (instead of the DDRAM)
+    pub cgram_selected: bool,
+
+    /// number of characters the display has been shifted to the left by
+    pub display_shift: usize,
+}
+
+struct State {
+    /// geometry of the glass the controller is connected to
+    properties: Properties,
+
+    /// number of data lines the controller is wired with (4 / 8)
+    width: usize,
+
+    registers: Registers,
+
+    /// high nibble of the byte being transferred over the 4-bit interface
+    pending_nibble: Option<u8>,
+
+    ddram: [u8; 0x80],
+    cgram: [u8; 0x40],
+
+    backlight: bool,
+    stats: Stats,
+}
+
+impl SimulatedController {
+    /// Creates a freshly powered-up controller connected to a screen of given size (see
+    /// `Properties::new`), wired with 4 data lines.
+    pub fn new(width: usize, height: usize) -> SimulatedController {
+        SimulatedController::with_properties(Properties::new(width, height))
+    }
+
+    /// Creates a freshly powered-up controller connected to a screen described by given
+    /// properties (only the geometry and the ROM are taken into account), wired with 4 data lines.
+    pub fn with_properties(properties: Properties) -> SimulatedController {
+        SimulatedController {
+            state: Arc::new(Mutex::new(State::new(properties))),
+        }
+    }
+
+    /// Changes the number of data lines the controller is wired with (`4` or `8`).
+    ///
+    /// # Panics
+    ///
+    /// Panics when given width is neither `4` nor `8`.
+    pub fn set_width(&mut self, width: usize) {
+        assert!(width == 4 || width == 8, "Bus width must be 4 or 8, got {}.", width);
+
+        self.lock().width = width;
+    }
+
+    /// Simulates a power cycle: the controller forgets everything and goes through the internal
+    /// reset (8-bit interface, 1-line mode, display off, blank DDRAM).
+    pub fn power_cycle(&self) {
+        let mut state = self.lock();
+
+        *state = State {
+            width: state.width,
+            ..State::new(state.properties)
+        };
+    }
+
+    /// Returns the controller's registers.
+    pub fn registers(&self) -> Registers {
+        self.lock().registers
+    }
+
+    /// Returns whether the controller has received only a half of a byte over the 4-bit interface
+    /// (and so takes the next nibble for the other half).
+    pub fn is_mid_byte(&self) -> bool {
+        self.lock().pending_nibble.is_some()
+    }
+
+    /// Returns what the screen shows, as text (one string per line - see `FrameCapture` for how
+    /// the codes get decoded); the screen is blank while the display is off.
+    pub fn lines(&self) -> Vec<String> {
+        let state = self.lock();
+
+        (0..state.properties.height)
+            .map(|y| {
+                state.line(y)
+                    .into_iter()
+                    .map(|code| decode_char(state.properties.rom, code))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Returns what the screen shows, as text (lines are separated with `\n`).
+    pub fn snapshot(&self) -> String {
+        self.lines().join("\n")
+    }
+
+    /// Returns the codes the screen shows at given line (taking the display shift into account).
+    pub fn line(&self, y: usize) -> Option<Vec<u8>> {
+        let state = self.lock();
+
+        if y < state.properties.height {
+            Some(state.line(y))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the DDRAM's contents at given address.
+    pub fn ddram(&self, address: u8) -> u8 {
+        self.lock().ddram[(address & 0x7F) as usize]
+    }
+
+    /// Returns bitmap of given custom character (`0..8`), as stored in the CGRAM.
+    pub fn custom_char(&self, idx: u8) -> [u8; 8] {
+        let state = self.lock();
+        let offset = (idx as usize % 8) * 8;
+
+        let mut bitmap = [0; 8];
+        bitmap.copy_from_slice(&state.cgram[offset..offset + 8]);
+        bitmap
+    }
+
+    /// Returns the position of the cursor on the screen - `None` when the address counter points
+    /// into the CGRAM or outside the screen.
+    pub fn cursor(&self) -> Option<Point> {
+        let state = self.lock();
+
+        if state.registers.cgram_selected {
+            return None;
+        }
+
+        let (width, height) = (state.properties.width, state.properties.height);
+
+        Point::region(Point::new(0, 0), Point::new(height - 1, width - 1))
+            .find(|point| state.address_at(point.y, point.x) == state.registers.address)
+    }
+
+    /// Returns whether the backlight is enabled.
+    pub fn backlight(&self) -> bool {
+        self.lock().backlight
+    }
+
+    /// Locks the controller, recovering it after a panic in some other thread.
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl State {
+    fn new(properties: Properties) -> State {
+        State {
+            properties,
+            width: 4,
+
+            // the internal reset circuit's initial state
+            registers: Registers {
+                eight_bit: true,
+                two_lines: false,
+                font_5x10: false,
+                increment: true,
+                autoscroll: false,
+                text_visible: false,
+                cursor_visible: false,
+                cursor_blinking: false,
+                address: 0,
+                cgram_selected: false,
+                display_shift: 0,
+            },
+
+            pending_nibble: None,
+            ddram: [b' '; 0x80],
+            cgram: [0; 0x40],
+            backlight: true,
+            stats: Stats::default(),
+        }
+    }
+
+    /// Latches the data lines (`D7..D0`; on the 4-bit bus, `D3..D0` are not connected and read as
+    /// zeros).
+    fn latch(&mut self, pins: u8, as_data: bool) {
+        if self.registers.eight_bit {
+            self.receive(pins, as_data);
+            return;
+        }
+
+        match self.pending_nibble.take() {
+            Some(high) => self.receive(high | (pins >> 4), as_data),
+            None => self.pending_nibble = Some(pins & 0xF0),
+        }
+    }
+
+    /// Interprets a complete byte.
+    fn receive(&mut self, value: u8, as_data: bool) {
+        if as_data {
+            self.write_data(value);
+        } else {
+            self.execute(value);
+        }
+    }
+
+    fn execute(&mut self, cmd: u8) {
+        let regs = &mut self.registers;
+
+        // the highest bit set tells the instruction apart
+        match 1 << (7 - cmd.leading_zeros().min(7)) {
+            // set DDRAM address
+            0x80 => {
+                regs.address = cmd & 0x7F;
+                regs.cgram_selected = false;
+            }
+
+            // set CGRAM address
+            0x40 => {
+                regs.address = cmd & 0x3F;
+                regs.cgram_selected = true;
+            }
+
+            // function set
+            0x20 => {
+                regs.eight_bit = cmd & 0x10 != 0;
+                regs.two_lines = cmd & 0x08 != 0;
+                regs.font_5x10 = cmd & 0x04 != 0;
+            }
+
+            // cursor / display shift
+            0x10 => {
+                let right = cmd & 0x04 != 0;
+
+                if cmd & 0x08 != 0 {
+                    self.shift_display(right);
+                } else {
+                    self.move_address(right);
+                }
+            }
+
+            // display on / off control
+            0x08 => {
+                regs.text_visible = cmd & 0x04 != 0;
+                regs.cursor_visible = cmd & 0x02 != 0;
+                regs.cursor_blinking = cmd & 0x01 != 0;
+            }
+
+            // entry mode set
+            0x04 => {
+                regs.increment = cmd & 0x02 != 0;
+                regs.autoscroll = cmd & 0x01 != 0;
+            }
+
+            // return home
+            0x02 => {
+                regs.address = 0;
+                regs.cgram_selected = false;
+                regs.display_shift = 0;
+            }
+
+            // clear display
+            0x01 => {
+                regs.address = 0;
+                regs.cgram_selected = false;
+                regs.display_shift = 0;
+                regs.increment = true;
+
+                self.ddram = [b' '; 0x80];
+            }
+
+            // a zero is not an instruction at all
+            _ => (),
+        }
+    }
+
+    fn write_data(&mut self, value: u8) {
+        let address = self.registers.address as usize;
+
+        if self.registers.cgram_selected {
+            self.cgram[address & 0x3F] = value;
+        } else {
+            self.ddram[address & 0x7F] = value;
+
+            if self.registers.autoscroll {
+                self.shift_display(!self.registers.increment);
+            }
+        }
+
+        self.move_address(self.registers.increment);
+    }
+
+    fn read_data(&mut self) -> u8 {
+        let address = self.registers.address as usize;
+
+        let value = if self.registers.cgram_selected {
+            self.cgram[address & 0x3F]
+        } else {
+            self.ddram[address & 0x7F]
+        };
+
+        self.move_address(self.registers.increment);
+        value
+    }
+
+    /// Moves the address counter by one, wrapping around the memory's end (in the 2-line mode the
+    /// first line ends at `0x27` and the second one starts at `0x40`).
+    fn move_address(&mut self, forward: bool) {
+        let regs = &mut self.registers;
+
+        let next = if forward {
+            regs.address.wrapping_add(1)
+        } else {
+            regs.address.wrapping_sub(1)
+        };
+
+        regs.address = if regs.cgram_selected {
+            next & 0x3F
+        } else if regs.two_lines {
+            match (forward, regs.address) {
+                (true, 0x27) => 0x40,
+                (true, 0x67) => 0x00,
+                (false, 0x40) => 0x27,
+                (false, 0x00) => 0x67,
+                _ => next & 0x7F,
+            }
+        } else {
+            match (forward, regs.address) {
+                (true, 0x4F) => 0x00,
+                (false, 0x00) => 0x4F,
+                _ => next & 0x7F,
+            }
+        };
+    }
+
+    /// Shifts the display by one character (to the right moves the text to the right).
+    fn shift_display(&mut self, right: bool) {
+        let shift = &mut self.registers.display_shift;
+
+        // 80 is a multiple of the lines' length in both the 1-line and the 2-line mode
+        *shift = if right { (*shift + 79) % 80 } else { (*shift + 1) % 80 };
+    }
+
+    /// Returns the length of each of the DDRAM's lines.
+    fn line_length(&self) -> usize {
+        if self.registers.two_lines { 40 } else { 80 }
+    }
+
+    /// Returns the DDRAM address shown at given position of the screen, taking the display shift
+    /// into account.
+    fn address_at(&self, y: usize, x: usize) -> u8 {
+        let address = self.properties.address_of(y, x);
+        let line_length = self.line_length();
+
+        let (start, offset) = if self.registers.two_lines {
+            (address & 0x40, (address & 0x3F) as usize)
+        } else {
+            (0, address as usize)
+        };
+
+        start + ((offset + self.registers.display_shift) % line_length) as u8
+    }
+
+    /// Returns the codes shown at given line of the screen.
+    fn line(&self, y: usize) -> Vec<u8> {
+        (0..self.properties.width)
+            .map(|x| {
+                let address = self.address_at(y, x);
+
+                // in the 1-line mode, the second line's commons are not driven at all
+                let driven = self.registers.two_lines || self.properties.address_of(y, x) < 0x40;
+
+                if self.registers.text_visible && driven {
+                    self.ddram[address as usize]
+                } else {
+                    b' '
+                }
+            })
+            .collect()
+    }
+}
+
+impl Bus for SimulatedController {
+    fn initialize(&mut self) -> UnitResult {
+        self.resync()
+    }
+
+    fn resync(&mut self) -> UnitResult {
+        // "initialization by instruction" - see the real buses
+        for _ in 0..3 {
+            self.write_raw_nibble(0x03, false)?;
+        }
+
+        if self.lock().width == 4 {
+            self.write_raw_nibble(0x02, false)?;
+        }
+
+        Ok(())
+    }
+
+    fn set_backlight(&mut self, enabled: bool) -> UnitResult {
+        self.lock().backlight = enabled;
+
+        Ok(())
+    }
+
+    fn read_byte(&mut self, as_data: bool) -> Result<u8> {
+        let mut state = self.lock();
+
+        if as_data {
+            Ok(state.read_data())
+        } else {
+            // the busy flag is never set
+            Ok(state.registers.address)
+        }
+    }
+
+    fn can_read(&self) -> bool {
+        true
+    }
+
+    fn write_byte(&mut self, value: u8, as_data: bool) -> UnitResult {
+        let mut state = self.lock();
+
+        state.stats.record_byte(as_data);
+
+        if state.width == 4 {
+            state.latch(value & 0xF0, as_data);
+            state.latch(value << 4, as_data);
+        } else {
+            state.latch(value, as_data);
+        }
+
+        Ok(())
+    }
+
+    fn width(&self) -> usize {
+        self.lock().width
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        self.lock().stats.record_delay_us(us);
+    }
+
+    fn stats(&self) -> Stats {
+        self.lock().stats
+    }
+
+    fn reset_stats(&mut self) {
+        self.lock().stats.clear();
+    }
+
+    fn probe(&mut self) -> Option<bool> {
+        Some(true)
+    }
+}
+
+impl RawNibbleBus for SimulatedController {
+    /// On the 8-bit bus, the nibble goes onto `D7..D4` (with `D3..D0` pulled down).
+    fn write_raw_nibble(&mut self, nibble: u8, as_data: bool) -> UnitResult {
+        self.lock().latch((nibble & 0x0F) << 4, as_data);
+
+        Ok(())
+    }
+
+    fn write_raw_nibble_ex(&mut self, nibble: u8, as_data: bool, backlight: bool) -> UnitResult {
+        let mut state = self.lock();
+
+        state.backlight = backlight;
+        state.latch((nibble & 0x0F) << 4, as_data);
+
+        Ok(())
+    }
+}
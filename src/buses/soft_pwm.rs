@@ -0,0 +1,114 @@
+//! Provides a software PWM, generated by toggling a GPIO pin from a background thread.
+//!
+//! Meant for dimming the backlight, where an occasional jitter (caused by the thread not being
+//! scheduled exactly on time) is not noticeable.
+
+use rppal::gpio::{Gpio, Level};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::thread;
+use std::time::Duration;
+use super::super::{Error, Result, UnitResult};
+
+pub(crate) struct SoftPwm {
+    duty: Arc<AtomicU8>,
+    stopped: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl SoftPwm {
+    /// Length of a single PWM cycle (200 Hz - fast enough not to flicker).
+    const PERIOD_US: u32 = 5000;
+
+    /// Starts generating the PWM on given pin (which must be already configured as an output),
+    /// with given duty cycle (`0` - always low, `255` - always high).
+    pub fn start(pin: u8, duty: u8) -> Result<SoftPwm> {
+        let mut gpio = Gpio::new().map_err(Error::Gpio)?;
+
+        // the pins are reset by the bus' own instance
+        gpio.set_clear_on_drop(false);
+
+        let duty = Arc::new(AtomicU8::new(duty));
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let thread = {
+            let duty = duty.clone();
+            let stopped = stopped.clone();
+
+            thread::spawn(move || {
+                while !stopped.load(Ordering::Relaxed) {
+                    let high_us = SoftPwm::PERIOD_US * duty.load(Ordering::Relaxed) as u32 / 255;
+
+                    if high_us > 0 {
+                        gpio.write(pin, Level::High);
+                        thread::sleep(Duration::from_micros(high_us as u64));
+                    }
+
+                    if high_us < SoftPwm::PERIOD_US {
+                        gpio.write(pin, Level::Low);
+                        thread::sleep(Duration::from_micros((SoftPwm::PERIOD_US - high_us) as u64));
+                    }
+                }
+            })
+        };
+
+        Ok(SoftPwm {
+            duty,
+            stopped,
+            thread: Some(thread),
+        })
+    }
+
+    /// Changes the duty cycle, taking effect since the next PWM cycle.
+    pub fn set_duty(&self, duty: u8) {
+        self.duty.store(duty, Ordering::Relaxed);
+    }
+}
+
+impl Drop for SoftPwm {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// An output pin which can be fully on, fully off or dimmed (using the software PWM).
+pub(crate) struct DimmablePin {
+    pin: u8,
+
+    /// running only when the pin is neither fully on nor off
+    pwm: Option<SoftPwm>,
+}
+
+impl DimmablePin {
+    pub fn new(pin: u8) -> DimmablePin {
+        DimmablePin {
+            pin,
+            pwm: None,
+        }
+    }
+
+    /// Changes the pin's level (`0` - always low, `255` - always high).
+    pub fn set(&mut self, gpio: &Gpio, level: u8) -> UnitResult {
+        match level {
+            0 | 255 => {
+                // stop the PWM first, so that it doesn't overwrite the pin's state
+                self.pwm = None;
+                gpio.write(self.pin, if level > 0 { Level::High } else { Level::Low });
+            }
+
+            _ => {
+                if let Some(ref pwm) = self.pwm {
+                    pwm.set_duty(level);
+                } else {
+                    self.pwm = Some(SoftPwm::start(self.pin, level)?);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
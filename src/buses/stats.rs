@@ -0,0 +1,76 @@
+//! Gathers statistics about the traffic sent to the LCD - how many commands and data bytes have
+//! been transferred, how many transfers had to be retried and how much time has been spent on
+//! waiting for the LCD (see `Stats`).
+//!
+//! Unlike the `TimingReport`, these are plain counters - they do not require a clock, so they are
+//! available without the `std` feature too, and are cheap enough to be gathered all the time.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! lcd.reset_stats();
+//! lcd.render()?;
+//!
+//! println!("{}", lcd.inner().stats());
+//! ```
+
+use core::{fmt, time};
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// number of commands sent to the LCD
+    pub commands: u64,
+
+    /// number of data bytes sent to the LCD (characters and the custom characters' bitmaps)
+    pub data_bytes: u64,
+
+    /// number of failed transfers that have been retried (see `RetryPolicy`)
+    pub retries: u64,
+
+    /// total time the driver has been waiting for the LCD (as requested - the actual time may be
+    /// longer, depending on the `DelayStrategy`)
+    pub delay: time::Duration,
+}
+
+impl Stats {
+    /// Merges statistics from other collector into this one.
+    pub fn merge(&mut self, other: &Stats) {
+        self.commands += other.commands;
+        self.data_bytes += other.data_bytes;
+        self.retries += other.retries;
+        self.delay += other.delay;
+    }
+
+    /// Records that given byte has been sent to the LCD.
+    pub fn record_byte(&mut self, as_data: bool) {
+        if as_data {
+            self.data_bytes += 1;
+        } else {
+            self.commands += 1;
+        }
+    }
+
+    /// Records that the driver has waited given number of nanoseconds.
+    pub fn record_delay_ns(&mut self, ns: u32) {
+        self.delay += time::Duration::from_nanos(u64::from(ns));
+    }
+
+    /// Records that the driver has waited given number of microseconds.
+    pub fn record_delay_us(&mut self, us: u32) {
+        self.delay += time::Duration::from_micros(u64::from(us));
+    }
+
+    /// Forgets all the statistics gathered so far.
+    pub fn clear(&mut self) {
+        *self = Stats::default();
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:<12} {:>10}", "commands", self.commands)?;
+        writeln!(f, "{:<12} {:>10}", "data bytes", self.data_bytes)?;
+        writeln!(f, "{:<12} {:>10}", "retries", self.retries)?;
+        writeln!(f, "{:<12} {:>10}", "delay (us)", self.delay.as_micros())
+    }
+}
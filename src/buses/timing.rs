@@ -0,0 +1,330 @@
+//! Configures the delays HD44780 requires (see `Timings`) and gathers statistics about how much
+//! time the LCD operations actually take (see `TimingReport`).
+//!
+//! Each measured operation falls into one of two groups:
+//!
+//! - command classes (eg. `Clear` or `SetDDRamAddress`), measured end-to-end by the frontend,
+//!   including all the sleeps performed by the bus,
+//!
+//! - bus phases (eg. `EnablePulse` or `Transfer`), measured by the bus itself - they allow to
+//!   see whether the time goes into the I2C / GPIO syscalls or into the delays required by the
+//!   HD44780.
+//!
+//! Measuring requires a clock, so without the `std` feature nothing is recorded.
+
+use alloc::collections::BTreeMap;
+use core::{fmt, time};
+use super::command::Command;
+
+/// Delays the buses wait for the LCD to process data & commands.
+///
+/// The defaults follow the HD44780's datasheet; slow clones may need them lengthened, while the
+/// fast ones work fine with much shorter delays (making the refreshes faster).
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let bus = I2CBus::new_ex("/dev/i2c-1", 0x27, Timings {
+///     settle_us: 50,
+///     ..Timings::default()
+/// })?;
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct Timings {
+    /// How long the `RS` (and `R/W`) and the data pins are held before the `enable` pin gets
+    /// pulled up (the datasheet requires >40ns); zero skips the wait, relying on the time it
+    /// takes to switch the pins.
+    ///
+    /// Used only by the buses driving the pins directly (eg. the GPIO one) - on the other ones
+    /// each transfer takes way longer than that anyway.
+    pub setup_ns: u32,
+
+    /// How long the `enable` pin is kept pulled up.
+    pub enable_pulse_ns: u32,
+
+    /// How long to wait after each transfer (most of the commands and data need 37us).
+    pub settle_us: u32,
+
+    /// How long to wait after the `clear` and `home` commands.
+    pub clear_home_us: u32,
+
+    /// How long to wait for the LCD to power up, before the initialization sequence (the datasheet
+    /// requires >15ms after Vcc rises to 4.5V and >40ms after it rises to 2.7V).
+    pub power_on_us: u32,
+
+    /// How long to wait after the first step of the initialization sequence (the datasheet
+    /// requires >4.1ms).
+    pub init_first_us: u32,
+
+    /// How long to wait after each of the remaining steps of the initialization sequence.
+    pub init_us: u32,
+
+    /// How long the constructors of the buses wait for another process to release the LCD (see
+    /// the `lock` module) before giving up; zero (the default) makes them fail right away.
+    pub lock_wait_ms: u32,
+}
+
+impl Default for Timings {
+    fn default() -> Timings {
+        Timings {
+            setup_ns: 60,
+            enable_pulse_ns: 450,
+            settle_us: 37,
+            clear_home_us: 1000,
+            power_on_us: 40_000,
+            init_first_us: 4100,
+            init_us: 100,
+            lock_wait_ms: 0,
+        }
+    }
+}
+
+impl Timings {
+    /// Returns how long to wait after given step (counted from zero) of the initialization
+    /// sequence.
+    pub fn init_step_us(&self, step: usize) -> u32 {
+        if step == 0 {
+            self.init_first_us
+        } else {
+            self.init_us
+        }
+    }
+
+    /// Returns how long to wait for another process to release the LCD (see `lock_wait_ms`).
+    pub fn lock_wait(&self) -> time::Duration {
+        time::Duration::from_millis(self.lock_wait_ms.into())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Operation {
+    // -- command classes -- //
+    Initialize,
+    Clear,
+    Home,
+    SetEntryMode,
+    SetDisplayFlags,
+    Shift,
+    SetFunctions,
+    SetCGRamAddress,
+    SetDDRamAddress,
+    WriteData,
+
+    /// Command sent with `DirectLcd::write_raw_command`.
+    RawCommand,
+
+    // -- bus phases -- //
+
+    /// Sending a value to the I2C device / setting the GPIO pins.
+    Transfer,
+
+    /// Keeping the `enable` pin pulled up.
+    EnablePulse,
+
+    /// Waiting for the LCD to process a command or data.
+    Settle,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct OperationStats {
+    /// number of times the operation has been performed
+    pub count: u64,
+
+    /// total time spent on the operation
+    pub total: time::Duration,
+
+    /// shortest time the operation took
+    pub min: time::Duration,
+
+    /// longest time the operation took
+    pub max: time::Duration,
+}
+
+/// Tracks how long a single transfer takes, so that the part of the settle delays covered by the
+/// transfers anyway can be skipped (see `Bus::transfer_latency_us`).
+///
+/// The LCD cannot be sent the next command sooner than a transfer later, so on slow buses (eg.
+/// I2C at 100kHz, where a transfer takes ~90us) there's no point in waiting the whole 37us after
+/// each command.
+#[cfg(feature = "i2cdev")]
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Latency {
+    /// the shortest transfer seen so far (`None` when unknown)
+    shortest: Option<time::Duration>,
+
+    enabled: bool,
+}
+
+#[cfg(feature = "i2cdev")]
+impl Latency {
+    pub(crate) fn new() -> Latency {
+        Latency {
+            shortest: None,
+            enabled: true,
+        }
+    }
+
+    /// Records a transfer which has taken given time.
+    pub(crate) fn record(&mut self, elapsed: time::Duration) {
+        self.shortest = Some(self.shortest.map_or(elapsed, |shortest| shortest.min(elapsed)));
+    }
+
+    /// Enables or disables skipping the delays (when disabled, `us` returns zero).
+    pub(crate) fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Returns the (shortest) transfer's duration, in microseconds.
+    pub(crate) fn us(&self) -> u32 {
+        match self.shortest {
+            Some(shortest) if self.enabled => shortest.as_micros().min(u32::MAX as u128) as u32,
+            _ => 0,
+        }
+    }
+
+    /// Returns the part of given delay which the next transfer does not cover.
+    pub(crate) fn remaining_us(&self, delay_us: u32) -> u32 {
+        delay_us.saturating_sub(self.us())
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TimingReport {
+    stats: BTreeMap<Operation, OperationStats>,
+}
+
+impl<'a> From<&'a Command> for Operation {
+    fn from(command: &'a Command) -> Operation {
+        match *command {
+            Command::Clear => Operation::Clear,
+            Command::Home => Operation::Home,
+            Command::SetEntryMode { .. } => Operation::SetEntryMode,
+            Command::SetDisplayFlags { .. } => Operation::SetDisplayFlags,
+            Command::Shift { .. } => Operation::Shift,
+            Command::SetFunctions { .. } => Operation::SetFunctions,
+            Command::SetFunctionsExtended { .. } => Operation::SetFunctions,
+            Command::SetExtendedFunctions { .. } => Operation::SetFunctions,
+            Command::SetOledFunctions { .. } => Operation::SetFunctions,
+            Command::SetModePower { .. } => Operation::SetFunctions,
+            Command::SetCGRamAddress { .. } => Operation::SetCGRamAddress,
+            Command::SetDDRamAddress { .. } => Operation::SetDDRamAddress,
+        }
+    }
+}
+
+impl OperationStats {
+    /// Returns average time the operation took.
+    pub fn average(&self) -> time::Duration {
+        if self.count == 0 {
+            return time::Duration::new(0, 0);
+        }
+
+        let nanos = self.total.as_nanos() / u128::from(self.count);
+
+        time::Duration::new((nanos / 1_000_000_000) as u64, (nanos % 1_000_000_000) as u32)
+    }
+
+    fn record(&mut self, elapsed: time::Duration) {
+        self.count += 1;
+        self.total += elapsed;
+        self.min = self.min.min(elapsed);
+        self.max = self.max.max(elapsed);
+    }
+
+    fn merge(&mut self, other: &OperationStats) {
+        self.count += other.count;
+        self.total += other.total;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+}
+
+impl TimingReport {
+    /// Records that given operation took `elapsed` time.
+    pub fn record(&mut self, operation: Operation, elapsed: time::Duration) {
+        self.stats
+            .entry(operation)
+            .or_insert(OperationStats {
+                count: 0,
+                total: time::Duration::new(0, 0),
+                min: elapsed,
+                max: elapsed,
+            })
+            .record(elapsed);
+    }
+
+    /// Records that given operation has been started at `started` and has just finished.
+    #[cfg(feature = "std")]
+    pub fn record_since(&mut self, operation: Operation, started: ::std::time::Instant) {
+        self.record(operation, started.elapsed());
+    }
+
+    /// Merges statistics from other report into this one.
+    pub fn merge(&mut self, other: &TimingReport) {
+        for (operation, other_stats) in &other.stats {
+            self.stats
+                .entry(*operation)
+                .and_modify(|stats| stats.merge(other_stats))
+                .or_insert(*other_stats);
+        }
+    }
+
+    /// Returns statistics of given operation, if it has been performed at least once.
+    pub fn get(&self, operation: Operation) -> Option<&OperationStats> {
+        self.stats.get(&operation)
+    }
+
+    /// Returns statistics of all the operations performed so far.
+    pub fn iter(&self) -> impl Iterator<Item=(&Operation, &OperationStats)> {
+        self.stats.iter()
+    }
+
+    /// Forgets all the statistics gathered so far.
+    pub fn clear(&mut self) {
+        self.stats.clear();
+    }
+}
+
+impl fmt::Display for TimingReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:<16} {:>10} {:>12} {:>10} {:>10} {:>10}", "operation", "count", "total (us)", "avg (us)", "min (us)", "max (us)")?;
+
+        for (operation, stats) in &self.stats {
+            writeln!(
+                f,
+                "{:<16} {:>10} {:>12} {:>10} {:>10} {:>10}",
+                format!("{:?}", operation),
+                stats.count,
+                stats.total.as_micros(),
+                stats.average().as_micros(),
+                stats.min.as_micros(),
+                stats.max.as_micros(),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Measures time elapsed since its creation; a no-op without the `std` feature.
+pub(crate) struct Stopwatch {
+    #[cfg(feature = "std")]
+    started: ::std::time::Instant,
+}
+
+impl Stopwatch {
+    pub(crate) fn start() -> Stopwatch {
+        Stopwatch {
+            #[cfg(feature = "std")]
+            started: ::std::time::Instant::now(),
+        }
+    }
+
+    /// Records time elapsed since the stopwatch's creation as given operation.
+    #[cfg_attr(not(feature = "std"), allow(unused_variables))]
+    pub(crate) fn record(&self, report: &mut TimingReport, operation: Operation) {
+        #[cfg(feature = "std")]
+        report.record_since(operation, self.started);
+    }
+}
@@ -0,0 +1,197 @@
+//! Manages the LCD's custom characters (CGRAM slots).
+//!
+//! HD44780 is able to hold only 8 custom characters at once, so when many independent pieces of
+//! code (widgets, user code) want to use them, they have to agree on who's using which slot.
+//! `CharBank` solves this by handing out slots on demand:
+//!
+//! - identical bitmaps are deduplicated (they share a single slot),
+//! - when all slots are taken, the least recently used one is evicted and reused,
+//! - slots can be also reserved for good (eg. for widgets requiring a contiguous range of slots),
+//!   which excludes them from the eviction.
+//!
+//! # Caveats
+//!
+//! 1. Evicting a slot changes the glyph of all characters already printed using it - when using
+//!    many more bitmaps than there are slots, make sure to redraw the screen.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut bank = CharBank::new();
+//!
+//! let heart = bank.get(&mut lcd, HEART)?;
+//! lcd.print_char(heart)?;
+//!
+//! let graph = BarGraph::with_first_slot(1, 0, 16, bank.reserve(BarGraph::SLOTS)?)?;
+//! ```
+
+use alloc::vec::Vec;
+use super::{CustomChars, Result, UnitResult};
+use super::glyphs::Glyph;
+
+/// Number of custom characters HD44780 is able to hold.
+pub const SLOTS: usize = 8;
+
+#[derive(Default)]
+pub struct CharBank {
+    slots: [Slot; SLOTS],
+    clock: u64,
+}
+
+#[derive(Copy, Clone, Default)]
+struct Slot {
+    /// bitmap assigned to the slot (if any)
+    bitmap: Option<[u8; 8]>,
+
+    /// whether the bitmap has been already uploaded to the LCD
+    uploaded: bool,
+
+    /// whether the slot has been reserved (and thus cannot be evicted)
+    reserved: bool,
+
+    /// the last time the slot has been used (as counted by `CharBank::clock`)
+    last_used: u64,
+}
+
+impl CharBank {
+    /// Creates a new bank, assuming all the slots are free.
+    pub fn new() -> CharBank {
+        CharBank::default()
+    }
+
+    /// Returns index of the slot holding given bitmap, uploading it first if necessary.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when all the slots are reserved or when the upload fails.
+    pub fn get<L: CustomChars>(&mut self, lcd: &mut L, bitmap: [u8; 8]) -> Result<u8> {
+        self.clock += 1;
+
+        let idx = match self.lookup(bitmap) {
+            Some(idx) => idx as usize,
+            None => self.evict()?,
+        };
+
+        let slot = &mut self.slots[idx];
+
+        if slot.bitmap != Some(bitmap) || !slot.uploaded {
+            lcd.create_char(idx as u8, bitmap)?;
+
+            slot.bitmap = Some(bitmap);
+            slot.uploaded = true;
+        }
+
+        slot.last_used = self.clock;
+
+        Ok(idx as u8)
+    }
+
+    /// Uploads given glyphs (see the `glyphs` module) and returns their slots, in the same order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the glyphs do not fit in the non-reserved slots all at once or when
+    /// the upload fails.
+    pub fn load_glyphs<L: CustomChars>(&mut self, lcd: &mut L, glyphs: &[Glyph]) -> Result<Vec<u8>> {
+        let mut distinct: Vec<Glyph> = glyphs.to_vec();
+
+        distinct.sort_unstable();
+        distinct.dedup();
+
+        let available = self.slots.iter().filter(|slot| !slot.reserved).count();
+
+        if distinct.len() > available {
+            return Err(format!("Tried to load {} distinct glyphs, but only {} custom characters are available.", distinct.len(), available).into());
+        }
+
+        glyphs
+            .iter()
+            .map(|&glyph| self.get(lcd, glyph))
+            .collect()
+    }
+
+    /// Returns index of the slot holding given bitmap, without uploading anything.
+    pub fn lookup(&self, bitmap: [u8; 8]) -> Option<u8> {
+        self.slots
+            .iter()
+            .position(|slot| !slot.reserved && slot.bitmap == Some(bitmap))
+            .map(|idx| idx as u8)
+    }
+
+    /// Reserves `count` contiguous slots for good and returns index of the first one.
+    ///
+    /// Reserved slots are never handed out by `get` - they are meant to be managed manually (eg.
+    /// by widgets requiring a few custom characters next to each other).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when there are not enough contiguous free (or evictable) slots.
+    pub fn reserve(&mut self, count: u8) -> Result<u8> {
+        let count = count as usize;
+
+        if count == 0 || count > SLOTS {
+            return Err(format!("Tried to reserve {} custom characters - at most {} can be reserved.", count, SLOTS).into());
+        }
+
+        // prefer free slots, then the least recently used ones
+        let first = (0..=(SLOTS - count))
+            .filter(|&first| self.slots[first..first + count].iter().all(|slot| !slot.reserved))
+            .min_by_key(|&first| {
+                self.slots[first..first + count]
+                    .iter()
+                    .map(|slot| if slot.bitmap.is_some() { slot.last_used + 1 } else { 0 })
+                    .max()
+            })
+            .ok_or("Not enough free custom characters to reserve.")?;
+
+        for slot in &mut self.slots[first..first + count] {
+            *slot = Slot {
+                reserved: true,
+                ..Slot::default()
+            };
+        }
+
+        Ok(first as u8)
+    }
+
+    /// Releases slots reserved using `reserve`.
+    pub fn release(&mut self, first: u8, count: u8) {
+        for slot in self.slots.iter_mut().skip(first as usize).take(count as usize) {
+            *slot = Slot::default();
+        }
+    }
+
+    /// Marks all the slots as not uploaded, so that they get uploaded again the next time they
+    /// are used (eg. after the LCD has been reinitialized).
+    pub fn invalidate(&mut self) {
+        for slot in &mut self.slots {
+            slot.uploaded = false;
+        }
+    }
+
+    /// Uploads again all the bitmaps the bank knows about (eg. after the LCD has been
+    /// reinitialized).
+    pub fn reupload<L: CustomChars>(&mut self, lcd: &mut L) -> UnitResult {
+        for (idx, slot) in self.slots.iter_mut().enumerate() {
+            if let Some(bitmap) = slot.bitmap {
+                lcd.create_char(idx as u8, bitmap)?;
+                slot.uploaded = true;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns index of a slot that can be used for a new bitmap.
+    fn evict(&self) -> Result<usize> {
+        let idx = self.slots
+            .iter()
+            .enumerate()
+            .filter(|&(_, slot)| !slot.reserved)
+            .min_by_key(|&(_, slot)| if slot.bitmap.is_some() { slot.last_used + 1 } else { 0 })
+            .map(|(idx, _)| idx)
+            .ok_or("All custom characters are reserved.")?;
+
+        Ok(idx)
+    }
+}
@@ -0,0 +1,96 @@
+//! Builds the custom characters' bitmaps from an "ASCII art", instead of hand-encoded binary
+//! lines.
+//!
+//! Each line of the art is a 5-characters long string, where `#`, `*` and `X` stand for a lit
+//! pixel and ` ` and `.` for an unlit one; up to 8 lines can be given (the missing ones at the
+//! bottom are left blank).
+//!
+//! The `char_bitmap!` macro builds the bitmap at compile time, so a malformed art fails the build
+//! instead of showing up as a garbled character.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! const HEART: [u8; 8] = CharBitmap::from_art(&[
+//!     "     ",
+//!     " # # ",
+//!     "#####",
+//!     "#####",
+//!     " ### ",
+//!     "  #  ",
+//! ]);
+//!
+//! lcd.create_char(0, HEART)?;
+//!
+//! lcd.create_char(1, char_bitmap![
+//!     "  #  ",
+//!     " ### ",
+//!     "#####",
+//! ])?;
+//! ```
+
+use super::Result;
+
+/// Width of a custom character, in pixels.
+pub const WIDTH: usize = 5;
+
+/// Height of a custom character, in pixels.
+pub const HEIGHT: usize = 8;
+
+pub struct CharBitmap;
+
+impl CharBitmap {
+    /// Builds a bitmap from given art.
+    ///
+    /// Can be used in constant expressions, in which case a malformed art fails the build.
+    ///
+    /// # Panics
+    ///
+    /// Panics when there are more than 8 lines, when any of them is not 5-characters long or
+    /// when it contains a character other than `#`, `*`, `X`, ` ` or `.` (see `try_from_art` for
+    /// a non-panicking version).
+    pub const fn from_art(art: &[&str]) -> [u8; 8] {
+        match CharBitmap::parse(art) {
+            Ok(lines) => lines,
+            Err(reason) => panic!("{}", reason),
+        }
+    }
+
+    /// Builds a bitmap from given art, returning an error when the art is malformed.
+    pub fn try_from_art(art: &[&str]) -> Result<[u8; 8]> {
+        CharBitmap::parse(art).map_err(|reason| format!("Invalid character art: {}", reason).into())
+    }
+
+    const fn parse(art: &[&str]) -> ::core::result::Result<[u8; 8], &'static str> {
+        if art.len() > HEIGHT {
+            return Err("the art must consist of at most 8 lines");
+        }
+
+        let mut lines = [0u8; 8];
+        let mut y = 0;
+
+        while y < art.len() {
+            let pixels = art[y].as_bytes();
+
+            if pixels.len() != WIDTH {
+                return Err("each line of the art must be exactly 5 characters long");
+            }
+
+            let mut x = 0;
+
+            while x < WIDTH {
+                match pixels[x] {
+                    b'#' | b'*' | b'X' => lines[y] |= 1 << (WIDTH - 1 - x),
+                    b' ' | b'.' => {}
+                    _ => return Err("the art must consist only of `#`, `*`, `X`, ` ` and `.`"),
+                }
+
+                x += 1;
+            }
+
+            y += 1;
+        }
+
+        Ok(lines)
+    }
+}
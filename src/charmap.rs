@@ -0,0 +1,359 @@
+//! Translates Unicode characters into the HD44780's character ROM codes.
+//!
+//! HD44780 comes with one of two character ROMs:
+//!
+//! - `A00` (Japanese) - ASCII, katakana and a handful of Greek / mathematical symbols (both the
+//!   full-width and half-width katakana are supported - the voiced ones, eg. `ガ`, are printed as
+//!   two characters: `ｶﾞ`),
+//! - `A02` (European) - ASCII, most of the accented Latin characters (laid out similarly to the
+//!   ISO-8859-1), the Cyrillic uppercase letters, some Greek letters, arrows and other symbols.
+//!
+//! Some displays come with a third, Cyrillic ROM instead (see `Rom::Cyrillic`); on the other ones
+//! Russian text can be transliterated (see `Fallback::Transliterate`).
+//!
+//! The ROMs' contents are described by the code pages (see the `code_page` module).
+//!
+//! Characters that are not present in the ROM are replaced with a question mark, unless a different
+//! `Fallback` is chosen (see `PrintOptions`).
+//!
+//! Text coming from untrusted sources (eg. song titles or user names received over the network)
+//! can be additionally cleaned of the control and the invisible formatting characters - see
+//! `Sanitize`.
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use super::{Result, UnitResult};
+use super::code_page::CodePage;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Rom {
+    #[default]
+    A00,
+    A02,
+
+    /// Cyrillic ROM of the Russian-market displays (eg. Winstar's WH1602B-xxx-CTK)
+    Cyrillic,
+}
+
+/// Character printed in place of the ones not present in the ROM.
+pub const REPLACEMENT_CHAR: u8 = b'?';
+
+/// What to print in place of the characters not present in the ROM.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Fallback {
+    /// Print given ROM code instead (`REPLACEMENT_CHAR` by default).
+    Replace(u8),
+
+    /// Print nothing.
+    Skip,
+
+    /// Print the closest ASCII approximation (eg. `e` for `ę`, `ss` for `ß`), or
+    /// `REPLACEMENT_CHAR` when there's none.
+    Transliterate,
+
+    /// Fail with an error.
+    Error,
+}
+
+impl Default for Fallback {
+    fn default() -> Fallback {
+        Fallback::Replace(REPLACEMENT_CHAR)
+    }
+}
+
+/// What to do with the control characters (`\0`, `\n`, `\x1B` etc.) and the invisible formatting
+/// ones (zero-width spaces, bidi overrides etc.) found in the printed text.
+///
+/// When enabled (see `PrintOptions::sanitize`), the sanitizer runs before anything else - so
+/// untrusted text is neither able to move the cursor (even with `PrintOptions::control_chars`
+/// enabled), nor to show up as question marks in place of characters the user cannot see.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum Sanitize {
+    /// Leave the text as it is.
+    #[default]
+    Off,
+
+    /// Remove the characters.
+    Strip,
+
+    /// Replace the characters with given one (eg. a space).
+    Replace(char),
+}
+
+impl Sanitize {
+    /// Returns what should be printed in place of given character (`None` when it should be
+    /// skipped).
+    pub fn sanitize_char(self, ch: char) -> Option<char> {
+        if self == Sanitize::Off || !is_unsafe(ch) {
+            return Some(ch);
+        }
+
+        match self {
+            Sanitize::Replace(replacement) => Some(replacement),
+            _ => None,
+        }
+    }
+
+    /// Returns given text sanitized (borrowed when there was nothing to change).
+    pub fn apply(self, text: &str) -> Cow<'_, str> {
+        if self == Sanitize::Off || !text.chars().any(is_unsafe) {
+            return Cow::Borrowed(text);
+        }
+
+        Cow::Owned(text.chars().filter_map(|ch| self.sanitize_char(ch)).collect::<String>())
+    }
+}
+
+/// Returns whether given character is a control one or an invisible formatting one.
+fn is_unsafe(ch: char) -> bool {
+    ch.is_control() || matches!(ch,
+        '\u{AD}' | '\u{61C}' | '\u{180E}' | '\u{200B}'..='\u{200F}' | '\u{202A}'..='\u{202E}' |
+        '\u{2060}'..='\u{2064}' | '\u{2066}'..='\u{206F}' | '\u{FEFF}' | '\u{FFF9}'..='\u{FFFB}'
+    )
+}
+
+impl Rom {
+    /// Returns the code page describing the ROM's contents.
+    pub fn code_page(&self) -> CodePage {
+        CodePage::from(*self)
+    }
+
+    /// Returns ROM code of given character or `None` if the ROM does not contain it.
+    pub fn translate(&self, ch: char) -> Option<u8> {
+        self.code_page().translate(ch)
+    }
+
+    /// Returns ROM code of given character or `REPLACEMENT_CHAR` if the ROM does not contain it.
+    pub fn encode(&self, ch: char) -> u8 {
+        self.translate(ch).unwrap_or(REPLACEMENT_CHAR)
+    }
+
+    /// Encodes given character, handling the ones not present in the ROM according to given
+    /// fallback; `f` is called with each resulting code (there might be none or a few of them).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the character is not present in the ROM and the fallback is
+    /// `Fallback::Error`, or when `f` fails.
+    pub fn encode_with<F>(&self, ch: char, fallback: Fallback, mut f: F) -> UnitResult where F: FnMut(u8) -> UnitResult {
+        if let Some(code) = self.translate(ch) {
+            return f(code);
+        }
+
+        if *self == Rom::A00 {
+            if let Some((code, Some(mark))) = katakana(ch) {
+                f(code)?;
+                return f(mark);
+            }
+        }
+
+        match fallback {
+            Fallback::Replace(code) => f(code),
+            Fallback::Skip => Ok(()),
+
+            Fallback::Transliterate => {
+                match transliterate(ch) {
+                    Some(approximation) => {
+                        approximation
+                            .chars()
+                            .try_for_each(|ch| f(self.encode(ch)))
+                    }
+
+                    None => f(REPLACEMENT_CHAR),
+                }
+            }
+
+            Fallback::Error => {
+                Err(format!("Character `{}` (U+{:04X}) is not present in the {:?} ROM.", ch, ch as u32, self).into())
+            }
+        }
+    }
+
+    /// Encodes given text (see `encode_with`), cutting it so that it takes at most `width`
+    /// characters on the screen - when the text does not fit, its last character is replaced with
+    /// given ellipsis (if any; eg. `>` - note that A00 has no tilde).
+    ///
+    /// The width is measured after the translation, so characters printed as many ROM codes (eg.
+    /// the voiced katakana or the transliterated ones) are accounted for properly - and never cut
+    /// in half.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // `Wi-Fi: MyVeryLongN>`
+    /// let codes = lcd.rom().fit("Wi-Fi: MyVeryLongNetworkName", 20, Fallback::default(), Some('>'))?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a character is not present in the ROM and the fallback is
+    /// `Fallback::Error`.
+    pub fn fit(&self, text: &str, width: usize, fallback: Fallback, ellipsis: Option<char>) -> Result<Vec<u8>> {
+        let mut codes = Vec::with_capacity(width);
+
+        // codes of the character being currently encoded
+        let mut char_codes = Vec::with_capacity(2);
+
+        // length of `codes` before each of the characters (so that the ellipsis can take the
+        // place of the last whole character)
+        let mut boundaries = Vec::new();
+
+        for ch in text.chars() {
+            char_codes.clear();
+
+            self.encode_with(ch, fallback, |code| {
+                char_codes.push(code);
+                Ok(())
+            })?;
+
+            if codes.len() + char_codes.len() > width {
+                if let Some(ellipsis) = ellipsis {
+                    // make room for the ellipsis, removing as many whole characters as needed
+                    while codes.len() + 1 > width {
+                        match boundaries.pop() {
+                            Some(len) => codes.truncate(len),
+                            None => break,
+                        }
+                    }
+
+                    if codes.len() < width {
+                        codes.push(self.encode(ellipsis));
+                    }
+                }
+
+                return Ok(codes);
+            }
+
+            boundaries.push(codes.len());
+            codes.extend_from_slice(&char_codes);
+        }
+
+        Ok(codes)
+    }
+
+    /// Returns character represented by given ROM code or `None` if it has no known Unicode
+    /// counterpart (eg. custom characters).
+    pub fn decode(&self, code: u8) -> Option<char> {
+        self.code_page().decode(code)
+    }
+}
+
+/// Katakana (and the Japanese punctuation) present in the A00, in the order of their codes
+/// (starting at `0xA1`).
+const KATAKANA: &str = "。「」、・ヲァィゥェォャュョッーアイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワン゛゜";
+
+/// Voiced katakana (`゛`) and their unvoiced counterparts.
+const VOICED: (&str, &str) = ("ガギグゲゴザジズゼゾダヂヅデドバビブベボヴヷヺ", "カキクケコサシスセソタチツテトハヒフヘホウワヲ");
+
+/// Semi-voiced katakana (`゜`) and their unvoiced counterparts.
+const SEMI_VOICED: (&str, &str) = ("パピプペポ", "ハヒフヘホ");
+
+/// Returns the A00's code of given katakana (full-width or half-width) - along with the code of
+/// the voiced / semi-voiced mark which has to be printed right after it, for the characters that
+/// require one (eg. `ガ` is printed as `ｶﾞ`).
+///
+/// # Example
+///
+/// ```rust,ignore
+/// assert_eq!(charmap::katakana('カ'), Some((0xB6, None)));
+/// assert_eq!(charmap::katakana('ガ'), Some((0xB6, Some(0xDE))));
+/// ```
+pub fn katakana(ch: char) -> Option<(u8, Option<u8>)> {
+    // the half-width forms are laid out just like in the ROM
+    if ('\u{FF61}'..='\u{FF9F}').contains(&ch) {
+        return Some(((ch as u32 - 0xFF61 + 0xA1) as u8, None));
+    }
+
+    let code = |ch: char| {
+        KATAKANA
+            .chars()
+            .position(|other| other == ch)
+            .map(|idx| 0xA1 + idx as u8)
+    };
+
+    if let Some(code) = code(ch) {
+        return Some((code, None));
+    }
+
+    for &((marked, unmarked), mark) in &[(VOICED, 0xDE), (SEMI_VOICED, 0xDF)] {
+        if let Some(idx) = marked.chars().position(|other| other == ch) {
+            let base = unmarked.chars().nth(idx)?;
+            return Some((code(base)?, Some(mark)));
+        }
+    }
+
+    None
+}
+
+/// Returns the closest ASCII approximation of given character.
+fn transliterate(ch: char) -> Option<&'static str> {
+    let approximation = match ch {
+        'À'..='Å' | 'Ā' | 'Ă' | 'Ą' => "A",
+        'à'..='å' | 'ā' | 'ă' | 'ą' => "a",
+        'Æ' => "AE",
+        'æ' => "ae",
+        'Ç' | 'Ć' | 'Č' => "C",
+        'ç' | 'ć' | 'č' => "c",
+        'Ď' | 'Đ' | 'Ð' => "D",
+        'ď' | 'đ' | 'ð' => "d",
+        'È'..='Ë' | 'Ē' | 'Ė' | 'Ę' | 'Ě' => "E",
+        'è'..='ë' | 'ē' | 'ė' | 'ę' | 'ě' => "e",
+        'Ì'..='Ï' | 'Ī' | 'Į' | 'İ' => "I",
+        'ì'..='ï' | 'ī' | 'į' | 'ı' => "i",
+        'Ł' => "L",
+        'ł' => "l",
+        'Ñ' | 'Ń' | 'Ň' => "N",
+        'ñ' | 'ń' | 'ň' => "n",
+        'Ò'..='Ö' | 'Ø' | 'Ō' | 'Ő' => "O",
+        'ò'..='ö' | 'ø' | 'ō' | 'ő' => "o",
+        'Œ' => "OE",
+        'œ' => "oe",
+        'Ř' => "R",
+        'ř' => "r",
+        'Ś' | 'Š' | 'Ş' => "S",
+        'ś' | 'š' | 'ş' => "s",
+        'ß' => "ss",
+        'Ť' | 'Ţ' => "T",
+        'ť' | 'ţ' => "t",
+        'Ù'..='Ü' | 'Ū' | 'Ů' | 'Ű' => "U",
+        'ù'..='ü' | 'ū' | 'ů' | 'ű' => "u",
+        'Ý' | 'Ÿ' => "Y",
+        'ý' | 'ÿ' => "y",
+        'Ź' | 'Ż' | 'Ž' => "Z",
+        'ź' | 'ż' | 'ž' => "z",
+        'Þ' => "Th",
+        'þ' => "th",
+
+        '‘' | '’' | '‚' | '′' => "'",
+        '“' | '”' | '„' | '″' | '«' | '»' => "\"",
+        '‐' | '‑' | '‒' | '–' | '—' | '−' => "-",
+        '…' => "...",
+        '•' | '·' => ".",
+        '×' => "x",
+        '\\' => "/",
+        '~' => "-",
+        'А' => "A", 'Б' => "B", 'В' => "V", 'Г' => "G", 'Д' => "D", 'Е' => "E", 'Ё' => "Yo",
+        'Ж' => "Zh", 'З' => "Z", 'И' => "I", 'Й' => "Y", 'К' => "K", 'Л' => "L", 'М' => "M",
+        'Н' => "N", 'О' => "O", 'П' => "P", 'Р' => "R", 'С' => "S", 'Т' => "T", 'У' => "U",
+        'Ф' => "F", 'Х' => "Kh", 'Ц' => "Ts", 'Ч' => "Ch", 'Ш' => "Sh", 'Щ' => "Shch", 'Ъ' => "",
+        'Ы' => "Y", 'Ь' => "", 'Э' => "E", 'Ю' => "Yu", 'Я' => "Ya",
+        'а' => "a", 'б' => "b", 'в' => "v", 'г' => "g", 'д' => "d", 'е' => "e", 'ё' => "yo",
+        'ж' => "zh", 'з' => "z", 'и' => "i", 'й' => "y", 'к' => "k", 'л' => "l", 'м' => "m",
+        'н' => "n", 'о' => "o", 'п' => "p", 'р' => "r", 'с' => "s", 'т' => "t", 'у' => "u",
+        'ф' => "f", 'х' => "kh", 'ц' => "ts", 'ч' => "ch", 'ш' => "sh", 'щ' => "shch", 'ъ' => "",
+        'ы' => "y", 'ь' => "", 'э' => "e", 'ю' => "yu", 'я' => "ya",
+
+        '€' => "EUR",
+        '£' => "GBP",
+        '©' => "(c)",
+        '®' => "(R)",
+        '™' => "TM",
+        '\u{A0}' => " ",
+
+        _ => return None,
+    };
+
+    Some(approximation)
+}
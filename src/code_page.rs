@@ -0,0 +1,84 @@
+//! Provides the character ROMs' lookup tables - which Unicode character each ROM code shows.
+//!
+//! The tables are generated at build time from the plain-text descriptions kept in `codepages/`
+//! (see `codepages/README.md` there), so adding a code page does not require writing any lookup
+//! code by hand.
+//!
+//! Most of the time there's no need to use the code pages directly - `Rom::translate` and
+//! `Rom::decode` do it; the characters printed as many codes (eg. the voiced katakana) and the
+//! fallbacks (see `Fallback`) are handled by the `Rom`, too.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! assert_eq!(CodePage::A02.translate('é'), Some(0xE9));
+//! assert_eq!(CodePage::Cyrillic.decode(0xA0), Some('Б'));
+//!
+//! // all the characters a display is able to show
+//! let supported: String = CodePage::from(lcd.rom()).chars().map(|(ch, _)| ch).collect();
+//! ```
+
+use super::Rom;
+
+include!(concat!(env!("OUT_DIR"), "/code_pages.rs"));
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CodePage {
+    /// Japanese ROM (see `Rom::A00`)
+    A00,
+
+    /// European ROM (see `Rom::A02`)
+    A02,
+
+    /// Cyrillic ROM (see `Rom::Cyrillic`)
+    Cyrillic,
+}
+
+impl CodePage {
+    /// All the supported code pages.
+    pub const ALL: [CodePage; 3] = [CodePage::A00, CodePage::A02, CodePage::Cyrillic];
+
+    /// Returns ROM code of given character or `None` if the code page does not contain it.
+    pub fn translate(self, ch: char) -> Option<u8> {
+        let (encode, _) = self.tables();
+
+        encode
+            .binary_search_by_key(&ch, |&(other, _)| other)
+            .ok()
+            .map(|idx| encode[idx].1)
+    }
+
+    /// Returns character shown by given ROM code or `None` if it has no known Unicode
+    /// counterpart (eg. custom characters).
+    pub fn decode(self, code: u8) -> Option<char> {
+        let (_, decode) = self.tables();
+
+        decode[code as usize]
+    }
+
+    /// Returns all the characters present in the code page along with their codes, sorted by
+    /// the characters (a code might show a few of them - eg. the half-width and full-width
+    /// katakana).
+    pub fn chars(self) -> impl Iterator<Item = (char, u8)> {
+        self.tables().0.iter().copied()
+    }
+
+    fn tables(self) -> (&'static [(char, u8)], &'static [Option<char>; 256]) {
+        match self {
+            CodePage::A00 => (&A00_ENCODE, &A00_DECODE),
+            CodePage::A02 => (&A02_ENCODE, &A02_DECODE),
+            CodePage::Cyrillic => (&CYRILLIC_ENCODE, &CYRILLIC_DECODE),
+        }
+    }
+}
+
+impl From<Rom> for CodePage {
+    fn from(rom: Rom) -> CodePage {
+        match rom {
+            Rom::A00 => CodePage::A00,
+            Rom::A02 => CodePage::A02,
+            Rom::Cyrillic => CodePage::Cyrillic,
+        }
+    }
+}
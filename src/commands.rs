@@ -0,0 +1,74 @@
+//! Executes the single-line text commands (eg. `print_at 1 0 Hello`) - shared by the `Daemon`
+//! and the `MqttBridge`; see the `daemon` module for the list of commands.
+
+use alloc::string::ToString;
+use super::{Backlight, Hd44780, Hd44780Ext, Result, UnitResult};
+use super::frontends::Buffered;
+
+/// Executes given command.
+pub(crate) fn execute(lcd: &mut Buffered, line: &str) -> UnitResult {
+    let (command, args) = line
+        .split_once(' ')
+        .unwrap_or((line, ""));
+
+    // splits off the next argument, leaving the rest of the line
+    let mut args = args;
+    let mut next = || -> Result<usize> {
+        let (arg, rest) = args
+            .trim_start()
+            .split_once(' ')
+            .unwrap_or((args.trim_start(), ""));
+
+        args = rest;
+
+        Ok(arg.parse()?)
+    };
+
+    match command {
+        "print" => {
+            lcd.print(args)
+        }
+
+        "print_at" => {
+            let y = next()?;
+            let x = next()?;
+
+            lcd.print_at(y, x, args.to_string())
+        }
+
+        "print_line" => {
+            let y = next()?;
+
+            lcd.print_line(y, args.to_string())
+        }
+
+        "goto" => {
+            let y = next()?;
+            let x = next()?;
+
+            lcd.move_at(y, x)
+        }
+
+        "clear" => {
+            lcd.clear()
+        }
+
+        "clear_line" => {
+            let y = next()?;
+
+            lcd.clear_line(y)
+        }
+
+        "backlight" => {
+            match args.trim() {
+                "on" => lcd.set_backlight(true),
+                "off" => lcd.set_backlight(false),
+                _ => Err("expected `on` or `off`".into()),
+            }
+        }
+
+        command => {
+            Err(format!("unknown command: {}", command).into())
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! Provides a serializable description of the LCD - its bus, wiring and dimensions - so that the
+//! deployments can swap the wiring by editing a file instead of recompiling.
+//!
+//! Requires the `serde` feature (and `i2cdev` - or `raspberrypi`, for the GPIO bus); the format
+//! itself is up to the application - any `serde`-compatible one (TOML, JSON, YAML...) will do.
+//!
+//! # Example
+//!
+//! ```toml
+//! width = 20
+//! height = 4
+//!
+//! splash = "Booting..."
+//!
+//! [bus]
+//! kind = "i2c"
+//! device = "/dev/i2c-1"
+//! address = 0x27
+//!
+//! [timings]
+//! settle_us = 50
+//! ```
+//!
+//! ```rust,ignore
+//! let config: Config = toml::from_str(&fs::read_to_string("lcd.toml")?)?;
+//! let lcd = DirectLcd::from_config(&config)?;
+//! ```
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::time::Duration;
+use serde::{Deserialize, Serialize};
+use super::{Controller, Font, Geometry, Properties, Result, Rom};
+use super::buses::{Backpack, Bus, I2C, I2C8, Mcp23008, Timings};
+#[cfg(feature = "raspberrypi")]
+use super::buses::{Gpio4, gpio4::Pins};
+use super::frontends::Direct;
+use super::splash::Splash;
+
+#[derive(Serialize, Deserialize)]
+pub struct Config {
+    /// bus the LCD is connected through
+    pub bus: BusConfig,
+
+    /// number of characters per line
+    pub width: usize,
+
+    /// number of lines
+    pub height: usize,
+
+    /// LCD's font; when not given, it's inferred from the dimensions (just like in `Direct::new`)
+    #[serde(default)]
+    pub font: Option<Font>,
+
+    /// LCD's character ROM
+    #[serde(default)]
+    pub rom: Rom,
+
+    /// DDRAM addresses of the lines' beginnings (see `Properties::row_addresses`)
+    #[serde(default)]
+    pub row_addresses: Option<[u8; 4]>,
+
+    /// layout of the characters in the LCD's memory
+    #[serde(default)]
+    pub geometry: Geometry,
+
+    /// LCD's controller
+    #[serde(default)]
+    pub controller: Controller,
+
+    /// bus' delays; when not given, the ones recommended for the controller are used (see
+    /// `ControllerProfile::timings`)
+    #[serde(default)]
+    pub timings: Option<Timings>,
+
+    /// message shown right after the initialization (lines are separated with `\n`; see `Splash`)
+    #[serde(default)]
+    pub splash: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum BusConfig {
+    /// I2C bus (see `buses::I2C`)
+    I2c {
+        /// path to the I2C device (eg. `/dev/i2c-1`)
+        device: String,
+
+        /// 7-bit address of the LCD's backpack (eg. `0x27`)
+        address: u16,
+    },
+
+    /// 8-bit I2C bus, for the PCF8575-based backpacks (see `buses::I2C8`)
+    I2c8 {
+        /// path to the I2C device (eg. `/dev/i2c-1`)
+        device: String,
+
+        /// 7-bit address of the LCD's backpack (eg. `0x20`)
+        address: u16,
+    },
+
+    /// I2C bus, for the MCP23008-based backpacks (see `buses::Mcp23008`)
+    Mcp23008 {
+        /// path to the I2C device (eg. `/dev/i2c-1`)
+        device: String,
+
+        /// 7-bit address of the LCD's backpack (eg. `0x20`)
+        address: u16,
+    },
+
+    /// I2C bus, for whichever backpack is found first (see `Backpack::auto`)
+    Auto {
+        /// path to the I2C device (eg. `/dev/i2c-1`)
+        device: String,
+    },
+
+    /// 4-bit GPIO bus (see `buses::Gpio4`; requires the `raspberrypi` feature)
+    #[cfg(feature = "raspberrypi")]
+    Gpio(Pins),
+}
+
+impl Config {
+    /// Returns the LCD's properties described by this config.
+    pub fn properties(&self) -> Properties {
+        let mut properties = Properties {
+            height: self.height,
+            width: self.width,
+
+            font: Font::Font5x8,
+            rom: self.rom,
+            row_addresses: self.row_addresses,
+            geometry: self.geometry,
+            controller: self.controller,
+        };
+
+        properties.font = match self.font {
+            Some(font) => font,
+            None if properties.controller_lines() == 1 => Font::Font5x10,
+            None => Font::Font5x8,
+        };
+
+        properties
+    }
+
+    /// Constructs the bus described by this config.
+    pub fn bus(&self) -> Result<Box<dyn Bus>> {
+        let timings = self.timings.unwrap_or_else(|| self.controller.profile().timings);
+
+        Ok(match &self.bus {
+            BusConfig::I2c { device, address } => {
+                Box::new(I2C::new_ex(device, *address, timings)?)
+            }
+
+            BusConfig::I2c8 { device, address } => {
+                Box::new(I2C8::new_ex(device, *address, timings)?)
+            }
+
+            BusConfig::Mcp23008 { device, address } => {
+                Box::new(Mcp23008::new_ex(device, *address, timings)?)
+            }
+
+            BusConfig::Auto { device } => {
+                Backpack::auto_ex(device, timings)?
+            }
+
+            #[cfg(feature = "raspberrypi")]
+            BusConfig::Gpio(pins) => {
+                Box::new(Gpio4::new_ex(pins.clone(), timings)?)
+            }
+        })
+    }
+}
+
+impl Direct {
+    /// Creates a new direct HD44780, as described by given config.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when the config is invalid (eg. the pins overlap or the
+    /// dimensions are not supported).
+    ///
+    /// When the config has a splash, it's shown straight away - and stays on the screen until
+    /// the application draws something else.
+    pub fn from_config(config: &Config) -> Result<Direct> {
+        let mut lcd = Direct::new_ex(config.bus()?, config.properties())?;
+
+        if let Some(splash) = &config.splash {
+            Splash::new(splash.as_str(), Duration::from_secs(0)).draw(&mut lcd)?;
+        }
+
+        Ok(lcd)
+    }
+}
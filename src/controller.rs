@@ -0,0 +1,196 @@
+//! Describes the HD44780's clones - each one gets a profile bundling what sets it apart (the
+//! extra initialization commands, the delays, the lines' addresses and the supported features),
+//! so that a clone-specific fix has a single place to live in.
+//!
+//! The controller is selected through `Properties::controller`:
+//!
+//! ```rust,ignore
+//! let mut properties = Properties::new(20, 4);
+//!
+//! properties.controller = Controller::St7066u;
+//!
+//! let bus = I2CBus::new_ex("/dev/i2c-1", 0x27, properties.controller.profile().timings)?;
+//! let lcd = DirectLcd::new_ex(Box::new(bus), properties)?;
+//! ```
+
+use alloc::vec::Vec;
+use super::{Font, Properties, Rom};
+use super::buses::Timings;
+use super::buses::command::Command;
+
+/// The LCD's controller - the HD44780 itself or one of its clones.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+pub enum Controller {
+    /// HD44780U or a fully compatible clone (eg. KS0066).
+    #[default]
+    Hd44780,
+
+    /// Sunplus SPLC780D.
+    Splc780d,
+
+    /// Sitronix ST7066U.
+    St7066u,
+
+    /// KS0073 (and the compatible ones, eg. SSD1803) - 4-line displays driven by it have to be
+    /// switched into the 4-line mode through the extended instruction set, with the lines
+    /// starting at `0x00`, `0x20`, `0x40` and `0x60`.
+    Ks0073,
+
+    /// Winstar WS0010 (and RS0010) - the character OLEDs' controller; it has no backlight (the
+    /// backlight's switch turns the panel's power on / off instead) and its memory is not
+    /// cleared on power-up, so the screen should be cleared right after creating the frontend.
+    Ws0010,
+}
+
+/// What sets a controller apart from the HD44780 (see `Controller::profile`).
+#[derive(Copy, Clone, Debug)]
+pub struct ControllerProfile {
+    /// controller's name, as printed in its datasheet
+    pub name: &'static str,
+
+    /// delays recommended by the controller's datasheet (to be given to the bus)
+    pub timings: Timings,
+
+    /// DDRAM addresses of the 4-line screens' lines, when they differ from the HD44780's layout
+    /// (see `Properties::row_addresses`)
+    pub four_line_addresses: Option<[u8; 4]>,
+
+    /// whether the 5x10 font is supported (in the 1-line mode)
+    pub font_5x10: bool,
+
+    /// whether the display has a backlight (unlike the OLEDs, which emit the light on their own)
+    pub backlight: bool,
+
+    /// commands configuring the controller during the initialization (see `init_sequence`)
+    init: fn(&Properties, bool) -> Vec<Command>,
+}
+
+impl Controller {
+    /// Returns the controller's profile.
+    pub fn profile(&self) -> ControllerProfile {
+        match self {
+            Controller::Hd44780 => ControllerProfile {
+                name: "HD44780U",
+                timings: Timings::default(),
+                four_line_addresses: None,
+                font_5x10: true,
+                backlight: true,
+                init: hd44780_init,
+            },
+
+            Controller::Splc780d => ControllerProfile {
+                name: "SPLC780D",
+                timings: Timings {
+                    clear_home_us: 1520,
+                    ..Timings::default()
+                },
+                four_line_addresses: None,
+                font_5x10: true,
+                backlight: true,
+                init: hd44780_init,
+            },
+
+            Controller::St7066u => ControllerProfile {
+                name: "ST7066U",
+                timings: Timings {
+                    clear_home_us: 1520,
+                    ..Timings::default()
+                },
+                four_line_addresses: None,
+                font_5x10: true,
+                backlight: true,
+                init: hd44780_init,
+            },
+
+            Controller::Ks0073 => ControllerProfile {
+                name: "KS0073",
+                timings: Timings {
+                    clear_home_us: 1530,
+                    ..Timings::default()
+                },
+                four_line_addresses: Some([0x00, 0x20, 0x40, 0x60]),
+
+                // the bit selecting the font is the `RE` bit for KS0073
+                font_5x10: false,
+
+                backlight: true,
+                init: ks0073_init,
+            },
+
+            Controller::Ws0010 => ControllerProfile {
+                name: "WS0010",
+
+                // the OLEDs are way faster than the LCDs, but the power-up takes longer
+                timings: Timings {
+                    power_on_us: 500_000,
+                    ..Timings::default()
+                },
+
+                four_line_addresses: None,
+                font_5x10: true,
+                backlight: false,
+                init: ws0010_init,
+            },
+        }
+    }
+}
+
+impl ControllerProfile {
+    /// Returns the commands configuring the controller for given properties - sent right after
+    /// the bus' initialization handshake (followed by the entry mode & display flags).
+    pub fn init_sequence(&self, properties: &Properties, eight_bit_bus: bool) -> Vec<Command> {
+        (self.init)(properties, eight_bit_bus)
+    }
+}
+
+fn function_set(properties: &Properties, eight_bit_bus: bool) -> Command {
+    Command::SetFunctions {
+        font_5x10: properties.font == Font::Font5x10,
+        height: properties.controller_lines(),
+        eight_bit_bus,
+    }
+}
+
+fn hd44780_init(properties: &Properties, eight_bit_bus: bool) -> Vec<Command> {
+    vec![function_set(properties, eight_bit_bus)]
+}
+
+/// KS0073's line mode lives in the extended instruction set, reachable with the `RE` bit set - the
+/// regular "function set" sent afterwards clears it back.
+fn ks0073_init(properties: &Properties, eight_bit_bus: bool) -> Vec<Command> {
+    vec![
+        Command::SetFunctionsExtended {
+            height: properties.controller_lines(),
+            eight_bit_bus,
+        },
+        Command::SetExtendedFunctions {
+            wide_font: false,
+            inverted_cursor: false,
+            four_lines: properties.is_four_line_mode(),
+        },
+        function_set(properties, eight_bit_bus),
+    ]
+}
+
+/// WS0010 selects the font table in the "function set" (instead of the font's size, which it
+/// supports anyway) and starts in the graphics mode with the panel's power off.
+fn ws0010_init(properties: &Properties, eight_bit_bus: bool) -> Vec<Command> {
+    let font_table = match properties.rom {
+        Rom::A00 => 0,
+        Rom::A02 => 1,
+        Rom::Cyrillic => 2,
+    };
+
+    vec![
+        Command::SetOledFunctions {
+            height: properties.controller_lines(),
+            eight_bit_bus,
+            font_table,
+        },
+        Command::SetModePower {
+            graphics: false,
+            power: true,
+        },
+    ]
+}
@@ -0,0 +1,129 @@
+//! Provides a daemon owning the LCD and accepting commands over a Unix domain socket - so that
+//! many processes can share a single display without fighting over the bus.
+//!
+//! Each command is a single line of text; the commands coming from different clients are
+//! executed one at a time (each one atomically), and each one is answered with either `ok` or
+//! `error: <reason>`.
+//!
+//! Requires the `daemon` feature.
+//!
+//! # Commands
+//!
+//! - `print <text>`,
+//! - `print_at <y> <x> <text>`,
+//! - `print_line <y> <text>` (replaces the whole line),
+//! - `goto <y> <x>`,
+//! - `clear`,
+//! - `clear_line <y>`,
+//! - `backlight <on|off>`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! Daemon::new(lcd).serve("/run/lcd.sock")?;
+//! ```
+//!
+//! ```text
+//! $ echo "print_line 0 Backup finished" | socat - UNIX-CONNECT:/run/lcd.sock
+//! ok
+//! ```
+
+use std::fs;
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
+use std::thread;
+use super::UnitResult;
+use super::commands;
+use super::frontends::{Buffered, Shared};
+
+pub struct Daemon {
+    lcd: Shared<Buffered>,
+}
+
+impl Daemon {
+    /// Creates a new daemon, taking over given LCD.
+    pub fn new(lcd: Buffered) -> Daemon {
+        Daemon {
+            lcd: Shared::new(lcd),
+        }
+    }
+
+    /// Listens on given socket and serves the clients (each one in a separate thread); never
+    /// returns, unless listening fails.
+    ///
+    /// A socket left over by a previous instance gets removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when given path is taken by something else than a stale socket - eg. a
+    /// regular file, or a socket another daemon is still listening on.
+    pub fn serve<P: AsRef<Path>>(&self, path: P) -> UnitResult {
+        let path = path.as_ref();
+
+        remove_stale_socket(path)?;
+
+        let listener = UnixListener::bind(path)?;
+
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let lcd = self.lcd.clone();
+
+            thread::spawn(move || {
+                let _ = handle(lcd, stream);
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Removes the socket left over by a previous instance, if there's one - but nothing else that
+/// might be living at given path.
+fn remove_stale_socket(path: &Path) -> UnitResult {
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err.into()),
+    };
+
+    if !metadata.file_type().is_socket() {
+        return Err(format!("Cannot listen on {}: the path exists and is not a socket", path.display()).into());
+    }
+
+    // a socket someone still accepts connections on is not a stale one
+    if UnixStream::connect(path).is_ok() {
+        return Err(format!("Cannot listen on {}: the socket is already in use", path.display()).into());
+    }
+
+    fs::remove_file(path)?;
+
+    Ok(())
+}
+
+/// Reads the commands and responds to them, until the client disconnects.
+fn handle(lcd: Shared<Buffered>, stream: UnixStream) -> UnitResult {
+    let mut writer = stream.try_clone()?;
+    let reader = BufReader::new(stream);
+
+    for line in reader.lines() {
+        let line = line?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let result = lcd.transaction(|lcd| {
+            commands::execute(lcd, &line)?;
+            lcd.render()
+        });
+
+        match result {
+            Ok(_) => writeln!(writer, "ok")?,
+            Err(err) => writeln!(writer, "error: {}", err)?,
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,147 @@
+//! Provides a way of waiting for the LCD to process commands.
+//!
+//! HD44780 requires the driver to wait after most of the operations (eg. ~37us after each
+//! command, ~1.5ms after clearing the screen); how exactly to wait depends on the platform - on
+//! Linux a regular `thread::sleep` does the job, while on bare-metal microcontrollers one would
+//! rather spin using a hardware timer.
+//!
+//! The buses wait using a `DelayStrategy` by default, but any implementation of `Delay` can be
+//! given to them instead (see eg. `I2C::set_delay`) - eg. a cycle-accurate one on an RTOS, or
+//! `NoDelay` in the tests, where waiting for real is just a waste of time.
+
+/// Something able to block for a given amount of time.
+pub trait Delay: Send {
+    /// Waits at least given number of nanoseconds.
+    fn delay_ns(&mut self, ns: u32);
+
+    /// Waits at least given number of microseconds.
+    fn delay_us(&mut self, us: u32) {
+        for _ in 0..(us / 1000) {
+            self.delay_ns(1000 * 1000);
+        }
+
+        self.delay_ns((us % 1000) * 1000);
+    }
+
+    /// Waits at least given number of milliseconds.
+    fn delay_ms(&mut self, ms: u32) {
+        for _ in 0..ms {
+            self.delay_us(1000);
+        }
+    }
+}
+
+/// Delay which does not wait at all - for the tests and simulators, where there's no real LCD to
+/// wait for.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct NoDelay;
+
+impl Delay for NoDelay {
+    fn delay_ns(&mut self, _: u32) {
+        //
+    }
+
+    fn delay_us(&mut self, _: u32) {
+        //
+    }
+
+    fn delay_ms(&mut self, _: u32) {
+        //
+    }
+}
+
+/// Delay implemented using `std::thread::sleep`.
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct StdDelay;
+
+#[cfg(feature = "std")]
+impl Delay for StdDelay {
+    fn delay_ns(&mut self, ns: u32) {
+        ::std::thread::sleep(::std::time::Duration::new(0, ns));
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        ::std::thread::sleep(::std::time::Duration::from_micros(u64::from(us)));
+    }
+
+    fn delay_ms(&mut self, ms: u32) {
+        ::std::thread::sleep(::std::time::Duration::from_millis(u64::from(ms)));
+    }
+}
+
+/// Delay implemented using the standard library, waiting in one of a few ways.
+///
+/// `thread::sleep` is cheap for the CPU, but (due to the scheduler's granularity) sleeping for
+/// eg. 450ns usually takes more like 50-100us - since each transferred byte requires a few such
+/// delays, refreshing the screen gets way slower than the LCD allows. Spinning is precise, at the
+/// cost of keeping a CPU core busy.
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum DelayStrategy {
+    /// Always sleeps (the default).
+    #[default]
+    Sleep,
+
+    /// Always spins.
+    Spin,
+
+    /// Spins for the short delays (below 1ms) and sleeps for the longer ones, spinning only
+    /// through the last bit of them.
+    Hybrid,
+}
+
+#[cfg(feature = "std")]
+impl DelayStrategy {
+    /// Delays shorter than this get spun through by the hybrid strategy.
+    const HYBRID_SPIN_THRESHOLD: ::std::time::Duration = ::std::time::Duration::from_millis(1);
+
+    /// Waits given amount of time.
+    pub fn delay(&self, duration: ::std::time::Duration) {
+        match *self {
+            DelayStrategy::Sleep => {
+                ::std::thread::sleep(duration);
+            }
+
+            DelayStrategy::Spin => {
+                spin(duration);
+            }
+
+            DelayStrategy::Hybrid => {
+                if duration < DelayStrategy::HYBRID_SPIN_THRESHOLD {
+                    spin(duration);
+                } else {
+                    let started = ::std::time::Instant::now();
+
+                    ::std::thread::sleep(duration - DelayStrategy::HYBRID_SPIN_THRESHOLD);
+                    spin(duration.saturating_sub(started.elapsed()));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Delay for DelayStrategy {
+    fn delay_ns(&mut self, ns: u32) {
+        self.delay(::std::time::Duration::from_nanos(u64::from(ns)));
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        self.delay(::std::time::Duration::from_micros(u64::from(us)));
+    }
+
+    fn delay_ms(&mut self, ms: u32) {
+        self.delay(::std::time::Duration::from_millis(u64::from(ms)));
+    }
+}
+
+/// Busy-waits given amount of time.
+#[cfg(feature = "std")]
+fn spin(duration: ::std::time::Duration) {
+    let started = ::std::time::Instant::now();
+
+    while started.elapsed() < duration {
+        ::core::hint::spin_loop();
+    }
+}
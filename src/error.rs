@@ -0,0 +1,155 @@
+//! Errors returned by the driver.
+//!
+//! Since all the functions return boxed errors, the ones listed here can be recovered using
+//! `downcast_ref`:
+//!
+//! ```rust,ignore
+//! match err.downcast_ref::<Error>() {
+//!     Some(Error::InvalidConfig { field, reason }) => {
+//!         // ...
+//!     }
+//!
+//!     Some(Error::ReadingNotSupported) => {
+//!         // ...
+//!     }
+//!
+//!     _ => {
+//!         // ...
+//!     }
+//! }
+//! ```
+//!
+//! The bus failures get wrapped with what was being done at the time (see `Error::Command` and
+//! `Error::RenderLine`) - the original error stays reachable through `source`.
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::error;
+use core::time::Duration;
+use super::buses::command::Command;
+
+/// Errors specific to this driver.
+///
+/// New variants may be added in the future, so matching on this enum requires a wildcard arm.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// Configuration passed to a bus or a frontend is invalid (eg. a pin does not exist).
+    #[error("Invalid configuration of `{field}`: {reason}")]
+    InvalidConfig {
+        field: &'static str,
+        reason: String,
+    },
+
+    /// The same pin has been given for two different purposes.
+    #[error("Invalid configuration of `{field}`: pin {pin} is already used as `{other_field}`")]
+    DuplicatePin {
+        field: &'static str,
+        other_field: &'static str,
+        pin: u8,
+    },
+
+    /// The screen's width or height is zero.
+    #[error("Invalid configuration of `properties`: dimensions must be non-zero (got width={width}, height={height})")]
+    InvalidDimensions {
+        width: usize,
+        height: usize,
+    },
+
+    /// Given custom character's index is out of range (`0..=max`).
+    #[error("Index out of range - character index must be in range <0, {max}>")]
+    CharIndexOutOfRange {
+        idx: u8,
+        max: u8,
+    },
+
+    /// The LCD (or its bus) is not able to read (eg. the `R/W` pin is not connected).
+    #[error("This LCD cannot be read from.")]
+    ReadingNotSupported,
+
+    /// The LCD (or its bus) is not able to control the contrast.
+    #[error("The bus does not support contrast control.")]
+    ContrastNotSupported,
+
+    /// The LCD's bus has no RGB backlight.
+    #[error("The bus has no RGB backlight.")]
+    RgbBacklightNotSupported,
+
+    /// The frontend is not able to scroll the display.
+    #[error("This LCD does not support scrolling the display.")]
+    ScrollingNotSupported,
+
+    /// Given operation has not finished in time (eg. the LCD has not cleared its busy flag).
+    #[error("{operation} has timed out after {}us", after.as_micros())]
+    Timeout {
+        operation: &'static str,
+        after: Duration,
+    },
+
+    /// A bus transfer has failed despite being retried (see `RetryPolicy`); `cause` is the error
+    /// returned by the last attempt.
+    #[error("Bus transfer has failed {attempts} times, last error: {cause}")]
+    RetriesExhausted {
+        attempts: u32,
+
+        #[source]
+        cause: Box<dyn error::Error>,
+    },
+
+    /// Sending given command to the LCD has failed; `source` is the error returned by the bus.
+    #[error("Command `{command:?}` has failed: {source}")]
+    Command {
+        command: Command,
+        source: Box<dyn error::Error>,
+    },
+
+    /// Rendering given line of the buffered frontend has failed; `source` is the error returned
+    /// by the underlying LCD.
+    #[error("Rendering line {y} has failed: {source}")]
+    RenderLine {
+        y: usize,
+        source: Box<dyn error::Error>,
+    },
+
+    /// The LCD is already being driven by another process (see the `buses::lock` module); `pid`
+    /// is its id, when known.
+    #[error(
+        "The LCD is already being driven by {} (locked through {lock})",
+        pid.map_or("another process".into(), |pid| format!("process {}", pid)),
+    )]
+    BusLocked {
+        lock: String,
+        pid: Option<u32>,
+    },
+
+    /// Accessing the GPIO has failed (eg. `/dev/gpiomem` is missing or not accessible).
+    #[cfg(feature = "raspberrypi")]
+    #[error("GPIO error: {0}")]
+    Gpio(#[from] ::rppal::gpio::Error),
+}
+
+impl Error {
+    pub(crate) fn invalid_config<T: Into<String>>(field: &'static str, reason: T) -> Error {
+        Error::InvalidConfig {
+            field,
+            reason: reason.into(),
+        }
+    }
+
+    pub(crate) fn command(command: Command, source: Box<dyn error::Error>) -> Error {
+        Error::Command { command, source }
+    }
+
+    pub(crate) fn render_line(y: usize, source: Box<dyn error::Error>) -> Error {
+        Error::RenderLine { y, source }
+    }
+
+    /// Checks given custom character's index (`0..=max`).
+    pub(crate) fn check_char_idx(idx: u8, max: u8) -> ::core::result::Result<(), Error> {
+        if idx > max {
+            return Err(Error::CharIndexOutOfRange { idx, max });
+        }
+
+        Ok(())
+    }
+}
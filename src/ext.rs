@@ -0,0 +1,386 @@
+//! Provides convenience methods available on every HD44780 frontend.
+//!
+//! The extension trait is sealed (it cannot be implemented outside of this crate) and
+//! blanket-implemented for all the `Hd44780`s, so that new methods can be added to it without
+//! breaking anyone's code.
+
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use core::fmt;
+use super::{check_region, Cells, Hd44780, Point, PrintOptions, Result, UnitResult};
+use super::number::NumberFormat;
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl<T: super::Hd44780> Sealed for T {}
+}
+
+pub trait Hd44780Ext: Hd44780 + sealed::Sealed {
+    /// Prints a string centered in given line.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// lcd.print_centered(0, "Hello World!");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when passed an invalid line or when the string is longer than the line.
+    fn print_centered<T: Into<String>>(&mut self, y: usize, str: T) -> UnitResult {
+        let str = str.into();
+        let x = self.width().saturating_sub(str.chars().count()) / 2;
+
+        self.print_str_at(y, x, &str)
+    }
+
+    /// Prints a string aligned to the right edge of given line.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// lcd.print_right(1, format!("{} rpm", rpm));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when passed an invalid line or when the string is longer than the line.
+    fn print_right<T: Into<String>>(&mut self, y: usize, str: T) -> UnitResult {
+        let str = str.into();
+        let x = self.width().saturating_sub(str.chars().count());
+
+        self.print_str_at(y, x, &str)
+    }
+
+    /// Replaces the whole line with given string - the string is truncated to the screen's width
+    /// and padded with spaces, so no leftovers from the previous contents remain.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// lcd.print_line(0, "Hello World!");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when passed an invalid line.
+    fn print_line<T: Into<String>>(&mut self, y: usize, str: T) -> UnitResult {
+        let width = self.width();
+
+        let line: String = str.into()
+            .chars()
+            .chain(::core::iter::repeat(' '))
+            .take(width)
+            .collect();
+
+        self.print_str_at(y, 0, &line)
+    }
+
+    /// Prints a string at current cursor's position, cutting it at the end of the line - when the
+    /// string does not fit, its last visible character is replaced with given ellipsis (if any;
+    /// see `Rom::fit`).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// lcd.move_at(1, 0)?;
+    /// lcd.print_truncated(song.title(), Some('>'))?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a character cannot be printed (see `Fallback::Error`).
+    fn print_truncated<T: Into<String>>(&mut self, str: T, ellipsis: Option<char>) -> UnitResult {
+        print_fitted(self, &str.into(), ellipsis)?;
+
+        Ok(())
+    }
+
+    /// Prints a string at current cursor's position (cutting it at the end of the line, just like
+    /// `print_truncated`) and returns how many characters have been printed and how many remain
+    /// on the line - so that the layout code does not have to repeat the width calculations.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// lcd.move_at(0, 0)?;
+    ///
+    /// let outcome = lcd.print_metered(label)?;
+    ///
+    /// // fill the rest of the line with dots
+    /// lcd.print(".".repeat(outcome.remaining))?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a character cannot be printed (see `Fallback::Error`).
+    fn print_metered<T: Into<String>>(&mut self, str: T) -> Result<PrintOutcome> {
+        print_fitted(self, &str.into(), None)
+    }
+
+    /// Moves the cursor at given position and returns a handle allowing to chain further calls,
+    /// so that composing a screen does not take a pair of `move_at` & `print` per value.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// lcd.at(0, 0)?.print("Temp: ")?.print(temp)?.print("C")?
+    ///    .at(1, 0)?.print("Hum: ")?.print(hum)?.print_char(b'%')?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when passed an invalid position.
+    fn at(&mut self, y: usize, x: usize) -> Result<Chain<'_, Self>> {
+        self.move_at(y, x)?;
+
+        Ok(Chain {
+            lcd: self,
+        })
+    }
+
+    /// Formats given arguments straight onto the screen at given position (without allocating),
+    /// truncating the text at the end of the line.
+    ///
+    /// Usually used through the `lcd_write!` macro.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// lcd.write_fmt_at(0, 1, format_args!("{:>4} rpm", rpm));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when passed an invalid coordinates.
+    fn write_fmt_at(&mut self, y: usize, x: usize, args: fmt::Arguments) -> UnitResult {
+        self.move_at(y, x)?;
+
+        let width = self.width();
+        let mut writer = Writer::new(self, width - x);
+
+        writer.write(args)?;
+
+        Ok(())
+    }
+
+    /// Returns an iterator over all the screen's positions, line by line.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// for point in lcd.cells() {
+    ///     lcd.print_char_at(point.y, point.x, b'#')?;
+    /// }
+    /// ```
+    fn cells(&self) -> Cells {
+        Point::region(Point::new(0, 0), Point::new(self.height().saturating_sub(1), self.width().saturating_sub(1)))
+    }
+
+    /// Returns an iterator over the positions of given line.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when passed an invalid line.
+    fn row_cells(&self, y: usize) -> Result<Cells> {
+        self.region_cells(Point::new(y, 0), Point::new(y, self.width().saturating_sub(1)))
+    }
+
+    /// Returns an iterator over the positions of given region (see `Point::region`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when passed an invalid coordinates or when `from` is not the top-left
+    /// corner of the region.
+    fn region_cells(&self, from: Point, to: Point) -> Result<Cells> {
+        check_region(self, from, to)?;
+
+        Ok(Point::region(from, to))
+    }
+
+    /// Prints given number at given position, formatted into a region of the format's width
+    /// (without allocating - see the `number` module).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// lcd.print_number_at(1, 10, rpm, &NumberFormat::new(6).thousands(','))?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the region does not fit on the screen.
+    fn print_number_at<T: Into<f64>>(&mut self, y: usize, x: usize, value: T, format: &NumberFormat) -> UnitResult {
+        if x + format.width() > self.width() {
+            return Err(
+                format!("Tried to print a number outside the screen (at y={}, x={}, width={}).", y, x, format.width()).into()
+            );
+        }
+
+        self.write_fmt_at(y, x, format_args!("{}", format.display(value.into())))
+    }
+
+    /// Formats given arguments straight onto given line (without allocating), replacing the whole
+    /// line - just like `print_line` does.
+    ///
+    /// Usually used through the `lcd_writeln!` macro.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when passed an invalid line.
+    fn write_line_fmt(&mut self, y: usize, args: fmt::Arguments) -> UnitResult {
+        self.move_at(y, 0)?;
+
+        let width = self.width();
+        let mut writer = Writer::new(self, width);
+
+        writer.write(args)?;
+
+        let remaining = writer.remaining;
+
+        for _ in 0..remaining {
+            self.print_char(b' ')?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: Hd44780> Hd44780Ext for T {}
+
+/// A handle returned by `Hd44780Ext::at`, printing at the LCD's cursor position.
+pub struct Chain<'a, L: ?Sized> {
+    lcd: &'a mut L,
+}
+
+impl<'a, L: Hd44780 + ?Sized> Chain<'a, L> {
+    /// Moves the cursor at given position.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when passed an invalid position.
+    pub fn at(self, y: usize, x: usize) -> Result<Chain<'a, L>> {
+        self.lcd.move_at(y, x)?;
+
+        Ok(self)
+    }
+
+    /// Prints given value (anything displayable - eg. a string or a number) at the cursor's
+    /// position.
+    pub fn print<T: fmt::Display>(self, value: T) -> Result<Chain<'a, L>> {
+        self.lcd.print_str(&value.to_string())?;
+
+        Ok(self)
+    }
+
+    /// Prints a single character (as a ROM code - eg. a custom character) at the cursor's
+    /// position.
+    pub fn print_char(self, ch: u8) -> Result<Chain<'a, L>> {
+        self.lcd.print_char(ch)?;
+
+        Ok(self)
+    }
+
+    /// Returns the LCD, for the calls not available on the chain.
+    pub fn lcd(self) -> &'a mut L {
+        self.lcd
+    }
+}
+
+/// What `Hd44780Ext::print_metered` has printed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PrintOutcome {
+    /// number of characters printed (as the ROM codes - so eg. a voiced katakana counts as two)
+    pub written: usize,
+
+    /// number of characters remaining on the line after the printed ones
+    pub remaining: usize,
+
+    /// whether the string has been cut, because it did not fit on the line
+    pub truncated: bool,
+}
+
+/// Prints given string at current cursor's position, cutting it at the end of the line (see
+/// `Rom::fit`).
+fn print_fitted<L: Hd44780 + ?Sized>(lcd: &mut L, str: &str, ellipsis: Option<char>) -> Result<PrintOutcome> {
+    let available = lcd.width().saturating_sub(lcd.cursor().x);
+    let PrintOptions { fallback, sanitize, .. } = lcd.print_options();
+    let str = sanitize.apply(str);
+    let str = str.as_ref();
+    let rom = lcd.rom();
+    let codes = rom.fit(str, available, fallback, ellipsis)?;
+
+    let mut len = 0;
+
+    for ch in str.chars() {
+        rom.encode_with(ch, fallback, |_| {
+            len += 1;
+            Ok(())
+        })?;
+    }
+
+    for &code in &codes {
+        lcd.print_char(code)?;
+    }
+
+    Ok(PrintOutcome {
+        written: codes.len(),
+        remaining: available - codes.len(),
+        truncated: len > available,
+    })
+}
+
+/// Adapts an LCD to `fmt::Write`, printing at most `remaining` characters.
+struct Writer<'a, L: ?Sized> {
+    lcd: &'a mut L,
+    remaining: usize,
+
+    /// error returned by the LCD (`fmt::Error` is not able to carry it)
+    error: Option<Box<dyn core::error::Error>>,
+}
+
+impl<'a, L: Hd44780 + ?Sized> Writer<'a, L> {
+    fn new(lcd: &'a mut L, remaining: usize) -> Writer<'a, L> {
+        Writer {
+            lcd,
+            remaining,
+            error: None,
+        }
+    }
+
+    fn write(&mut self, args: fmt::Arguments) -> UnitResult {
+        if fmt::Write::write_fmt(self, args).is_err() {
+            return Err(self.error.take().unwrap_or_else(|| "Formatting has failed.".into()));
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, L: Hd44780 + ?Sized> fmt::Write for Writer<'a, L> {
+    fn write_str(&mut self, str: &str) -> fmt::Result {
+        let rom = self.lcd.rom();
+        let PrintOptions { fallback, sanitize, .. } = self.lcd.print_options();
+
+        for ch in str.chars().filter_map(|ch| sanitize.sanitize_char(ch)) {
+            let (lcd, remaining) = (&mut *self.lcd, &mut self.remaining);
+
+            let result = rom.encode_with(ch, fallback, |code| {
+                if *remaining > 0 {
+                    lcd.print_char(code)?;
+                    *remaining -= 1;
+                }
+
+                Ok(())
+            });
+
+            if let Err(err) = result {
+                self.error = Some(err);
+                return Err(fmt::Error);
+            }
+        }
+
+        Ok(())
+    }
+}
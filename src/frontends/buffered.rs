@@ -1,30 +1,192 @@
-/// Provides a buffered access to the HD44780.
-///
-/// Can be used like a regular HD44780, except that one must manually call the `render` method at
-/// some point to actually refresh the screen.
-///
-/// # Additional methods
-///
-/// This frontend provides some new methods, which are not present in the direct one - namely:
-/// - `render`,
-/// - `println`.
-///
-/// # Caveats
-///
-/// 1. Although rendering the text requires a call to the `render` method, modifying the LCD's state
-///    does not. Thus calling eg. the `set_backlight` method results in an instant change. Same
-///    applies to `create_char` and a few other ones.
-///
-/// 2. `set_cursor_blinking` & `set_cursor_visible` do not play well with buffering and thus their
-///    usage is discouraged.
+//! Provides a buffered access to the HD44780.
+//!
+//! Can be used like a regular HD44780, except that one must manually call the `render` method at
+//! some point to actually refresh the screen.
+//!
+//! Only the characters that have changed since the previous `render` are sent to the LCD, so
+//! refreshing a mostly unchanged screen is cheap.
+//!
+//! # Additional methods
+//!
+//! This frontend provides some new methods, which are not present in the direct one - namely:
+//! - `render`,
+//! - `println`,
+//! - `set_line_ticker`,
+//! - `set_blinking`,
+//! - `set_overlay`,
+//! - `set_virtual_cursor`,
+//! - `bind`,
+//! - `set_verify`,
+//! - `set_screensaver`,
+//! - `set_move_cost`,
+//! - `set_lines` & `replace_screen`.
+//!
+//! # Caveats
+//!
+//! 1. Although rendering the text requires a call to the `render` method, modifying the LCD's state
+//!    does not. Thus calling eg. the `set_backlight` method results in an instant change. Same
+//!    applies to `create_char` and a few other ones.
+//!
+//! 2. `set_cursor_blinking` & `set_cursor_visible` do not play well with buffering and thus their
+//!    usage is discouraged - `set_virtual_cursor` draws a cursor that does.
 
-use super::Direct;
-use super::super::{Hd44780, Result, UnitResult};
+use super::{Direct, DropAction, Region};
+use super::super::{check_region, print_with, Backlight, Capabilities, CursorStyle, CustomChars, DisplayControl, Error, Hd44780, Point, PrintOptions, Result, Rom, UnitResult};
+use super::super::buses::{RgbBacklight, Stats, TimingReport};
+use super::super::screen::Screen;
+use super::super::widgets::Marquee;
+#[cfg(feature = "png")]
+use super::super::preview::PngPreview;
+#[cfg(feature = "std")]
+use super::super::binding::{Binding, Slot};
+#[cfg(feature = "std")]
+use super::super::layout::{pad, Align};
+#[cfg(feature = "std")]
+use std::sync::Arc;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::{fmt, mem};
+use core::time::Duration;
 
-pub struct Buffered {
-    lcd: Box<Direct>,
+/// Buffered frontend on top of another one (`Direct` by default, but any `Hd44780` will do - eg. a
+/// custom one in a `no_std` environment).
+pub struct Buffered<L = Direct> {
+    lcd: L,
     cursor: Cursor,
     buffer: Buffer,
+
+    /// lines scrolled automatically during each render (see `set_line_ticker`)
+    tickers: Vec<Option<LineTicker>>,
+
+    /// number of renders after which the tickers move by one character
+    ticker_period: usize,
+
+    /// number of spaces separating the end of the tickers' texts from their beginnings
+    ticker_gap: usize,
+
+    /// number of renders after which the blinking characters appear / disappear
+    blink_period: usize,
+
+    /// number of renders done so far (drives the blinking)
+    renders: usize,
+
+    virtual_cursor: Option<VirtualCursor>,
+
+    /// how many characters moving the cursor costs (see `set_move_cost`)
+    move_cost: usize,
+
+    /// whether the characters get read back after being rendered (see `set_verify`)
+    verify: bool,
+
+    /// number of mismatches found by the verification so far
+    mismatches: usize,
+
+    /// regions updated from the bindings during each render (see `bind`)
+    #[cfg(feature = "std")]
+    bindings: Vec<BoundRegion>,
+
+    /// number of renders without any changes after which the screen gets blanked (see
+    /// `set_screensaver`)
+    screensaver: Option<usize>,
+
+    /// turns the backlight on / off along with the screensaver (see `set_screensaver_backlight`)
+    screensaver_backlight: Option<fn(&mut L, bool) -> UnitResult>,
+
+    /// number of renders done since the buffer has changed the last time
+    idle_renders: usize,
+
+    /// buffer's contents as of the previous render (to tell whether it has changed since then)
+    snapshot: Vec<Vec<u8>>,
+
+    /// whether the screensaver is currently active
+    blanked: bool,
+
+    overflow: Overflow,
+    print_options: PrintOptions,
+
+    /// cursors of the regions created so far, as `(from, to, cursor, pending_new_line)` (see
+    /// `region`)
+    region_cursors: Vec<(Point, Point, Point, bool)>,
+}
+
+/// What happens when the text goes past the last line (see `Buffered::set_overflow`).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum Overflow {
+    /// The cursor moves back to the first line, overwriting what's there.
+    #[default]
+    Wrap,
+
+    /// All the lines are moved up by one (the first one disappears) and the cursor moves at the
+    /// beginning of the - now blank - last line, just like in a terminal.
+    Scroll,
+}
+
+/// Summary of a single render (see `Buffered::render`) - eg. for adapting the refresh rate to how
+/// much the screen changes, or for asserting that the renders send only what's needed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct RenderReport {
+    /// number of characters sent to the LCD (including the unchanged ones written through - see
+    /// `Buffered::set_move_cost` - and the ones rewritten by the verification)
+    pub cells: usize,
+
+    /// number of commands sent to the LCD (ie. the cursor's movements)
+    pub commands: usize,
+
+    /// time the render has taken (always zero without the `std` feature)
+    pub elapsed: Duration,
+}
+
+impl RenderReport {
+    /// Returns whether the render has changed anything on the screen.
+    pub fn changed(&self) -> bool {
+        self.cells > 0
+    }
+}
+
+/// A cursor drawn by the buffered frontend itself, at its logical cursor's position (see
+/// `Buffered::set_virtual_cursor`).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct VirtualCursor {
+    /// character the cursor is drawn with (as a ROM code)
+    pub glyph: u8,
+
+    /// whether the cursor alternates with the character beneath it (see
+    /// `Buffered::set_blink_period`)
+    pub blinking: bool,
+}
+
+impl VirtualCursor {
+    /// A steady underscore.
+    pub const UNDERLINE: VirtualCursor = VirtualCursor {
+        glyph: b'_',
+        blinking: false,
+    };
+
+    /// A blinking, filled box - just like the hardware one.
+    pub const BLOCK: VirtualCursor = VirtualCursor {
+        glyph: 0xFF,
+        blinking: true,
+    };
+}
+
+#[cfg(feature = "std")]
+struct BoundRegion {
+    y: usize,
+    x: usize,
+    width: usize,
+    align: Align,
+    slot: Arc<Slot>,
+
+    /// the binding's most recent value, already padded to the region's width
+    text: String,
+}
+
+struct LineTicker {
+    marquee: Marquee,
+
+    /// number of renders done since the ticker's been set
+    renders: usize,
 }
 
 struct Cursor {
@@ -34,69 +196,889 @@ struct Cursor {
 
 struct Buffer {
     lines: Vec<Vec<u8>>,
+
+    /// what's currently displayed on the screen (`None` when unknown)
+    rendered: Vec<Vec<Option<u8>>>,
+
+    /// which characters are blinking (see `Buffered::set_blinking`)
+    blinking: Vec<Vec<bool>>,
+
+    /// custom characters shown instead of the buffered ones (see `Buffered::set_overlay`)
+    overlays: Vec<Vec<Option<u8>>>,
+
     height: usize,
     width: usize,
 }
 
-impl Buffered {
+impl Buffered<Direct> {
+    /// Changes what happens to the screen when the frontend gets dropped (see
+    /// `Direct::on_drop`).
+    ///
+    /// The action is performed on the LCD's actual contents - so whatever has not been rendered
+    /// yet is not taken into account.
+    pub fn on_drop(&mut self, action: DropAction) {
+        self.lcd.on_drop(action);
+    }
+
     /// Creates a new buffered HD44780 basing on previously existing direct one.
+    ///
+    /// The box is kept for backwards compatibility - see `from_lcd` for a non-boxed version.
+    #[allow(clippy::boxed_local)]
     pub fn new(lcd: Box<Direct>) -> Result<Buffered> {
+        Ok(Buffered::from_lcd(*lcd))
+    }
+
+    /// Re-initializes the LCD (see `Direct::reinitialize`) and makes the next `render` refresh the
+    /// whole screen.
+    pub fn reinitialize(&mut self) -> UnitResult {
+        self.lcd.reinitialize()?;
+        self.invalidate();
+
+        Ok(())
+    }
+
+    /// Brings the LCD back in sync after an interrupted transfer (see `Direct::resync`) and makes
+    /// the next `render` refresh the whole screen (getting rid of the garbage shown meanwhile).
+    pub fn resync(&mut self) -> UnitResult {
+        self.lcd.resync()?;
+        self.invalidate();
+
+        Ok(())
+    }
+
+    /// Checks whether the LCD is connected (see `Direct::probe`).
+    pub fn probe(&mut self) -> Option<bool> {
+        self.lcd.probe()
+    }
+
+    /// Creates a 5x10 custom character (see `Direct::create_char_5x10`).
+    pub fn create_char_5x10(&mut self, idx: u8, lines: [u8; 10]) -> UnitResult {
+        self.lcd.create_char_5x10(idx, lines)
+    }
+
+    /// Returns how much time each operation has taken so far (see `Direct::timing_report`).
+    pub fn timing_report(&self) -> TimingReport {
+        self.lcd.timing_report()
+    }
+
+    /// Returns the traffic statistics gathered so far (see `Direct::stats`).
+    pub fn stats(&self) -> Stats {
+        self.lcd.stats()
+    }
+
+    /// Forgets all the traffic statistics gathered so far.
+    pub fn reset_stats(&mut self) {
+        self.lcd.reset_stats()
+    }
+
+    /// Renders the buffer's contents (including the custom characters) into a PNG image, using
+    /// the default look (see `PngPreview`).
+    ///
+    /// Requires the `png` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the image could not be written.
+    #[cfg(feature = "png")]
+    pub fn render_png<W: ::std::io::Write>(&self, writer: W) -> UnitResult {
+        PngPreview::default().render(&self.buffer.lines, self.lcd.custom_chars(), writer)
+    }
+
+    /// Returns a copy of the buffer's contents, including the custom characters (eg. to cache
+    /// the screen and restore it later with `Screen::render`).
+    pub fn screen(&self) -> Screen {
+        Screen::from_parts(self.buffer.lines.clone(), *self.lcd.custom_chars(), self.lcd.rom())
+    }
+}
+
+impl<L: Hd44780> Buffered<L> {
+    /// Creates a new buffered HD44780 on top of given frontend (without boxing it).
+    pub fn from_lcd(lcd: L) -> Buffered<L> {
         let (height, width) = (lcd.height(), lcd.width());
 
-        Ok(
-            Buffered {
-                lcd,
+        Buffered {
+            lcd,
+
+            cursor: Cursor {
+                y: 0,
+                x: 0,
+            },
+
+            buffer: Buffer {
+                lines: vec![vec![b' '; width]; height],
+                rendered: vec![vec![None; width]; height],
+                blinking: vec![vec![false; width]; height],
+                overlays: vec![vec![None; width]; height],
+                height,
+                width,
+            },
+
+            tickers: Vec::new(),
+            ticker_period: 1,
+            ticker_gap: 3,
+            blink_period: 1,
+            renders: 0,
+            virtual_cursor: None,
+            move_cost: 2,
+            verify: false,
+            mismatches: 0,
+            #[cfg(feature = "std")]
+            bindings: Vec::new(),
+            screensaver: None,
+            screensaver_backlight: None,
+            idle_renders: 0,
+            snapshot: Vec::new(),
+            blanked: false,
+            overflow: Overflow::Wrap,
+            print_options: PrintOptions::default(),
+            region_cursors: Vec::new(),
+        }
+    }
+
+    /// Returns the underlying frontend.
+    pub fn inner(&self) -> &L {
+        &self.lcd
+    }
+
+    /// Returns the underlying frontend; modifying the screen through it makes the buffer
+    /// out-of-date (see `invalidate`).
+    pub fn inner_mut(&mut self) -> &mut L {
+        &mut self.lcd
+    }
+
+    /// Refreshes the screen, sending only the characters that have changed since the previous
+    /// render; returns what has been sent.
+    pub fn render(&mut self) -> Result<RenderReport> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("render").entered();
+
+        #[cfg(feature = "std")]
+        let started = ::std::time::Instant::now();
+
+        let mut report = RenderReport::default();
+
+        #[cfg(feature = "std")]
+        self.draw_bindings();
+
+        self.track_activity()?;
+        self.draw_tickers()?;
+
+        if self.screensaver.is_some() {
+            self.snapshot.clone_from(&self.buffer.lines);
+        }
+
+        let blanked = self.blanked;
+
+        // the blinking characters are hidden during every other period
+        let blink_hidden = (self.renders / self.blink_period) % 2 == 1;
+
+        self.renders = self.renders.wrapping_add(1);
+
+        // position the virtual cursor is drawn at (if any)
+        let virtual_cursor = self.virtual_cursor
+            .filter(|cursor| !(blanked || (cursor.blinking && blink_hidden)))
+            .map(|cursor| (self.cursor.y, self.cursor.x, cursor.glyph));
+
+        let buffer = &mut self.buffer;
+        let blinking = &buffer.blinking;
+        let overlays = &buffer.overlays;
+
+        // character that should be shown at given position
+        let shown = |y: usize, x: usize, ch: u8| {
+            match virtual_cursor {
+                Some((cursor_y, cursor_x, glyph)) if (cursor_y, cursor_x) == (y, x) => glyph,
+                _ if blanked || (blink_hidden && blinking[y][x]) => b' ',
+                _ => overlays[y][x].unwrap_or(ch),
+            }
+        };
+
+        // characters sent during this render, to be read back (when verifying)
+        let mut sent = Vec::new();
+
+        for (y, (line, rendered_line)) in buffer.lines.iter().zip(buffer.rendered.iter_mut()).enumerate() {
+            // column the LCD's cursor is currently at (if known)
+            let mut cursor_x = None;
+
+            for (x, &ch) in line.iter().enumerate() {
+                // already sent as a part of a run (see below)
+                if cursor_x.is_some_and(|cursor_x| x < cursor_x) {
+                    continue;
+                }
+
+                let ch = shown(y, x, ch);
+
+                if rendered_line[x] == Some(ch) {
+                    continue;
+                }
+
+                match cursor_x {
+                    Some(cursor_x) if cursor_x == x => {
+                        //
+                    }
+
+                    // the characters in between are unchanged, so re-sending them is harmless -
+                    // and cheaper than moving the cursor, when there are just a few of them
+                    Some(cursor_x) if cursor_x < x && x - cursor_x < self.move_cost => {
+                        for rendered_ch in &rendered_line[cursor_x..x] {
+                            self.lcd
+                                .print_char(rendered_ch.unwrap_or(b' '))
+                                .map_err(|err| Error::render_line(y, err))?;
+                        }
+
+                        report.cells += x - cursor_x;
+                    }
 
-                cursor: Cursor {
-                    y: 0,
-                    x: 0,
-                },
+                    _ => {
+                        self.lcd.move_at(y, x).map_err(|err| Error::render_line(y, err))?;
+                        report.commands += 1;
+                    }
+                }
 
-                buffer: Buffer {
-                    lines: vec![vec![' ' as u8; width]; height],
-                    height,
-                    width,
-                },
+                // a run of the same character (eg. the spaces left by `clear_line` or a bar's fill)
+                // is sent at once, so that the bus can coalesce it (see `Bus::write_data_repeated`);
+                // the characters already in place are re-sent only when it's cheaper than moving
+                // the cursor past them, as above
+                let mut run = 1;
+                let mut in_place = 0;
+
+                for (offset, &next) in line.iter().enumerate().skip(x + 1) {
+                    if shown(y, offset, next) != ch {
+                        break;
+                    }
+
+                    if rendered_line[offset] == Some(ch) {
+                        in_place += 1;
+
+                        if in_place >= self.move_cost {
+                            break;
+                        }
+                    } else {
+                        run = offset - x + 1;
+                        in_place = 0;
+                    }
+                }
+
+                self.lcd.print_char_repeated(ch, run).map_err(|err| Error::render_line(y, err))?;
+                report.cells += run;
+
+                for (offset, rendered_ch) in rendered_line[x..x + run].iter_mut().enumerate() {
+                    *rendered_ch = Some(ch);
+
+                    if self.verify {
+                        sent.push((Point::new(y, x + offset), ch));
+                    }
+                }
+
+                cursor_x = Some(x + run);
             }
-        )
+        }
+
+        for (point, ch) in sent {
+            if self.lcd.read_char_at(point)? == ch {
+                continue;
+            }
+
+            #[cfg(feature = "tracing")]
+            tracing::warn!(y = point.y, x = point.x, expected = ch, "character mismatch, rewriting");
+
+            self.mismatches += 1;
+            self.lcd.print_char_at(point.y, point.x, ch)?;
+
+            report.cells += 1;
+            report.commands += 1;
+        }
+
+        #[cfg(feature = "std")]
+        {
+            report.elapsed = started.elapsed();
+        }
+
+        Ok(report)
     }
 
-    /// Refreshes the screen.
-    pub fn render(&mut self) -> UnitResult {
-        let mut y = 0;
+    /// Makes given line show given text, scrolling it by one character every few renders (see
+    /// `set_ticker_period`) when it's too long to fit on the screen.
+    ///
+    /// The line is redrawn during each render (overwriting anything printed on it in the
+    /// meantime), until `clear_line_ticker` is called.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// lcd.set_line_ticker(1, "Never Gonna Give You Up - Rick Astley")?;
+    ///
+    /// loop {
+    ///     lcd.render()?;
+    ///     thread::sleep(time::Duration::from_millis(300));
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the line lies outside the screen.
+    pub fn set_line_ticker<T: Into<String>>(&mut self, y: usize, text: T) -> UnitResult {
+        if y >= self.buffer.height {
+            return Err(format!("Tried to set a ticker outside the screen (at y={}).", y).into());
+        }
 
-        for line in &self.buffer.lines {
-            self.lcd.move_at(y, 0)?;
+        if self.tickers.len() <= y {
+            self.tickers.resize_with(y + 1, || None);
+        }
 
-            for ch in line {
-                self.lcd.print_char(*ch)?;
+        let mut marquee = Marquee::new(y, 0, self.buffer.width, text);
+        marquee.set_gap(self.ticker_gap);
+
+        self.tickers[y] = Some(LineTicker {
+            marquee,
+            renders: 0,
+        });
+
+        Ok(())
+    }
+
+    /// Stops redrawing given line (leaving its current contents as they are).
+    pub fn clear_line_ticker(&mut self, y: usize) {
+        if let Some(ticker) = self.tickers.get_mut(y) {
+            *ticker = None;
+        }
+    }
+
+    /// Changes number of renders after which the tickers move by one character (1 by default,
+    /// ie. each render).
+    pub fn set_ticker_period(&mut self, renders: usize) {
+        self.ticker_period = renders.max(1);
+    }
+
+    /// Changes number of spaces separating the end of the tickers' texts from their beginnings
+    /// (3 by default).
+    pub fn set_ticker_gap(&mut self, gap: usize) {
+        self.ticker_gap = gap;
+
+        for ticker in self.tickers.iter_mut().flatten() {
+            ticker.marquee.set_gap(gap);
+        }
+    }
+
+    /// Makes the characters in given region (inclusive) blink - that is: alternate with spaces
+    /// every few renders (see `set_blink_period`) - or stop blinking.
+    ///
+    /// Unlike `set_cursor_blinking`, any number of characters can blink at once (eg. an alarm's
+    /// time and an "unsaved changes" indicator). The attribute belongs to the positions, not to
+    /// the text - so whatever gets printed there blinks, until the attribute is cleared.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// lcd.print_at(0, 0, "Alarm: 07:30")?;
+    /// lcd.set_blinking(Point::new(0, 7), Point::new(0, 11), true)?;
+    ///
+    /// loop {
+    ///     lcd.render()?;
+    ///     thread::sleep(time::Duration::from_millis(500));
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the region does not lie on the screen.
+    pub fn set_blinking(&mut self, from: Point, to: Point, enabled: bool) -> UnitResult {
+        check_region(self, from, to)?;
+
+        for line in &mut self.buffer.blinking[from.y..=to.y] {
+            for blinking in &mut line[from.x..=to.x] {
+                *blinking = enabled;
             }
+        }
+
+        Ok(())
+    }
+
+    /// Makes all the characters stop blinking.
+    pub fn clear_blinking(&mut self) {
+        for line in &mut self.buffer.blinking {
+            for blinking in line {
+                *blinking = false;
+            }
+        }
+    }
+
+    /// Returns whether the character at given position is blinking (see `set_blinking`).
+    pub fn is_blinking(&self, point: Point) -> bool {
+        self.buffer.blinking
+            .get(point.y)
+            .and_then(|line| line.get(point.x))
+            .cloned()
+            .unwrap_or(false)
+    }
+
+    /// Makes given position show custom character `idx` instead of the buffered one - or show
+    /// the buffered one again, for `None`.
+    ///
+    /// The overlays form a separate plane on top of the text, so that the widgets can put icons
+    /// or partial-block graphics (eg. a bar's tip) over it without fighting the text buffer: just
+    /// like the blinking, the overlay belongs to the position - whatever gets printed there
+    /// stays in the buffer (eg. `char_at` returns it) and reappears once the overlay is removed,
+    /// with the render sending only the cells whose shown character has actually changed.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// lcd.create_char(0, CharBitmap::from_art(&BELL))?;
+    /// lcd.print_at(0, 0, "07:30 alarm")?;
+    ///
+    /// // the bell covers the `a` until the alarm gets disabled
+    /// lcd.set_overlay(Point::new(0, 6), Some(0))?;
+    /// lcd.render()?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the position lies outside the screen or when there's no such custom
+    /// character.
+    pub fn set_overlay(&mut self, point: Point, idx: Option<u8>) -> UnitResult {
+        check_region(self, point, point)?;
+
+        if let Some(idx) = idx {
+            Error::check_char_idx(idx, 7)?;
+        }
+
+        let overlay = &mut self.buffer.overlays[point.y][point.x];
+
+        if *overlay != idx {
+            *overlay = idx;
 
-            y += 1;
+            // the screensaver tracks the buffer only, so the overlays wake it up on their own
+            self.reset_idle();
         }
 
         Ok(())
     }
 
+    /// Removes all the overlays (see `set_overlay`).
+    pub fn clear_overlays(&mut self) {
+        for line in &mut self.buffer.overlays {
+            for overlay in line {
+                *overlay = None;
+            }
+        }
+    }
+
+    /// Returns custom character shown over given position (see `set_overlay`), or `None` if
+    /// there's no overlay there (or the position lies outside the screen).
+    pub fn overlay(&self, point: Point) -> Option<u8> {
+        self.buffer.overlays
+            .get(point.y)
+            .and_then(|line| line.get(point.x))
+            .cloned()
+            .flatten()
+    }
+
+    /// Changes number of renders after which the blinking characters appear / disappear (1 by
+    /// default, ie. each render).
+    pub fn set_blink_period(&mut self, renders: usize) {
+        self.blink_period = renders.max(1);
+    }
+
+    /// Changes how many characters moving the cursor costs (2 by default) - during the render,
+    /// gaps of unchanged characters shorter than that are written through instead of being
+    /// jumped over.
+    ///
+    /// Moving the cursor takes a single command, but on the slow buses (eg. the 4-bit I2C one) it
+    /// takes as long as sending two characters; `0` or `1` makes the render always jump.
+    pub fn set_move_cost(&mut self, characters: usize) {
+        self.move_cost = characters;
+    }
+
+    /// Enables or disables the verification - when enabled, each render reads back the characters
+    /// it has sent (see `Hd44780::read_char_at`) and rewrites the ones that do not match, catching
+    /// the corruption caused by a marginal wiring or electromagnetic interference.
+    ///
+    /// Verifying doubles the bus traffic (at least), so it's disabled by default; it requires the
+    /// LCD to be readable (eg. the GPIO bus needs the `R/W` pin), or the render fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// lcd.set_verify(true);
+    /// lcd.render()?;
+    ///
+    /// if lcd.mismatches() > 0 {
+    ///     eprintln!("LCD's wiring seems flaky: {} mismatch(es) so far", lcd.mismatches());
+    /// }
+    /// ```
+    pub fn set_verify(&mut self, enabled: bool) {
+        self.verify = enabled;
+    }
+
+    /// Returns number of mismatches found (and repaired) by the verification so far.
+    pub fn mismatches(&self) -> usize {
+        self.mismatches
+    }
+
+    /// Enables the screensaver (or disables it, for `None`) - once the buffer's contents have not
+    /// changed for given number of renders, the screen gets blanked; it comes back during the
+    /// first render after the next change (or after `reset_idle`).
+    ///
+    /// Meant mostly for the OLED variants of the character displays, which suffer from burn-in
+    /// when showing the same text for a long time. The tickers moving on their own do not count as
+    /// changes.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // rendering at 10 fps, so blank the screen after 5 minutes of inactivity
+    /// lcd.set_screensaver(Some(10 * 60 * 5));
+    /// ```
+    pub fn set_screensaver(&mut self, renders: Option<usize>) {
+        self.screensaver = renders;
+        self.idle_renders = 0;
+        self.snapshot.clear();
+    }
+
+    /// Resets the inactivity counter, as if the buffer has just changed (eg. after a button has
+    /// been pressed) - waking the screen up during the next render.
+    pub fn reset_idle(&mut self) {
+        self.idle_renders = 0;
+        self.snapshot.clear();
+    }
+
+    /// Returns whether the screen is currently blanked by the screensaver.
+    pub fn is_blanked(&self) -> bool {
+        self.blanked
+    }
+
+    /// Makes the render draw a cursor at the frontend's cursor position (or stops drawing it, for
+    /// `None`) - for the input fields and other UIs needing a visible cursor, which the hardware
+    /// one (see `set_cursor_visible`) cannot be used for, since the render moves it around.
+    ///
+    /// The cursor is not a part of the buffer (eg. `char_at` does not return it).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// lcd.set_virtual_cursor(Some(VirtualCursor::BLOCK));
+    /// lcd.print_at(1, 0, "Name: ")?;
+    ///
+    /// // the cursor is drawn right after the prompt
+    /// lcd.render()?;
+    /// ```
+    pub fn set_virtual_cursor(&mut self, cursor: Option<VirtualCursor>) {
+        self.virtual_cursor = cursor;
+    }
+
+    /// Binds given region (`width` characters of line `y`, starting at column `x`) to a value -
+    /// each render draws the value most recently set through the returned handle (see the
+    /// `binding` module).
+    ///
+    /// The region is redrawn during each render (overwriting anything printed on it in the
+    /// meantime); it's blank until the first value is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the region does not fit on the screen.
+    #[cfg(feature = "std")]
+    pub fn bind<T: fmt::Display>(&mut self, y: usize, x: usize, width: usize, align: Align) -> Result<Binding<T>> {
+        if y >= self.buffer.height || x + width > self.buffer.width {
+            return Err(
+                format!("Tried to bind a region outside the screen (at y={}, x={}, width={}).", y, x, width).into()
+            );
+        }
+
+        let binding = Binding::new();
+
+        self.bindings.push(BoundRegion {
+            y,
+            x,
+            width,
+            align,
+            slot: binding.slot(),
+            text: pad("", width, align),
+        });
+
+        Ok(binding)
+    }
+
+    /// Updates the inactivity counter, blanking the screen (or bringing it back) when due.
+    fn track_activity(&mut self) -> UnitResult {
+        let blanked = match self.screensaver {
+            Some(renders) => {
+                if self.buffer.lines == self.snapshot {
+                    self.idle_renders = self.idle_renders.saturating_add(1);
+                } else {
+                    self.idle_renders = 0;
+                }
+
+                self.idle_renders >= renders
+            }
+
+            None => false,
+        };
+
+        if blanked != self.blanked {
+            self.blanked = blanked;
+
+            if let Some(switch_backlight) = self.screensaver_backlight {
+                switch_backlight(&mut self.lcd, !blanked)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Draws the bound values into the buffer, taking the ones that have changed.
+    #[cfg(feature = "std")]
+    fn draw_bindings(&mut self) {
+        let rom = self.lcd.rom();
+
+        for region in &mut self.bindings {
+            if let Some(value) = region.slot.take() {
+                region.text = pad(&value, region.width, region.align);
+            }
+
+            let line = &mut self.buffer.lines[region.y][region.x..];
+
+            for (cell, ch) in line.iter_mut().zip(region.text.chars()) {
+                *cell = rom.encode(ch);
+            }
+        }
+    }
+
+    /// Draws the tickers into the buffer, moving the ones that are due.
+    fn draw_tickers(&mut self) -> UnitResult {
+        if self.tickers.is_empty() {
+            return Ok(());
+        }
+
+        // the tickers draw on `self`, so they have to be moved out of it for a moment
+        let mut tickers = mem::take(&mut self.tickers);
+        let cursor = self.cursor();
+
+        let result = tickers
+            .iter_mut()
+            .flatten()
+            .try_for_each(|ticker| {
+                let moves = ticker.renders > 0 && ticker.renders % self.ticker_period == 0;
+
+                ticker.renders += 1;
+
+                if moves {
+                    ticker.marquee.tick(self)
+                } else {
+                    ticker.marquee.draw(self)
+                }
+            });
+
+        self.tickers = tickers;
+        self.move_at(cursor.y, cursor.x)?;
+
+        result
+    }
+
+    /// Returns character at given position (as a ROM code), or `None` if the position lies
+    /// outside the screen.
+    pub fn char_at(&self, point: Point) -> Option<u8> {
+        self.buffer.lines
+            .get(point.y)
+            .and_then(|line| line.get(point.x))
+            .cloned()
+    }
+
+    /// Returns given line (as ROM codes), or `None` if the line lies outside the screen.
+    pub fn line(&self, y: usize) -> Option<&[u8]> {
+        self.buffer.lines
+            .get(y)
+            .map(|line| line.as_slice())
+    }
+
+    /// Replaces the whole screen with given lines - each one truncated to the screen's width and
+    /// padded with spaces, the missing ones left blank - without moving the cursor.
+    ///
+    /// Only the characters that actually differ get sent during the next `render`, so the whole
+    /// screen can be rebuilt on each sensor's poll.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// lcd.set_lines(&[
+    ///     &format!("Temperature: {:>5.1}C", temperature),
+    ///     &format!("Humidity:    {:>5.1}%", humidity),
+    /// ])?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error (leaving the screen intact) when given more lines than the screen has, or
+    /// when a character cannot be printed (see `Fallback::Error`).
+    pub fn set_lines(&mut self, lines: &[&str]) -> UnitResult {
+        if lines.len() > self.buffer.height {
+            return Err(format!("Expected at most {} lines, got {}.", self.buffer.height, lines.len()).into());
+        }
+
+        let rom = self.lcd.rom();
+        let PrintOptions { fallback, sanitize, .. } = self.print_options;
+
+        let lines = lines
+            .iter()
+            .map(|line| rom.fit(&sanitize.apply(line), self.buffer.width, fallback, None))
+            .collect::<Result<Vec<_>>>()?;
+
+        for (y, line) in self.buffer.lines.iter_mut().enumerate() {
+            let codes = lines.get(y).map_or(&[][..], |codes| codes.as_slice());
+
+            for (x, ch) in line.iter_mut().enumerate() {
+                *ch = codes.get(x).copied().unwrap_or(b' ');
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the whole screen with given one (see `Screen`), without moving the cursor - only
+    /// the characters that actually differ get sent during the next `render`.
+    ///
+    /// The screen's custom characters are left out (this works with any underlying LCD) - use
+    /// `Screen::render` to upload them, too.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error (leaving the screen intact) when the screens' sizes differ.
+    pub fn replace_screen(&mut self, screen: &Screen) -> UnitResult {
+        if screen.width() != self.buffer.width || screen.height() != self.buffer.height {
+            return Err(format!(
+                "Expected a {}x{} screen, got {}x{}.",
+                self.buffer.width, self.buffer.height, screen.width(), screen.height(),
+            ).into());
+        }
+
+        for (line, other) in self.buffer.lines.iter_mut().zip(screen.lines()) {
+            line.copy_from_slice(other);
+        }
+
+        Ok(())
+    }
+
+    /// Forgets what's currently displayed on the screen, so that the next `render` refreshes all
+    /// the characters (eg. after the LCD has been reinitialized or modified directly).
+    pub fn invalidate(&mut self) {
+        for line in &mut self.buffer.rendered {
+            for ch in line {
+                *ch = None;
+            }
+        }
+    }
+
+    /// Changes what happens when the text goes past the last line (`Overflow::Wrap` by default).
+    pub fn set_overflow(&mut self, overflow: Overflow) {
+        self.overflow = overflow;
+    }
+
+    /// Changes the options used by `print` (eg. enables interpreting the control characters; `\n`
+    /// honors the overflow policy then).
+    pub fn set_print_options(&mut self, options: PrintOptions) {
+        self.print_options = options;
+    }
+
     /// Prints text at current cursor's position and moves to the next line.
+    ///
+    /// What happens after the last line depends on the overflow policy (see `set_overflow`).
     pub fn println<T: Into<String>>(&mut self, str: T) -> UnitResult {
+        let str = str.into();
+        let empty = str.is_empty();
+
         self.print(str)?;
 
+        // when the text has reached the end of the line, the cursor is already on the next one
+        if empty || self.cursor.x > 0 {
+            self.new_line();
+        }
+
+        Ok(())
+    }
+
+    /// Returns a rectangular part of the screen (from the top-left corner to the bottom-right one,
+    /// inclusive) acting like an independent LCD, with the text scrolling up within it (see the
+    /// `Region`'s docs).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the region does not fit on the screen.
+    pub fn region(&mut self, from: Point, to: Point) -> Result<Region<'_, L>> {
+        check_region(self, from, to)?;
+
+        Ok(Region::new(self, from, to))
+    }
+
+    /// Returns cursor of given region (along with whether it is waiting to scroll), as it has been
+    /// left the last time (see `region`).
+    pub(super) fn region_cursor(&self, from: Point, to: Point) -> (Point, bool) {
+        self.region_cursors
+            .iter()
+            .find(|&&(f, t, _, _)| (f, t) == (from, to))
+            .map_or((Point::new(0, 0), false), |&(_, _, cursor, pending)| (cursor, pending))
+    }
+
+    /// Remembers cursor of given region (see `region`).
+    pub(super) fn set_region_cursor(&mut self, from: Point, to: Point, cursor: Point, pending_new_line: bool) {
+        match self.region_cursors.iter_mut().find(|(f, t, _, _)| (*f, *t) == (from, to)) {
+            Some(region) => {
+                region.2 = cursor;
+                region.3 = pending_new_line;
+            }
+
+            None => self.region_cursors.push((from, to, cursor, pending_new_line)),
+        }
+    }
+
+    /// Puts given character into the buffer, without moving the cursor.
+    pub(super) fn put_char(&mut self, point: Point, ch: u8) {
+        if let Some(cell) = self.buffer.lines.get_mut(point.y).and_then(|line| line.get_mut(point.x)) {
+            *cell = ch;
+        }
+    }
+
+    /// Moves lines of given region up by one, blanking its last line.
+    pub(super) fn scroll_region_up(&mut self, from: Point, to: Point) {
+        for y in from.y..to.y {
+            let (upper, lower) = self.buffer.lines.split_at_mut(y + 1);
+            upper[y][from.x..=to.x].copy_from_slice(&lower[0][from.x..=to.x]);
+        }
+
+        for ch in &mut self.buffer.lines[to.y][from.x..=to.x] {
+            *ch = b' ';
+        }
+    }
+
+    /// Moves the cursor at the beginning of the next line, honoring the overflow policy.
+    fn new_line(&mut self) {
         self.cursor.x = 0;
         self.cursor.y += 1;
 
-        Ok(())
+        if self.cursor.y >= self.buffer.height {
+            match self.overflow {
+                Overflow::Wrap => {
+                    self.cursor.y = 0;
+                }
+
+                Overflow::Scroll => {
+                    self.buffer.lines.remove(0);
+                    self.buffer.lines.push(vec![b' '; self.buffer.width]);
+                    self.cursor.y = self.buffer.height - 1;
+                }
+            }
+        }
     }
 }
 
-impl Hd44780 for Buffered {
+impl<L: Hd44780> Hd44780 for Buffered<L> {
     fn clear(&mut self) -> UnitResult {
         for line in &mut self.buffer.lines {
             for ch in line {
-                *ch = ' ' as u8;
+                *ch = b' ';
             }
         }
 
+        self.region_cursors.clear();
+
         self.move_at(0, 0)
     }
 
@@ -104,6 +1086,18 @@ impl Hd44780 for Buffered {
         self.move_at(0, 0)
     }
 
+    fn clear_region(&mut self, from: Point, to: Point) -> UnitResult {
+        check_region(self, from, to)?;
+
+        for line in &mut self.buffer.lines[from.y..=to.y] {
+            for ch in &mut line[from.x..=to.x] {
+                *ch = b' ';
+            }
+        }
+
+        self.move_at(from.y, from.x)
+    }
+
     fn move_at(&mut self, y: usize, x: usize) -> UnitResult {
         if y >= self.height() || x >= self.width() {
             return Err(
@@ -117,6 +1111,10 @@ impl Hd44780 for Buffered {
         Ok(())
     }
 
+    fn cursor(&self) -> Point {
+        Point::new(self.cursor.y, self.cursor.x)
+    }
+
     fn print_char(&mut self, ch: u8) -> UnitResult {
         if self.cursor.y >= self.buffer.height || self.cursor.x >= self.buffer.width {
             return Err("Tried to print a character outside the screen.".into());
@@ -129,21 +1127,78 @@ impl Hd44780 for Buffered {
         self.cursor.x += 1;
 
         if self.cursor.x >= self.buffer.width {
-            self.cursor.x = 0;
-            self.cursor.y += 1;
-
-            if self.cursor.y >= self.buffer.height {
-                self.cursor.y = 0;
-            }
+            self.new_line();
         }
 
         Ok(())
     }
 
+    fn print_str(&mut self, str: &str) -> UnitResult {
+        let options = self.print_options;
+
+        print_with(self, str, options, |lcd| {
+            lcd.new_line();
+            Ok(())
+        })
+    }
+
+    fn print_options(&self) -> PrintOptions {
+        self.print_options
+    }
+
+    /// Reads from the underlying LCD (not from the buffer - see `char_at` for that).
+    fn read_char_at(&mut self, point: Point) -> Result<u8> {
+        self.lcd.read_char_at(point)
+    }
+
+    fn rom(&self) -> Rom {
+        self.lcd.rom()
+    }
+
+    fn height(&self) -> usize {
+        self.buffer.height
+    }
+
+    fn width(&self) -> usize {
+        self.buffer.width
+    }
+
+    /// The scrolling is emulated, so it's always supported.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            scrolling: true,
+            ..self.lcd.capabilities()
+        }
+    }
+}
+
+impl<L: Backlight> Buffered<L> {
+    /// Makes the screensaver turn the backlight off, along with blanking the screen (and back
+    /// on, when the screen wakes up) - see `set_screensaver`.
+    pub fn set_screensaver_backlight(&mut self, enabled: bool) {
+        self.screensaver_backlight = if enabled {
+            Some(|lcd, enabled| lcd.set_backlight(enabled))
+        } else {
+            None
+        };
+    }
+}
+
+impl<L: Backlight> Backlight for Buffered<L> {
     fn set_backlight(&mut self, enabled: bool) -> UnitResult {
         self.lcd.set_backlight(enabled)
     }
 
+    fn set_backlight_brightness(&mut self, brightness: u8) -> UnitResult {
+        self.lcd.set_backlight_brightness(brightness)
+    }
+
+    fn can_dim_backlight(&self) -> bool {
+        self.lcd.can_dim_backlight()
+    }
+}
+
+impl<L: DisplayControl> DisplayControl for Buffered<L> {
     fn set_cursor_blinking(&mut self, enabled: bool) -> UnitResult {
         self.lcd.set_cursor_blinking(enabled)
     }
@@ -152,19 +1207,71 @@ impl Hd44780 for Buffered {
         self.lcd.set_cursor_visible(enabled)
     }
 
+    fn set_cursor_style(&mut self, style: CursorStyle) -> UnitResult {
+        self.lcd.set_cursor_style(style)
+    }
+
     fn set_text_visible(&mut self, enabled: bool) -> UnitResult {
         self.lcd.set_text_visible(enabled)
     }
 
+    fn sleep(&mut self) -> UnitResult {
+        self.lcd.sleep()
+    }
+
+    fn wake(&mut self) -> UnitResult {
+        self.lcd.wake()
+    }
+
+    /// Emulated by rotating each line of the buffer (the hardware shift would desynchronize the
+    /// buffer with the screen) - the change is visible after the next `render`.
+    fn scroll_display_left(&mut self) -> UnitResult {
+        for line in &mut self.buffer.lines {
+            line.rotate_left(1);
+        }
+
+        Ok(())
+    }
+
+    /// Emulated by rotating each line of the buffer (see `scroll_display_left`).
+    fn scroll_display_right(&mut self) -> UnitResult {
+        for line in &mut self.buffer.lines {
+            line.rotate_right(1);
+        }
+
+        Ok(())
+    }
+}
+
+impl<L: CustomChars> CustomChars for Buffered<L> {
     fn create_char(&mut self, idx: u8, lines: [u8; 8]) -> UnitResult {
         self.lcd.create_char(idx, lines)
     }
+}
 
-    fn height(&self) -> usize {
-        self.buffer.height
+impl<L: Hd44780 + RgbBacklight> RgbBacklight for Buffered<L> {
+    fn set_backlight_color(&mut self, r: u8, g: u8, b: u8) -> UnitResult {
+        self.lcd.set_backlight_color(r, g, b)
     }
+}
 
-    fn width(&self) -> usize {
-        self.buffer.width
+/// Formats the buffer's contents as text, one line per screen's line (without a trailing newline).
+///
+/// Characters without a Unicode counterpart (eg. custom characters) are shown as `�`.
+impl<L: Hd44780> fmt::Display for Buffered<L> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rom = self.rom();
+
+        for (y, line) in self.buffer.lines.iter().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+
+            for &code in line {
+                write!(f, "{}", rom.decode(code).unwrap_or('\u{FFFD}'))?;
+            }
+        }
+
+        Ok(())
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,330 @@
+//! Provides an in-memory LCD recording the frames drawn onto it as text - for testing the code
+//! drawing onto the LCD (eg. widgets or layouts) without any hardware.
+//!
+//! The frames are plain strings (one line of text per LCD's line), so they can be compared with
+//! `assert_eq!` or fed straight into a snapshot testing library (eg. `insta`).
+//!
+//! Characters are decoded using the LCD's ROM; custom characters are shown as `⓪`..`⑦` and the
+//! codes having no Unicode counterpart as `�`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut lcd = BufferedLcd::from_lcd(FrameCapture::new(16, 2));
+//!
+//! spinner.tick(&mut lcd)?;
+//! lcd.render()?;
+//! lcd.inner_mut().capture();
+//!
+//! spinner.tick(&mut lcd)?;
+//! lcd.render()?;
+//! lcd.inner_mut().capture();
+//!
+//! insta::assert_debug_snapshot!(lcd.inner().frames());
+//! ```
+//!
+//! # Timing
+//!
+//! `FrameCapture` does not take any time at all - to see how the code performs on a real display,
+//! it can be paired with a `DirectLcd` talking to a `NullBus` (see `InMemory`):
+//!
+//! ```rust,ignore
+//! let mut lcd = InMemoryLcd::in_memory(NullBus::realistic(), 20, 4)?;
+//!
+//! lcd.print_at(0, 0, "Hello World!")?;
+//!
+//! println!("{}", lcd.secondary().snapshot());
+//! println!("{}", lcd.primary().stats());
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::boxed::Box;
+use super::super::{Backlight, Capabilities, CustomChars, DisplayControl, Error, Hd44780, Point, PrintOptions, Result, Rom, UnitResult};
+use super::super::buses::null::NullBus;
+use super::{Direct, Tee};
+
+pub struct FrameCapture {
+    lines: Vec<Vec<u8>>,
+    cursor: Point,
+    rom: Rom,
+    print_options: PrintOptions,
+
+    /// backlight's brightness (`0` - off)
+    backlight: u8,
+    text_visible: bool,
+    chars: [Option<[u8; 8]>; 8],
+
+    frames: Vec<String>,
+}
+
+/// Characters the custom characters are shown as.
+const CUSTOM_CHARS: [char; 8] = ['⓪', '①', '②', '③', '④', '⑤', '⑥', '⑦'];
+
+/// Returns the character given ROM code is shown as in the snapshots (see the module's docs).
+pub(crate) fn decode_char(rom: Rom, code: u8) -> char {
+    match code {
+        0..=7 => CUSTOM_CHARS[code as usize],
+        8..=15 => CUSTOM_CHARS[code as usize - 8],
+        code => rom.decode(code).unwrap_or('\u{FFFD}'),
+    }
+}
+
+impl FrameCapture {
+    /// Creates a blank LCD of given size, using the `A00` ROM.
+    pub fn new(width: usize, height: usize) -> FrameCapture {
+        FrameCapture::with_rom(width, height, Rom::A00)
+    }
+
+    /// Creates a blank LCD of given size, using given ROM.
+    pub fn with_rom(width: usize, height: usize, rom: Rom) -> FrameCapture {
+        FrameCapture {
+            lines: vec![vec![b' '; width]; height],
+            cursor: Point::new(0, 0),
+            rom,
+            print_options: PrintOptions::default(),
+            backlight: 255,
+            text_visible: true,
+            chars: [None; 8],
+            frames: Vec::new(),
+        }
+    }
+
+    /// Changes the options used when printing strings (see `PrintOptions`).
+    pub fn set_print_options(&mut self, options: PrintOptions) {
+        self.print_options = options;
+    }
+
+    /// Returns the current contents of the screen, as text (lines are separated with `\n`).
+    pub fn snapshot(&self) -> String {
+        let lines: Vec<String> = self.lines
+            .iter()
+            .map(|line| {
+                line.iter()
+                    .map(|&code| decode_char(self.rom, code))
+                    .collect()
+            })
+            .collect();
+
+        lines.join("\n")
+    }
+
+    /// Records the current contents of the screen as a new frame and returns it.
+    pub fn capture(&mut self) -> &str {
+        let frame = self.snapshot();
+
+        self.frames.push(frame);
+        &self.frames[self.frames.len() - 1]
+    }
+
+    /// Returns all the frames recorded so far.
+    pub fn frames(&self) -> &[String] {
+        &self.frames
+    }
+
+    /// Forgets all the frames recorded so far.
+    pub fn clear_frames(&mut self) {
+        self.frames.clear();
+    }
+
+    /// Returns given line, as the ROM codes.
+    pub fn line(&self, y: usize) -> Option<&[u8]> {
+        self.lines
+            .get(y)
+            .map(|line| line.as_slice())
+    }
+
+    /// Returns the bitmap of given custom character (`None` if it has not been created).
+    pub fn custom_char(&self, idx: u8) -> Option<[u8; 8]> {
+        self.chars
+            .get(idx as usize)
+            .copied()
+            .flatten()
+    }
+
+    /// Returns whether the backlight is turned on.
+    pub fn is_backlight_enabled(&self) -> bool {
+        self.backlight > 0
+    }
+
+    /// Returns the backlight's brightness (`0` - off, `255` - full brightness).
+    pub fn backlight_brightness(&self) -> u8 {
+        self.backlight
+    }
+
+    /// Returns whether the text is visible.
+    pub fn is_text_visible(&self) -> bool {
+        self.text_visible
+    }
+}
+
+/// An LCD living in memory only (see `InMemory::in_memory`).
+pub type InMemory = Tee<Direct, FrameCapture>;
+
+impl InMemory {
+    /// Creates an LCD of given size living in memory only - a `DirectLcd` talking to given bus
+    /// (taking as much time as a real display would), mirrored onto a `FrameCapture` (keeping
+    /// the screen's contents).
+    pub fn in_memory(bus: NullBus, width: usize, height: usize) -> Result<InMemory> {
+        Ok(Tee::new(
+            Direct::new(Box::new(bus), width, height)?,
+            FrameCapture::new(width, height),
+        ))
+    }
+}
+
+impl Hd44780 for FrameCapture {
+    fn clear(&mut self) -> UnitResult {
+        for line in &mut self.lines {
+            for ch in line {
+                *ch = b' ';
+            }
+        }
+
+        self.cursor = Point::new(0, 0);
+
+        Ok(())
+    }
+
+    fn home(&mut self) -> UnitResult {
+        self.cursor = Point::new(0, 0);
+
+        Ok(())
+    }
+
+    fn move_at(&mut self, y: usize, x: usize) -> UnitResult {
+        if y >= self.height() || x >= self.width() {
+            return Err(
+                format!("Tried to move the cursor outside the screen (at y={}, x={}).", y, x).into()
+            );
+        }
+
+        self.cursor = Point::new(y, x);
+
+        Ok(())
+    }
+
+    fn cursor(&self) -> Point {
+        self.cursor
+    }
+
+    fn print_char(&mut self, ch: u8) -> UnitResult {
+        let Point { y, x } = self.cursor;
+
+        self.lines[y][x] = ch;
+
+        self.cursor.x += 1;
+
+        if self.cursor.x >= self.width() {
+            self.cursor = Point::new((y + 1) % self.height(), 0);
+        }
+
+        Ok(())
+    }
+
+    fn print_options(&self) -> PrintOptions {
+        self.print_options
+    }
+
+    fn read_char_at(&mut self, point: Point) -> Result<u8> {
+        self.lines
+            .get(point.y)
+            .and_then(|line| line.get(point.x))
+            .cloned()
+            .ok_or_else(|| format!("Tried to read a character outside the screen (at y={}, x={}).", point.y, point.x).into())
+    }
+
+    fn rom(&self) -> Rom {
+        self.rom
+    }
+
+    fn height(&self) -> usize {
+        self.lines.len()
+    }
+
+    fn width(&self) -> usize {
+        self.lines
+            .first()
+            .map_or(0, |line| line.len())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            backlight: true,
+            dimmable_backlight: true,
+            readable: true,
+            custom_chars: 8,
+            scrolling: true,
+        }
+    }
+}
+
+impl Backlight for FrameCapture {
+    fn set_backlight(&mut self, enabled: bool) -> UnitResult {
+        self.backlight = if enabled { 255 } else { 0 };
+
+        Ok(())
+    }
+
+    fn set_backlight_brightness(&mut self, brightness: u8) -> UnitResult {
+        self.backlight = brightness;
+
+        Ok(())
+    }
+
+    fn can_dim_backlight(&self) -> bool {
+        true
+    }
+}
+
+impl DisplayControl for FrameCapture {
+    fn set_cursor_blinking(&mut self, _: bool) -> UnitResult {
+        Ok(())
+    }
+
+    fn set_cursor_visible(&mut self, _: bool) -> UnitResult {
+        Ok(())
+    }
+
+    fn set_text_visible(&mut self, enabled: bool) -> UnitResult {
+        self.text_visible = enabled;
+
+        Ok(())
+    }
+
+    fn sleep(&mut self) -> UnitResult {
+        self.set_text_visible(false)?;
+        self.set_backlight(false)
+    }
+
+    fn wake(&mut self) -> UnitResult {
+        self.set_text_visible(true)?;
+        self.set_backlight(true)
+    }
+
+    fn scroll_display_left(&mut self) -> UnitResult {
+        for line in &mut self.lines {
+            line.rotate_left(1);
+        }
+
+        Ok(())
+    }
+
+    fn scroll_display_right(&mut self) -> UnitResult {
+        for line in &mut self.lines {
+            line.rotate_right(1);
+        }
+
+        Ok(())
+    }
+}
+
+impl CustomChars for FrameCapture {
+    fn create_char(&mut self, idx: u8, lines: [u8; 8]) -> UnitResult {
+        Error::check_char_idx(idx, 7)?;
+
+        self.chars[idx as usize] = Some(lines);
+
+        Ok(())
+    }
+}
@@ -1,85 +1,906 @@
-/// Provides a direct access to the HD44780.
-///
-/// # Caveats
-///
-/// 1. The `clear` and `home` methods are slow (HD44780 requires an additional delay to process
-///    them). If performance is a concern, please consider using the buffered frontend.
+//! Provides a direct access to the HD44780.
+//!
+//! # Caveats
+//!
+//! 1. The `clear` and `home` methods are slow (HD44780 requires an additional delay to process
+//!    them). If performance is a concern, please consider using the buffered frontend.
+//!
+//! 2. The cursor's position is tracked in software, basing on the commands sent to the LCD - it
+//!    does not take into account anything done to the LCD behind the frontend's back (eg. using
+//!    the bus directly).
 
 use super::super::*;
-use super::super::buses::Bus;
+use super::super::buses::{Bus, RgbBacklight, Stats};
 use super::super::buses::command::*;
+use super::super::buses::timing::{Operation, Stopwatch, TimingReport};
+use super::RetryPolicy;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::time::Duration;
 
 pub struct Direct {
-    bus: Box<Bus>,
+    bus: Box<dyn Bus>,
     properties: Properties,
     state: State,
+    timings: TimingReport,
+
+    /// retries performed so far (the rest of the statistics is gathered by the bus)
+    stats: Stats,
+
+    /// bytes queued by the current batch (see `batch`)
+    batch: Option<Vec<(u8, bool)>>,
+
+    retry_policy: RetryPolicy,
+    print_options: PrintOptions,
+
+    /// custom characters uploaded so far (re-uploaded by `reinitialize`)
+    cgram: [Option<[u8; 8]>; 8],
+
+    drop_action: DropAction,
+
+    /// how long to wait for the LCD to clear its busy flag (`None` when not polling it - see
+    /// `set_busy_timeout`)
+    busy_timeout: Option<Duration>,
 }
 
+/// Interval between the busy flag's reads.
+const BUSY_POLL_US: u32 = 10;
+
+/// What happens to the screen when the frontend gets dropped (see `Direct::on_drop`).
+///
+/// Note that the destructors do not run when the process gets killed by a signal or exits through
+/// `std::process::exit` - the application has to handle these by itself (eg. by catching
+/// `SIGTERM` and dropping the LCD).
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub enum DropAction {
+    /// Leave the screen as it is.
+    #[default]
+    Nothing,
+
+    /// Clear the screen.
+    Clear,
+
+    /// Clear the screen and turn the backlight off.
+    ClearAndDisableBacklight,
+
+    /// Turn the backlight off (leaving the text in place - eg. for a transflective display).
+    DisableBacklight,
+
+    /// Clear the screen and print given message (each line of which goes onto a separate line of
+    /// the screen).
+    Message(String),
+}
+
+#[derive(Clone)]
 struct State {
+    /// current DDRAM address (the cursor's position)
+    address: u8,
+
+    direction: Direction,
+    autoscroll: bool,
     cursor_blinking: bool,
     cursor_visible: bool,
     text_visible: bool,
+    backlight: bool,
+
+    /// text visibility & backlight from before `sleep` (`None` when awake)
+    asleep: Option<(bool, bool)>,
 }
 
 impl Direct {
     /// Creates a new direct HD44780 on given bus.
-    pub fn new(bus: Box<Bus>, width: usize, height: usize) -> Result<Direct> {
-        Direct::new_ex(
-            bus,
+    pub fn new(bus: Box<dyn Bus>, width: usize, height: usize) -> Result<Direct> {
+        Direct::new_ex(bus, Properties::new(width, height))
+    }
 
-            Properties {
-                height,
-                width,
+    /// Creates a new direct HD44780 on given bus.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when given properties are invalid (see
+    /// `Properties::validate`).
+    pub fn new_ex(bus: Box<dyn Bus>, properties: Properties) -> Result<Direct> {
+        let mut lcd = Direct::build(bus, properties)?;
 
-                font: if height == 1 { Font::Font5x10 } else { Font::Font5x8 },
-            },
-        )
+        lcd.initialize(false)?;
+
+        Ok(lcd)
     }
 
-    /// Creates a new direct HD44780 on given bus.
-    pub fn new_ex(bus: Box<Bus>, properties: Properties) -> Result<Direct> {
-        let mut lcd = Direct {
+    /// Creates a new direct HD44780 on given bus, assuming the LCD has already been initialized
+    /// (eg. by the previous run of a daemon that has just been restarted).
+    ///
+    /// The forced "8-bit mode" reset sequence (along with its ~45ms of delays) is skipped, so the
+    /// screen does not flicker and its contents are kept; the functions, entry mode and display
+    /// flags are sent anyway, to bring the LCD in sync with the frontend.
+    ///
+    /// The cursor's position is read from the LCD when the bus supports reading - otherwise the
+    /// cursor is moved to the top-left corner.
+    ///
+    /// The custom characters are not known to the frontend, so they are not re-uploaded by
+    /// `reinitialize` until created again.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when given properties are invalid (see
+    /// `Properties::validate`).
+    pub fn new_warm(bus: Box<dyn Bus>, properties: Properties) -> Result<Direct> {
+        let mut lcd = Direct::build(bus, properties)?;
+
+        lcd.initialize(true)?;
+
+        // the cursor could have been left anywhere
+        match lcd.address_counter() {
+            Ok(address) => lcd.state.address = address,
+            Err(_) => lcd.move_at(0, 0)?,
+        }
+
+        Ok(lcd)
+    }
+
+    fn build(bus: Box<dyn Bus>, properties: Properties) -> Result<Direct> {
+        properties.validate()?;
+
+        Ok(Direct {
             bus,
             properties,
 
             state: State {
+                address: 0,
+                direction: Direction::LeftToRight,
+                autoscroll: false,
                 cursor_blinking: false,
                 cursor_visible: false,
                 text_visible: true,
+                backlight: true,
+                asleep: None,
             },
+
+            timings: TimingReport::default(),
+            stats: Stats::default(),
+            batch: None,
+            retry_policy: RetryPolicy::none(),
+            print_options: PrintOptions::default(),
+            cgram: [None; 8],
+            drop_action: DropAction::Nothing,
+            busy_timeout: None,
+        })
+    }
+
+    /// Initializes the screen; when `warm`, the LCD is assumed to be initialized already (see
+    /// `new_warm`).
+    fn initialize(&mut self, warm: bool) -> UnitResult {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("initialize", width = self.properties.width, height = self.properties.height).entered();
+
+        // initialize the bus
+        let stopwatch = Stopwatch::start();
+
+        if warm {
+            self.bus.initialize_warm()?;
+        } else {
+            self.bus.initialize()?;
+        }
+
+        stopwatch.record(&mut self.timings, Operation::Initialize);
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(bus_width = self.bus.width(), "bus initialized");
+
+        self.configure()
+    }
+
+    /// Sends the LCD's configuration (functions, entry mode and display flags).
+    fn configure(&mut self) -> UnitResult {
+        let commands = self.properties.controller
+            .profile()
+            .init_sequence(&self.properties, self.bus.width() == 8);
+
+        for command in commands {
+            self.execute(command)?;
+        }
+
+        self.refresh_entry_mode()?;
+        self.refresh_display_flags()
+    }
+
+    /// Brings the LCD back in sync after a 4-bit transfer has been interrupted in the middle of a
+    /// byte (eg. the process has been killed between the nibbles) - the LCD shows garbage then,
+    /// because it takes each next nibble for the other half of a byte.
+    ///
+    /// Performs the datasheet's "initialization by instruction" handshake (see `Bus::resync`),
+    /// restores the configuration and moves the cursor back where it was - unlike `reinitialize`
+    /// the screen is not cleared, so whatever has been printed before the desync stays there.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // the previous instance of the application has been killed mid-transfer
+    /// let mut lcd = DirectLcd::new_warm(bus, properties)?;
+    ///
+    /// lcd.resync()?;
+    /// ```
+    pub fn resync(&mut self) -> UnitResult {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("resync").entered();
+
+        self.bus.resync()?;
+        self.configure()?;
+
+        let cursor = self.cursor();
+
+        self.move_at(cursor.y, cursor.x)
+    }
+
+    /// Re-runs the whole initialization sequence (which includes the resync handshake - see
+    /// `resync`), clears the screen, restores the backlight and
+    /// re-uploads all the custom characters created so far - restoring the LCD into the state it
+    /// should be in.
+    ///
+    /// Meant for recovering after the LCD has browned out or has been hot-plugged (it comes back
+    /// with the default configuration and garbage in its memory), without having to reconstruct
+    /// the frontend.
+    pub fn reinitialize(&mut self) -> UnitResult {
+        self.initialize(false)?;
+        self.clear()?;
+        Backlight::set_backlight(self, self.state.backlight)?;
+
+        let cgram = self.cgram;
+
+        for (idx, bitmap) in cgram.iter().enumerate() {
+            if let Some(bitmap) = *bitmap {
+                self.upload_char(idx as u8, bitmap)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Forgets which custom characters are loaded, so that the next `create_char` uploads the
+    /// bitmap even if it's the same as before.
+    ///
+    /// Meant for the cases the CGRAM might have been changed behind the frontend's back (eg. when
+    /// the LCD has been power-cycled without calling `reinitialize`).
+    pub fn forget_custom_chars(&mut self) {
+        self.cgram = [None; 8];
+    }
+
+    /// Checks whether the LCD is connected - `None` when the bus is not able to tell (see
+    /// `Bus::probe`).
+    pub fn probe(&mut self) -> Option<bool> {
+        self.bus.probe()
+    }
+
+    /// Creates a 5x10 custom character from given bitmap (10 lines, of which only the last 5 bits
+    /// are important) - for the 1-line displays using the `Font5x10` font.
+    ///
+    /// A 5x10 character takes the CGRAM space of two 5x8 ones, so only 4 of them are possible:
+    /// `idx` must be from range `<0, 3>` and the character is printed using code `2 * idx`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// lcd.create_char_5x10(1, [0b00100; 10])?;
+    /// lcd.print_char(2)?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when the LCD does not use the `Font5x10` font and an error
+    /// when passed an invalid index.
+    pub fn create_char_5x10(&mut self, idx: u8, lines: [u8; 10]) -> UnitResult {
+        if self.properties.font != Font::Font5x10 {
+            return Err(Error::invalid_config("properties.font", "5x10 custom characters require the `Font5x10` font").into());
+        }
+
+        Error::check_char_idx(idx, 3)?;
+
+        // the character spans two 5x8 ones: the first one holds the upper 8 lines, while the
+        // second one holds the lower 2 lines (plus the cursor's line and some unused space)
+        let mut upper = [0u8; 8];
+        let mut lower = [0u8; 8];
+
+        upper.copy_from_slice(&lines[..8]);
+        lower[..2].copy_from_slice(&lines[8..]);
+
+        self.create_char(2 * idx, upper)?;
+        self.create_char(2 * idx + 1, lower)
+    }
+
+    /// Changes the way the LCD behaves after printing a character.
+    ///
+    /// `direction` determines where the cursor moves (right for `LeftToRight`, left for
+    /// `RightToLeft`), while `autoscroll` makes the LCD shift the whole display instead, so that
+    /// the cursor stays in place and the already printed text gets pushed away.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // print "olleH" ending at the last column
+    /// lcd.set_entry_mode(Direction::RightToLeft, false)?;
+    /// lcd.move_at(0, 19)?;
+    /// lcd.print("Hello")?;
+    /// ```
+    pub fn set_entry_mode(&mut self, direction: Direction, autoscroll: bool) -> UnitResult {
+        self.state.direction = direction;
+        self.state.autoscroll = autoscroll;
+        self.refresh_entry_mode()
+    }
+
+    /// Enables / disables the autoscroll (keeping the direction) - just like the Arduino's
+    /// `LiquidCrystal::autoscroll()`: when enabled, each printed character pushes the already
+    /// printed text away (left for `LeftToRight`), while the cursor stays in place.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // type the text at the right edge of the screen, pushing it left
+    /// lcd.move_at(0, 16)?;
+    /// lcd.enable_autoscroll(true)?;
+    /// lcd.print("Hello")?;
+    /// ```
+    pub fn enable_autoscroll(&mut self, enabled: bool) -> UnitResult {
+        self.set_entry_mode(self.state.direction, enabled)
+    }
+
+    /// Returns the DDRAM address following (or preceding, depending on the direction) given one -
+    /// just like the LCD's address counter does after writing a character.
+    fn next_address(&self, address: u8) -> u8 {
+        self.properties.next_address(address, self.state.direction == Direction::LeftToRight)
+    }
+
+    /// Issues the "set entry mode" command with current LCD's state.
+    fn refresh_entry_mode(&mut self) -> UnitResult {
+        self.execute(Command::SetEntryMode {
+            enable_shift: self.state.autoscroll,
+            increment_counter: self.state.direction == Direction::LeftToRight,
+        })
+    }
+
+    /// Shifts the whole display (all the lines at once) one character to the left, without
+    /// changing the DDRAM contents.
+    ///
+    /// Can be used for cheap, hardware-accelerated scrolling effects - each line behaves like a
+    /// ring of 40 characters (or 80 for 1-line displays), so the text leaving the screen on the
+    /// left comes back from the right after a while.
+    pub fn shift_display_left(&mut self) -> UnitResult {
+        self.execute(Command::Shift {
+            display: true,
+            right: false,
+        })
+    }
+
+    /// Shifts the whole display (all the lines at once) one character to the right, without
+    /// changing the DDRAM contents.
+    pub fn shift_display_right(&mut self) -> UnitResult {
+        self.execute(Command::Shift {
+            display: true,
+            right: true,
+        })
+    }
+
+    /// Sends a raw command byte to the LCD - an escape hatch for the controller-specific
+    /// extensions (eg. the KS0073's extended instructions or the OLED variants' power commands).
+    ///
+    /// The frontend's state (eg. the cursor's position) is not updated, so the command should
+    /// not move the cursor nor change the display's mode behind the frontend's back - or the
+    /// frontend has to be brought back in sync afterwards (eg. with `move_at`).
+    ///
+    /// The "clear" and "home" commands are given the additional delay they require.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// lcd.write_raw_command(Command::Shift { display: true, right: true }.encode())?;
+    /// ```
+    pub fn write_raw_command(&mut self, value: u8) -> UnitResult {
+        // 0x01 - clear, 0x02 / 0x03 - home
+        let delay = if value != 0 && value & 0xFC == 0 {
+            self.bus.timings().clear_home_us
+        } else {
+            0
         };
 
-        lcd.initialize()?;
+        #[cfg(feature = "tracing")]
+        tracing::trace!(byte = %format_args!("{:#04x}", value), "raw command");
 
-        Ok(lcd)
+        if self.batch.is_some() {
+            if delay == 0 {
+                self.queue(value, false);
+                return Ok(());
+            }
+
+            self.flush()?;
+        }
+
+        let stopwatch = Stopwatch::start();
+
+        let result = self.retry_policy.run(&mut *self.bus, &mut self.stats, |bus| {
+            bus.write_command(value)?;
+
+            if delay > 0 {
+                bus.delay_us(delay);
+            }
+
+            Ok(())
+        });
+
+        stopwatch.record(&mut self.timings, Operation::RawCommand);
+
+        result
     }
 
-    /// Initializes the screen.
-    fn initialize(&mut self) -> UnitResult {
-        // initialize the bus
-        self.bus.initialize()?;
+    /// Sends a raw data byte to the LCD (into the DDRAM or CGRAM, whichever was addressed last).
+    ///
+    /// Just like with `write_raw_command`, the frontend's state (eg. the cursor's position) is
+    /// not updated.
+    pub fn write_raw_data(&mut self, value: u8) -> UnitResult {
+        self.write_data(value)
+    }
 
-        // initialize the screen
-        let height = self.height();
-        let bus_width = self.bus.width();
+    /// Changes the backlight's brightness (`0` - off, `255` - full brightness).
+    ///
+    /// Buses unable to dim the backlight (eg. the I2C one) just turn it on for any non-zero
+    /// brightness; for the displays without a backlight, the panel's power is switched instead
+    /// (see `ControllerProfile::backlight`).
+    pub fn set_backlight_brightness(&mut self, brightness: u8) -> UnitResult {
+        if self.properties.controller.profile().backlight {
+            self.bus.set_backlight_brightness(brightness)?;
+        } else {
+            self.switch_panel_power(brightness > 0)?;
+        }
 
-        self.bus.execute(Command::SetFunctions {
-            font_5x10: self.properties.font == Font::Font5x10,
-            height,
-            eight_bit_bus: bus_width == 8,
+        self.state.backlight = brightness > 0;
+
+        Ok(())
+    }
+
+    /// Turns the OLED panel's power on / off - the closest thing to the backlight the OLEDs have.
+    fn switch_panel_power(&mut self, enabled: bool) -> UnitResult {
+        self.execute(Command::SetModePower {
+            graphics: false,
+            power: enabled,
+        })
+    }
+
+    /// Changes the display's contrast (`0` - lowest, `255` - highest).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the bus is not able to control the contrast (eg. the I2C one, where
+    /// the contrast is set with a trimpot).
+    pub fn set_contrast(&mut self, level: u8) -> UnitResult {
+        self.bus.set_contrast(level)
+    }
+
+    /// Reads the address counter straight from the LCD.
+    ///
+    /// Unlike `cursor` (which is tracked in software), it reflects the LCD's actual state - so it
+    /// can be used to verify that both agree, or to synchronize after the LCD has been modified
+    /// behind the frontend's back.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the bus is not able to read from the LCD (eg. when the GPIO bus has
+    /// no `R/W` pin given).
+    pub fn address_counter(&mut self) -> Result<u8> {
+        self.flush()?;
+
+        let (_, address) = self.bus.read_busy_and_address()?;
+
+        Ok(address)
+    }
+
+    /// Reads the character displayed at given position straight from the LCD's DDRAM.
+    ///
+    /// Useful for verifying what has been actually written (eg. when debugging a flaky wiring).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when given position lies outside the screen, or when the bus is not able
+    /// to read from the LCD.
+    pub fn read_char_at(&mut self, point: Point) -> Result<u8> {
+        if point.y >= self.height() || point.x >= self.width() {
+            return Err(
+                format!("Tried to read a character outside the screen (at y={}, x={}).", point.y, point.x).into()
+            );
+        }
+
+        let address = self.properties.address_of(point.y, point.x);
+
+        self.execute(Command::SetDDRamAddress {
+            address,
         })?;
 
-        self.bus.execute(Command::SetEntryMode {
-            enable_shift: false,
-            increment_counter: true,
+        self.flush()?;
+
+        let result = self.bus.read_byte(true);
+
+        // reading has moved the address counter, so put it back where the cursor is
+        self.restore_address()?;
+
+        result
+    }
+
+    /// Sends given custom character to the CGRAM, even if it's already loaded there.
+    fn upload_char(&mut self, idx: u8, lines: [u8; 8]) -> UnitResult {
+        // a half-uploaded character is neither the old nor the new one
+        self.cgram[idx as usize] = None;
+
+        self.execute(Command::SetCGRamAddress {
+            address: idx << 3,
         })?;
 
-        self.refresh_display_flags()
+        for line in lines.iter() {
+            self.write_data(*line)?;
+        }
+
+        self.cgram[idx as usize] = Some(lines);
+
+        // go back to the DDRAM, so that the next character gets printed where the cursor was
+        self.restore_address()
+    }
+
+    /// Returns bitmaps of the custom characters created so far (`None` for the ones that have not
+    /// been created).
+    pub fn custom_chars(&self) -> &[Option<[u8; 8]>; 8] {
+        &self.cgram
+    }
+
+    /// Reads bitmap of given custom character straight from the LCD's CGRAM.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when given index is out of range, or when the bus is not able to read
+    /// from the LCD.
+    pub fn read_cgram(&mut self, idx: u8) -> Result<[u8; 8]> {
+        Error::check_char_idx(idx, 7)?;
+
+        self.execute(Command::SetCGRamAddress {
+            address: idx << 3,
+        })?;
+
+        self.flush()?;
+
+        let mut lines = [0; 8];
+        let mut result = Ok(());
+
+        for line in lines.iter_mut() {
+            match self.bus.read_byte(true) {
+                Ok(value) => *line = value & 0x1F,
+
+                Err(err) => {
+                    result = Err(err);
+                    break;
+                }
+            }
+        }
+
+        // go back to the DDRAM, so that the next character gets printed where the cursor was
+        self.restore_address()?;
+
+        result.map(|_| lines)
+    }
+
+    /// Sets the DDRAM address back to the cursor's position.
+    fn restore_address(&mut self) -> UnitResult {
+        let address = self.state.address;
+
+        self.execute(Command::SetDDRamAddress {
+            address,
+        })
+    }
+
+    /// Returns how much time each operation has taken so far - both the command classes (measured
+    /// end-to-end, including all the delays) and the bus phases (transfers, enable pulses,
+    /// settling).
+    ///
+    /// Useful for checking where the time actually goes when refreshing the screen.
+    pub fn timing_report(&self) -> TimingReport {
+        let mut report = self.timings.clone();
+        report.merge(&self.bus.timing_report());
+        report
+    }
+
+    /// Forgets all the timing statistics gathered so far.
+    pub fn reset_timing_report(&mut self) {
+        self.timings.clear();
+        self.bus.reset_timing_report();
+    }
+
+    /// Returns the traffic statistics gathered so far - the number of commands and data bytes
+    /// sent, the number of retried transfers and the total time spent on waiting for the LCD.
+    ///
+    /// Useful for tuning the refreshes, eg. for checking how many bytes a single `render` of the
+    /// buffered frontend actually sends.
+    ///
+    /// The commands, data bytes and delays are counted by the bus - buses not gathering them
+    /// report zeros there (see `Bus::stats`).
+    pub fn stats(&self) -> Stats {
+        let mut stats = self.bus.stats();
+        stats.merge(&self.stats);
+        stats
+    }
+
+    /// Forgets all the traffic statistics gathered so far.
+    pub fn reset_stats(&mut self) {
+        self.stats.clear();
+        self.bus.reset_stats();
+    }
+
+    /// Changes how the failed bus transfers get retried (by default they are not).
+    ///
+    /// When all the attempts fail, `Error::RetriesExhausted` is returned, with the last error as
+    /// its cause.
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry_policy = policy;
+    }
+
+    /// Makes the frontend wait for the LCD to clear its busy flag after each command (instead of
+    /// relying on the fixed delays alone), giving up after given time - `None` (the default)
+    /// disables the polling.
+    ///
+    /// An absent or wedged controller surfaces as `Error::Timeout` then (eg. so that a supervisor
+    /// can reinitialize the LCD - see `ResilientLcd`), instead of going unnoticed. The slow
+    /// commands (`clear`, `home`) get faster, too - they take as long as the LCD needs.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// lcd.set_busy_timeout(Some(Duration::from_millis(10)))?;
+    ///
+    /// if let Err(err) = lcd.clear() {
+    ///     if let Some(Error::Timeout { .. }) = err.downcast_ref() {
+    ///         lcd.reinitialize()?;
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ReadingNotSupported` when the bus is not able to read from the LCD (eg.
+    /// when the GPIO bus has no `R/W` pin given).
+    pub fn set_busy_timeout(&mut self, timeout: Option<Duration>) -> UnitResult {
+        if timeout.is_some() && !self.bus.can_read() {
+            return Err(Error::ReadingNotSupported.into());
+        }
+
+        self.busy_timeout = timeout;
+
+        Ok(())
+    }
+
+    /// Changes the options used by `print` (eg. enables interpreting the control characters).
+    pub fn set_print_options(&mut self, options: PrintOptions) {
+        self.print_options = options;
+    }
+
+    /// Changes what happens to the screen when the frontend gets dropped (by default, nothing -
+    /// the last contents stay on the screen) - so that exiting processes do not leave stale data
+    /// glowing.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// lcd.on_drop(DropAction::Message("Service stopped".into()));
+    /// ```
+    pub fn on_drop(&mut self, action: DropAction) {
+        self.drop_action = action;
+    }
+
+    /// Performs the drop action (see `on_drop`).
+    fn perform_drop_action(&mut self) -> UnitResult {
+        match ::core::mem::take(&mut self.drop_action) {
+            DropAction::Nothing => Ok(()),
+            DropAction::Clear => self.clear(),
+
+            DropAction::ClearAndDisableBacklight => {
+                self.clear()?;
+                Backlight::set_backlight(self, false)
+            }
+
+            DropAction::DisableBacklight => Backlight::set_backlight(self, false),
+
+            DropAction::Message(message) => {
+                self.wake()?;
+                self.clear()?;
+
+                for (y, line) in message.lines().take(self.properties.height).enumerate() {
+                    self.print_str_at(y, 0, line)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Executes given closure in a batch: instead of being sent right away, all the commands and
+    /// data get queued and then sent at once - in a single transaction on buses supporting it
+    /// (eg. the I2C one), which is much faster than sending them one by one.
+    ///
+    /// The batch is atomic: when the closure returns an error, nothing gets sent and the LCD's
+    /// state is left untouched.
+    ///
+    /// That's not the case when the batch has been split though: commands requiring additional
+    /// delays (`clear` and `home`) and reads (eg. `read_char_at`) send everything queued before
+    /// them right away.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// lcd.batch(|lcd| {
+    ///     lcd.print_at(0, 0, "Temperature:")?;
+    ///     lcd.print_at(1, 0, format!("{:.1} C", temperature))
+    /// })?;
+    /// ```
+    pub fn batch<F>(&mut self, f: F) -> UnitResult where F: FnOnce(&mut Direct) -> UnitResult {
+        if self.batch.is_some() {
+            return f(self);
+        }
+
+        let state = self.state.clone();
+        let cgram = self.cgram;
+
+        self.batch = Some(Vec::new());
+
+        let result = f(self);
+        let bytes = self.batch.take().unwrap_or_default();
+
+        match result {
+            Ok(()) => self.write_bytes(&bytes).inspect_err(|_| self.forget_uploads(&cgram)),
+
+            Err(err) => {
+                self.state = state;
+                self.forget_uploads(&cgram);
+                Err(err)
+            }
+        }
+    }
+
+    /// Forgets the custom characters uploaded since given snapshot of the CGRAM has been taken -
+    /// after a failed batch, their bytes might have not reached the LCD (or, when the batch has
+    /// been split, reached it only partially), so neither the old nor the new bitmap is known.
+    fn forget_uploads(&mut self, snapshot: &[Option<[u8; 8]>; 8]) {
+        for (loaded, before) in self.cgram.iter_mut().zip(snapshot) {
+            if loaded != before {
+                *loaded = None;
+            }
+        }
+    }
+
+    /// Sends everything queued by the current batch so far.
+    fn flush(&mut self) -> UnitResult {
+        match self.batch {
+            Some(ref mut bytes) if !bytes.is_empty() => {
+                let bytes = ::core::mem::take(bytes);
+                self.write_bytes(&bytes)
+            }
+
+            _ => Ok(()),
+        }
+    }
+
+    /// Executes given command, measuring how long it took.
+    fn execute(&mut self, command: Command) -> UnitResult {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(?command, byte = %format_args!("{:#04x}", command.encode()), "command");
+
+        if self.batch.is_some() {
+            if command.extra_delay_us(&self.bus.timings()) == 0 {
+                self.queue(command.encode(), false);
+                return Ok(());
+            }
+
+            self.flush()?;
+        }
+
+        let operation = Operation::from(&command);
+        let polling = self.busy_timeout.is_some();
+
+        let stopwatch = Stopwatch::start();
+
+        // when polling the busy flag, the command's extra delay is not needed
+        let result = self.retry_policy.run(&mut *self.bus, &mut self.stats, |bus| {
+            if polling {
+                bus.write_command(command.encode())
+            } else {
+                bus.execute(command)
+            }
+        });
+
+        let result = result
+            .map_err(|err| Error::command(command, err).into())
+            .and_then(|()| self.wait_until_ready());
+
+        stopwatch.record(&mut self.timings, operation);
+
+        result
+    }
+
+    /// Waits for the LCD to clear its busy flag (when polling it - see `set_busy_timeout`).
+    fn wait_until_ready(&mut self) -> UnitResult {
+        let timeout = match self.busy_timeout {
+            Some(timeout) => timeout,
+            None => return Ok(()),
+        };
+
+        let mut waited = Duration::from_secs(0);
+
+        loop {
+            let (busy, _) = self.bus.read_busy_and_address()?;
+
+            if !busy {
+                return Ok(());
+            }
+
+            if waited >= timeout {
+                return Err(Error::Timeout {
+                    operation: "Waiting for the busy flag",
+                    after: waited,
+                }.into());
+            }
+
+            self.bus.delay_us(BUSY_POLL_US);
+            waited += Duration::from_micros(u64::from(BUSY_POLL_US));
+        }
+    }
+
+    /// Sends a single data byte, measuring how long it took.
+    fn write_data(&mut self, value: u8) -> UnitResult {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(byte = %format_args!("{:#04x}", value), "data");
+
+        if self.batch.is_some() {
+            self.queue(value, true);
+            return Ok(());
+        }
+
+        let stopwatch = Stopwatch::start();
+        let result = self.retry_policy.run(&mut *self.bus, &mut self.stats, |bus| bus.write_data(value));
+        stopwatch.record(&mut self.timings, Operation::WriteData);
+
+        result
+    }
+
+    /// Sends the same data byte `count` times in a row, measuring how long it took (see
+    /// `Bus::write_data_repeated`).
+    fn write_data_repeated(&mut self, value: u8, count: usize) -> UnitResult {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(byte = %format_args!("{:#04x}", value), count, "data run");
+
+        if self.batch.is_some() {
+            for _ in 0..count {
+                self.queue(value, true);
+            }
+
+            return Ok(());
+        }
+
+        let stopwatch = Stopwatch::start();
+        let result = self.retry_policy.run(&mut *self.bus, &mut self.stats, |bus| bus.write_data_repeated(value, count));
+        stopwatch.record(&mut self.timings, Operation::WriteData);
+
+        result
+    }
+
+    /// Sends given bytes at once (see `Bus::write_bytes`).
+    fn write_bytes(&mut self, bytes: &[(u8, bool)]) -> UnitResult {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(len = bytes.len(), "sending batch");
+
+        self.retry_policy.run(&mut *self.bus, &mut self.stats, |bus| bus.write_bytes(bytes))
+    }
+
+    /// Appends given byte to the current batch.
+    fn queue(&mut self, value: u8, as_data: bool) {
+        if let Some(ref mut bytes) = self.batch {
+            bytes.push((value, as_data));
+        }
     }
 
     /// Issues the "set display flags" command with current LCD's state.
     fn refresh_display_flags(&mut self) -> UnitResult {
-        self.bus.execute(Command::SetDisplayFlags {
+        self.execute(Command::SetDisplayFlags {
             cursor_blinking: self.state.cursor_blinking,
             cursor_visible: self.state.cursor_visible,
             text_visible: self.state.text_visible,
@@ -92,14 +913,25 @@ impl Hd44780 for Direct {
     /// It's a slow command, re-writing screen with new data should be a preferred way if one is
     /// concerned about the performance (that's precisely what the "buffered" frontend does).
     fn clear(&mut self) -> UnitResult {
-        self.bus.execute(Command::Clear {})
+        self.execute(Command::Clear {})?;
+        self.state.address = 0;
+
+        // "clear" command resets the entry mode's direction
+        if self.state.direction != Direction::LeftToRight {
+            self.refresh_entry_mode()?;
+        }
+
+        Ok(())
     }
 
     /// Moves cursor at (0, 0).
     /// It's actually slower than "move_at(0, 0)", because HD44780 takes some time to process this
     /// one.
     fn home(&mut self) -> UnitResult {
-        self.bus.execute(Command::Home {})
+        self.execute(Command::Home {})?;
+        self.state.address = 0;
+
+        Ok(())
     }
 
     fn move_at(&mut self, y: usize, x: usize) -> UnitResult {
@@ -109,21 +941,131 @@ impl Hd44780 for Direct {
             );
         }
 
-        let addresses = vec![0x00, 0x40, 0x14, 0x54];
+        let address = self.properties.address_of(y, x);
 
-        self.bus.execute(Command::SetDDRamAddress {
-            address: (addresses[y] + x) as u8,
-        })
+        self.execute(Command::SetDDRamAddress {
+            address,
+        })?;
+
+        self.state.address = address;
+
+        Ok(())
     }
 
     fn print_char(&mut self, ch: u8) -> UnitResult {
-        self.bus.write_data(ch)
+        self.write_data(ch)?;
+        self.state.address = self.next_address(self.state.address);
+
+        // on split displays the LCD does not know that both halves form a single line, so the
+        // cursor has to be moved manually when crossing the middle of the screen
+        if self.properties.is_split() {
+            let half = self.width() / 2;
+
+            let crossing = match self.state.direction {
+                Direction::LeftToRight => self.state.address == half as u8,
+                Direction::RightToLeft => self.state.address == 0x27,
+            };
+
+            if crossing {
+                let x = if self.state.direction == Direction::LeftToRight { half } else { half - 1 };
+
+                self.move_at(0, x)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sends the whole run at once (see `Bus::write_data_repeated`), unless it crosses the middle
+    /// of a split display (where the cursor has to be moved manually).
+    fn print_char_repeated(&mut self, ch: u8, count: usize) -> UnitResult {
+        if count == 0 {
+            return Ok(());
+        }
+
+        if self.properties.is_split() {
+            for _ in 0..count {
+                self.print_char(ch)?;
+            }
+
+            return Ok(());
+        }
+
+        self.write_data_repeated(ch, count)?;
+
+        for _ in 0..count {
+            self.state.address = self.next_address(self.state.address);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the cursor's position.
+    ///
+    /// When the cursor has moved past the end of a line, the returned position lies outside the
+    /// screen (eg. `x = 16` on a 16x2 display) - that's where the next character would be put in
+    /// the LCD's memory.
+    fn cursor(&self) -> Point {
+        self.properties.position_of(self.state.address)
     }
 
+    fn print_options(&self) -> PrintOptions {
+        self.print_options
+    }
+
+    fn read_char_at(&mut self, point: Point) -> Result<u8> {
+        Direct::read_char_at(self, point)
+    }
+
+    fn rom(&self) -> Rom {
+        self.properties.rom
+    }
+
+    fn height(&self) -> usize {
+        self.properties.height
+    }
+
+    fn width(&self) -> usize {
+        self.properties.width
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            backlight: self.properties.controller.profile().backlight,
+            dimmable_backlight: self.bus.can_dim_backlight(),
+            readable: self.bus.can_read(),
+
+            // a 5x10 character takes the place of two 5x8 ones (see `create_char_5x10`)
+            custom_chars: if self.properties.font == Font::Font5x10 { 4 } else { 8 },
+
+            scrolling: true,
+        }
+    }
+}
+
+impl Backlight for Direct {
     fn set_backlight(&mut self, enabled: bool) -> UnitResult {
-        self.bus.set_backlight(enabled)
+        if self.properties.controller.profile().backlight {
+            self.bus.set_backlight(enabled)?;
+        } else {
+            self.switch_panel_power(enabled)?;
+        }
+
+        self.state.backlight = enabled;
+
+        Ok(())
+    }
+
+    fn set_backlight_brightness(&mut self, brightness: u8) -> UnitResult {
+        Direct::set_backlight_brightness(self, brightness)
     }
 
+    fn can_dim_backlight(&self) -> bool {
+        self.bus.can_dim_backlight()
+    }
+}
+
+impl DisplayControl for Direct {
     fn set_cursor_blinking(&mut self, enabled: bool) -> UnitResult {
         self.state.cursor_blinking = enabled;
         self.refresh_display_flags()
@@ -134,32 +1076,91 @@ impl Hd44780 for Direct {
         self.refresh_display_flags()
     }
 
+    /// Sends a single command, instead of one per each flag.
+    fn set_cursor_style(&mut self, style: CursorStyle) -> UnitResult {
+        self.state.cursor_visible = style.underline();
+        self.state.cursor_blinking = style.blinking();
+        self.refresh_display_flags()
+    }
+
     fn set_text_visible(&mut self, enabled: bool) -> UnitResult {
         self.state.text_visible = enabled;
         self.refresh_display_flags()
     }
 
-    fn create_char(&mut self, idx: u8, lines: [u8; 8]) -> UnitResult {
-        if idx > 7 {
-            return Err("Index out of range - character index must be in range <0, 7>".into());
+    /// Hides the text and turns the backlight off, remembering their previous state; does nothing
+    /// when already asleep.
+    fn sleep(&mut self) -> UnitResult {
+        if self.state.asleep.is_some() {
+            return Ok(());
         }
 
-        self.bus.execute(Command::SetCGRamAddress {
-            address: idx << 3,
-        })?;
+        let previous = (self.state.text_visible, self.state.backlight);
 
-        for line in lines.iter() {
-            self.bus.write_data(*line)?;
+        self.set_text_visible(false)?;
+        self.set_backlight(false)?;
+        self.state.asleep = Some(previous);
+
+        Ok(())
+    }
+
+    /// Restores the text visibility and backlight from before `sleep`; does nothing when awake.
+    fn wake(&mut self) -> UnitResult {
+        if let Some((text_visible, backlight)) = self.state.asleep {
+            self.set_text_visible(text_visible)?;
+            self.set_backlight(backlight)?;
+            self.state.asleep = None;
         }
 
         Ok(())
     }
 
-    fn height(&self) -> usize {
-        self.properties.height
+    fn scroll_display_left(&mut self) -> UnitResult {
+        self.shift_display_left()
     }
 
-    fn width(&self) -> usize {
-        self.properties.width
+    fn scroll_display_right(&mut self) -> UnitResult {
+        self.shift_display_right()
+    }
+}
+
+impl CustomChars for Direct {
+    /// Does nothing when the same bitmap is already loaded at given index (only the last 5 bits
+    /// of each line are compared) - so that widgets can re-create their characters on each frame
+    /// without wasting the bus' time.
+    fn create_char(&mut self, idx: u8, lines: [u8; 8]) -> UnitResult {
+        Error::check_char_idx(idx, 7)?;
+
+        if self.cgram[idx as usize].is_some_and(|loaded| same_bitmap(&loaded, &lines)) {
+            return Ok(());
+        }
+
+        self.upload_char(idx, lines)
+    }
+}
+
+/// Returns whether given custom characters look the same (ignoring the unused, upper bits).
+fn same_bitmap(a: &[u8; 8], b: &[u8; 8]) -> bool {
+    a.iter().zip(b).all(|(a, b)| a & 0x1F == b & 0x1F)
+}
+
+impl Drop for Direct {
+    fn drop(&mut self) {
+        // there's no one to report the error to
+        let _ = self.perform_drop_action();
     }
-}
\ No newline at end of file
+}
+
+impl RgbBacklight for Direct {
+    /// Changes the backlight's color.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the bus has no RGB backlight.
+    fn set_backlight_color(&mut self, r: u8, g: u8, b: u8) -> UnitResult {
+        self.bus
+            .as_rgb_backlight()
+            .ok_or(Error::RgbBacklightNotSupported)?
+            .set_backlight_color(r, g, b)
+    }
+}
@@ -0,0 +1,192 @@
+//! Carries the LCD's dimensions in its type, so that moving the cursor at a constant position
+//! outside the screen gets caught by the compiler instead of failing at runtime.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut lcd: FixedLcd<_, 16, 2> = FixedLcd::new(lcd)?;
+//!
+//! lcd.print_at_const::<1, 0>("Hello World!")?;
+//!
+//! // does not compile: `the cursor must stay within the screen`
+//! lcd.print_at_const::<2, 0>("Hello World!")?;
+//! ```
+//!
+//! The dimensions get checked against the LCD's ones once, when the frontend is created - the
+//! regular (runtime-checked) methods of the `Hd44780` trait keep working too.
+//!
+//! Any frontend can be wrapped (eg. `FixedLcd<BufferedLcd, 20, 4>`), but it only gains the checks -
+//! the buffered frontend still keeps its buffer on the heap; see `FixedBufferedLcd` for one keeping
+//! it in arrays sized by the type.
+
+use super::super::{Backlight, Capabilities, CustomChars, DisplayControl, Error, Hd44780, Point, PrintOptions, Result, Rom, UnitResult};
+
+pub struct Fixed<L, const W: usize, const H: usize> {
+    lcd: L,
+}
+
+/// Fails to compile for a position lying outside the screen.
+pub(super) struct Bounds<const Y: usize, const X: usize, const W: usize, const H: usize>;
+
+impl<const Y: usize, const X: usize, const W: usize, const H: usize> Bounds<Y, X, W, H> {
+    pub(super) const CHECK: () = assert!(Y < H && X < W, "the cursor must stay within the screen");
+}
+
+impl<L: Hd44780, const W: usize, const H: usize> Fixed<L, W, H> {
+    /// Wraps given LCD.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when the LCD's dimensions differ from the type's ones.
+    pub fn new(lcd: L) -> Result<Fixed<L, W, H>> {
+        if (lcd.width(), lcd.height()) != (W, H) {
+            return Err(Error::invalid_config(
+                "dimensions",
+                format!("the LCD is {}x{}, while the type says {}x{}", lcd.width(), lcd.height(), W, H),
+            ).into());
+        }
+
+        Ok(Fixed { lcd })
+    }
+
+    /// Returns the underlying LCD.
+    pub fn inner(&self) -> &L {
+        &self.lcd
+    }
+
+    /// Returns the underlying LCD.
+    pub fn inner_mut(&mut self) -> &mut L {
+        &mut self.lcd
+    }
+
+    /// Returns the underlying LCD.
+    pub fn into_inner(self) -> L {
+        self.lcd
+    }
+
+    /// Moves the cursor at given position, checked at compile time.
+    #[allow(clippy::let_unit_value)]
+    pub fn move_to<const Y: usize, const X: usize>(&mut self) -> UnitResult {
+        let () = Bounds::<Y, X, W, H>::CHECK;
+
+        self.lcd.move_at(Y, X)
+    }
+
+    /// Prints a single character at given position, checked at compile time (see
+    /// `Hd44780::print_char_at` for the runtime-checked one).
+    pub fn print_char_at_const<const Y: usize, const X: usize>(&mut self, ch: u8) -> UnitResult {
+        self.move_to::<Y, X>()?;
+        self.lcd.print_char(ch)
+    }
+
+    /// Prints a string at given position, checked at compile time (see `Hd44780::print_at` for the
+    /// runtime-checked one).
+    pub fn print_at_const<const Y: usize, const X: usize>(&mut self, str: &str) -> UnitResult {
+        self.move_to::<Y, X>()?;
+        self.lcd.print_str(str)
+    }
+}
+
+impl<L: Hd44780, const W: usize, const H: usize> Hd44780 for Fixed<L, W, H> {
+    fn clear(&mut self) -> UnitResult {
+        self.lcd.clear()
+    }
+
+    fn home(&mut self) -> UnitResult {
+        self.lcd.home()
+    }
+
+    fn move_at(&mut self, y: usize, x: usize) -> UnitResult {
+        self.lcd.move_at(y, x)
+    }
+
+    fn cursor(&self) -> Point {
+        self.lcd.cursor()
+    }
+
+    fn print_char(&mut self, ch: u8) -> UnitResult {
+        self.lcd.print_char(ch)
+    }
+
+    fn print_char_repeated(&mut self, ch: u8, count: usize) -> UnitResult {
+        self.lcd.print_char_repeated(ch, count)
+    }
+
+    fn print_options(&self) -> PrintOptions {
+        self.lcd.print_options()
+    }
+
+    fn clear_region(&mut self, from: Point, to: Point) -> UnitResult {
+        self.lcd.clear_region(from, to)
+    }
+
+    fn read_char_at(&mut self, point: Point) -> Result<u8> {
+        self.lcd.read_char_at(point)
+    }
+
+    fn rom(&self) -> Rom {
+        self.lcd.rom()
+    }
+
+    fn height(&self) -> usize {
+        H
+    }
+
+    fn width(&self) -> usize {
+        W
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.lcd.capabilities()
+    }
+}
+
+impl<L: Backlight, const W: usize, const H: usize> Backlight for Fixed<L, W, H> {
+    fn set_backlight(&mut self, enabled: bool) -> UnitResult {
+        self.lcd.set_backlight(enabled)
+    }
+
+    fn set_backlight_brightness(&mut self, brightness: u8) -> UnitResult {
+        self.lcd.set_backlight_brightness(brightness)
+    }
+
+    fn can_dim_backlight(&self) -> bool {
+        self.lcd.can_dim_backlight()
+    }
+}
+
+impl<L: DisplayControl, const W: usize, const H: usize> DisplayControl for Fixed<L, W, H> {
+    fn set_cursor_blinking(&mut self, enabled: bool) -> UnitResult {
+        self.lcd.set_cursor_blinking(enabled)
+    }
+
+    fn set_cursor_visible(&mut self, enabled: bool) -> UnitResult {
+        self.lcd.set_cursor_visible(enabled)
+    }
+
+    fn set_text_visible(&mut self, enabled: bool) -> UnitResult {
+        self.lcd.set_text_visible(enabled)
+    }
+
+    fn sleep(&mut self) -> UnitResult {
+        self.lcd.sleep()
+    }
+
+    fn wake(&mut self) -> UnitResult {
+        self.lcd.wake()
+    }
+
+    fn scroll_display_left(&mut self) -> UnitResult {
+        self.lcd.scroll_display_left()
+    }
+
+    fn scroll_display_right(&mut self) -> UnitResult {
+        self.lcd.scroll_display_right()
+    }
+}
+
+impl<L: CustomChars, const W: usize, const H: usize> CustomChars for Fixed<L, W, H> {
+    fn create_char(&mut self, idx: u8, lines: [u8; 8]) -> UnitResult {
+        self.lcd.create_char(idx, lines)
+    }
+}
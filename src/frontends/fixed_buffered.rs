@@ -0,0 +1,309 @@
+//! Provides a buffered frontend for the screens whose dimensions are known at compile time - the
+//! buffer is kept in arrays sized by the type (so it does not allocate), and the positions given
+//! as constants get checked by the compiler (just like in the `Fixed` frontend).
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! # use pwr_hd44780::{DirectLcd, FixedBufferedLcd, UnitResult};
+//! # fn example(lcd: DirectLcd) -> UnitResult {
+//! let mut lcd: FixedBufferedLcd<_, 16, 2> = FixedBufferedLcd::new(lcd)?;
+//!
+//! lcd.print_at_const::<0, 0>("Hello World!")?;
+//! lcd.render()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Only the buffering itself is provided - the extras of the `Buffered` frontend (blinking,
+//! overlays, tickers, the screensaver and so on) require that one.
+
+use super::fixed::Bounds;
+use super::super::{check_region, print_with, Backlight, Capabilities, CursorStyle, CustomChars, DisplayControl, Error, Hd44780, Point, PrintOptions, Result, Rom, UnitResult};
+
+pub struct FixedBuffered<L, const W: usize, const H: usize> {
+    lcd: L,
+
+    /// what's been printed
+    lines: [[u8; W]; H],
+
+    /// what's currently displayed on the screen (`None` when unknown)
+    rendered: [[Option<u8>; W]; H],
+
+    cursor: Point,
+}
+
+impl<L: Hd44780, const W: usize, const H: usize> FixedBuffered<L, W, H> {
+    /// Creates a new buffered HD44780 basing on previously existing one; the first `render`
+    /// refreshes the whole screen.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when the LCD's dimensions differ from the type's ones.
+    pub fn new(lcd: L) -> Result<FixedBuffered<L, W, H>> {
+        if (lcd.width(), lcd.height()) != (W, H) {
+            return Err(Error::invalid_config(
+                "dimensions",
+                format!("the LCD is {}x{}, while the type says {}x{}", lcd.width(), lcd.height(), W, H),
+            ).into());
+        }
+
+        Ok(FixedBuffered {
+            lcd,
+            lines: [[b' '; W]; H],
+            rendered: [[None; W]; H],
+            cursor: Point::new(0, 0),
+        })
+    }
+
+    /// Returns the underlying LCD.
+    pub fn inner(&self) -> &L {
+        &self.lcd
+    }
+
+    /// Returns the underlying LCD; modifying the screen through it makes the buffer out-of-date
+    /// (see `invalidate`).
+    pub fn inner_mut(&mut self) -> &mut L {
+        &mut self.lcd
+    }
+
+    /// Returns the underlying LCD.
+    pub fn into_inner(self) -> L {
+        self.lcd
+    }
+
+    /// Returns the buffer's contents (as ROM codes).
+    pub fn lines(&self) -> &[[u8; W]; H] {
+        &self.lines
+    }
+
+    /// Makes the next `render` refresh the whole screen.
+    pub fn invalidate(&mut self) {
+        self.rendered = [[None; W]; H];
+    }
+
+    /// Sends the characters that have changed since the previous render to the LCD.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::RenderLine` when the LCD fails while rendering one of the lines; the
+    /// characters not sent get rendered by the next call.
+    pub fn render(&mut self) -> UnitResult {
+        for y in 0..H {
+            // column the LCD's cursor is currently at (if known)
+            let mut cursor_x = None;
+            let mut x = 0;
+
+            while x < W {
+                let ch = self.lines[y][x];
+
+                if self.rendered[y][x] == Some(ch) {
+                    x += 1;
+                    continue;
+                }
+
+                // a run of the same character is sent at once (see `Bus::write_data_repeated`)
+                let run = self.lines[y][x..]
+                    .iter()
+                    .zip(&self.rendered[y][x..])
+                    .take_while(|&(&next, &rendered)| next == ch && rendered != Some(ch))
+                    .count();
+
+                if cursor_x != Some(x) {
+                    self.lcd.move_at(y, x).map_err(|err| Error::render_line(y, err))?;
+                }
+
+                self.lcd.print_char_repeated(ch, run).map_err(|err| Error::render_line(y, err))?;
+
+                for rendered in &mut self.rendered[y][x..x + run] {
+                    *rendered = Some(ch);
+                }
+
+                x += run;
+                cursor_x = Some(x);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Moves the cursor at given position, checked at compile time.
+    #[allow(clippy::let_unit_value)]
+    pub fn move_to<const Y: usize, const X: usize>(&mut self) -> UnitResult {
+        let () = Bounds::<Y, X, W, H>::CHECK;
+
+        self.move_at(Y, X)
+    }
+
+    /// Prints a single character at given position, checked at compile time (see
+    /// `Hd44780::print_char_at` for the runtime-checked one).
+    pub fn print_char_at_const<const Y: usize, const X: usize>(&mut self, ch: u8) -> UnitResult {
+        self.move_to::<Y, X>()?;
+        self.print_char(ch)
+    }
+
+    /// Prints a string at given position, checked at compile time (see `Hd44780::print_at` for the
+    /// runtime-checked one).
+    pub fn print_at_const<const Y: usize, const X: usize>(&mut self, str: &str) -> UnitResult {
+        self.move_to::<Y, X>()?;
+        self.print_str(str)
+    }
+
+    /// Moves the cursor at the beginning of the next line, wrapping back to the first one.
+    fn new_line(&mut self) {
+        self.cursor.x = 0;
+        self.cursor.y = (self.cursor.y + 1) % H;
+    }
+}
+
+impl<L: Hd44780, const W: usize, const H: usize> Hd44780 for FixedBuffered<L, W, H> {
+    fn clear(&mut self) -> UnitResult {
+        self.lines = [[b' '; W]; H];
+        self.move_at(0, 0)
+    }
+
+    fn home(&mut self) -> UnitResult {
+        self.move_at(0, 0)
+    }
+
+    fn clear_region(&mut self, from: Point, to: Point) -> UnitResult {
+        check_region(self, from, to)?;
+
+        for line in &mut self.lines[from.y..=to.y] {
+            for ch in &mut line[from.x..=to.x] {
+                *ch = b' ';
+            }
+        }
+
+        self.move_at(from.y, from.x)
+    }
+
+    fn move_at(&mut self, y: usize, x: usize) -> UnitResult {
+        if y >= H || x >= W {
+            return Err(
+                format!("Tried to move the cursor outside the screen (at y={}, x={}).", y, x).into()
+            );
+        }
+
+        self.cursor = Point::new(y, x);
+
+        Ok(())
+    }
+
+    fn cursor(&self) -> Point {
+        self.cursor
+    }
+
+    fn print_char(&mut self, ch: u8) -> UnitResult {
+        self.lines[self.cursor.y][self.cursor.x] = ch;
+        self.cursor.x += 1;
+
+        if self.cursor.x >= W {
+            self.new_line();
+        }
+
+        Ok(())
+    }
+
+    fn print_str(&mut self, str: &str) -> UnitResult {
+        let options = self.print_options();
+
+        print_with(self, str, options, |lcd| {
+            lcd.new_line();
+            Ok(())
+        })
+    }
+
+    fn print_options(&self) -> PrintOptions {
+        self.lcd.print_options()
+    }
+
+    /// Reads from the underlying LCD (not from the buffer - see `lines` for that).
+    fn read_char_at(&mut self, point: Point) -> Result<u8> {
+        self.lcd.read_char_at(point)
+    }
+
+    fn rom(&self) -> Rom {
+        self.lcd.rom()
+    }
+
+    fn height(&self) -> usize {
+        H
+    }
+
+    fn width(&self) -> usize {
+        W
+    }
+
+    /// The scrolling is emulated, so it's always supported.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            scrolling: true,
+            ..self.lcd.capabilities()
+        }
+    }
+}
+
+impl<L: Backlight, const W: usize, const H: usize> Backlight for FixedBuffered<L, W, H> {
+    fn set_backlight(&mut self, enabled: bool) -> UnitResult {
+        self.lcd.set_backlight(enabled)
+    }
+
+    fn set_backlight_brightness(&mut self, brightness: u8) -> UnitResult {
+        self.lcd.set_backlight_brightness(brightness)
+    }
+
+    fn can_dim_backlight(&self) -> bool {
+        self.lcd.can_dim_backlight()
+    }
+}
+
+impl<L: DisplayControl, const W: usize, const H: usize> DisplayControl for FixedBuffered<L, W, H> {
+    fn set_cursor_blinking(&mut self, enabled: bool) -> UnitResult {
+        self.lcd.set_cursor_blinking(enabled)
+    }
+
+    fn set_cursor_visible(&mut self, enabled: bool) -> UnitResult {
+        self.lcd.set_cursor_visible(enabled)
+    }
+
+    fn set_cursor_style(&mut self, style: CursorStyle) -> UnitResult {
+        self.lcd.set_cursor_style(style)
+    }
+
+    fn set_text_visible(&mut self, enabled: bool) -> UnitResult {
+        self.lcd.set_text_visible(enabled)
+    }
+
+    fn sleep(&mut self) -> UnitResult {
+        self.lcd.sleep()
+    }
+
+    fn wake(&mut self) -> UnitResult {
+        self.lcd.wake()
+    }
+
+    /// Emulated by rotating each line of the buffer (see `Buffered::scroll_display_left`).
+    fn scroll_display_left(&mut self) -> UnitResult {
+        for line in &mut self.lines {
+            line.rotate_left(1);
+        }
+
+        Ok(())
+    }
+
+    /// Emulated by rotating each line of the buffer (see `Buffered::scroll_display_right`).
+    fn scroll_display_right(&mut self) -> UnitResult {
+        for line in &mut self.lines {
+            line.rotate_right(1);
+        }
+
+        Ok(())
+    }
+}
+
+impl<L: CustomChars, const W: usize, const H: usize> CustomChars for FixedBuffered<L, W, H> {
+    fn create_char(&mut self, idx: u8, lines: [u8; 8]) -> UnitResult {
+        self.lcd.create_char(idx, lines)
+    }
+}
@@ -0,0 +1,101 @@
+//! Periodically checks whether the LCD is still connected, reinitializing it once it has been
+//! plugged back - instead of an endless stream of write errors after the backpack's cable has
+//! been pulled out.
+//!
+//! Requires a bus able to tell whether the LCD is connected (see `Bus::probe`) - for the other
+//! ones the check does nothing.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut health = HealthCheck::new(Duration::from_secs(1));
+//!
+//! loop {
+//!     match health.poll(&mut lcd, started.elapsed())? {
+//!         Some(Health::Disconnected) => eprintln!("LCD has been disconnected"),
+//!         Some(Health::Reconnected) => eprintln!("LCD is back"),
+//!         None => (),
+//!     }
+//!
+//!     if health.is_connected() {
+//!         lcd.render()?;
+//!     }
+//! }
+//! ```
+
+use core::time::Duration;
+use super::super::Result;
+use super::Reinitialize;
+
+pub struct HealthCheck {
+    period: Duration,
+
+    /// when the LCD has been probed the last time
+    last_probe: Option<Duration>,
+
+    connected: bool,
+}
+
+/// Change of the LCD's state, as noticed by the health check.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Health {
+    /// The LCD has stopped responding.
+    Disconnected,
+
+    /// The LCD has responded again and has been reinitialized.
+    Reconnected,
+}
+
+impl HealthCheck {
+    /// Creates a health check probing the LCD every `period`; the LCD is assumed to be connected
+    /// at first.
+    pub fn new(period: Duration) -> HealthCheck {
+        HealthCheck {
+            period,
+            last_probe: None,
+            connected: true,
+        }
+    }
+
+    /// Returns whether the LCD was connected when probed the last time.
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    /// Probes the LCD, if the period has elapsed since the previous probe; returns the change of
+    /// the LCD's state (if any).
+    ///
+    /// `now` is the time elapsed since an arbitrary (but fixed) moment - eg. the application's
+    /// start.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the LCD has been reconnected, but could not be reinitialized - the
+    /// LCD is then still considered disconnected, so the next poll tries again.
+    pub fn poll<L: Reinitialize>(&mut self, lcd: &mut L, now: Duration) -> Result<Option<Health>> {
+        if let Some(last_probe) = self.last_probe {
+            if now < last_probe + self.period {
+                return Ok(None);
+            }
+        }
+
+        self.last_probe = Some(now);
+
+        match (self.connected, lcd.probe()) {
+            (true, Some(false)) => {
+                self.connected = false;
+
+                Ok(Some(Health::Disconnected))
+            }
+
+            (false, Some(true)) => {
+                lcd.reinitialize()?;
+                self.connected = true;
+
+                Ok(Some(Health::Reconnected))
+            }
+
+            _ => Ok(None),
+        }
+    }
+}
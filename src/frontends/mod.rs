@@ -1,5 +1,47 @@
-pub use self::buffered::Buffered;
-pub use self::direct::Direct;
+pub use self::buffered::{Buffered, Overflow, RenderReport, VirtualCursor};
+pub use self::capture::{FrameCapture, InMemory};
+#[cfg(feature = "std")]
+pub(crate) use self::capture::decode_char;
+pub use self::direct::{Direct, DropAction};
+pub use self::fixed::Fixed;
+pub use self::fixed_buffered::FixedBuffered;
+pub use self::health::{Health, HealthCheck};
+pub use self::queued::Queued;
+pub use self::region::Region;
+pub use self::resilient::{Recovery, Reinitialize, Resilient};
+pub use self::retry::RetryPolicy;
+pub use self::tee::Tee;
+pub use self::tiled::Tiled;
+#[cfg(feature = "raspberrypi")]
+pub use self::multi::MultiLcd;
+#[cfg(feature = "std")]
+pub use self::pages::{Page, Pages};
+#[cfg(feature = "std")]
+pub use self::render_loop::{RenderHandle, RenderLoop};
+#[cfg(feature = "std")]
+pub use self::shared::Shared;
+#[cfg(feature = "std")]
+pub use self::worker::{LcdHandle, LcdWorker};
 
 mod buffered;
-mod direct;
\ No newline at end of file
+mod capture;
+mod direct;
+mod fixed;
+mod fixed_buffered;
+mod health;
+mod queued;
+mod region;
+mod resilient;
+mod retry;
+mod tee;
+mod tiled;
+#[cfg(feature = "raspberrypi")]
+mod multi;
+#[cfg(feature = "std")]
+mod pages;
+#[cfg(feature = "std")]
+mod render_loop;
+#[cfg(feature = "std")]
+mod shared;
+#[cfg(feature = "std")]
+mod worker;
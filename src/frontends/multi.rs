@@ -0,0 +1,210 @@
+//! Drives many HD44780s sharing the data lines, each one having its own `enable` pin (see
+//! `Pins::extra_en`) - eg. to build a large operator panel out of a few displays, without wasting
+//! six GPIOs per display.
+//!
+//! Each display is controlled by a separate direct frontend; they all share a single GPIO bus,
+//! which selects the right `enable` pin before each transfer.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let bus = Gpio4Bus::new(Pins {
+//!     data: [26, 6, 5, 16],
+//!     rs: 23,
+//!     en: 24,
+//!     extra_en: vec![25, 12],
+//!     // ...
+//! })?;
+//!
+//! let mut lcds = MultiLcd::new(bus, 20, 4)?;
+//!
+//! lcds.lcd(0).unwrap().print("Left")?;
+//! lcds.lcd(2).unwrap().print("Right")?;
+//! ```
+//!
+//! # Caveats
+//!
+//! 1. The backlight and the contrast are shared by all the displays - changing them through any
+//!    of the frontends affects all of them.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use std::sync::{Arc, Mutex, MutexGuard};
+use super::Direct;
+use super::super::{Properties, Result, UnitResult};
+use super::super::buses::{Bus, Gpio4, Stats, TimingReport, Timings};
+
+pub struct MultiLcd {
+    bus: Arc<Mutex<Gpio4>>,
+    lcds: Vec<Direct>,
+}
+
+/// A bus talking to a single display on a shared GPIO bus.
+struct SelectedDisplay {
+    bus: Arc<Mutex<Gpio4>>,
+    idx: usize,
+}
+
+impl MultiLcd {
+    /// Initializes all the displays connected to given bus, assuming they are of the same size.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when any of the displays could not be initialized.
+    pub fn new(bus: Gpio4, width: usize, height: usize) -> Result<MultiLcd> {
+        MultiLcd::new_ex(bus, Properties::new(width, height))
+    }
+
+    /// Initializes all the displays connected to given bus with given properties.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when any of the displays could not be initialized.
+    pub fn new_ex(bus: Gpio4, properties: Properties) -> Result<MultiLcd> {
+        let displays = bus.displays();
+        let bus = Arc::new(Mutex::new(bus));
+
+        let lcds = (0..displays)
+            .map(|idx| {
+                let display = SelectedDisplay {
+                    bus: bus.clone(),
+                    idx,
+                };
+
+                Direct::new_ex(Box::new(display), properties)
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(MultiLcd { bus, lcds })
+    }
+
+    /// Returns number of the displays.
+    pub fn len(&self) -> usize {
+        self.lcds.len()
+    }
+
+    /// Returns whether there are no displays (never happens, since the bus always has at least
+    /// one).
+    pub fn is_empty(&self) -> bool {
+        self.lcds.is_empty()
+    }
+
+    /// Returns given display: `0` is the one connected to `Pins::en`, the next ones - to the
+    /// subsequent `Pins::extra_en`.
+    pub fn lcd(&mut self, idx: usize) -> Option<&mut Direct> {
+        self.lcds.get_mut(idx)
+    }
+
+    /// Returns all the displays.
+    pub fn lcds(&mut self) -> &mut [Direct] {
+        &mut self.lcds
+    }
+
+    /// Locks the shared bus (eg. to change the RGB backlight's color).
+    pub fn bus(&self) -> MutexGuard<'_, Gpio4> {
+        lock(&self.bus)
+    }
+
+    /// Returns the frontends, so that they can be used on their own (eg. each one wrapped in the
+    /// buffered frontend).
+    pub fn into_inner(self) -> Vec<Direct> {
+        self.lcds
+    }
+}
+
+impl SelectedDisplay {
+    fn lock(&self) -> Result<MutexGuard<'_, Gpio4>> {
+        let mut bus = lock(&self.bus);
+
+        bus.select_display(self.idx)?;
+
+        Ok(bus)
+    }
+}
+
+impl Bus for SelectedDisplay {
+    fn initialize(&mut self) -> UnitResult {
+        self.lock()?.initialize()
+    }
+
+    fn initialize_warm(&mut self) -> UnitResult {
+        self.lock()?.initialize_warm()
+    }
+
+    fn resync(&mut self) -> UnitResult {
+        self.lock()?.resync()
+    }
+
+    fn set_backlight(&mut self, enabled: bool) -> UnitResult {
+        self.lock()?.set_backlight(enabled)
+    }
+
+    fn set_backlight_brightness(&mut self, brightness: u8) -> UnitResult {
+        self.lock()?.set_backlight_brightness(brightness)
+    }
+
+    fn can_dim_backlight(&self) -> bool {
+        lock(&self.bus).can_dim_backlight()
+    }
+
+    fn transfer_latency_us(&self) -> u32 {
+        lock(&self.bus).transfer_latency_us()
+    }
+
+    fn set_contrast(&mut self, level: u8) -> UnitResult {
+        self.lock()?.set_contrast(level)
+    }
+
+    fn can_read(&self) -> bool {
+        lock(&self.bus).can_read()
+    }
+
+    fn read_byte(&mut self, as_data: bool) -> Result<u8> {
+        self.lock()?.read_byte(as_data)
+    }
+
+    fn write_byte(&mut self, value: u8, as_data: bool) -> UnitResult {
+        self.lock()?.write_byte(value, as_data)
+    }
+
+    fn write_bytes(&mut self, bytes: &[(u8, bool)]) -> UnitResult {
+        self.lock()?.write_bytes(bytes)
+    }
+
+    fn write_data_repeated(&mut self, value: u8, count: usize) -> UnitResult {
+        self.lock()?.write_data_repeated(value, count)
+    }
+
+    fn width(&self) -> usize {
+        lock(&self.bus).width()
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        lock(&self.bus).delay_us(us)
+    }
+
+    fn timings(&self) -> Timings {
+        lock(&self.bus).timings()
+    }
+
+    fn timing_report(&self) -> TimingReport {
+        lock(&self.bus).timing_report()
+    }
+
+    fn reset_timing_report(&mut self) {
+        lock(&self.bus).reset_timing_report()
+    }
+
+    fn stats(&self) -> Stats {
+        lock(&self.bus).stats()
+    }
+
+    fn reset_stats(&mut self) {
+        lock(&self.bus).reset_stats()
+    }
+}
+
+/// Locks the shared bus, recovering it after a panic in some other thread.
+fn lock(bus: &Mutex<Gpio4>) -> MutexGuard<'_, Gpio4> {
+    bus.lock().unwrap_or_else(|err| err.into_inner())
+}
@@ -0,0 +1,326 @@
+//! Provides a set of virtual screens (pages), shown one at a time on a single buffered LCD.
+//!
+//! Each page has its own contents (and custom characters), which can be modified at any time -
+//! no matter whether the page is currently shown or not; switching between the pages (manually or
+//! every few seconds) re-renders only the characters that differ.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut pages = Pages::new(lcd);
+//!
+//! pages.add("cpu")?.print_at(0, 0, "CPU: 12%")?;
+//! pages.add("mem")?.print_at(0, 0, "MEM: 1.2 GB")?;
+//! pages.set_rotation(Some(Duration::from_secs(5)));
+//!
+//! loop {
+//!     pages.page_mut("cpu").unwrap().print_at(0, 5, format!("{:>2}%", cpu_usage()))?;
+//!     pages.render()?;
+//!
+//!     thread::sleep(Duration::from_millis(250));
+//! }
+//! ```
+//!
+//! # Caveats
+//!
+//! 1. Pages do not control the LCD's state (they implement neither `Backlight` nor
+//!    `DisplayControl`) - such changes have to be made through `Pages::lcd_mut`.
+//!
+//! 2. Custom characters created on a page are uploaded to the LCD each time the page gets shown,
+//!    overwriting the ones uploaded by the other pages.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use std::time::{Duration, Instant};
+use super::{Buffered, RenderReport};
+use super::super::{Capabilities, CustomChars, Hd44780, Point, Result, Rom, UnitResult};
+use super::super::screen::Screen;
+
+pub struct Pages {
+    lcd: Buffered,
+    pages: Vec<Page>,
+
+    /// index of the page being shown
+    current: usize,
+
+    /// whether another page has been shown since the previous render
+    switched: bool,
+
+    rotation: Option<Duration>,
+    shown_at: Instant,
+}
+
+/// A single virtual screen - see `Pages`.
+pub struct Page {
+    id: String,
+    screen: Screen,
+
+    /// whether the custom characters have changed since they were last uploaded
+    chars_changed: bool,
+}
+
+impl Pages {
+    /// Creates an empty set of pages, shown on given LCD.
+    pub fn new(lcd: Buffered) -> Pages {
+        Pages {
+            lcd,
+            pages: Vec::new(),
+            current: 0,
+            switched: true,
+            rotation: None,
+            shown_at: Instant::now(),
+        }
+    }
+
+    /// Adds a new, blank page at the end and returns it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a page with given id already exists.
+    pub fn add<T: Into<String>>(&mut self, id: T) -> Result<&mut Page> {
+        let id = id.into();
+
+        if self.position(&id).is_some() {
+            return Err(format!("Page `{}` already exists.", id).into());
+        }
+
+        let idx = self.pages.len();
+
+        self.pages.push(Page {
+            id,
+            screen: Screen::with_rom(self.lcd.width(), self.lcd.height(), self.lcd.rom()),
+            chars_changed: false,
+        });
+
+        Ok(&mut self.pages[idx])
+    }
+
+    /// Removes given page; when it was being shown, the next one gets shown instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when there's no such page.
+    pub fn remove(&mut self, id: &str) -> UnitResult {
+        let idx = self.position(id).ok_or_else(|| unknown_page(id))?;
+
+        self.pages.remove(idx);
+
+        if idx < self.current {
+            self.current -= 1;
+        } else if idx == self.current {
+            self.switch(if idx < self.pages.len() { idx } else { 0 });
+        }
+
+        Ok(())
+    }
+
+    /// Returns given page.
+    pub fn page(&self, id: &str) -> Option<&Page> {
+        self.pages.iter().find(|page| page.id == id)
+    }
+
+    /// Returns given page.
+    pub fn page_mut(&mut self, id: &str) -> Option<&mut Page> {
+        self.pages.iter_mut().find(|page| page.id == id)
+    }
+
+    /// Returns id of the page being shown (`None` when there are no pages).
+    pub fn current(&self) -> Option<&str> {
+        self.pages
+            .get(self.current)
+            .map(|page| page.id.as_str())
+    }
+
+    /// Shows given page (during the next `render`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when there's no such page.
+    pub fn show(&mut self, id: &str) -> UnitResult {
+        let idx = self.position(id).ok_or_else(|| unknown_page(id))?;
+
+        self.switch(idx);
+
+        Ok(())
+    }
+
+    /// Shows the next page, wrapping around after the last one.
+    pub fn next(&mut self) {
+        if !self.pages.is_empty() {
+            self.switch((self.current + 1) % self.pages.len());
+        }
+    }
+
+    /// Shows the previous page, wrapping around after the first one.
+    pub fn prev(&mut self) {
+        if !self.pages.is_empty() {
+            self.switch((self.current + self.pages.len() - 1) % self.pages.len());
+        }
+    }
+
+    /// Enables (or disables, when given `None`) switching to the next page every `period`.
+    ///
+    /// The pages are switched during `render`, so it has to be called frequently enough.
+    pub fn set_rotation(&mut self, period: Option<Duration>) {
+        self.rotation = period;
+        self.shown_at = Instant::now();
+    }
+
+    /// Rotates the pages (if it's time to) and refreshes the screen with the page being shown.
+    pub fn render(&mut self) -> Result<RenderReport> {
+        if let Some(period) = self.rotation {
+            if self.shown_at.elapsed() >= period {
+                self.next();
+            }
+        }
+
+        let page = match self.pages.get_mut(self.current) {
+            Some(page) => page,
+            None => return self.lcd.render(),
+        };
+
+        if self.switched || page.chars_changed {
+            let mut uploaded = false;
+
+            for (idx, lines) in page.screen.custom_chars().iter().enumerate() {
+                if let Some(lines) = lines {
+                    self.lcd.create_char(idx as u8, *lines)?;
+                    uploaded = true;
+                }
+            }
+
+            // the custom characters' codes might now mean something else, so they have to be
+            // re-sent
+            if uploaded {
+                self.lcd.invalidate();
+            }
+
+            self.switched = false;
+            page.chars_changed = false;
+        }
+
+        for (y, line) in page.screen.lines().iter().enumerate() {
+            self.lcd.move_at(y, 0)?;
+
+            for &ch in line {
+                self.lcd.print_char(ch)?;
+            }
+        }
+
+        self.lcd.render()
+    }
+
+    /// Returns the underlying LCD.
+    pub fn lcd_mut(&mut self) -> &mut Buffered {
+        &mut self.lcd
+    }
+
+    /// Returns the underlying LCD, dropping the pages.
+    pub fn into_inner(self) -> Buffered {
+        self.lcd
+    }
+
+    fn position(&self, id: &str) -> Option<usize> {
+        self.pages.iter().position(|page| page.id == id)
+    }
+
+    fn switch(&mut self, idx: usize) {
+        self.switched = true;
+        self.current = idx;
+        self.shown_at = Instant::now();
+
+        if self.pages.is_empty() {
+            let _ = self.lcd.clear();
+        }
+    }
+}
+
+fn unknown_page(id: &str) -> String {
+    format!("Unknown page: `{}`.", id)
+}
+
+impl Page {
+    /// Returns the page's id.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns given line (as ROM codes), or `None` if the line lies outside the page.
+    pub fn line(&self, y: usize) -> Option<&[u8]> {
+        self.screen.line(y)
+    }
+
+    /// Returns the page's contents.
+    pub fn screen(&self) -> &Screen {
+        &self.screen
+    }
+
+    /// Replaces the page's contents (including the custom characters) with given screen.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error (leaving the page intact) when the screens' sizes differ.
+    pub fn set_screen(&mut self, screen: Screen) -> UnitResult {
+        if screen.width() != self.width() || screen.height() != self.height() {
+            return Err(format!(
+                "Expected a {}x{} screen, got {}x{}.", self.width(), self.height(), screen.width(), screen.height(),
+            ).into());
+        }
+
+        self.chars_changed |= screen.custom_chars() != self.screen.custom_chars();
+        self.screen = screen;
+
+        Ok(())
+    }
+}
+
+impl Hd44780 for Page {
+    fn clear(&mut self) -> UnitResult {
+        self.screen.clear()
+    }
+
+    fn home(&mut self) -> UnitResult {
+        self.screen.home()
+    }
+
+    fn move_at(&mut self, y: usize, x: usize) -> UnitResult {
+        self.screen.move_at(y, x)
+    }
+
+    fn cursor(&self) -> Point {
+        self.screen.cursor()
+    }
+
+    fn print_char(&mut self, ch: u8) -> UnitResult {
+        self.screen.print_char(ch)
+    }
+
+    fn rom(&self) -> Rom {
+        self.screen.rom()
+    }
+
+    fn height(&self) -> usize {
+        self.screen.height()
+    }
+
+    fn width(&self) -> usize {
+        self.screen.width()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            custom_chars: 8,
+            ..Capabilities::default()
+        }
+    }
+}
+
+impl CustomChars for Page {
+    /// Remembers given custom character, to be uploaded each time the page gets shown.
+    fn create_char(&mut self, idx: u8, lines: [u8; 8]) -> UnitResult {
+        self.screen.create_char(idx, lines)?;
+        self.chars_changed = true;
+
+        Ok(())
+    }
+}
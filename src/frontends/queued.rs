@@ -0,0 +1,300 @@
+//! Queues the printing instead of performing it right away - the queue is flushed bit by bit (see
+//! `flush_some`), so that a single-threaded event loop (or an async executor) can drive the LCD
+//! without getting blocked for the whole refresh.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut lcd = Queued::new(lcd);
+//!
+//! lcd.print_at(0, 0, "Hello World!")?;
+//!
+//! loop {
+//!     // do at most 2ms of the LCD's work per iteration
+//!     lcd.flush_some(Duration::from_millis(2))?;
+//!
+//!     handle_events()?;
+//! }
+//! ```
+//!
+//! # Caveats
+//!
+//! 1. Each operation still waits for the LCD to process it (tens of microseconds; ~1.5ms for
+//!    `clear`), so the budget is a soft limit - at least one operation is performed per flush.
+//!
+//! 2. Only the printing (along with moving the cursor and clearing) is queued - modifying the
+//!    LCD's state (eg. `set_backlight` or `create_char`) is performed right away.
+//!
+//! 3. `cursor` returns the position the cursor will be at once the queue is flushed, assuming
+//!    that the text wraps onto the next line after the last column.
+
+use alloc::collections::VecDeque;
+#[cfg(feature = "std")]
+use core::time::Duration;
+use super::super::{check_region, Backlight, Capabilities, CustomChars, DisplayControl, Hd44780, Point, PrintOptions, Result, Rom, UnitResult};
+
+pub struct Queued<L> {
+    lcd: L,
+    queue: VecDeque<Operation>,
+
+    /// where the cursor will be once the queue is flushed
+    cursor: Point,
+}
+
+enum Operation {
+    Clear,
+    Home,
+    MoveAt(usize, usize),
+    PrintChar(u8),
+    ClearRegion(Point, Point),
+}
+
+impl<L: Hd44780> Queued<L> {
+    /// Creates a queue in front of given LCD.
+    pub fn new(lcd: L) -> Queued<L> {
+        let cursor = lcd.cursor();
+
+        Queued {
+            lcd,
+            queue: VecDeque::new(),
+            cursor,
+        }
+    }
+
+    /// Returns the underlying LCD.
+    pub fn inner(&self) -> &L {
+        &self.lcd
+    }
+
+    /// Returns the underlying LCD.
+    ///
+    /// Printing on it directly bypasses the queue (so the queued operations may overwrite it).
+    pub fn inner_mut(&mut self) -> &mut L {
+        &mut self.lcd
+    }
+
+    /// Returns the underlying LCD, dropping whatever has not been flushed yet.
+    pub fn into_inner(self) -> L {
+        self.lcd
+    }
+
+    /// Returns number of operations waiting in the queue.
+    pub fn pending(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns whether the queue is empty.
+    pub fn is_flushed(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Performs at most `count` of the queued operations; returns whether the queue is empty
+    /// afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first failed operation's error - that operation is dropped, while the rest of
+    /// them stays in the queue.
+    pub fn flush_count(&mut self, count: usize) -> Result<bool> {
+        for _ in 0..count {
+            if !self.flush_one()? {
+                break;
+            }
+        }
+
+        Ok(self.queue.is_empty())
+    }
+
+    /// Performs as many of the queued operations as fit in given time (but at least one);
+    /// returns whether the queue is empty afterwards.
+    ///
+    /// # Errors
+    ///
+    /// See `flush_count`.
+    #[cfg(feature = "std")]
+    pub fn flush_some(&mut self, budget: Duration) -> Result<bool> {
+        let started = ::std::time::Instant::now();
+
+        while self.flush_one()? {
+            if started.elapsed() >= budget {
+                break;
+            }
+        }
+
+        Ok(self.queue.is_empty())
+    }
+
+    /// Performs all the queued operations.
+    pub fn flush(&mut self) -> UnitResult {
+        while self.flush_one()? {
+            //
+        }
+
+        Ok(())
+    }
+
+    /// Performs the first queued operation; returns whether there was any.
+    fn flush_one(&mut self) -> Result<bool> {
+        let operation = match self.queue.pop_front() {
+            Some(operation) => operation,
+            None => return Ok(false),
+        };
+
+        match operation {
+            Operation::Clear => self.lcd.clear()?,
+            Operation::Home => self.lcd.home()?,
+            Operation::MoveAt(y, x) => self.lcd.move_at(y, x)?,
+            Operation::PrintChar(ch) => self.lcd.print_char(ch)?,
+            Operation::ClearRegion(from, to) => self.lcd.clear_region(from, to)?,
+        }
+
+        Ok(true)
+    }
+
+    /// Queues given operation, merging it with the previous one when possible.
+    fn push(&mut self, operation: Operation) {
+        // moving the cursor twice in a row is the same as moving it once
+        if let (Operation::MoveAt(..), Some(Operation::MoveAt(..))) = (&operation, self.queue.back()) {
+            self.queue.pop_back();
+        }
+
+        self.queue.push_back(operation);
+    }
+}
+
+impl<L: Hd44780> Hd44780 for Queued<L> {
+    fn clear(&mut self) -> UnitResult {
+        // clearing makes everything queued so far pointless
+        self.queue.clear();
+        self.push(Operation::Clear);
+        self.cursor = Point::new(0, 0);
+
+        Ok(())
+    }
+
+    fn home(&mut self) -> UnitResult {
+        self.push(Operation::Home);
+        self.cursor = Point::new(0, 0);
+
+        Ok(())
+    }
+
+    fn move_at(&mut self, y: usize, x: usize) -> UnitResult {
+        if y >= self.height() || x >= self.width() {
+            return Err(
+                format!("Tried to move the cursor outside the screen (at y={}, x={}).", y, x).into()
+            );
+        }
+
+        self.push(Operation::MoveAt(y, x));
+        self.cursor = Point::new(y, x);
+
+        Ok(())
+    }
+
+    fn cursor(&self) -> Point {
+        self.cursor
+    }
+
+    fn print_char(&mut self, ch: u8) -> UnitResult {
+        self.push(Operation::PrintChar(ch));
+
+        self.cursor.x += 1;
+
+        if self.cursor.x >= self.width() {
+            self.cursor.x = 0;
+            self.cursor.y = (self.cursor.y + 1) % self.height();
+        }
+
+        Ok(())
+    }
+
+    fn print_options(&self) -> PrintOptions {
+        self.lcd.print_options()
+    }
+
+    fn clear_region(&mut self, from: Point, to: Point) -> UnitResult {
+        check_region(self, from, to)?;
+
+        self.push(Operation::ClearRegion(from, to));
+        self.cursor = from;
+
+        Ok(())
+    }
+
+    /// Flushes the queue first, so that the character is read as it's meant to be.
+    fn read_char_at(&mut self, point: Point) -> Result<u8> {
+        self.flush()?;
+        self.lcd.read_char_at(point)
+    }
+
+    fn rom(&self) -> Rom {
+        self.lcd.rom()
+    }
+
+    fn height(&self) -> usize {
+        self.lcd.height()
+    }
+
+    fn width(&self) -> usize {
+        self.lcd.width()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.lcd.capabilities()
+    }
+}
+
+impl<L: Backlight> Backlight for Queued<L> {
+    fn set_backlight(&mut self, enabled: bool) -> UnitResult {
+        self.lcd.set_backlight(enabled)
+    }
+
+    fn set_backlight_brightness(&mut self, brightness: u8) -> UnitResult {
+        self.lcd.set_backlight_brightness(brightness)
+    }
+
+    fn can_dim_backlight(&self) -> bool {
+        self.lcd.can_dim_backlight()
+    }
+}
+
+impl<L: DisplayControl> DisplayControl for Queued<L> {
+    fn set_cursor_blinking(&mut self, enabled: bool) -> UnitResult {
+        self.lcd.set_cursor_blinking(enabled)
+    }
+
+    fn set_cursor_visible(&mut self, enabled: bool) -> UnitResult {
+        self.lcd.set_cursor_visible(enabled)
+    }
+
+    fn set_text_visible(&mut self, enabled: bool) -> UnitResult {
+        self.lcd.set_text_visible(enabled)
+    }
+
+    fn sleep(&mut self) -> UnitResult {
+        self.lcd.sleep()
+    }
+
+    fn wake(&mut self) -> UnitResult {
+        self.lcd.wake()
+    }
+
+    /// Flushes the queue first, so that the already queued text gets scrolled too.
+    fn scroll_display_left(&mut self) -> UnitResult {
+        self.flush()?;
+        self.lcd.scroll_display_left()
+    }
+
+    /// Flushes the queue first (see `scroll_display_left`).
+    fn scroll_display_right(&mut self) -> UnitResult {
+        self.flush()?;
+        self.lcd.scroll_display_right()
+    }
+}
+
+impl<L: CustomChars> CustomChars for Queued<L> {
+    fn create_char(&mut self, idx: u8, lines: [u8; 8]) -> UnitResult {
+        self.lcd.create_char(idx, lines)
+    }
+}
@@ -0,0 +1,209 @@
+//! Provides a rectangular part of the buffered frontend's screen acting like a tiny, independent
+//! LCD - with its own cursor, coordinates relative to its top-left corner and the text scrolling
+//! up within the region only.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! // a fixed header...
+//! lcd.print_at(0, 0, "== Build status ==")?;
+//!
+//! // ... plus a 3-line log below it
+//! let mut log = lcd.region(Point::new(1, 0), Point::new(3, 19))?;
+//!
+//! log.println("Fetching...")?;
+//! log.println("Compiling...")?;
+//! log.println("Testing...")?;
+//! log.println("Done!")?; // `Fetching...` scrolls out, the header stays in place
+//!
+//! lcd.render()?;
+//! ```
+//!
+//! The region's cursor is remembered by the buffered frontend, so creating the same region again
+//! (eg. during the next frame) continues where the previous one has left off; the frontend's own
+//! cursor is not affected by the regions.
+
+use alloc::string::String;
+use super::Buffered;
+use super::super::{print_with, Capabilities, Hd44780, Point, PrintOptions, Result, Rom, UnitResult};
+
+pub struct Region<'a, L: Hd44780> {
+    lcd: &'a mut Buffered<L>,
+
+    /// region's top-left corner (on the screen)
+    from: Point,
+
+    /// region's bottom-right corner (on the screen)
+    to: Point,
+
+    /// cursor's position (relative to the region)
+    cursor: Point,
+
+    /// whether the cursor has gone past the last line, so the region has to be scrolled up before
+    /// printing anything more
+    pending_new_line: bool,
+}
+
+impl<'a, L: Hd44780> Region<'a, L> {
+    pub(super) fn new(lcd: &'a mut Buffered<L>, from: Point, to: Point) -> Region<'a, L> {
+        let (cursor, pending_new_line) = lcd.region_cursor(from, to);
+
+        Region {
+            lcd,
+            from,
+            to,
+            cursor,
+            pending_new_line,
+        }
+    }
+
+    /// Prints text at current cursor's position and moves to the next line.
+    ///
+    /// The region gets scrolled up only once something is printed past its last line - so the
+    /// last line printed stays visible (instead of the region ending with a blank one).
+    pub fn println<T: Into<String>>(&mut self, str: T) -> UnitResult {
+        let str = str.into();
+        let empty = str.is_empty();
+
+        self.print(str)?;
+
+        // when the text has reached the end of the line, the cursor is already on the next one
+        if empty || self.cursor.x > 0 {
+            self.new_line();
+        }
+
+        Ok(())
+    }
+
+    /// Moves the cursor at the beginning of the next line; on the last line, the cursor stays in
+    /// place until something is printed (see `scroll_if_pending`).
+    fn new_line(&mut self) {
+        self.scroll_if_pending();
+
+        self.cursor.x = 0;
+
+        if self.cursor.y + 1 < self.height() {
+            self.cursor.y += 1;
+        } else {
+            self.pending_new_line = true;
+        }
+    }
+
+    /// Scrolls the region up, if the cursor has gone past its last line.
+    fn scroll_if_pending(&mut self) {
+        if self.pending_new_line {
+            self.lcd.scroll_region_up(self.from, self.to);
+            self.pending_new_line = false;
+        }
+    }
+
+    /// Converts given position (relative to the region) into the screen's one.
+    fn to_screen(&self, y: usize, x: usize) -> Point {
+        Point::new(self.from.y + y, self.from.x + x)
+    }
+}
+
+impl<L: Hd44780> Hd44780 for Region<'_, L> {
+    fn clear(&mut self) -> UnitResult {
+        for point in Point::region(self.from, self.to) {
+            self.lcd.put_char(point, b' ');
+        }
+
+        self.cursor = Point::new(0, 0);
+        self.pending_new_line = false;
+
+        Ok(())
+    }
+
+    fn home(&mut self) -> UnitResult {
+        self.cursor = Point::new(0, 0);
+        self.pending_new_line = false;
+
+        Ok(())
+    }
+
+    fn move_at(&mut self, y: usize, x: usize) -> UnitResult {
+        if y >= self.height() || x >= self.width() {
+            return Err(
+                format!("Tried to move the cursor outside the region (at y={}, x={}).", y, x).into()
+            );
+        }
+
+        self.cursor = Point::new(y, x);
+        self.pending_new_line = false;
+
+        Ok(())
+    }
+
+    fn cursor(&self) -> Point {
+        self.cursor
+    }
+
+    fn print_char(&mut self, ch: u8) -> UnitResult {
+        self.scroll_if_pending();
+
+        let point = self.to_screen(self.cursor.y, self.cursor.x);
+
+        self.lcd.put_char(point, ch);
+        self.cursor.x += 1;
+
+        if self.cursor.x >= self.width() {
+            self.new_line();
+        }
+
+        Ok(())
+    }
+
+    /// Prints given text; `\n` (when enabled - see `PrintOptions::control_chars`) scrolls the
+    /// region just like `println` does.
+    fn print_str(&mut self, str: &str) -> UnitResult {
+        let options = self.print_options();
+
+        print_with(self, str, options, |region| {
+            region.new_line();
+            Ok(())
+        })
+    }
+
+    fn print_options(&self) -> PrintOptions {
+        self.lcd.print_options()
+    }
+
+    /// Reads from the buffer (see `Buffered::char_at`).
+    fn read_char_at(&mut self, point: Point) -> Result<u8> {
+        if point.y >= self.height() || point.x >= self.width() {
+            return Err(
+                format!("Tried to read a character outside the region (at y={}, x={}).", point.y, point.x).into()
+            );
+        }
+
+        self.lcd
+            .char_at(self.to_screen(point.y, point.x))
+            .ok_or_else(|| "Tried to read a character outside the screen.".into())
+    }
+
+    fn rom(&self) -> Rom {
+        self.lcd.rom()
+    }
+
+    fn height(&self) -> usize {
+        self.to.y - self.from.y + 1
+    }
+
+    fn width(&self) -> usize {
+        self.to.x - self.from.x + 1
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            readable: true,
+            ..Capabilities::default()
+        }
+    }
+}
+
+impl<L: Hd44780> Drop for Region<'_, L> {
+    fn drop(&mut self) {
+        self.lcd.set_region_cursor(self.from, self.to, self.cursor, self.pending_new_line);
+    }
+}
@@ -0,0 +1,522 @@
+//! Provides a buffered access to the HD44780, rendered in a background thread.
+//!
+//! The render loop takes over a buffered LCD and refreshes it at a fixed rate, while the
+//! application modifies the screen's contents through cheap, cloneable handles - so that the slow
+//! bus transfers never block the application's logic.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let render_loop = RenderLoop::new(lcd, 20)?;
+//! let mut lcd = render_loop.handle();
+//!
+//! // returns immediately - the text gets sent to the LCD during the next frame
+//! lcd.print_at(0, 0, "Hello World!")?;
+//!
+//! // stops the thread, rendering all the pending changes first
+//! let lcd = render_loop.stop()?;
+//! ```
+//!
+//! # Caveats
+//!
+//! 1. The handles operate on a copy of the screen's contents, so errors (eg. a failed bus
+//!    transfer) are not reported by the handles' methods - instead they stop the render loop and
+//!    are returned from `stop`.
+//!
+//! 2. Changes of the LCD's state (eg. `set_backlight` or `create_char`) are queued and applied at
+//!    the beginning of the next frame, in the order they were made.
+//!
+//! 3. While the LCD is asleep (see `sleep`), rendering is paused - the changes made in the
+//!    meantime get rendered after waking up.
+//!
+//! # Low-power mode
+//!
+//! On battery-powered devices, `RenderHandle::enter_low_power` puts the LCD to sleep and stops
+//! the background thread from waking up at all (so neither the CPU nor the bus is used) - until
+//! `exit_low_power` is called.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use super::Buffered;
+use super::super::{Backlight, Capabilities, CustomChars, DisplayControl, Error, Hd44780, Point, Result, Rom, UnitResult};
+use super::super::buses::RgbBacklight;
+use super::super::ticker::Ticker;
+
+pub struct RenderLoop {
+    frame: Arc<Mutex<Frame>>,
+    stopped: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<::core::result::Result<Buffered, String>>>,
+}
+
+/// A handle allowing to modify the screen rendered by a `RenderLoop`.
+///
+/// Can be cloned freely and sent to other threads - all the handles share the same contents.
+#[derive(Clone)]
+pub struct RenderHandle {
+    frame: Arc<Mutex<Frame>>,
+}
+
+/// Screen's contents, as seen by the handles.
+struct Frame {
+    lines: Vec<Vec<u8>>,
+    cursor: Point,
+    changed: bool,
+
+    /// LCD's state changes waiting for the next frame
+    pending: Vec<Pending>,
+
+    rom: Rom,
+    height: usize,
+    width: usize,
+
+    /// whether the LCD is able to dim the backlight
+    dimmable: bool,
+
+    /// number of custom characters the LCD has room for
+    custom_chars: usize,
+
+    /// the rendering thread (so that it can be woken up from the low-power mode)
+    renderer: Option<thread::Thread>,
+}
+
+enum Pending {
+    Backlight(bool),
+    BacklightBrightness(u8),
+    BacklightColor(u8, u8, u8),
+    CursorBlinking(bool),
+    CursorVisible(bool),
+    TextVisible(bool),
+    CreateChar(u8, [u8; 8]),
+    Sleep,
+    Wake,
+    LowPower(bool),
+}
+
+impl RenderLoop {
+    /// Starts rendering given LCD in a background thread, `fps` times per second.
+    ///
+    /// Frames are rendered only when something has changed since the previous one.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when `fps` is zero.
+    pub fn new(lcd: Buffered, fps: u32) -> Result<RenderLoop> {
+        if fps == 0 {
+            return Err(Error::invalid_config("fps", "refresh rate must be greater than zero".to_string()).into());
+        }
+
+        let lines = (0..lcd.height())
+            .map(|y| lcd.line(y).unwrap_or_default().to_vec())
+            .collect();
+
+        let frame = Arc::new(Mutex::new(Frame {
+            lines,
+            cursor: lcd.cursor(),
+            changed: true,
+            pending: Vec::new(),
+            rom: lcd.rom(),
+            height: lcd.height(),
+            dimmable: lcd.can_dim_backlight(),
+            custom_chars: lcd.capabilities().custom_chars,
+            width: lcd.width(),
+            renderer: None,
+        }));
+
+        let stopped = Arc::new(AtomicBool::new(false));
+        let period = Duration::from_secs(1) / fps;
+
+        let thread = {
+            let frame = frame.clone();
+            let stopped = stopped.clone();
+
+            thread::spawn(move || {
+                run(lcd, &frame, &stopped, period).map_err(|err| err.to_string())
+            })
+        };
+
+        lock(&frame).renderer = Some(thread.thread().clone());
+
+        Ok(RenderLoop {
+            frame,
+            stopped,
+            thread: Some(thread),
+        })
+    }
+
+    /// Returns a new handle allowing to modify the screen.
+    pub fn handle(&self) -> RenderHandle {
+        RenderHandle {
+            frame: self.frame.clone(),
+        }
+    }
+
+    /// Returns whether the background thread is still rendering (ie. it has not been stopped by
+    /// an error).
+    pub fn is_running(&self) -> bool {
+        self.thread
+            .as_ref()
+            .is_some_and(|thread| !thread.is_finished())
+    }
+
+    /// Stops the background thread (rendering all the pending changes first) and gives the LCD
+    /// back.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error that has stopped the rendering, if any.
+    pub fn stop(mut self) -> Result<Buffered> {
+        self.join()
+    }
+
+    fn join(&mut self) -> Result<Buffered> {
+        let thread = self.thread
+            .take()
+            .ok_or("The render loop has already been stopped.")?;
+
+        self.stopped.store(true, Ordering::SeqCst);
+        thread.thread().unpark();
+
+        match thread.join() {
+            Ok(result) => result.map_err(|err| err.into()),
+            Err(_) => Err("The render loop's thread has panicked.".into()),
+        }
+    }
+}
+
+impl Drop for RenderLoop {
+    fn drop(&mut self) {
+        if self.thread.is_some() {
+            let _ = self.join();
+        }
+    }
+}
+
+/// Renders the frames until stopped.
+fn run(mut lcd: Buffered, frame: &Mutex<Frame>, stopped: &AtomicBool, period: Duration) -> Result<Buffered> {
+    let mut asleep = false;
+    let mut low_power = false;
+
+    let started = Instant::now();
+    let mut ticker = Ticker::new(period);
+
+    loop {
+        // checked before rendering, so that the last frame gets rendered after stopping
+        let last = stopped.load(Ordering::SeqCst);
+
+        // waking up early (eg. spuriously) does not make the frames come any faster
+        if ticker.tick(started.elapsed()) || last {
+            render(&mut lcd, frame, &mut asleep, &mut low_power)?;
+        }
+
+        if last {
+            return Ok(lcd);
+        }
+
+        if low_power {
+            // woken up by `exit_low_power` or `stop` (or spuriously, which is harmless)
+            thread::park();
+            ticker.reset();
+        } else {
+            thread::park_timeout(ticker.until_next(started.elapsed()));
+        }
+    }
+}
+
+/// Copies the frame into the LCD and renders it, if anything has changed.
+fn render(lcd: &mut Buffered, frame: &Mutex<Frame>, asleep: &mut bool, low_power: &mut bool) -> UnitResult {
+    // take what's needed and release the lock as soon as possible, so that the handles don't have
+    // to wait for the bus
+    let pending = {
+        let mut frame = lock(frame);
+
+        if !frame.changed && frame.pending.is_empty() {
+            return Ok(());
+        }
+
+        ::core::mem::take(&mut frame.pending)
+    };
+
+    for change in pending {
+        match change {
+            Pending::Backlight(enabled) => lcd.set_backlight(enabled)?,
+            Pending::BacklightBrightness(brightness) => lcd.set_backlight_brightness(brightness)?,
+            Pending::BacklightColor(r, g, b) => lcd.set_backlight_color(r, g, b)?,
+            Pending::CursorBlinking(enabled) => lcd.set_cursor_blinking(enabled)?,
+            Pending::CursorVisible(enabled) => lcd.set_cursor_visible(enabled)?,
+            Pending::TextVisible(enabled) => lcd.set_text_visible(enabled)?,
+            Pending::CreateChar(idx, lines) => lcd.create_char(idx, lines)?,
+
+            Pending::Sleep => {
+                lcd.sleep()?;
+                *asleep = true;
+            }
+
+            Pending::Wake => {
+                lcd.wake()?;
+                *asleep = false;
+                *low_power = false;
+            }
+
+            Pending::LowPower(enabled) => {
+                if enabled {
+                    lcd.sleep()?;
+                } else {
+                    lcd.wake()?;
+                }
+
+                *asleep = enabled;
+                *low_power = enabled;
+            }
+        }
+    }
+
+    // while asleep, the changes are left for after waking up
+    if *asleep {
+        return Ok(());
+    }
+
+    let lines = {
+        let mut frame = lock(frame);
+
+        frame.changed = false;
+        frame.lines.clone()
+    };
+
+    for (y, line) in lines.iter().enumerate() {
+        lcd.move_at(y, 0)?;
+
+        for &ch in line {
+            lcd.print_char(ch)?;
+        }
+    }
+
+    lcd.render()?;
+
+    Ok(())
+}
+
+/// Locks the frame, recovering it if some other thread has panicked while holding the lock.
+fn lock(frame: &Mutex<Frame>) -> MutexGuard<'_, Frame> {
+    frame
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+}
+
+impl Frame {
+    /// Puts given character at the cursor's position and moves the cursor, wrapping around just
+    /// like the buffered frontend does.
+    fn put(&mut self, ch: u8) {
+        let Point { y, x } = self.cursor;
+
+        self.lines[y][x] = ch;
+        self.changed = true;
+
+        self.cursor = if x + 1 < self.width {
+            Point::new(y, x + 1)
+        } else if y + 1 < self.height {
+            Point::new(y + 1, 0)
+        } else {
+            Point::new(0, 0)
+        };
+    }
+}
+
+impl RenderHandle {
+    fn lock(&self) -> MutexGuard<'_, Frame> {
+        lock(&self.frame)
+    }
+
+    fn push(&self, change: Pending) -> UnitResult {
+        self.lock().pending.push(change);
+
+        Ok(())
+    }
+
+    /// Puts the LCD to sleep (turning off the text and the backlight) and pauses the background
+    /// thread completely, until `exit_low_power` is called - for the battery-powered devices.
+    ///
+    /// Changes made in the meantime are rendered after exiting the low-power mode.
+    pub fn enter_low_power(&self) -> UnitResult {
+        self.push(Pending::LowPower(true))
+    }
+
+    /// Wakes the LCD up and resumes rendering at the regular rate (see `enter_low_power`).
+    pub fn exit_low_power(&self) -> UnitResult {
+        self.push_waking(Pending::LowPower(false))
+    }
+
+    /// Queues given change, waking the rendering thread up (in case it's in the low-power mode).
+    fn push_waking(&self, change: Pending) -> UnitResult {
+        let mut frame = self.lock();
+
+        frame.pending.push(change);
+
+        if let Some(ref renderer) = frame.renderer {
+            renderer.unpark();
+        }
+
+        Ok(())
+    }
+}
+
+impl Hd44780 for RenderHandle {
+    fn clear(&mut self) -> UnitResult {
+        let mut frame = self.lock();
+
+        for line in &mut frame.lines {
+            for ch in line {
+                *ch = b' ';
+            }
+        }
+
+        frame.cursor = Point::new(0, 0);
+        frame.changed = true;
+
+        Ok(())
+    }
+
+    fn home(&mut self) -> UnitResult {
+        self.move_at(0, 0)
+    }
+
+    fn move_at(&mut self, y: usize, x: usize) -> UnitResult {
+        let mut frame = self.lock();
+
+        if y >= frame.height || x >= frame.width {
+            return Err(
+                format!("Tried to move the cursor outside the screen (at y={}, x={}).", y, x).into()
+            );
+        }
+
+        frame.cursor = Point::new(y, x);
+
+        Ok(())
+    }
+
+    fn cursor(&self) -> Point {
+        self.lock().cursor
+    }
+
+    fn print_char(&mut self, ch: u8) -> UnitResult {
+        self.lock().put(ch);
+
+        Ok(())
+    }
+
+    fn print_str(&mut self, str: &str) -> UnitResult {
+        let mut frame = self.lock();
+        let rom = frame.rom;
+
+        // keep the lock for the whole string, so that it never gets rendered half-printed
+        for ch in str.chars() {
+            frame.put(rom.encode(ch));
+        }
+
+        Ok(())
+    }
+
+    fn rom(&self) -> Rom {
+        self.lock().rom
+    }
+
+    fn height(&self) -> usize {
+        self.lock().height
+    }
+
+    fn width(&self) -> usize {
+        self.lock().width
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        let frame = self.lock();
+
+        Capabilities {
+            backlight: true,
+            dimmable_backlight: frame.dimmable,
+            readable: false,
+            custom_chars: frame.custom_chars,
+            scrolling: true,
+        }
+    }
+}
+
+impl Backlight for RenderHandle {
+    fn set_backlight(&mut self, enabled: bool) -> UnitResult {
+        self.push(Pending::Backlight(enabled))
+    }
+
+    fn set_backlight_brightness(&mut self, brightness: u8) -> UnitResult {
+        self.push(Pending::BacklightBrightness(brightness))
+    }
+
+    fn can_dim_backlight(&self) -> bool {
+        self.lock().dimmable
+    }
+}
+
+impl DisplayControl for RenderHandle {
+    fn set_cursor_blinking(&mut self, enabled: bool) -> UnitResult {
+        self.push(Pending::CursorBlinking(enabled))
+    }
+
+    fn set_cursor_visible(&mut self, enabled: bool) -> UnitResult {
+        self.push(Pending::CursorVisible(enabled))
+    }
+
+    fn set_text_visible(&mut self, enabled: bool) -> UnitResult {
+        self.push(Pending::TextVisible(enabled))
+    }
+
+    /// Puts the LCD to sleep and pauses rendering, until `wake` is called.
+    fn sleep(&mut self) -> UnitResult {
+        self.push(Pending::Sleep)
+    }
+
+    fn wake(&mut self) -> UnitResult {
+        self.push_waking(Pending::Wake)
+    }
+
+    /// Emulated by rotating each line of the frame (see `Buffered::scroll_display_left`).
+    fn scroll_display_left(&mut self) -> UnitResult {
+        let mut frame = self.lock();
+
+        for line in &mut frame.lines {
+            line.rotate_left(1);
+        }
+
+        frame.changed = true;
+
+        Ok(())
+    }
+
+    /// Emulated by rotating each line of the frame (see `Buffered::scroll_display_left`).
+    fn scroll_display_right(&mut self) -> UnitResult {
+        let mut frame = self.lock();
+
+        for line in &mut frame.lines {
+            line.rotate_right(1);
+        }
+
+        frame.changed = true;
+
+        Ok(())
+    }
+}
+
+impl CustomChars for RenderHandle {
+    fn create_char(&mut self, idx: u8, lines: [u8; 8]) -> UnitResult {
+        Error::check_char_idx(idx, 7)?;
+
+        self.push(Pending::CreateChar(idx, lines))
+    }
+}
+
+impl RgbBacklight for RenderHandle {
+    fn set_backlight_color(&mut self, r: u8, g: u8, b: u8) -> UnitResult {
+        self.push(Pending::BacklightColor(r, g, b))
+    }
+}
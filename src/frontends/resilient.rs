@@ -0,0 +1,333 @@
+//! Recovers the LCD after it has stopped responding (eg. browned out, or its cable has been
+//! re-plugged) - after a few consecutive failures the LCD gets reinitialized (see
+//! `Reinitialize`) and the failed operation is retried, so the application can keep on going as
+//! if nothing happened.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut lcd = Resilient::new(BufferedLcd::new(Box::new(lcd))?);
+//!
+//! lcd.set_failure_threshold(3);
+//!
+//! lcd.on_recovery(|recovery| {
+//!     eprintln!("{}", recovery);
+//! });
+//!
+//! loop {
+//!     lcd.print_at(0, 0, clock())?;
+//!     lcd.render()?;
+//! }
+//! ```
+//!
+//! # Caveats
+//!
+//! 1. Reinitializing clears the screen, so the wrapper works best on top of the buffered frontend
+//!    (which redraws the whole screen on the next `render`) - with the direct one, everything
+//!    printed before the recovery is lost.
+//!
+//! 2. Only the positions are validated up front - any other error returned by the LCD is treated
+//!    as a bus failure.
+
+use alloc::boxed::Box;
+use core::{error, fmt, mem};
+use super::super::{Backlight, Capabilities, CustomChars, DisplayControl, Error, Hd44780, Point, PrintOptions, Result, Rom, UnitResult};
+use super::{Buffered, Direct, RenderReport};
+
+/// Frontends able to bring the LCD back into the state it should be in, after it has been reset
+/// (see `Direct::reinitialize`).
+pub trait Reinitialize: Hd44780 {
+    fn reinitialize(&mut self) -> UnitResult;
+
+    /// Checks whether the LCD is connected - `None` when it cannot be told (see `Bus::probe`).
+    fn probe(&mut self) -> Option<bool> {
+        None
+    }
+}
+
+pub struct Resilient<L> {
+    lcd: L,
+
+    /// number of consecutive failures after which the LCD gets reinitialized
+    threshold: u32,
+
+    /// number of consecutive failures so far
+    failures: u32,
+
+    on_recovery: Option<RecoveryCallback>,
+}
+
+type RecoveryCallback = Box<dyn FnMut(Recovery) + Send>;
+
+/// Reported after each attempt to recover the LCD (see `Resilient::on_recovery`).
+#[derive(Debug)]
+pub enum Recovery<'a> {
+    /// The LCD has been reinitialized after given number of consecutive failures, the last one
+    /// being `error`.
+    Reinitialized {
+        failures: u32,
+        error: &'a (dyn error::Error + 'static),
+    },
+
+    /// Reinitializing the LCD has failed with `error` - it will be attempted again on the next
+    /// failure.
+    Failed {
+        failures: u32,
+        error: &'a (dyn error::Error + 'static),
+    },
+}
+
+impl<L: Reinitialize> Resilient<L> {
+    /// Wraps given LCD, reinitializing it right after the first failure.
+    pub fn new(lcd: L) -> Resilient<L> {
+        Resilient {
+            lcd,
+            threshold: 1,
+            failures: 0,
+            on_recovery: None,
+        }
+    }
+
+    /// Changes the number of consecutive failures after which the LCD gets reinitialized (the
+    /// failures before that are returned as-is).
+    pub fn set_failure_threshold(&mut self, failures: u32) {
+        self.threshold = failures.max(1);
+    }
+
+    /// Registers a callback invoked after each attempt to recover the LCD (eg. for logging).
+    pub fn on_recovery<F>(&mut self, f: F) where F: FnMut(Recovery) + Send + 'static {
+        self.on_recovery = Some(Box::new(f));
+    }
+
+    /// Returns the wrapped LCD.
+    pub fn inner(&self) -> &L {
+        &self.lcd
+    }
+
+    /// Returns the wrapped LCD.
+    pub fn inner_mut(&mut self) -> &mut L {
+        &mut self.lcd
+    }
+
+    /// Returns the wrapped LCD.
+    pub fn into_inner(self) -> L {
+        self.lcd
+    }
+
+    /// Performs given operation, reinitializing the LCD and retrying the operation once when the
+    /// failure threshold has been reached.
+    fn guard<T, F>(&mut self, mut f: F) -> Result<T> where F: FnMut(&mut L) -> Result<T> {
+        let err = match f(&mut self.lcd) {
+            Ok(value) => {
+                self.failures = 0;
+                return Ok(value);
+            }
+
+            Err(err) => err,
+        };
+
+        self.failures += 1;
+
+        if self.failures < self.threshold {
+            return Err(err);
+        }
+
+        let failures = mem::replace(&mut self.failures, 0);
+
+        if let Err(reinit_err) = self.lcd.reinitialize() {
+            self.notify(Recovery::Failed { failures, error: reinit_err.as_ref() });
+            self.failures = failures;
+
+            return Err(err);
+        }
+
+        self.notify(Recovery::Reinitialized { failures, error: err.as_ref() });
+
+        f(&mut self.lcd).inspect_err(|_| {
+            self.failures = 1;
+        })
+    }
+
+    fn notify(&mut self, recovery: Recovery) {
+        if let Some(ref mut on_recovery) = self.on_recovery {
+            on_recovery(recovery);
+        }
+    }
+
+    /// Makes sure given position lies on the screen, so that invalid arguments are not mistaken
+    /// for the bus failures.
+    fn check_position(&self, y: usize, x: usize) -> UnitResult {
+        if y >= self.lcd.height() || x >= self.lcd.width() {
+            return Err(
+                format!("Tried to move the cursor outside the screen (at y={}, x={}).", y, x).into()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Resilient<Buffered<Direct>> {
+    /// Renders the buffer's contents (see `Buffered::render`) - after a recovery, the whole screen
+    /// gets redrawn.
+    pub fn render(&mut self) -> Result<RenderReport> {
+        self.guard(|lcd| lcd.render())
+    }
+}
+
+impl fmt::Display for Recovery<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Recovery::Reinitialized { failures, error } => {
+                write!(f, "LCD has been reinitialized after {} failure(s), last error: {}", failures, error)
+            }
+
+            Recovery::Failed { failures, error } => {
+                write!(f, "LCD could not be reinitialized after {} failure(s): {}", failures, error)
+            }
+        }
+    }
+}
+
+impl Reinitialize for Direct {
+    fn reinitialize(&mut self) -> UnitResult {
+        Direct::reinitialize(self)
+    }
+
+    fn probe(&mut self) -> Option<bool> {
+        Direct::probe(self)
+    }
+}
+
+impl Reinitialize for Buffered<Direct> {
+    fn reinitialize(&mut self) -> UnitResult {
+        Buffered::reinitialize(self)
+    }
+
+    fn probe(&mut self) -> Option<bool> {
+        Buffered::probe(self)
+    }
+}
+
+impl<L: Reinitialize> Hd44780 for Resilient<L> {
+    fn clear(&mut self) -> UnitResult {
+        self.guard(|lcd| lcd.clear())
+    }
+
+    fn home(&mut self) -> UnitResult {
+        self.guard(|lcd| lcd.home())
+    }
+
+    fn move_at(&mut self, y: usize, x: usize) -> UnitResult {
+        self.check_position(y, x)?;
+        self.guard(|lcd| lcd.move_at(y, x))
+    }
+
+    fn cursor(&self) -> Point {
+        self.lcd.cursor()
+    }
+
+    fn print_char(&mut self, ch: u8) -> UnitResult {
+        self.guard(|lcd| lcd.print_char(ch))
+    }
+
+    fn print_char_repeated(&mut self, ch: u8, count: usize) -> UnitResult {
+        self.guard(|lcd| lcd.print_char_repeated(ch, count))
+    }
+
+    fn print_char_at(&mut self, y: usize, x: usize, ch: u8) -> UnitResult {
+        self.check_position(y, x)?;
+        self.guard(|lcd| lcd.print_char_at(y, x, ch))
+    }
+
+    fn print_str(&mut self, str: &str) -> UnitResult {
+        self.guard(|lcd| lcd.print_str(str))
+    }
+
+    fn print_options(&self) -> PrintOptions {
+        self.lcd.print_options()
+    }
+
+    fn print_str_at(&mut self, y: usize, x: usize, str: &str) -> UnitResult {
+        self.check_position(y, x)?;
+        self.guard(|lcd| lcd.print_str_at(y, x, str))
+    }
+
+    fn clear_line(&mut self, y: usize) -> UnitResult {
+        self.check_position(y, 0)?;
+        self.guard(|lcd| lcd.clear_line(y))
+    }
+
+    fn read_char_at(&mut self, point: Point) -> Result<u8> {
+        self.check_position(point.y, point.x)?;
+        self.guard(|lcd| lcd.read_char_at(point))
+    }
+
+    fn rom(&self) -> Rom {
+        self.lcd.rom()
+    }
+
+    fn height(&self) -> usize {
+        self.lcd.height()
+    }
+
+    fn width(&self) -> usize {
+        self.lcd.width()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.lcd.capabilities()
+    }
+}
+
+impl<L: Reinitialize + Backlight> Backlight for Resilient<L> {
+    fn set_backlight(&mut self, enabled: bool) -> UnitResult {
+        self.guard(|lcd| lcd.set_backlight(enabled))
+    }
+
+    fn set_backlight_brightness(&mut self, brightness: u8) -> UnitResult {
+        self.guard(|lcd| lcd.set_backlight_brightness(brightness))
+    }
+
+    fn can_dim_backlight(&self) -> bool {
+        self.lcd.can_dim_backlight()
+    }
+}
+
+impl<L: Reinitialize + DisplayControl> DisplayControl for Resilient<L> {
+    fn set_cursor_blinking(&mut self, enabled: bool) -> UnitResult {
+        self.guard(|lcd| lcd.set_cursor_blinking(enabled))
+    }
+
+    fn set_cursor_visible(&mut self, enabled: bool) -> UnitResult {
+        self.guard(|lcd| lcd.set_cursor_visible(enabled))
+    }
+
+    fn set_text_visible(&mut self, enabled: bool) -> UnitResult {
+        self.guard(|lcd| lcd.set_text_visible(enabled))
+    }
+
+    fn sleep(&mut self) -> UnitResult {
+        self.guard(|lcd| lcd.sleep())
+    }
+
+    fn wake(&mut self) -> UnitResult {
+        self.guard(|lcd| lcd.wake())
+    }
+
+    fn scroll_display_left(&mut self) -> UnitResult {
+        self.guard(|lcd| lcd.scroll_display_left())
+    }
+
+    fn scroll_display_right(&mut self) -> UnitResult {
+        self.guard(|lcd| lcd.scroll_display_right())
+    }
+}
+
+impl<L: Reinitialize + CustomChars> CustomChars for Resilient<L> {
+    fn create_char(&mut self, idx: u8, lines: [u8; 8]) -> UnitResult {
+        Error::check_char_idx(idx, 7)?;
+
+        self.guard(|lcd| lcd.create_char(idx, lines))
+    }
+}
@@ -0,0 +1,90 @@
+//! Provides a way of retrying the bus transfers that have failed (eg. due to an I2C device not
+//! acknowledging a transfer on a long, noisy cable).
+
+use super::super::{Error, UnitResult};
+use super::super::buses::{Bus, Stats};
+
+/// Determines how many times (and how eagerly) failed bus transfers get retried.
+///
+/// After each failure, the frontend waits `backoff_us` microseconds before the next attempt, and
+/// the wait doubles each time.
+///
+/// # Caveats
+///
+/// On the 4-bit buses each byte is transferred as two nibbles - when the transfer fails in
+/// between them, the LCD is left expecting the second nibble and the retried byte gets misread.
+/// Retrying copes well with failures affecting whole transfers (like I2C NAKs), but nothing more.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// // try each transfer up to 3 times, waiting 1ms and then 2ms in between
+/// lcd.set_retry_policy(RetryPolicy::new(3, 1000));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// How many times each transfer is attempted (including the first attempt).
+    pub attempts: u32,
+
+    /// How long to wait before the first retry.
+    pub backoff_us: u32,
+}
+
+impl RetryPolicy {
+    pub fn new(attempts: u32, backoff_us: u32) -> RetryPolicy {
+        RetryPolicy {
+            attempts,
+            backoff_us,
+        }
+    }
+
+    /// Returns a policy which does not retry at all (the default).
+    pub fn none() -> RetryPolicy {
+        RetryPolicy::new(1, 0)
+    }
+
+    /// Executes given transfer, retrying it according to the policy.
+    ///
+    /// When all the attempts fail, returns `Error::RetriesExhausted` wrapping the last error (or
+    /// the error itself, when the policy does not retry at all).
+    ///
+    /// Each retry is recorded in `stats`.
+    pub(crate) fn run<F>(&self, bus: &mut dyn Bus, stats: &mut Stats, mut transfer: F) -> UnitResult where F: FnMut(&mut dyn Bus) -> UnitResult {
+        let attempts = self.attempts.max(1);
+        let mut backoff_us = self.backoff_us;
+        let mut attempt = 1;
+
+        loop {
+            match transfer(bus) {
+                Ok(()) => {
+                    return Ok(());
+                }
+
+                Err(err) if attempt >= attempts => {
+                    return Err(if attempts > 1 {
+                        Error::RetriesExhausted { attempts, cause: err }.into()
+                    } else {
+                        err
+                    });
+                }
+
+                #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+                Err(err) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(attempt, error = %err, "transfer failed, retrying");
+
+                    stats.retries += 1;
+                    bus.delay_us(backoff_us);
+                    backoff_us = backoff_us.saturating_mul(2);
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy::none()
+    }
+}
@@ -0,0 +1,199 @@
+//! Provides a thread-safe, shareable access to the HD44780.
+//!
+//! Wraps any other frontend (eg. the direct or buffered one) in a mutex and can be cloned freely -
+//! each clone controls the very same LCD, so handles can be passed to many threads at once.
+//!
+//! # Atomicity
+//!
+//! Every method locks the LCD for its whole duration, so multi-step operations can never be
+//! interleaved with calls coming from other threads - eg. `create_char` (which sets the CGRAM
+//! address and then sends eight data bytes) or `print_at` (which moves the cursor and then prints
+//! the text).
+//!
+//! When a longer sequence of calls has to be atomic as a whole, use the `transaction` method.
+
+use std::sync::{Arc, Mutex, MutexGuard};
+use super::super::{Backlight, Capabilities, CursorStyle, CustomChars, DisplayControl, Hd44780, Point, PrintOptions, Result, Rom, UnitResult};
+use super::super::buses::RgbBacklight;
+
+pub struct Shared<T: Hd44780> {
+    lcd: Arc<Mutex<T>>,
+}
+
+impl<T: Hd44780> Shared<T> {
+    /// Creates a new shared HD44780 basing on previously existing one.
+    pub fn new(lcd: T) -> Shared<T> {
+        Shared {
+            lcd: Arc::new(Mutex::new(lcd)),
+        }
+    }
+
+    /// Executes given closure with an exclusive access to the underlying LCD.
+    ///
+    /// No other handle is able to talk to the LCD until the closure returns, so all the operations
+    /// performed inside are guaranteed not to be interleaved with anything else.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use pwr_hd44780::{CustomChars, Hd44780, SharedLcd, UnitResult};
+    /// # fn example(lcd: &SharedLcd<impl Hd44780 + CustomChars>, glyph: [u8; 8]) -> UnitResult {
+    /// lcd.transaction(|lcd| {
+    ///     lcd.create_char(1, glyph)?;
+    ///     lcd.print_char_at(0, 0, 1)
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn transaction<F, R>(&self, f: F) -> R where F: FnOnce(&mut T) -> R {
+        f(&mut self.lock())
+    }
+
+    /// Locks the underlying LCD.
+    ///
+    /// A panic inside some other thread's transaction does not render the LCD unusable - the lock
+    /// is recovered and the LCD is handed over as-is.
+    fn lock(&self) -> MutexGuard<'_, T> {
+        self.lcd
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl<T: Hd44780> Clone for Shared<T> {
+    fn clone(&self) -> Shared<T> {
+        Shared {
+            lcd: self.lcd.clone(),
+        }
+    }
+}
+
+impl<T: Hd44780> Hd44780 for Shared<T> {
+    fn clear(&mut self) -> UnitResult {
+        self.lock().clear()
+    }
+
+    fn home(&mut self) -> UnitResult {
+        self.lock().home()
+    }
+
+    fn move_at(&mut self, y: usize, x: usize) -> UnitResult {
+        self.lock().move_at(y, x)
+    }
+
+    fn cursor(&self) -> Point {
+        self.lock().cursor()
+    }
+
+    fn print_char(&mut self, ch: u8) -> UnitResult {
+        self.lock().print_char(ch)
+    }
+
+    fn print_char_repeated(&mut self, ch: u8, count: usize) -> UnitResult {
+        self.lock().print_char_repeated(ch, count)
+    }
+
+    fn print_char_at(&mut self, y: usize, x: usize, ch: u8) -> UnitResult {
+        self.lock().print_char_at(y, x, ch)
+    }
+
+    fn print_str(&mut self, str: &str) -> UnitResult {
+        self.lock().print_str(str)
+    }
+
+    fn print_str_at(&mut self, y: usize, x: usize, str: &str) -> UnitResult {
+        self.lock().print_str_at(y, x, str)
+    }
+
+    fn clear_line(&mut self, y: usize) -> UnitResult {
+        self.lock().clear_line(y)
+    }
+
+    fn clear_region(&mut self, from: Point, to: Point) -> UnitResult {
+        self.lock().clear_region(from, to)
+    }
+
+    fn read_char_at(&mut self, point: Point) -> Result<u8> {
+        self.lock().read_char_at(point)
+    }
+
+    fn print_options(&self) -> PrintOptions {
+        self.lock().print_options()
+    }
+
+    fn rom(&self) -> Rom {
+        self.lock().rom()
+    }
+
+    fn height(&self) -> usize {
+        self.lock().height()
+    }
+
+    fn width(&self) -> usize {
+        self.lock().width()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.lock().capabilities()
+    }
+}
+
+impl<T: Backlight> Backlight for Shared<T> {
+    fn set_backlight(&mut self, enabled: bool) -> UnitResult {
+        self.lock().set_backlight(enabled)
+    }
+
+    fn set_backlight_brightness(&mut self, brightness: u8) -> UnitResult {
+        self.lock().set_backlight_brightness(brightness)
+    }
+
+    fn can_dim_backlight(&self) -> bool {
+        self.lock().can_dim_backlight()
+    }
+}
+
+impl<T: DisplayControl> DisplayControl for Shared<T> {
+    fn set_cursor_blinking(&mut self, enabled: bool) -> UnitResult {
+        self.lock().set_cursor_blinking(enabled)
+    }
+
+    fn set_cursor_visible(&mut self, enabled: bool) -> UnitResult {
+        self.lock().set_cursor_visible(enabled)
+    }
+
+    fn set_cursor_style(&mut self, style: CursorStyle) -> UnitResult {
+        self.lock().set_cursor_style(style)
+    }
+
+    fn set_text_visible(&mut self, enabled: bool) -> UnitResult {
+        self.lock().set_text_visible(enabled)
+    }
+
+    fn sleep(&mut self) -> UnitResult {
+        self.lock().sleep()
+    }
+
+    fn wake(&mut self) -> UnitResult {
+        self.lock().wake()
+    }
+
+    fn scroll_display_left(&mut self) -> UnitResult {
+        self.lock().scroll_display_left()
+    }
+
+    fn scroll_display_right(&mut self) -> UnitResult {
+        self.lock().scroll_display_right()
+    }
+}
+
+impl<T: CustomChars> CustomChars for Shared<T> {
+    fn create_char(&mut self, idx: u8, lines: [u8; 8]) -> UnitResult {
+        self.lock().create_char(idx, lines)
+    }
+}
+
+impl<T: Hd44780 + RgbBacklight> RgbBacklight for Shared<T> {
+    fn set_backlight_color(&mut self, r: u8, g: u8, b: u8) -> UnitResult {
+        self.lock().set_backlight_color(r, g, b)
+    }
+}
@@ -0,0 +1,192 @@
+//! Mirrors everything printed onto two LCDs at once - eg. a physical display and a simulator
+//! (for debugging a deployment), or two identical operator panels.
+//!
+//! More LCDs can be mirrored by nesting the tees (`Tee::new(a, Tee::new(b, c))`).
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut lcd = Tee::new(panel, simulator);
+//!
+//! lcd.print_at(0, 0, "Hello World!")?;
+//! ```
+//!
+//! # Caveats
+//!
+//! 1. The queries (eg. `cursor` or `width`) are answered by the primary LCD (the first one), so
+//!    the secondary one should be at least as large.
+//!
+//! 2. Each call is forwarded to both the LCDs, even if it has failed on the first one - so that
+//!    a single broken display does not stop the other one; the first error is returned.
+//!
+//! 3. The capabilities (eg. `Backlight`) are provided only when both the LCDs provide them.
+
+use super::super::{Backlight, Capabilities, CursorStyle, CustomChars, DisplayControl, Hd44780, Point, PrintOptions, Result, Rom, UnitResult};
+use super::super::buses::RgbBacklight;
+
+pub struct Tee<A, B> {
+    primary: A,
+    secondary: B,
+}
+
+impl<A: Hd44780, B: Hd44780> Tee<A, B> {
+    pub fn new(primary: A, secondary: B) -> Tee<A, B> {
+        Tee { primary, secondary }
+    }
+
+    /// Returns the primary LCD.
+    pub fn primary(&mut self) -> &mut A {
+        &mut self.primary
+    }
+
+    /// Returns the secondary LCD.
+    pub fn secondary(&mut self) -> &mut B {
+        &mut self.secondary
+    }
+
+    /// Returns both the LCDs.
+    pub fn into_inner(self) -> (A, B) {
+        (self.primary, self.secondary)
+    }
+
+    /// Performs given operation on both the LCDs, returning the first error.
+    fn both<F, G>(&mut self, a: F, b: G) -> UnitResult where F: FnOnce(&mut A) -> UnitResult, G: FnOnce(&mut B) -> UnitResult {
+        let primary = a(&mut self.primary);
+        let secondary = b(&mut self.secondary);
+
+        primary.and(secondary)
+    }
+}
+
+impl<A: Hd44780, B: Hd44780> Hd44780 for Tee<A, B> {
+    fn clear(&mut self) -> UnitResult {
+        self.both(|lcd| lcd.clear(), |lcd| lcd.clear())
+    }
+
+    fn home(&mut self) -> UnitResult {
+        self.both(|lcd| lcd.home(), |lcd| lcd.home())
+    }
+
+    fn move_at(&mut self, y: usize, x: usize) -> UnitResult {
+        self.both(|lcd| lcd.move_at(y, x), |lcd| lcd.move_at(y, x))
+    }
+
+    fn cursor(&self) -> Point {
+        self.primary.cursor()
+    }
+
+    fn print_char(&mut self, ch: u8) -> UnitResult {
+        self.both(|lcd| lcd.print_char(ch), |lcd| lcd.print_char(ch))
+    }
+
+    fn print_char_repeated(&mut self, ch: u8, count: usize) -> UnitResult {
+        self.both(|lcd| lcd.print_char_repeated(ch, count), |lcd| lcd.print_char_repeated(ch, count))
+    }
+
+    fn print_char_at(&mut self, y: usize, x: usize, ch: u8) -> UnitResult {
+        self.both(|lcd| lcd.print_char_at(y, x, ch), |lcd| lcd.print_char_at(y, x, ch))
+    }
+
+    /// Prints given string on both the LCDs - each one translates it using its own ROM.
+    fn print_str(&mut self, str: &str) -> UnitResult {
+        self.both(|lcd| lcd.print_str(str), |lcd| lcd.print_str(str))
+    }
+
+    fn print_options(&self) -> PrintOptions {
+        self.primary.print_options()
+    }
+
+    fn print_str_at(&mut self, y: usize, x: usize, str: &str) -> UnitResult {
+        self.both(|lcd| lcd.print_str_at(y, x, str), |lcd| lcd.print_str_at(y, x, str))
+    }
+
+    fn clear_line(&mut self, y: usize) -> UnitResult {
+        self.both(|lcd| lcd.clear_line(y), |lcd| lcd.clear_line(y))
+    }
+
+    fn clear_region(&mut self, from: Point, to: Point) -> UnitResult {
+        self.both(|lcd| lcd.clear_region(from, to), |lcd| lcd.clear_region(from, to))
+    }
+
+    /// Reads from the primary LCD.
+    fn read_char_at(&mut self, point: Point) -> Result<u8> {
+        self.primary.read_char_at(point)
+    }
+
+    fn rom(&self) -> Rom {
+        self.primary.rom()
+    }
+
+    fn height(&self) -> usize {
+        self.primary.height()
+    }
+
+    fn width(&self) -> usize {
+        self.primary.width()
+    }
+
+    /// Returns the capabilities supported by both of the LCDs.
+    fn capabilities(&self) -> Capabilities {
+        self.primary.capabilities().intersect(self.secondary.capabilities())
+    }
+}
+
+impl<A: Backlight, B: Backlight> Backlight for Tee<A, B> {
+    fn set_backlight(&mut self, enabled: bool) -> UnitResult {
+        self.both(|lcd| lcd.set_backlight(enabled), |lcd| lcd.set_backlight(enabled))
+    }
+
+    fn set_backlight_brightness(&mut self, brightness: u8) -> UnitResult {
+        self.both(|lcd| lcd.set_backlight_brightness(brightness), |lcd| lcd.set_backlight_brightness(brightness))
+    }
+
+    fn can_dim_backlight(&self) -> bool {
+        self.primary.can_dim_backlight() && self.secondary.can_dim_backlight()
+    }
+}
+
+impl<A: DisplayControl, B: DisplayControl> DisplayControl for Tee<A, B> {
+    fn set_cursor_blinking(&mut self, enabled: bool) -> UnitResult {
+        self.both(|lcd| lcd.set_cursor_blinking(enabled), |lcd| lcd.set_cursor_blinking(enabled))
+    }
+
+    fn set_cursor_visible(&mut self, enabled: bool) -> UnitResult {
+        self.both(|lcd| lcd.set_cursor_visible(enabled), |lcd| lcd.set_cursor_visible(enabled))
+    }
+
+    fn set_cursor_style(&mut self, style: CursorStyle) -> UnitResult {
+        self.both(|lcd| lcd.set_cursor_style(style), |lcd| lcd.set_cursor_style(style))
+    }
+
+    fn set_text_visible(&mut self, enabled: bool) -> UnitResult {
+        self.both(|lcd| lcd.set_text_visible(enabled), |lcd| lcd.set_text_visible(enabled))
+    }
+
+    fn sleep(&mut self) -> UnitResult {
+        self.both(|lcd| lcd.sleep(), |lcd| lcd.sleep())
+    }
+
+    fn wake(&mut self) -> UnitResult {
+        self.both(|lcd| lcd.wake(), |lcd| lcd.wake())
+    }
+
+    fn scroll_display_left(&mut self) -> UnitResult {
+        self.both(|lcd| lcd.scroll_display_left(), |lcd| lcd.scroll_display_left())
+    }
+
+    fn scroll_display_right(&mut self) -> UnitResult {
+        self.both(|lcd| lcd.scroll_display_right(), |lcd| lcd.scroll_display_right())
+    }
+}
+
+impl<A: CustomChars, B: CustomChars> CustomChars for Tee<A, B> {
+    fn create_char(&mut self, idx: u8, lines: [u8; 8]) -> UnitResult {
+        self.both(|lcd| lcd.create_char(idx, lines), |lcd| lcd.create_char(idx, lines))
+    }
+}
+
+impl<A: Hd44780 + RgbBacklight, B: Hd44780 + RgbBacklight> RgbBacklight for Tee<A, B> {
+    fn set_backlight_color(&mut self, r: u8, g: u8, b: u8) -> UnitResult {
+        self.both(|lcd| lcd.set_backlight_color(r, g, b), |lcd| lcd.set_backlight_color(r, g, b))
+    }
+}
@@ -0,0 +1,295 @@
+//! Stitches many LCDs into a single, larger screen - eg. two 20x4 displays stacked on top of each
+//! other become a single 20x8 one.
+//!
+//! The tiles are laid out either vertically (one below another; they must be of the same width)
+//! or horizontally (side by side; they must be of the same height) - grids can be built by
+//! nesting the tiled frontends.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut lcd = Tiled::vertical(vec![top, bottom])?;
+//!
+//! // lands on the second display
+//! lcd.print_at(5, 0, "Hello World!")?;
+//! ```
+//!
+//! # Caveats
+//!
+//! 1. Text overflowing a tile's line continues on the next line of the tiled screen (not the
+//!    tile's).
+//!
+//! 2. The LCD's state (eg. the backlight or the custom characters) is changed on all the tiles at
+//!    once.
+
+use alloc::vec::Vec;
+use super::super::{Backlight, Capabilities, CustomChars, DisplayControl, Error, Hd44780, Point, PrintOptions, Result, Rom, UnitResult};
+
+pub struct Tiled<L> {
+    tiles: Vec<Tile<L>>,
+    height: usize,
+    width: usize,
+    cursor: Point,
+    print_options: PrintOptions,
+}
+
+struct Tile<L> {
+    /// position of the tile's top-left corner on the tiled screen
+    offset: Point,
+    lcd: L,
+}
+
+impl<L: Hd44780> Tiled<L> {
+    /// Stacks given LCDs one below another (the first one at the top).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when no LCDs are given or when they differ in width.
+    pub fn vertical(lcds: Vec<L>) -> Result<Tiled<L>> {
+        Tiled::new(lcds, true)
+    }
+
+    /// Puts given LCDs side by side (the first one on the left).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when no LCDs are given or when they differ in height.
+    pub fn horizontal(lcds: Vec<L>) -> Result<Tiled<L>> {
+        Tiled::new(lcds, false)
+    }
+
+    fn new(lcds: Vec<L>, vertical: bool) -> Result<Tiled<L>> {
+        let first = lcds
+            .first()
+            .ok_or_else(|| Error::invalid_config("lcds", "at least one LCD is required"))?;
+
+        let (height, width) = (first.height(), first.width());
+        let mut tiles = Vec::with_capacity(lcds.len());
+        let mut offset = Point::new(0, 0);
+
+        for lcd in lcds {
+            if vertical && lcd.width() != width {
+                return Err(Error::invalid_config("lcds", format!("all the LCDs must be {} characters wide", width)).into());
+            }
+
+            if !vertical && lcd.height() != height {
+                return Err(Error::invalid_config("lcds", format!("all the LCDs must be {} lines high", height)).into());
+            }
+
+            let size = if vertical { lcd.height() } else { lcd.width() };
+
+            tiles.push(Tile { offset, lcd });
+
+            if vertical {
+                offset.y += size;
+            } else {
+                offset.x += size;
+            }
+        }
+
+        Ok(
+            Tiled {
+                tiles,
+                height: if vertical { offset.y } else { height },
+                width: if vertical { width } else { offset.x },
+                cursor: Point::new(0, 0),
+                print_options: PrintOptions::default(),
+            }
+        )
+    }
+
+    /// Changes the options used when printing strings (see `PrintOptions`).
+    pub fn set_print_options(&mut self, options: PrintOptions) {
+        self.print_options = options;
+    }
+
+    /// Returns given tile (in the order they were given in).
+    pub fn tile(&mut self, idx: usize) -> Option<&mut L> {
+        self.tiles
+            .get_mut(idx)
+            .map(|tile| &mut tile.lcd)
+    }
+
+    /// Returns the LCDs, in the order they were given in.
+    pub fn into_inner(self) -> Vec<L> {
+        self.tiles
+            .into_iter()
+            .map(|tile| tile.lcd)
+            .collect()
+    }
+
+    /// Returns the tile containing given position and the position relative to that tile.
+    fn locate(&mut self, y: usize, x: usize) -> Result<(&mut L, Point)> {
+        self.tiles
+            .iter_mut()
+            .find(|tile| {
+                y >= tile.offset.y && y < tile.offset.y + tile.lcd.height() &&
+                    x >= tile.offset.x && x < tile.offset.x + tile.lcd.width()
+            })
+            .map(|tile| {
+                let point = Point::new(y - tile.offset.y, x - tile.offset.x);
+                (&mut tile.lcd, point)
+            })
+            .ok_or_else(|| format!("Tried to access a position outside the screen (at y={}, x={}).", y, x).into())
+    }
+
+    /// Performs given operation on all the tiles (even if it fails on some of them), returning
+    /// the first error.
+    fn each<F>(&mut self, mut f: F) -> UnitResult where F: FnMut(&mut L) -> UnitResult {
+        let mut result = Ok(());
+
+        for tile in &mut self.tiles {
+            result = result.and(f(&mut tile.lcd));
+        }
+
+        result
+    }
+
+    /// Scrolls all the tiles at once - supported only for the vertically stacked tiles, since the
+    /// side-by-side ones would each scroll their own part of the line.
+    fn scroll_each<F>(&mut self, f: F) -> UnitResult where F: FnMut(&mut L) -> UnitResult {
+        if self.tiles.iter().any(|tile| tile.offset.x > 0) {
+            return Err("Only the vertically stacked tiles can be scrolled.".into());
+        }
+
+        self.each(f)
+    }
+}
+
+impl<L: Hd44780> Hd44780 for Tiled<L> {
+    fn clear(&mut self) -> UnitResult {
+        self.each(|lcd| lcd.clear())?;
+        self.cursor = Point::new(0, 0);
+
+        Ok(())
+    }
+
+    fn home(&mut self) -> UnitResult {
+        self.each(|lcd| lcd.home())?;
+        self.cursor = Point::new(0, 0);
+
+        Ok(())
+    }
+
+    fn move_at(&mut self, y: usize, x: usize) -> UnitResult {
+        let (lcd, point) = self.locate(y, x)?;
+
+        lcd.move_at(point.y, point.x)?;
+        self.cursor = Point::new(y, x);
+
+        Ok(())
+    }
+
+    fn cursor(&self) -> Point {
+        self.cursor
+    }
+
+    fn print_char(&mut self, ch: u8) -> UnitResult {
+        let Point { y, x } = self.cursor;
+        let (lcd, point) = self.locate(y, x)?;
+
+        // the tile's cursor is usually in place already (unless the previous character has been
+        // printed on another tile)
+        if lcd.cursor() == point {
+            lcd.print_char(ch)?;
+        } else {
+            lcd.print_char_at(point.y, point.x, ch)?;
+        }
+
+        self.cursor.x += 1;
+
+        if self.cursor.x >= self.width {
+            self.cursor = Point::new((y + 1) % self.height, 0);
+        }
+
+        Ok(())
+    }
+
+    fn print_options(&self) -> PrintOptions {
+        self.print_options
+    }
+
+    fn read_char_at(&mut self, point: Point) -> Result<u8> {
+        let (lcd, point) = self.locate(point.y, point.x)?;
+
+        lcd.read_char_at(point)
+    }
+
+    fn rom(&self) -> Rom {
+        self.tiles[0].lcd.rom()
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the capabilities supported by all of the tiles (see `scroll_display_left` for the
+    /// scrolling's limitations).
+    fn capabilities(&self) -> Capabilities {
+        let mut capabilities = self.tiles
+            .iter()
+            .map(|tile| tile.lcd.capabilities())
+            .reduce(Capabilities::intersect)
+            .unwrap_or_default();
+
+        if self.tiles.iter().any(|tile| tile.offset.x > 0) {
+            capabilities.scrolling = false;
+        }
+
+        capabilities
+    }
+}
+
+impl<L: Backlight> Backlight for Tiled<L> {
+    fn set_backlight(&mut self, enabled: bool) -> UnitResult {
+        self.each(|lcd| lcd.set_backlight(enabled))
+    }
+
+    fn set_backlight_brightness(&mut self, brightness: u8) -> UnitResult {
+        self.each(|lcd| lcd.set_backlight_brightness(brightness))
+    }
+
+    fn can_dim_backlight(&self) -> bool {
+        self.tiles.iter().all(|tile| tile.lcd.can_dim_backlight())
+    }
+}
+
+impl<L: DisplayControl> DisplayControl for Tiled<L> {
+    fn set_cursor_blinking(&mut self, enabled: bool) -> UnitResult {
+        self.each(|lcd| lcd.set_cursor_blinking(enabled))
+    }
+
+    fn set_cursor_visible(&mut self, enabled: bool) -> UnitResult {
+        self.each(|lcd| lcd.set_cursor_visible(enabled))
+    }
+
+    fn set_text_visible(&mut self, enabled: bool) -> UnitResult {
+        self.each(|lcd| lcd.set_text_visible(enabled))
+    }
+
+    fn sleep(&mut self) -> UnitResult {
+        self.each(|lcd| lcd.sleep())
+    }
+
+    fn wake(&mut self) -> UnitResult {
+        self.each(|lcd| lcd.wake())
+    }
+
+    fn scroll_display_left(&mut self) -> UnitResult {
+        self.scroll_each(|lcd| lcd.scroll_display_left())
+    }
+
+    fn scroll_display_right(&mut self) -> UnitResult {
+        self.scroll_each(|lcd| lcd.scroll_display_right())
+    }
+}
+
+impl<L: CustomChars> CustomChars for Tiled<L> {
+    fn create_char(&mut self, idx: u8, lines: [u8; 8]) -> UnitResult {
+        self.each(|lcd| lcd.create_char(idx, lines))
+    }
+}
@@ -0,0 +1,222 @@
+//! Provides a clonable handle to the HD44780 owned by a background thread.
+//!
+//! The worker takes over an LCD (any frontend) and executes the jobs sent through a channel, one
+//! after another - so that the web handlers, MQTT callbacks and timers can all update the display
+//! without sharing a `&mut` access and without waiting for the bus (which is slow, especially
+//! over I2C).
+//!
+//! Unlike `Shared`, sending a job never blocks - the handles' methods return as soon as the job
+//! gets queued; unlike `RenderLoop`, there's no frame being copied around - each job talks to the
+//! LCD directly, in the order the jobs were sent.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let worker = LcdWorker::spawn(lcd);
+//! let lcd = worker.handle();
+//!
+//! // returns immediately
+//! lcd.print_at(0, 0, "Hello World!")?;
+//!
+//! // any other operation can be sent as a closure
+//! lcd.execute(|lcd| lcd.create_char(1, glyph))?;
+//!
+//! // waits for the job to finish and returns its result
+//! let ch = lcd.call(|lcd| lcd.read_char_at(Point::new(0, 0)))?;
+//!
+//! // stops the thread, executing all the pending jobs first
+//! let lcd = worker.stop()?;
+//! ```
+//!
+//! # Caveats
+//!
+//! 1. Errors returned by the jobs sent through `execute` (and the other non-blocking methods) are
+//!    not reported by the handles - instead they stop the worker and are returned from `stop`;
+//!    wrap the LCD in `ResilientLcd` to have the bus failures recovered from instead.
+//!
+//! 2. Errors returned by the jobs sent through `call` are returned to the caller and do not stop
+//!    the worker.
+//!
+//! 3. Once the worker has stopped, the handles' methods return an error.
+
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use super::Buffered;
+use super::super::{Backlight, Hd44780, Result, UnitResult};
+
+pub struct LcdWorker<L> {
+    sender: Sender<Job<L>>,
+    thread: Option<thread::JoinHandle<::core::result::Result<L, String>>>,
+}
+
+/// A handle sending jobs to an `LcdWorker`.
+///
+/// Can be cloned freely and sent to other threads - all the handles talk to the same LCD.
+pub struct LcdHandle<L> {
+    sender: Sender<Job<L>>,
+}
+
+enum Job<L> {
+    Run(Box<dyn FnOnce(&mut L) -> UnitResult + Send>),
+    Stop,
+}
+
+impl<L: Send + 'static> LcdWorker<L> {
+    /// Moves given LCD into a background thread, which starts waiting for the jobs.
+    pub fn spawn(lcd: L) -> LcdWorker<L> {
+        let (sender, receiver) = mpsc::channel();
+
+        let thread = thread::spawn(move || {
+            run(lcd, receiver).map_err(|err| err.to_string())
+        });
+
+        LcdWorker {
+            sender,
+            thread: Some(thread),
+        }
+    }
+
+    /// Returns a new handle allowing to send the jobs.
+    pub fn handle(&self) -> LcdHandle<L> {
+        LcdHandle {
+            sender: self.sender.clone(),
+        }
+    }
+
+    /// Returns whether the background thread is still running (ie. it has not been stopped by
+    /// an error).
+    pub fn is_running(&self) -> bool {
+        self.thread
+            .as_ref()
+            .is_some_and(|thread| !thread.is_finished())
+    }
+
+    /// Stops the background thread (executing all the pending jobs first) and gives the LCD back.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error that has stopped the worker, if any.
+    pub fn stop(mut self) -> Result<L> {
+        self.join()
+    }
+
+    fn join(&mut self) -> Result<L> {
+        let thread = self.thread
+            .take()
+            .ok_or("The LCD worker has already been stopped.")?;
+
+        // fails only when the thread has already stopped, which `join` reports anyway
+        let _ = self.sender.send(Job::Stop);
+
+        match thread.join() {
+            Ok(result) => result.map_err(|err| err.into()),
+            Err(_) => Err("The LCD worker's thread has panicked.".into()),
+        }
+    }
+}
+
+impl<L> Drop for LcdWorker<L> {
+    fn drop(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            let _ = self.sender.send(Job::Stop);
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Executes the jobs until stopped.
+fn run<L>(mut lcd: L, receiver: Receiver<Job<L>>) -> Result<L> {
+    for job in receiver {
+        match job {
+            Job::Run(job) => job(&mut lcd)?,
+            Job::Stop => break,
+        }
+    }
+
+    Ok(lcd)
+}
+
+impl<L: Send + 'static> LcdHandle<L> {
+    /// Queues given job, without waiting for it to be executed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the worker has stopped.
+    pub fn execute<F>(&self, job: F) -> UnitResult where F: FnOnce(&mut L) -> UnitResult + Send + 'static {
+        self.sender
+            .send(Job::Run(Box::new(job)))
+            .map_err(|_| "The LCD worker has stopped.".into())
+    }
+
+    /// Queues given job and waits for its result.
+    ///
+    /// # Errors
+    ///
+    /// Returns the job's error or an error when the worker has stopped.
+    pub fn call<F, R>(&self, job: F) -> Result<R> where F: FnOnce(&mut L) -> Result<R> + Send + 'static, R: Send + 'static {
+        let (sender, receiver) = mpsc::channel();
+
+        self.execute(move |lcd| {
+            // the caller might have given up waiting, which is fine
+            let _ = sender.send(job(lcd).map_err(|err| err.to_string()));
+
+            Ok(())
+        })?;
+
+        receiver
+            .recv()
+            .map_err(|_| "The LCD worker has stopped.")?
+            .map_err(|err| err.into())
+    }
+}
+
+impl<L: Hd44780 + Send + 'static> LcdHandle<L> {
+    /// Queues clearing the screen.
+    pub fn clear(&self) -> UnitResult {
+        self.execute(|lcd| lcd.clear())
+    }
+
+    /// Queues printing given text at given position.
+    pub fn print_at<T: Into<String>>(&self, y: usize, x: usize, text: T) -> UnitResult {
+        let text = text.into();
+
+        self.execute(move |lcd| lcd.print_str_at(y, x, &text))
+    }
+
+    /// Queues clearing given line.
+    pub fn clear_line(&self, y: usize) -> UnitResult {
+        self.execute(move |lcd| lcd.clear_line(y))
+    }
+}
+
+impl<L: Backlight + Send + 'static> LcdHandle<L> {
+    /// Queues enabling / disabling the backlight.
+    pub fn set_backlight(&self, enabled: bool) -> UnitResult {
+        self.execute(move |lcd| lcd.set_backlight(enabled))
+    }
+
+    /// Queues changing the backlight's brightness.
+    pub fn set_backlight_brightness(&self, brightness: u8) -> UnitResult {
+        self.execute(move |lcd| lcd.set_backlight_brightness(brightness))
+    }
+}
+
+impl<L: Hd44780 + Send + 'static> LcdHandle<Buffered<L>> {
+    /// Queues rendering the buffer (see `Buffered::render`).
+    pub fn render(&self) -> UnitResult {
+        self.execute(|lcd| {
+            lcd.render()?;
+            Ok(())
+        })
+    }
+}
+
+impl<L> Clone for LcdHandle<L> {
+    fn clone(&self) -> LcdHandle<L> {
+        LcdHandle {
+            sender: self.sender.clone(),
+        }
+    }
+}
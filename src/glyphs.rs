@@ -0,0 +1,210 @@
+//! Provides a set of ready-made custom characters (glyphs).
+//!
+//! Each glyph is a bitmap ready to be passed to `create_char`; `CharBank::load_glyphs` uploads
+//! many of them at once, picking the slots automatically.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut bank = CharBank::new();
+//! let slots = bank.load_glyphs(&mut lcd, &[glyphs::BATTERY[3], glyphs::SIGNAL[4]])?;
+//!
+//! lcd.print_char_at(0, 14, slots[0])?;
+//! lcd.print_char_at(0, 15, slots[1])?;
+//! ```
+
+use super::CharBitmap;
+
+/// Bitmap of a custom character.
+pub type Glyph = [u8; 8];
+
+/// Battery levels, from empty (`BATTERY[0]`) to full (`BATTERY[5]`).
+pub const BATTERY: [Glyph; 6] = [battery(0), battery(1), battery(2), battery(3), battery(4), battery(5)];
+
+/// Signal strength (eg. Wi-Fi) bars, from none (`SIGNAL[0]`) to full (`SIGNAL[4]`).
+pub const SIGNAL: [Glyph; 5] = [signal(0), signal(1), signal(2), signal(3), signal(4)];
+
+pub const ARROW_UP: Glyph = CharBitmap::from_art(&[
+    "  #  ",
+    " ### ",
+    "# # #",
+    "  #  ",
+    "  #  ",
+    "  #  ",
+    "  #  ",
+]);
+
+pub const ARROW_DOWN: Glyph = CharBitmap::from_art(&[
+    "  #  ",
+    "  #  ",
+    "  #  ",
+    "  #  ",
+    "# # #",
+    " ### ",
+    "  #  ",
+]);
+
+pub const ARROW_LEFT: Glyph = CharBitmap::from_art(&[
+    "     ",
+    "  #  ",
+    " #   ",
+    "#####",
+    " #   ",
+    "  #  ",
+    "     ",
+]);
+
+pub const ARROW_RIGHT: Glyph = CharBitmap::from_art(&[
+    "     ",
+    "  #  ",
+    "   # ",
+    "#####",
+    "   # ",
+    "  #  ",
+    "     ",
+]);
+
+/// Degree sign (`°`), for the ROMs lacking one.
+pub const DEGREE: Glyph = CharBitmap::from_art(&[
+    " ##  ",
+    "#  # ",
+    "#  # ",
+    " ##  ",
+]);
+
+pub const BELL: Glyph = CharBitmap::from_art(&[
+    "  #  ",
+    " ### ",
+    " ### ",
+    " ### ",
+    "#####",
+    "     ",
+    "  #  ",
+]);
+
+pub const HEART: Glyph = CharBitmap::from_art(&[
+    "     ",
+    " # # ",
+    "#####",
+    "#####",
+    " ### ",
+    "  #  ",
+]);
+
+pub const PLAY: Glyph = CharBitmap::from_art(&[
+    "#    ",
+    "##   ",
+    "###  ",
+    "#### ",
+    "###  ",
+    "##   ",
+    "#    ",
+]);
+
+pub const PAUSE: Glyph = CharBitmap::from_art(&[
+    "     ",
+    "## ##",
+    "## ##",
+    "## ##",
+    "## ##",
+    "## ##",
+    "     ",
+]);
+
+pub const STOP: Glyph = CharBitmap::from_art(&[
+    "     ",
+    "#####",
+    "#####",
+    "#####",
+    "#####",
+    "#####",
+    "     ",
+]);
+
+/// Box-drawing corners and tees (`┌`, `┐`, `└`, `┘`, `├`, `┤`) - lining up with the ROM's `-` and
+/// `|` (see `widgets::Frame`).
+pub const BOX_TOP_LEFT: Glyph = CharBitmap::from_art(&[
+    "     ",
+    "     ",
+    "     ",
+    "  ###",
+    "  #  ",
+    "  #  ",
+    "  #  ",
+]);
+
+pub const BOX_TOP_RIGHT: Glyph = CharBitmap::from_art(&[
+    "     ",
+    "     ",
+    "     ",
+    "###  ",
+    "  #  ",
+    "  #  ",
+    "  #  ",
+]);
+
+pub const BOX_BOTTOM_LEFT: Glyph = CharBitmap::from_art(&[
+    "  #  ",
+    "  #  ",
+    "  #  ",
+    "  ###",
+]);
+
+pub const BOX_BOTTOM_RIGHT: Glyph = CharBitmap::from_art(&[
+    "  #  ",
+    "  #  ",
+    "  #  ",
+    "###  ",
+]);
+
+pub const BOX_TEE_LEFT: Glyph = CharBitmap::from_art(&[
+    "  #  ",
+    "  #  ",
+    "  #  ",
+    "  ###",
+    "  #  ",
+    "  #  ",
+    "  #  ",
+]);
+
+pub const BOX_TEE_RIGHT: Glyph = CharBitmap::from_art(&[
+    "  #  ",
+    "  #  ",
+    "  #  ",
+    "###  ",
+    "  #  ",
+    "  #  ",
+    "  #  ",
+]);
+
+/// Builds a battery filled in `level` fifths.
+const fn battery(level: usize) -> Glyph {
+    let mut lines = [0b01110, 0b11111, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b11111];
+    let mut filled = 0;
+
+    while filled < level {
+        lines[6 - filled] = 0b11111;
+        filled += 1;
+    }
+
+    lines
+}
+
+/// Builds `bars` (out of four) signal strength bars.
+const fn signal(bars: usize) -> Glyph {
+    let mut lines = [0u8; 8];
+    let mut bar = 1;
+
+    while bar <= bars {
+        let mut y = 8 - 2 * bar;
+
+        while y < 8 {
+            lines[y] |= 1 << (4 - bar);
+            y += 1;
+        }
+
+        bar += 1;
+    }
+
+    lines
+}
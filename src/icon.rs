@@ -0,0 +1,225 @@
+//! Converts small monochrome images (logos, icons) into custom characters, so that they can be
+//! displayed across a few cells with a single call.
+//!
+//! The image is split into 5x8 pixels tiles - one custom character each; identical tiles share a
+//! single character and the blank ones are drawn as spaces, so eg. a 10x16 pixels image takes at
+//! most 4 custom characters.
+//!
+//! Images can be loaded from the XBM format (which is plain text, so it can be easily embedded
+//! using `include_str!`) or - with the `png` feature - from PNG files, where the dark, opaque
+//! pixels are treated as the lit ones.
+//!
+//! # Caveats
+//!
+//! 1. HD44780 leaves a gap between the cells, so the images get slightly "exploded".
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let logo = Icon::from_xbm(include_str!("logo.xbm"))?;
+//!
+//! logo.draw(&mut lcd, 0, 0, 0)?;
+//! ```
+
+use alloc::vec::Vec;
+use super::{CustomChars, Result, UnitResult};
+use super::char_bank::SLOTS;
+
+/// A monochrome image split into the custom characters.
+pub struct Icon {
+    cells: Vec<IconCell>,
+
+    /// size, in cells
+    height: usize,
+    width: usize,
+}
+
+/// A single cell of an icon.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct IconCell {
+    /// position, relative to the icon's top-left corner
+    pub y: usize,
+    pub x: usize,
+
+    /// `None` for the blank cells
+    pub bitmap: Option<[u8; 8]>,
+}
+
+/// Size of a cell, in pixels.
+const CELL_WIDTH: usize = 5;
+const CELL_HEIGHT: usize = 8;
+
+impl Icon {
+    /// Builds an icon of given size (in pixels) from a function telling whether given pixel
+    /// (`(y, x)`) is lit.
+    pub fn from_pixels<F>(height: usize, width: usize, is_lit: F) -> Icon where F: Fn(usize, usize) -> bool {
+        let rows = height.div_ceil(CELL_HEIGHT);
+        let cols = width.div_ceil(CELL_WIDTH);
+        let mut cells = Vec::with_capacity(rows * cols);
+
+        for y in 0..rows {
+            for x in 0..cols {
+                let mut bitmap = [0u8; 8];
+
+                for (line_y, line) in bitmap.iter_mut().enumerate() {
+                    for pixel_x in 0..CELL_WIDTH {
+                        let (py, px) = (y * CELL_HEIGHT + line_y, x * CELL_WIDTH + pixel_x);
+
+                        if py < height && px < width && is_lit(py, px) {
+                            *line |= 1 << (CELL_WIDTH - 1 - pixel_x);
+                        }
+                    }
+                }
+
+                cells.push(IconCell {
+                    y,
+                    x,
+                    bitmap: if bitmap == [0; 8] { None } else { Some(bitmap) },
+                });
+            }
+        }
+
+        Icon {
+            cells,
+            height: rows,
+            width: cols,
+        }
+    }
+
+    /// Builds an icon from an XBM image.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the image is malformed.
+    pub fn from_xbm(xbm: &str) -> Result<Icon> {
+        let define = |suffix: &str| -> Result<usize> {
+            xbm.lines()
+                .filter_map(|line| line.trim().strip_prefix("#define "))
+                .filter_map(|line| line.split_once(' '))
+                .find(|(name, _)| name.ends_with(suffix))
+                .ok_or_else(|| format!("Invalid XBM image: missing `{}`.", suffix).into())
+                .and_then(|(_, value)| Ok(value.trim().parse()?))
+        };
+
+        let (width, height) = (define("_width")?, define("_height")?);
+
+        let bits = xbm
+            .split_once('{')
+            .and_then(|(_, bits)| bits.split_once('}'))
+            .ok_or("Invalid XBM image: missing the pixels.")?
+            .0;
+
+        let bytes = bits
+            .split(',')
+            .map(|byte| byte.trim())
+            .filter(|byte| !byte.is_empty())
+            .map(|byte| u8::from_str_radix(byte.trim_start_matches("0x").trim_start_matches("0X"), 16))
+            .collect::<::core::result::Result<Vec<u8>, _>>()?;
+
+        // each line is padded to whole bytes, with the leftmost pixel in the least significant bit
+        let stride = width.div_ceil(8);
+
+        if bytes.len() < stride * height {
+            return Err("Invalid XBM image: not enough pixels.".into());
+        }
+
+        Ok(Icon::from_pixels(height, width, |y, x| {
+            bytes[y * stride + x / 8] & (1 << (x % 8)) != 0
+        }))
+    }
+
+    /// Builds an icon from a PNG image; the dark (luminance below 50%), opaque pixels are treated
+    /// as the lit ones.
+    ///
+    /// Requires the `png` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the image cannot be decoded.
+    #[cfg(feature = "png")]
+    pub fn from_png<R: std::io::Read>(reader: R) -> Result<Icon> {
+        let mut decoder = png::Decoder::new(reader);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+
+        let mut reader = decoder.read_info()?;
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels)?;
+
+        let channels = info.color_type.samples();
+        let (width, height) = (info.width as usize, info.height as usize);
+
+        Ok(Icon::from_pixels(height, width, |y, x| {
+            let pixel = &pixels[y * info.line_size + x * channels..][..channels];
+
+            let (luma, alpha) = match pixel {
+                [luma] => (*luma as u32, 255),
+                [luma, alpha] => (*luma as u32, *alpha),
+                [r, g, b] => ((*r as u32 * 299 + *g as u32 * 587 + *b as u32 * 114) / 1000, 255),
+                [r, g, b, alpha] => ((*r as u32 * 299 + *g as u32 * 587 + *b as u32 * 114) / 1000, *alpha),
+                _ => (255, 0),
+            };
+
+            luma < 128 && alpha >= 128
+        }))
+    }
+
+    /// Returns the icon's cells, line by line.
+    pub fn cells(&self) -> &[IconCell] {
+        &self.cells
+    }
+
+    /// Returns the icon's height, in cells.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the icon's width, in cells.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the distinct bitmaps the icon consists of (ie. the custom characters it requires).
+    pub fn bitmaps(&self) -> Vec<[u8; 8]> {
+        let mut bitmaps: Vec<[u8; 8]> = Vec::new();
+
+        for bitmap in self.cells.iter().filter_map(|cell| cell.bitmap) {
+            if !bitmaps.contains(&bitmap) {
+                bitmaps.push(bitmap);
+            }
+        }
+
+        bitmaps
+    }
+
+    /// Uploads the icon's custom characters (starting at `first_slot`) and draws the icon with its
+    /// top-left corner at (`y`, `x`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the icon requires more custom characters than available starting
+    /// at `first_slot` or when it does not fit on the screen.
+    pub fn draw<L: CustomChars>(&self, lcd: &mut L, y: usize, x: usize, first_slot: u8) -> UnitResult {
+        let bitmaps = self.bitmaps();
+
+        if first_slot as usize + bitmaps.len() > SLOTS {
+            return Err(
+                format!("Icon requires {} custom characters - first slot must be in range <0, {}>.", bitmaps.len(), SLOTS.saturating_sub(bitmaps.len())).into()
+            );
+        }
+
+        for (idx, bitmap) in bitmaps.iter().enumerate() {
+            lcd.create_char(first_slot + idx as u8, *bitmap)?;
+        }
+
+        for cell in &self.cells {
+            let ch = match cell.bitmap {
+                Some(bitmap) => first_slot + bitmaps.iter().position(|other| *other == bitmap).unwrap_or_default() as u8,
+                None => b' ',
+            };
+
+            lcd.print_char_at(y + cell.y, x + cell.x, ch)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,191 @@
+//! Debounces the buttons of the LCD keypad shields and turns them into press & release events.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! // Adafruit's RGB LCD shield
+//! let mut buttons = Buttons::new(Mcp23017Buttons::adafruit("/dev/i2c-1", 0x20)?);
+//! let started = Instant::now();
+//!
+//! loop {
+//!     for event in buttons.poll(started.elapsed())? {
+//!         match event {
+//!             ButtonEvent::Pressed(Button::Up) | ButtonEvent::Repeated(Button::Up) => { /* ... */ }
+//!             ButtonEvent::Pressed(Button::Down) | ButtonEvent::Repeated(Button::Down) => { /* ... */ }
+//!             _ => (),
+//!         }
+//!     }
+//!
+//!     thread::sleep(Duration::from_millis(5));
+//! }
+//! ```
+
+use alloc::vec::Vec;
+use core::time::Duration;
+use super::super::Result;
+
+/// One of the keypad shield's buttons.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Button {
+    Select,
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ButtonEvent {
+    /// The button has been pressed.
+    Pressed(Button),
+
+    /// The button is being held (see `Buttons::set_repeat`).
+    Repeated(Button),
+
+    /// The button has been released.
+    Released(Button),
+}
+
+/// Reads the buttons' raw (not debounced) state - implemented for the expanders and GPIO pins the
+/// buttons can be wired to, and for closures (eg. for the buttons read some other way).
+pub trait ButtonSource {
+    /// Returns the buttons being pressed right now, as a mask of `Button::mask`s.
+    fn read_buttons(&mut self) -> Result<u8>;
+}
+
+impl<F: FnMut() -> Result<u8>> ButtonSource for F {
+    fn read_buttons(&mut self) -> Result<u8> {
+        self()
+    }
+}
+
+pub struct Buttons<S: ButtonSource> {
+    source: S,
+
+    /// how long the buttons' state has to stay unchanged to be accepted
+    debounce: Duration,
+
+    /// delay before the first `Repeated` event & the interval between the next ones
+    repeat: Option<(Duration, Duration)>,
+
+    /// debounced state
+    state: u8,
+
+    /// last read state & when it has been read for the first time
+    raw: u8,
+    raw_since: Duration,
+
+    /// when the next `Repeated` event is due, for each button
+    repeat_at: [Option<Duration>; 5],
+}
+
+impl Button {
+    /// All the buttons, in the order of their masks.
+    pub const ALL: [Button; 5] = [Button::Select, Button::Left, Button::Right, Button::Up, Button::Down];
+
+    /// Returns the bit representing this button in the masks returned by `ButtonSource`.
+    pub fn mask(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+impl ButtonEvent {
+    /// Returns the button this event concerns.
+    pub fn button(self) -> Button {
+        match self {
+            ButtonEvent::Pressed(button) | ButtonEvent::Repeated(button) | ButtonEvent::Released(button) => button,
+        }
+    }
+}
+
+impl<S: ButtonSource> Buttons<S> {
+    /// Creates a reader debouncing given buttons for 20 ms, without repeating.
+    pub fn new(source: S) -> Buttons<S> {
+        Buttons {
+            source,
+            debounce: Duration::from_millis(20),
+            repeat: None,
+            state: 0,
+            raw: 0,
+            raw_since: Duration::from_secs(0),
+            repeat_at: [None; 5],
+        }
+    }
+
+    /// Changes how long the buttons have to stay unchanged before a press or release is reported
+    /// (20 ms by default).
+    pub fn set_debounce(&mut self, debounce: Duration) {
+        self.debounce = debounce;
+    }
+
+    /// Makes a held button report `Repeated` after `delay`, and then every `interval` - eg. to
+    /// scroll through a menu by holding the arrow.
+    pub fn set_repeat(&mut self, delay: Duration, interval: Duration) {
+        self.repeat = Some((delay, interval));
+    }
+
+    /// Disables the repeating (the default).
+    pub fn disable_repeat(&mut self) {
+        self.repeat = None;
+        self.repeat_at = [None; 5];
+    }
+
+    /// Reads the buttons and returns what has happened since the last poll; `now` is the time
+    /// elapsed since any fixed point (eg. `Instant::now() - started`).
+    ///
+    /// Should be called at least a few times per the debouncing period - the presses shorter than
+    /// the interval between the polls may get lost.
+    pub fn poll(&mut self, now: Duration) -> Result<Vec<ButtonEvent>> {
+        let raw = self.source.read_buttons()? & Button::ALL.iter().fold(0, |mask, button| mask | button.mask());
+
+        if raw != self.raw {
+            self.raw = raw;
+            self.raw_since = now;
+        }
+
+        let mut events = Vec::new();
+
+        if raw != self.state && now.saturating_sub(self.raw_since) >= self.debounce {
+            let changed = raw ^ self.state;
+
+            self.state = raw;
+
+            for (idx, &button) in Button::ALL.iter().enumerate() {
+                if changed & button.mask() == 0 {
+                    continue;
+                }
+
+                if raw & button.mask() != 0 {
+                    self.repeat_at[idx] = self.repeat.map(|(delay, _)| now + delay);
+                    events.push(ButtonEvent::Pressed(button));
+                } else {
+                    self.repeat_at[idx] = None;
+                    events.push(ButtonEvent::Released(button));
+                }
+            }
+        }
+
+        if let Some((_, interval)) = self.repeat {
+            for (idx, &button) in Button::ALL.iter().enumerate() {
+                if let Some(repeat_at) = self.repeat_at[idx] {
+                    if now >= repeat_at {
+                        self.repeat_at[idx] = Some(now + interval);
+                        events.push(ButtonEvent::Repeated(button));
+                    }
+                }
+            }
+        }
+
+        Ok(events)
+    }
+
+    /// Returns whether given button is being pressed (as of the last poll, debounced).
+    pub fn is_pressed(&self, button: Button) -> bool {
+        self.state & button.mask() != 0
+    }
+
+    /// Returns the source of the buttons' state.
+    pub fn source(&mut self) -> &mut S {
+        &mut self.source
+    }
+}
@@ -0,0 +1,124 @@
+//! Decodes the rotary encoders (the quadrature ones, found on most of the "knob" modules) into the
+//! detents turned, and drives anything `Navigate`-able (eg. `Pages`) with them.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut encoder = RotaryEncoder::new(GpioEncoder::new(17, 27)?);
+//! let mut knob = Buttons::new(GpioButtons::new(&[(Button::Select, 22)])?);
+//! let started = Instant::now();
+//!
+//! loop {
+//!     encoder.navigate(&mut pages)?;
+//!
+//!     for event in knob.poll(started.elapsed())? {
+//!         pages.handle(event);
+//!     }
+//!
+//!     pages.render()?;
+//!     thread::sleep(Duration::from_millis(1));
+//! }
+//! ```
+//!
+//! # Caveats
+//!
+//! The encoder is polled, so it has to be read frequently (at least every millisecond or two) -
+//! the transitions happening in between the reads get lost, making the knob feel sluggish.
+
+use super::super::Result;
+use super::Navigate;
+
+/// Reads the levels of the encoder's `A` and `B` pins - implemented for the GPIO pins and for
+/// closures (eg. for the encoders read some other way).
+pub trait EncoderSource {
+    /// Returns whether the `A` and `B` pins are high.
+    fn read_encoder(&mut self) -> Result<(bool, bool)>;
+}
+
+impl<F: FnMut() -> Result<(bool, bool)>> EncoderSource for F {
+    fn read_encoder(&mut self) -> Result<(bool, bool)> {
+        self()
+    }
+}
+
+pub struct RotaryEncoder<S: EncoderSource> {
+    source: S,
+
+    /// number of the transitions between two detents
+    steps_per_detent: i8,
+
+    /// pins' state as of the last read (`A` being the higher bit)
+    state: Option<u8>,
+
+    /// transitions made since the last detent (positive ones are clockwise)
+    steps: i8,
+}
+
+/// Direction of each transition between the pins' states, indexed by `old_state << 2 | new_state`
+/// (`0` for no change and for the invalid transitions, which mean a skipped state).
+const TRANSITIONS: [i8; 16] = [0, -1, 1, 0, 1, 0, 0, -1, -1, 0, 0, 1, 0, 1, -1, 0];
+
+impl<S: EncoderSource> RotaryEncoder<S> {
+    /// Creates a decoder of an encoder making 4 transitions per detent (which is the most common
+    /// kind).
+    ///
+    /// When the encoder turns the opposite way, swap its `A` and `B` pins.
+    pub fn new(source: S) -> RotaryEncoder<S> {
+        RotaryEncoder {
+            source,
+            steps_per_detent: 4,
+            state: None,
+            steps: 0,
+        }
+    }
+
+    /// Changes the number of transitions per detent (`4` by default; some encoders make `2` or
+    /// `1`).
+    pub fn set_steps_per_detent(&mut self, steps: u8) {
+        self.steps_per_detent = steps.clamp(1, 4) as i8;
+        self.steps = 0;
+    }
+
+    /// Reads the encoder and returns the number of detents turned since the last poll (positive
+    /// ones are clockwise).
+    pub fn poll(&mut self) -> Result<i32> {
+        let (a, b) = self.source.read_encoder()?;
+        let state = (a as u8) << 1 | b as u8;
+
+        let old_state = match self.state.replace(state) {
+            Some(old_state) => old_state,
+            None => return Ok(0),
+        };
+
+        self.steps += TRANSITIONS[(old_state << 2 | state) as usize];
+
+        let detents = self.steps / self.steps_per_detent;
+
+        self.steps %= self.steps_per_detent;
+
+        Ok(detents as i32)
+    }
+
+    /// Reads the encoder and moves given target by the detents turned - `next` for each clockwise
+    /// one, `prev` for each counter-clockwise one.
+    ///
+    /// Returns whether the target has been moved.
+    pub fn navigate<N: Navigate + ?Sized>(&mut self, target: &mut N) -> Result<bool> {
+        let detents = self.poll()?;
+
+        for _ in 0..detents.abs() {
+            if detents > 0 {
+                target.next();
+            } else {
+                target.prev();
+            }
+        }
+
+        Ok(detents != 0)
+    }
+
+    /// Returns the source of the pins' levels.
+    pub fn source(&mut self) -> &mut S {
+        &mut self.source
+    }
+}
@@ -0,0 +1,142 @@
+//! Reads the buttons wired to the I2C expanders - either the one driving the LCD (using the pins
+//! spare on the backpack) or a separate one.
+//!
+//! Both readers open their own handle of the I2C device, so they can be used alongside the LCD's
+//! bus; they never write into the pins the LCD is connected to.
+
+use alloc::vec::Vec;
+use i2cdev::core::I2CDevice;
+use i2cdev::linux::LinuxI2CDevice;
+use std::path;
+use super::super::{Error, Result};
+use super::{Button, ButtonSource};
+
+/// Reads the buttons connected to a PCF8574's pins, pulling them down when pressed.
+///
+/// The pins have to be high to be read (PCF8574 can only pull its pins down) - which they are on
+/// power-up; when the buttons are wired to the LCD's backpack, the pins have to be declared as
+/// spare & set high (see `I2C::set_spare_pins` and `I2C::write_spare_pins`).
+pub struct Pcf8574Buttons {
+    dev: LinuxI2CDevice,
+    pins: Vec<(Button, u8)>,
+}
+
+/// Reads the buttons connected to an MCP23017's pins, pulling them down when pressed (the pins
+/// get configured as inputs, with the pull-ups enabled).
+pub struct Mcp23017Buttons {
+    dev: LinuxI2CDevice,
+    pins: Vec<(Button, u8)>,
+}
+
+/// MCP23017's registers (in the default, `BANK = 0` layout); the `B` ones follow the `A` ones.
+const REG_IODIRA: u8 = 0x00;
+const REG_GPPUA: u8 = 0x0C;
+const REG_GPIOA: u8 = 0x12;
+
+impl Pcf8574Buttons {
+    /// Creates a reader of given pins (`0..=7`, that's `P0..=P7`).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // a button between the backpack's spare `Rw` pin and the ground
+    /// let buttons = Pcf8574Buttons::new("/dev/i2c-1", 0x27, &[(Button::Select, 1)])?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when given pin does not exist.
+    pub fn new<P: AsRef<path::Path>>(i2c_device: P, i2c_address: u16, pins: &[(Button, u8)]) -> Result<Pcf8574Buttons> {
+        check_pins(pins, 7)?;
+
+        Ok(Pcf8574Buttons {
+            dev: LinuxI2CDevice::new(i2c_device, i2c_address)?,
+            pins: pins.to_vec(),
+        })
+    }
+}
+
+impl ButtonSource for Pcf8574Buttons {
+    fn read_buttons(&mut self) -> Result<u8> {
+        let state = self.dev.smbus_read_byte()?;
+
+        Ok(pressed(&self.pins, state as u16))
+    }
+}
+
+impl Mcp23017Buttons {
+    /// Pins of the Adafruit's RGB LCD shield (and its clones).
+    pub const ADAFRUIT_PINS: [(Button, u8); 5] = [
+        (Button::Select, 0),
+        (Button::Right, 1),
+        (Button::Down, 2),
+        (Button::Up, 3),
+        (Button::Left, 4),
+    ];
+
+    /// Creates a reader of given pins (`0..=7` for `GPA0..=GPA7`, `8..=15` for `GPB0..=GPB7`) and
+    /// configures them as inputs; the other pins are left intact.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when given pin does not exist, or an I2C error when the
+    /// expander does not respond.
+    pub fn new<P: AsRef<path::Path>>(i2c_device: P, i2c_address: u16, pins: &[(Button, u8)]) -> Result<Mcp23017Buttons> {
+        check_pins(pins, 15)?;
+
+        let mut dev = LinuxI2CDevice::new(i2c_device, i2c_address)?;
+
+        for port in 0..2 {
+            let mask = pins
+                .iter()
+                .filter(|(_, pin)| pin / 8 == port)
+                .fold(0, |mask, (_, pin)| mask | 1 << (pin % 8));
+
+            if mask == 0 {
+                continue;
+            }
+
+            for reg in [REG_IODIRA, REG_GPPUA].iter() {
+                let value = dev.smbus_read_byte_data(reg + port)?;
+                dev.smbus_write_byte_data(reg + port, value | mask)?;
+            }
+        }
+
+        Ok(Mcp23017Buttons {
+            dev,
+            pins: pins.to_vec(),
+        })
+    }
+
+    /// Creates a reader of the Adafruit's RGB LCD shield's buttons (see `ADAFRUIT_PINS`).
+    pub fn adafruit<P: AsRef<path::Path>>(i2c_device: P, i2c_address: u16) -> Result<Mcp23017Buttons> {
+        Mcp23017Buttons::new(i2c_device, i2c_address, &Mcp23017Buttons::ADAFRUIT_PINS)
+    }
+}
+
+impl ButtonSource for Mcp23017Buttons {
+    fn read_buttons(&mut self) -> Result<u8> {
+        let a = self.dev.smbus_read_byte_data(REG_GPIOA)?;
+        let b = self.dev.smbus_read_byte_data(REG_GPIOA + 1)?;
+
+        Ok(pressed(&self.pins, u16::from(a) | u16::from(b) << 8))
+    }
+}
+
+fn check_pins(pins: &[(Button, u8)], max: u8) -> Result<()> {
+    if let Some((_, pin)) = pins.iter().find(|(_, pin)| *pin > max) {
+        return Err(Error::invalid_config(
+            "pins",
+            format!("pin {} does not exist - pins must be in range <0, {}>", pin, max),
+        ).into());
+    }
+
+    Ok(())
+}
+
+/// Returns the buttons pulling their pins down.
+fn pressed(pins: &[(Button, u8)], state: u16) -> u8 {
+    pins.iter()
+        .filter(|(_, pin)| state & (1 << pin) == 0)
+        .fold(0, |mask, (button, _)| mask | button.mask())
+}
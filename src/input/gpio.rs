@@ -0,0 +1,89 @@
+//! Reads the buttons and rotary encoders wired straight to the Raspberry Pi's GPIO pins.
+
+use alloc::vec::Vec;
+use rppal::gpio::{Gpio, Level, Mode, PullUpDown};
+use super::super::{Error, Result};
+use super::{Button, ButtonSource, EncoderSource};
+
+/// Reads the buttons connected between the GPIO pins (BCM numbering) and the ground - the pins
+/// get configured as inputs, with the internal pull-ups enabled.
+pub struct GpioButtons {
+    gpio: Gpio,
+    pins: Vec<(Button, u8)>,
+}
+
+/// Reads a rotary encoder's `A` and `B` pins (BCM numbering), with its common pin connected to
+/// the ground - the pins get configured as inputs, with the internal pull-ups enabled.
+pub struct GpioEncoder {
+    gpio: Gpio,
+    pin_a: u8,
+    pin_b: u8,
+}
+
+impl GpioButtons {
+    /// Creates a reader of given pins.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let buttons = GpioButtons::new(&[
+    ///     (Button::Up, 5),
+    ///     (Button::Down, 6),
+    ///     (Button::Select, 13),
+    /// ])?;
+    /// ```
+    pub fn new(pins: &[(Button, u8)]) -> Result<GpioButtons> {
+        let mut gpio = Gpio::new().map_err(Error::Gpio)?;
+
+        for &(_, pin) in pins {
+            gpio.set_mode(pin, Mode::Input);
+            gpio.set_pullupdown(pin, PullUpDown::PullUp);
+        }
+
+        Ok(GpioButtons {
+            gpio,
+            pins: pins.to_vec(),
+        })
+    }
+}
+
+impl ButtonSource for GpioButtons {
+    fn read_buttons(&mut self) -> Result<u8> {
+        let mut mask = 0;
+
+        for &(button, pin) in &self.pins {
+            if self.gpio.read(pin).map_err(Error::Gpio)? == Level::Low {
+                mask |= button.mask();
+            }
+        }
+
+        Ok(mask)
+    }
+}
+
+impl GpioEncoder {
+    /// Creates a reader of given pins.
+    pub fn new(pin_a: u8, pin_b: u8) -> Result<GpioEncoder> {
+        let mut gpio = Gpio::new().map_err(Error::Gpio)?;
+
+        for &pin in &[pin_a, pin_b] {
+            gpio.set_mode(pin, Mode::Input);
+            gpio.set_pullupdown(pin, PullUpDown::PullUp);
+        }
+
+        Ok(GpioEncoder {
+            gpio,
+            pin_a,
+            pin_b,
+        })
+    }
+}
+
+impl EncoderSource for GpioEncoder {
+    fn read_encoder(&mut self) -> Result<(bool, bool)> {
+        let a = self.gpio.read(self.pin_a).map_err(Error::Gpio)?;
+        let b = self.gpio.read(self.pin_b).map_err(Error::Gpio)?;
+
+        Ok((a == Level::High, b == Level::High))
+    }
+}
@@ -0,0 +1,21 @@
+//! Reads the user's input - eg. the buttons of the LCD keypad shields (see `Buttons`) - so that
+//! the menus can be navigated without reaching for any other crate.
+//!
+//! Both the buttons and the rotary encoders (see `RotaryEncoder`) drive anything implementing
+//! `Navigate` - eg. the `Pages`.
+
+pub use self::buttons::{Button, ButtonEvent, ButtonSource, Buttons};
+pub use self::encoder::{EncoderSource, RotaryEncoder};
+#[cfg(feature = "i2cdev")]
+pub use self::expander::{Mcp23017Buttons, Pcf8574Buttons};
+#[cfg(feature = "raspberrypi")]
+pub use self::gpio::{GpioButtons, GpioEncoder};
+pub use self::navigate::Navigate;
+
+mod buttons;
+mod encoder;
+#[cfg(feature = "i2cdev")]
+mod expander;
+#[cfg(feature = "raspberrypi")]
+mod gpio;
+mod navigate;
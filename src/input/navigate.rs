@@ -0,0 +1,40 @@
+use super::{Button, ButtonEvent};
+#[cfg(feature = "std")]
+use super::super::frontends::Pages;
+
+/// Something the user moves through with the buttons or a knob - eg. the pages.
+pub trait Navigate {
+    /// Moves to the next item.
+    fn next(&mut self);
+
+    /// Moves to the previous item.
+    fn prev(&mut self);
+
+    /// Activates the current item (does nothing by default).
+    fn select(&mut self) {}
+
+    /// Reacts to given button - `Up` & `Left` move to the previous item, `Down` & `Right` to the
+    /// next one, `Select` activates the current one; the repeats count as presses.
+    fn handle(&mut self, event: ButtonEvent) {
+        match event {
+            ButtonEvent::Pressed(button) | ButtonEvent::Repeated(button) => match button {
+                Button::Up | Button::Left => self.prev(),
+                Button::Down | Button::Right => self.next(),
+                Button::Select => self.select(),
+            },
+
+            ButtonEvent::Released(_) => (),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Navigate for Pages {
+    fn next(&mut self) {
+        Pages::next(self);
+    }
+
+    fn prev(&mut self) {
+        Pages::prev(self);
+    }
+}
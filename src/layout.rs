@@ -0,0 +1,379 @@
+//! Describes a screen as a set of named fields - each one with its own position, width and
+//! alignment - so that updating a value is as simple as `set("temp", "23.4C")`.
+//!
+//! Only the fields whose values have changed since the previous `draw` get redrawn, and each
+//! field is always drawn padded to its width - so there are no leftovers of the longer values,
+//! nor the values spilling into their neighbours.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! // Temp:   23.4C
+//! // Hum:      41%
+//! let mut layout = Layout::new();
+//!
+//! lcd.print_at(0, 0, "Temp:")?;
+//! lcd.print_at(1, 0, "Hum:")?;
+//!
+//! layout.add_field("temp", 0, 7, 6, Align::Right)?;
+//! layout.add_field("hum", 1, 7, 6, Align::Right)?;
+//!
+//! loop {
+//!     layout.set("temp", format!("{:.1}C", sensor.temperature()))?;
+//!     layout.set("hum", format!("{}%", sensor.humidity()))?;
+//!
+//!     layout.draw(&mut lcd)?;
+//!     lcd.render()?;
+//! }
+//! ```
+//!
+//! # Templates
+//!
+//! The same screen can be described as data, one template per line (see `Layout::from_template`)
+//! - which makes it possible to load the screens from a configuration file:
+//!
+//! ```rust,ignore
+//! let mut layout = Layout::from_template(&[
+//!     "Temp:  {temp:>6}",
+//!     "Hum:   {hum:>6}",
+//! ])?;
+//!
+//! layout.set("temp", "23.4C")?;
+//! ```
+//!
+//! Each placeholder is written as `{name}` or `{name:<alignment><width>}`, where the alignment is
+//! one of `<` (left; the default), `>` (right) or `^` (center). Placeholders without a width are as
+//! wide as the placeholders themselves (so `{unit}` occupies 6 characters) and `{{` / `}}` stand for
+//! the braces themselves. Everything else is a label - a text drawn as-is.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::mem;
+use super::{Hd44780, Point, Result, UnitResult};
+
+#[derive(Default)]
+pub struct Layout {
+    fields: Vec<Field>,
+    labels: Vec<Label>,
+}
+
+/// How a value shorter than its field is positioned inside it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum Align {
+    #[default]
+    Left,
+    Right,
+    Center,
+}
+
+struct Field {
+    name: String,
+    position: Point,
+    width: usize,
+    align: Align,
+    value: String,
+
+    /// whether the value has changed since the previous draw
+    dirty: bool,
+}
+
+/// A text which never changes (eg. a field's caption).
+struct Label {
+    position: Point,
+    text: String,
+
+    /// whether the label has to be drawn during the next draw
+    dirty: bool,
+}
+
+impl Layout {
+    /// Creates a layout without any fields.
+    pub fn new() -> Layout {
+        Layout::default()
+    }
+
+    /// Creates a layout from given templates, one per line (see the module's docs for the
+    /// syntax).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a template is malformed (eg. a placeholder is not closed, or its
+    /// alignment is unknown) or when a field is declared twice.
+    pub fn from_template<I, S>(lines: I) -> Result<Layout> where I: IntoIterator<Item = S>, S: AsRef<str> {
+        let mut layout = Layout::new();
+
+        for (y, line) in lines.into_iter().enumerate() {
+            layout.parse_line(y, line.as_ref())
+                .map_err(|err| format!("Invalid template at line {}: {}", y, err))?;
+        }
+
+        Ok(layout)
+    }
+
+    /// Declares a field occupying `width` characters of line `y`, starting at column `x`.
+    ///
+    /// The field is empty at first (it gets drawn as spaces).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a field with given name has been already declared.
+    pub fn add_field<N: Into<String>>(&mut self, name: N, y: usize, x: usize, width: usize, align: Align) -> UnitResult {
+        let name = name.into();
+
+        if self.find(&name).is_some() {
+            return Err(format!("Field `{}` has been already declared.", name).into());
+        }
+
+        self.fields.push(Field {
+            name,
+            position: Point::new(y, x),
+            width,
+            align,
+            value: String::new(),
+            dirty: true,
+        });
+
+        Ok(())
+    }
+
+    /// Declares a label - a text drawn at given position once (and then again after each
+    /// `invalidate`).
+    pub fn add_label<T: Into<String>>(&mut self, y: usize, x: usize, text: T) {
+        self.labels.push(Label {
+            position: Point::new(y, x),
+            text: text.into(),
+            dirty: true,
+        });
+    }
+
+    /// Changes given field's value; the field gets redrawn during the next `draw` only if the
+    /// value is actually different.
+    ///
+    /// Values longer than the field are truncated.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when there's no such field.
+    pub fn set<T: ToString>(&mut self, name: &str, value: T) -> UnitResult {
+        let field = self.find_mut(name)
+            .ok_or_else(|| format!("There's no field named `{}`.", name))?;
+
+        let value = value.to_string();
+
+        if field.value != value {
+            field.value = value;
+            field.dirty = true;
+        }
+
+        Ok(())
+    }
+
+    /// Changes values of many fields at once (see `set`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when there's no such field - the values preceding it are changed anyway.
+    pub fn set_many<I, N, T>(&mut self, values: I) -> UnitResult where I: IntoIterator<Item = (N, T)>, N: AsRef<str>, T: ToString {
+        for (name, value) in values {
+            self.set(name.as_ref(), value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns given field's value, or `None` if there's no such field.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.find(name).map(|field| field.value.as_str())
+    }
+
+    /// Returns names of all the fields, in the order they have been declared.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.fields.iter().map(|field| field.name.as_str())
+    }
+
+    /// Draws the fields that have changed since the previous draw (and the labels, when drawn for
+    /// the first time).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a field or a label does not fit on the screen.
+    pub fn draw<L: Hd44780 + ?Sized>(&mut self, lcd: &mut L) -> UnitResult {
+        let cursor = lcd.cursor();
+
+        for label in self.labels.iter_mut().filter(|label| label.dirty) {
+            let Point { y, x } = label.position;
+
+            if y >= lcd.height() || x + label.text.chars().count() > lcd.width() {
+                return Err(
+                    format!("Label `{}` does not fit on the screen (at y={}, x={}).", label.text, y, x).into()
+                );
+            }
+
+            lcd.print_str_at(y, x, &label.text)?;
+            label.dirty = false;
+        }
+
+        for field in self.fields.iter_mut().filter(|field| field.dirty) {
+            let Point { y, x } = field.position;
+
+            if y >= lcd.height() || x + field.width > lcd.width() {
+                return Err(
+                    format!("Field `{}` does not fit on the screen (at y={}, x={}, width={}).", field.name, y, x, field.width).into()
+                );
+            }
+
+            lcd.print_str_at(y, x, &field.padded())?;
+            field.dirty = false;
+        }
+
+        lcd.move_at(cursor.y, cursor.x)
+    }
+
+    /// Makes the next `draw` redraw all the fields and labels (eg. after the screen has been
+    /// cleared).
+    pub fn invalidate(&mut self) {
+        for field in &mut self.fields {
+            field.dirty = true;
+        }
+
+        for label in &mut self.labels {
+            label.dirty = true;
+        }
+    }
+
+    /// Declares the fields and labels described by given line's template.
+    fn parse_line(&mut self, y: usize, line: &str) -> UnitResult {
+        let mut chars = line.chars().peekable();
+
+        // label being currently parsed, along with the column it starts at
+        let mut label = String::new();
+        let mut label_x = 0;
+
+        // column the next character lands on
+        let mut x = 0;
+
+        while let Some(ch) = chars.next() {
+            match ch {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                }
+
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                }
+
+                '{' => {
+                    let mut placeholder = String::new();
+
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(ch) => placeholder.push(ch),
+                            None => return Err(format!("placeholder `{{{}` is not closed", placeholder).into()),
+                        }
+                    }
+
+                    if !label.is_empty() {
+                        self.add_label(y, label_x, mem::take(&mut label));
+                    }
+
+                    let (name, align, width) = parse_placeholder(&placeholder)?;
+
+                    self.add_field(name, y, x, width, align)?;
+
+                    x += width;
+                    label_x = x;
+
+                    continue;
+                }
+
+                '}' => {
+                    return Err("unmatched `}` (use `}}` for a brace)".into());
+                }
+
+                _ => (),
+            }
+
+            label.push(ch);
+            x += 1;
+        }
+
+        if !label.is_empty() {
+            self.add_label(y, label_x, label);
+        }
+
+        Ok(())
+    }
+
+    fn find(&self, name: &str) -> Option<&Field> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    fn find_mut(&mut self, name: &str) -> Option<&mut Field> {
+        self.fields.iter_mut().find(|field| field.name == name)
+    }
+}
+
+/// Parses a placeholder (without the braces) into the field's name, alignment and width.
+fn parse_placeholder(placeholder: &str) -> Result<(&str, Align, usize)> {
+    let (name, spec) = match placeholder.find(':') {
+        Some(idx) => (&placeholder[..idx], Some(&placeholder[idx + 1..])),
+        None => (placeholder, None),
+    };
+
+    if name.is_empty() {
+        return Err(format!("placeholder `{{{}}}` has no name", placeholder).into());
+    }
+
+    // by default the field is as wide as the placeholder itself (braces included)
+    let default_width = placeholder.chars().count() + 2;
+
+    let spec = match spec {
+        Some(spec) => spec,
+        None => return Ok((name, Align::Left, default_width)),
+    };
+
+    let (align, width) = match spec.chars().next() {
+        Some('<') => (Align::Left, &spec[1..]),
+        Some('>') => (Align::Right, &spec[1..]),
+        Some('^') => (Align::Center, &spec[1..]),
+        _ => (Align::Left, spec),
+    };
+
+    let width = if width.is_empty() {
+        default_width
+    } else {
+        width
+            .parse()
+            .map_err(|_| format!("placeholder `{{{}}}` has an invalid format (expected eg. `{{{}:>5}}`)", placeholder, name))?
+    };
+
+    Ok((name, align, width))
+}
+
+impl Field {
+    /// Returns the value truncated / padded with spaces to the field's width.
+    fn padded(&self) -> String {
+        pad(&self.value, self.width, self.align)
+    }
+}
+
+/// Truncates / pads given value with spaces to given width.
+pub(crate) fn pad(value: &str, width: usize, align: Align) -> String {
+    let value: String = value.chars().take(width).collect();
+    let padding = width - value.chars().count();
+
+    let left = match align {
+        Align::Left => 0,
+        Align::Right => padding,
+        Align::Center => padding / 2,
+    };
+
+    let mut padded = String::with_capacity(width);
+
+    padded.extend((0..left).map(|_| ' '));
+    padded.push_str(&value);
+    padded.extend((left..padding).map(|_| ' '));
+
+    padded
+}
@@ -0,0 +1,362 @@
+//! Provides a server speaking (a subset of) the LCDproc's protocol, so that the existing LCDproc
+//! clients can use this crate as their hardware layer - just like they'd use the `LCDd` daemon.
+//!
+//! Requires the `lcdproc` feature.
+//!
+//! # Supported commands
+//!
+//! - `hello`, `client_set`, `noop`,
+//! - `screen_add`, `screen_set` (the options are accepted, but ignored), `screen_del`,
+//! - `widget_add`, `widget_set`, `widget_del` - for the `string` and `title` widgets (the other
+//!   types are accepted, but not drawn),
+//! - `backlight` (`on` / `off`).
+//!
+//! The screens are not rotated - the most recently added one is shown.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! LcdprocServer::new(lcd).serve(("0.0.0.0", LcdprocServer::DEFAULT_PORT))?;
+//! ```
+//!
+//! ```text
+//! $ lcdproc -s localhost C M
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use super::{Backlight, Hd44780, Hd44780Ext, Result, UnitResult};
+use super::frontends::{Buffered, Shared};
+
+pub struct LcdprocServer {
+    lcd: Shared<Buffered>,
+    screens: Arc<Mutex<Vec<Screen>>>,
+    next_client: Arc<AtomicU64>,
+}
+
+struct Screen {
+    client: u64,
+    id: String,
+    widgets: Vec<Widget>,
+}
+
+struct Widget {
+    id: String,
+    kind: WidgetKind,
+    x: usize,
+    y: usize,
+    text: String,
+}
+
+#[derive(PartialEq)]
+enum WidgetKind {
+    String,
+    Title,
+    Unsupported,
+}
+
+impl LcdprocServer {
+    /// Port used by the `LCDd` daemon.
+    pub const DEFAULT_PORT: u16 = 13666;
+
+    /// Creates a new server, drawing on given LCD.
+    pub fn new(lcd: Buffered) -> LcdprocServer {
+        LcdprocServer {
+            lcd: Shared::new(lcd),
+            screens: Arc::new(Mutex::new(Vec::new())),
+            next_client: Arc::new(AtomicU64::new(1)),
+        }
+    }
+
+    /// Listens on given address and serves the clients (each one in a separate thread); never
+    /// returns, unless listening fails.
+    pub fn serve<A: ToSocketAddrs>(&self, addr: A) -> UnitResult {
+        let listener = TcpListener::bind(addr)?;
+
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let client = self.next_client.fetch_add(1, Ordering::SeqCst);
+
+            let connection = Connection {
+                lcd: self.lcd.clone(),
+                screens: self.screens.clone(),
+                client,
+            };
+
+            thread::spawn(move || {
+                let _ = connection.handle(stream);
+                connection.disconnect();
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// A single client's connection.
+struct Connection {
+    lcd: Shared<Buffered>,
+    screens: Arc<Mutex<Vec<Screen>>>,
+    client: u64,
+}
+
+impl Connection {
+    /// Reads the commands and responds to them, until the client disconnects.
+    fn handle(&self, stream: TcpStream) -> UnitResult {
+        let mut writer = stream.try_clone()?;
+        let reader = BufReader::new(stream);
+
+        for line in reader.lines() {
+            let line = line?;
+            let args = tokenize(&line);
+
+            if args.is_empty() {
+                continue;
+            }
+
+            let response = match self.execute(&args) {
+                Ok(response) => response,
+                Err(err) => format!("huh? {}", err),
+            };
+
+            writeln!(writer, "{}", response)?;
+        }
+
+        Ok(())
+    }
+
+    /// Executes given command, returning the response.
+    fn execute(&self, args: &[String]) -> Result<String> {
+        let arg = |idx: usize| -> Result<&str> {
+            args.get(idx)
+                .map(|arg| arg.as_str())
+                .ok_or_else(|| format!("{}: not enough arguments", args[0]).into())
+        };
+
+        match args[0].as_str() {
+            "hello" => {
+                let (width, height) = (self.lcd.width(), self.lcd.height());
+
+                return Ok(format!(
+                    "connect LCDproc 0.5.9 protocol 0.3 lcd wid {} hgt {} cellwid 5 cellhgt 8", width, height,
+                ));
+            }
+
+            "client_set" | "screen_set" | "noop" => {}
+
+            "screen_add" => {
+                let id = arg(1)?;
+                let mut screens = self.screens();
+
+                if screens.iter().any(|screen| self.owns(screen, id)) {
+                    return Err("screen_add: screen already exists".into());
+                }
+
+                screens.push(Screen {
+                    client: self.client,
+                    id: id.to_string(),
+                    widgets: Vec::new(),
+                });
+            }
+
+            "screen_del" => {
+                let id = arg(1)?;
+                self.screens().retain(|screen| !self.owns(screen, id));
+            }
+
+            "widget_add" => {
+                let kind = match arg(3)? {
+                    "string" => WidgetKind::String,
+                    "title" => WidgetKind::Title,
+                    _ => WidgetKind::Unsupported,
+                };
+
+                let id = arg(2)?.to_string();
+
+                self.with_screen(arg(1)?, |screen| {
+                    screen.widgets.push(Widget {
+                        id,
+                        kind,
+                        x: 1,
+                        y: 1,
+                        text: String::new(),
+                    });
+
+                    Ok(())
+                })?;
+            }
+
+            "widget_set" => {
+                let id = arg(2)?;
+
+                self.with_screen(arg(1)?, |screen| {
+                    let widget = screen.widgets
+                        .iter_mut()
+                        .find(|widget| widget.id == id)
+                        .ok_or("widget_set: unknown widget")?;
+
+                    match widget.kind {
+                        WidgetKind::String => {
+                            widget.x = arg(3)?.parse()?;
+                            widget.y = arg(4)?.parse()?;
+                            widget.text = arg(5)?.to_string();
+                        }
+
+                        WidgetKind::Title => {
+                            widget.text = arg(3)?.to_string();
+                        }
+
+                        WidgetKind::Unsupported => {}
+                    }
+
+                    Ok(())
+                })?;
+            }
+
+            "widget_del" => {
+                let id = arg(2)?;
+
+                self.with_screen(arg(1)?, |screen| {
+                    screen.widgets.retain(|widget| widget.id != id);
+                    Ok(())
+                })?;
+            }
+
+            "backlight" => {
+                let enabled = match arg(1)? {
+                    "on" => true,
+                    "off" => false,
+                    _ => return Err("backlight: expected `on` or `off`".into()),
+                };
+
+                self.lcd.transaction(|lcd| lcd.set_backlight(enabled))?;
+            }
+
+            command => {
+                return Err(format!("unknown command: {}", command).into());
+            }
+        }
+
+        self.render()?;
+
+        Ok("success".to_string())
+    }
+
+    /// Executes given closure on one of client's screens.
+    fn with_screen<F>(&self, id: &str, f: F) -> UnitResult where F: FnOnce(&mut Screen) -> UnitResult {
+        let mut screens = self.screens();
+
+        let screen = screens
+            .iter_mut()
+            .find(|screen| self.owns(screen, id))
+            .ok_or("unknown screen")?;
+
+        f(screen)
+    }
+
+    /// Forgets all the client's screens.
+    fn disconnect(&self) {
+        self.screens().retain(|screen| screen.client != self.client);
+        let _ = self.render();
+    }
+
+    /// Draws the most recently added screen.
+    fn render(&self) -> UnitResult {
+        let screens = self.screens();
+
+        self.lcd.transaction(|lcd| {
+            lcd.clear()?;
+
+            if let Some(screen) = screens.last() {
+                for widget in &screen.widgets {
+                    match widget.kind {
+                        WidgetKind::String => draw_string(lcd, widget)?,
+                        WidgetKind::Title => lcd.print_centered(0, widget.text.as_str())?,
+                        WidgetKind::Unsupported => {}
+                    }
+                }
+            }
+
+            lcd.render()?;
+
+            Ok(())
+        })
+    }
+
+    fn owns(&self, screen: &Screen, id: &str) -> bool {
+        screen.client == self.client && screen.id == id
+    }
+
+    fn screens(&self) -> MutexGuard<'_, Vec<Screen>> {
+        self.screens
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+/// Draws given string widget, clipping it to the screen (LCDproc uses 1-based coordinates).
+fn draw_string(lcd: &mut Buffered, widget: &Widget) -> UnitResult {
+    let (width, height) = (lcd.width(), lcd.height());
+
+    if widget.x == 0 || widget.y == 0 || widget.x > width || widget.y > height {
+        return Ok(());
+    }
+
+    let text: String = widget.text
+        .chars()
+        .take(width - (widget.x - 1))
+        .collect();
+
+    lcd.print_at(widget.y - 1, widget.x - 1, text)
+}
+
+/// Splits given line into arguments, honoring the quotes (`"..."`) and braces (`{...}`).
+fn tokenize(line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut chars = line.trim().chars().peekable();
+
+    while let Some(&ch) = chars.peek() {
+        if ch.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let mut arg = String::new();
+
+        match ch {
+            '"' | '{' => {
+                let end = if ch == '"' { '"' } else { '}' };
+
+                chars.next();
+
+                while let Some(ch) = chars.next() {
+                    match ch {
+                        '\\' => arg.extend(chars.next()),
+                        ch if ch == end => break,
+                        ch => arg.push(ch),
+                    }
+                }
+            }
+
+            _ => {
+                while let Some(&ch) = chars.peek() {
+                    if ch.is_whitespace() {
+                        break;
+                    }
+
+                    arg.push(ch);
+                    chars.next();
+                }
+            }
+        }
+
+        args.push(arg);
+    }
+
+    args
+}
@@ -1,26 +1,256 @@
-/// A convenient, high-level driver for the HD44780 display.
-/// Supports both the `I2C` and `GPIO` buses + has a buffered implementation.
-///
-/// # License
-///
-/// Copyright (c) 2018, Patryk Wychowaniec <wychowaniec.patryk@gmail.com>.
-/// Licensed under the MIT license.
+//! A convenient, high-level driver for the HD44780 display.
+//! Supports both the `I2C` and `GPIO` buses + has a buffered implementation.
+//!
+//! # License
+//!
+//! Copyright (c) 2018, Patryk Wychowaniec <wychowaniec.patryk@gmail.com>.
+//! Licensed under the MIT license.
+//!
+//! # `no_std`
+//!
+//! Disabling the default `std` feature makes the crate `no_std` (it still requires `alloc`
+//! though); the I2C & GPIO buses and the shared frontend are not available then - one has to
+//! provide their own implementation of the `Bus` trait.
+//!
+//! # `raspberrypi`
+//!
+//! The I2C & GPIO buses are Linux-only, so they live behind the default `raspberrypi` feature -
+//! disabling it (while keeping `std`) allows to build the crate (the traits, the frontends, the
+//! mock buses) on any platform, eg. to develop and test the applications on a workstation:
+//!
+//! ```toml
+//! pwr-hd44780 = { version = "0.1", default-features = false, features = ["std"] }
+//! ```
+//!
+//! # `i2cdev`
+//!
+//! The I2C buses alone (without the GPIO one, which requires a Raspberry Pi) are available
+//! through the `i2cdev` feature - they work with any Linux I2C device, eg. on the other
+//! single-board computers or with the USB-to-I2C adapters.
+//!
+//! # `async`
+//!
+//! Enabling the `async` feature provides an asynchronous version of the driver (see the
+//! `asynchronous` module), where the delays are awaited instead of blocking the thread.
+//!
+//! # `serde`
+//!
+//! Enabling the `serde` feature makes the configuration types (de)serializable and provides the
+//! `Config` (see the `config` module), allowing to describe the LCD in a file.
+//!
+//! # `png`
+//!
+//! Enabling the `png` feature allows to load icons from PNG files (see `Icon::from_png`) and to
+//! render the screen's contents into PNG images (see the `preview` module).
+//!
+//! # `tracing`
+//!
+//! Enabling the `tracing` feature makes the driver emit `tracing` spans around the initialization
+//! and renders (`DEBUG` level), and events for each command & data byte sent to the LCD (`TRACE`
+//! level) - so that garbled output or slow refreshes can be diagnosed from the logs, without
+//! reaching for a logic analyzer.
+//!
+//! # Panics
+//!
+//! The driver does not panic - every failure (a missing GPIO peripheral, an I2C transfer gone
+//! wrong, an out-of-screen coordinate) is reported through `Result`, so that it can be safely
+//! embedded in long-running daemons. The only exception is `CharBitmap::from_art`, meant for the
+//! constant expressions (where a malformed art fails the build) - `CharBitmap::try_from_art` is
+//! its fallible counterpart.
+
+#![no_std]
+#![deny(clippy::unwrap_used, clippy::expect_used)]
+
+#[macro_use]
+extern crate alloc;
 
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "i2cdev")]
 extern crate i2cdev;
+
+#[cfg(feature = "raspberrypi")]
 extern crate rppal;
 
-pub(crate) use buses::Bus;
+#[cfg(feature = "async")]
+extern crate tokio;
+
+#[cfg(feature = "log")]
+extern crate log;
+
+#[cfg(feature = "serde")]
+extern crate serde;
+
+#[cfg(feature = "png")]
+extern crate png;
+
+#[cfg(feature = "tracing")]
+extern crate tracing;
+
+#[cfg(feature = "mqtt")]
+extern crate rumqttc;
+
+#[cfg(feature = "sysinfo")]
+extern crate libc;
+
+#[cfg(feature = "web")]
+extern crate tiny_http;
+
+use alloc::boxed::Box;
+use alloc::string::String;
+
+#[cfg(feature = "async")]
+pub use asynchronous::{AsyncBus, AsyncLcd};
+#[cfg(feature = "std")]
+pub use binding::Binding;
+pub use backlight::{BacklightFader, BacklightScheduler};
+pub use buses::RgbBacklight;
+pub use buses::command::Command;
+pub use char_bank::CharBank;
+pub use char_bitmap::CharBitmap;
+pub use charmap::{Fallback, Rom, Sanitize};
+pub use code_page::CodePage;
+#[cfg(all(feature = "serde", feature = "i2cdev"))]
+pub use config::{BusConfig, Config};
+pub use controller::{Controller, ControllerProfile};
+pub use error::Error;
+pub use ext::{Chain, Hd44780Ext, PrintOutcome};
+pub use layout::{Align, Layout};
+pub use number::NumberFormat;
+pub use screen::Screen;
+pub use splash::Splash;
+#[cfg(feature = "std")]
+pub use splash::ShownSplash;
+pub use delay::{Delay, NoDelay};
+#[cfg(feature = "std")]
+pub use delay::DelayStrategy;
+#[cfg(feature = "raspberrypi")]
 pub use buses::Gpio4 as Gpio4Bus;
+#[cfg(feature = "i2cdev")]
 pub use buses::I2C as I2CBus;
+#[cfg(feature = "i2cdev")]
+pub use buses::I2C8 as I2C8Bus;
+#[cfg(feature = "i2cdev")]
+pub use buses::I2CAdapter;
+#[cfg(feature = "std")]
+pub use buses::LoggingBus;
+#[cfg(feature = "i2cdev")]
+pub use buses::Mcp23008 as Mcp23008Bus;
+pub use buses::NullBus;
+#[cfg(feature = "std")]
+pub use buses::SimulatedController;
+#[cfg(feature = "i2cdev")]
+pub use buses::Backpack;
+#[cfg(feature = "daemon")]
+pub use daemon::Daemon;
+#[cfg(feature = "lcdproc")]
+pub use lcdproc::LcdprocServer;
+#[cfg(feature = "log")]
+pub use logger::LcdLogger;
+#[cfg(feature = "mqtt")]
+pub use mqtt::MqttBridge;
+#[cfg(feature = "png")]
+pub use preview::PngPreview;
+#[cfg(feature = "remote")]
+pub use remote::{RemoteAgent, RemoteBus};
+#[cfg(feature = "sysinfo")]
+pub use status::{StatusScreen, SystemStatus};
+pub use ticker::Ticker;
+#[cfg(feature = "web")]
+pub use web::WebPreview;
+pub use input::{Button, ButtonEvent, ButtonSource, Buttons, EncoderSource, Navigate, RotaryEncoder};
+#[cfg(feature = "i2cdev")]
+pub use input::{Mcp23017Buttons, Pcf8574Buttons};
+#[cfg(feature = "raspberrypi")]
+pub use input::{GpioButtons, GpioEncoder};
 pub use frontends::Buffered as BufferedLcd;
+pub use frontends::FrameCapture;
+pub use frontends::{Overflow, RenderReport, VirtualCursor};
 pub use frontends::Direct as DirectLcd;
+pub use frontends::DropAction;
+pub use frontends::Fixed as FixedLcd;
+pub use frontends::FixedBuffered as FixedBufferedLcd;
+pub use frontends::{Health, HealthCheck, Recovery, Reinitialize};
+pub use frontends::InMemory as InMemoryLcd;
+pub use frontends::Queued as QueuedLcd;
+pub use frontends::Region;
+pub use frontends::Resilient as ResilientLcd;
+pub use frontends::RetryPolicy;
+pub use frontends::Tee as TeeLcd;
+pub use frontends::Tiled as TiledLcd;
+#[cfg(feature = "raspberrypi")]
+pub use frontends::MultiLcd;
+#[cfg(feature = "std")]
+pub use frontends::{Page, Pages};
+#[cfg(feature = "std")]
+pub use frontends::{RenderHandle, RenderLoop};
+#[cfg(feature = "std")]
+pub use frontends::Shared as SharedLcd;
+#[cfg(feature = "std")]
+pub use frontends::{LcdHandle, LcdWorker};
+
+#[macro_use]
+mod macros;
 
+#[cfg(feature = "async")]
+pub mod asynchronous;
+pub mod backlight;
+#[cfg(feature = "std")]
+pub mod binding;
 pub mod buses;
+pub mod char_bank;
+pub mod char_bitmap;
+pub mod charmap;
+pub mod code_page;
+#[cfg(any(feature = "daemon", feature = "mqtt"))]
+mod commands;
+#[cfg(all(feature = "serde", feature = "i2cdev"))]
+pub mod config;
+pub mod controller;
+#[cfg(feature = "daemon")]
+pub mod daemon;
+pub mod delay;
+pub mod error;
+pub mod ext;
 pub mod frontends;
+pub mod glyphs;
+pub mod icon;
+pub mod input;
+pub mod layout;
+pub mod number;
+#[cfg(feature = "lcdproc")]
+pub mod lcdproc;
+#[cfg(feature = "log")]
+pub mod logger;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+pub mod prelude;
+#[cfg(feature = "remote")]
+pub mod remote;
+pub mod screen;
+pub mod splash;
+#[cfg(feature = "sysinfo")]
+pub mod status;
+pub mod ticker;
+#[cfg(feature = "png")]
+pub mod preview;
+#[cfg(feature = "web")]
+pub mod web;
+pub mod widgets;
 
-pub type Result<T> = ::std::result::Result<T, Box<std::error::Error>>;
+pub type Result<T> = ::core::result::Result<T, Box<dyn core::error::Error>>;
 pub type UnitResult = Result<()>;
 
+/// The LCD's frontend - printing text, moving the cursor and the screen's geometry.
+///
+/// The hardware features - which not every frontend (eg. a page or a simulator) is able to
+/// provide - are described by the capability traits: `Backlight`, `DisplayControl` and
+/// `CustomChars`; generic code states which ones it requires in its bounds.
+///
+/// The trait is object-safe - functions can accept either `&mut dyn Hd44780` or a generic
+/// `impl Hd44780` (which `&mut L` and `Box<L>` implement, too); since `print` and `print_at` are
+/// generic, trait objects provide `print_str` and `print_str_at` instead.
 pub trait Hd44780 {
     /// Clears the screen and moves cursor at (0, 0).
     fn clear(&mut self) -> UnitResult;
@@ -32,8 +262,12 @@ pub trait Hd44780 {
     ///
     /// # Example
     ///
-    /// ```rust
-    /// lcd.move_at(2, 2);
+    /// ```rust,no_run
+    /// # use pwr_hd44780::{Hd44780, UnitResult};
+    /// # fn example(lcd: &mut impl Hd44780) -> UnitResult {
+    /// lcd.move_at(2, 2)?;
+    /// # Ok(())
+    /// # }
     /// ```
     ///
     /// # Errors
@@ -42,23 +276,53 @@ pub trait Hd44780 {
     /// update the cursor position.
     fn move_at(&mut self, y: usize, x: usize) -> UnitResult;
 
+    /// Returns the cursor's position.
+    fn cursor(&self) -> Point;
+
     /// Prints a single ASCII character at current cursor's position and moves the cursor.
     /// Can be used to print custom-made characters (ie. the ones created by `create_char`).
     ///
     /// # Example
     ///
-    /// ```rust
-    /// lcd.print_char(2);
+    /// ```rust,no_run
+    /// # use pwr_hd44780::{Hd44780, UnitResult};
+    /// # fn example(lcd: &mut impl Hd44780) -> UnitResult {
+    /// lcd.print_char(2)?;
+    /// # Ok(())
+    /// # }
     /// ```
     fn print_char(&mut self, ch: u8) -> UnitResult;
 
+    /// Prints given character `count` times in a row, moving the cursor - that's the same as
+    /// calling `print_char` in a loop (which is the default), but the frontends talking to a bus
+    /// may send the whole run at once (see `Bus::write_data_repeated`).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // a bar filling the whole line
+    /// lcd.move_at(1, 0)?;
+    /// lcd.print_char_repeated(0xFF, lcd.width())?;
+    /// ```
+    fn print_char_repeated(&mut self, ch: u8, count: usize) -> UnitResult {
+        for _ in 0..count {
+            self.print_char(ch)?;
+        }
+
+        Ok(())
+    }
+
     /// Prints a single ASCII character at given position and moves the cursor.
     /// Can be used to print custom-made characters (ie. the ones created by `create_char`).
     ///
     /// # Example
     ///
-    /// ```rust
-    /// lcd.print_char_at(1, 0, 2);
+    /// ```rust,no_run
+    /// # use pwr_hd44780::{Hd44780, UnitResult};
+    /// # fn example(lcd: &mut impl Hd44780) -> UnitResult {
+    /// lcd.print_char_at(1, 0, 2)?;
+    /// # Ok(())
+    /// # }
     /// ```
     ///
     /// # Errors
@@ -73,31 +337,64 @@ pub trait Hd44780 {
 
     /// Prints a string at current cursor's position and moves the cursor.
     ///
+    /// Characters are translated into the codes of LCD's character ROM (see `rom`); the ones not
+    /// present in the ROM are printed as a question mark (unless a different fallback is chosen
+    /// in the print options).
+    ///
     /// # Example
     ///
-    /// ```rust
-    /// lcd.print("Hello World!");
-    /// lcd.print(format!("Hello, {}!", someone));
+    /// ```rust,no_run
+    /// # use pwr_hd44780::{Hd44780, UnitResult};
+    /// # fn example(lcd: &mut impl Hd44780) -> UnitResult {
+    /// # let someone = "Anna";
+    /// lcd.print("Hello World!")?;
+    /// lcd.print(format!("Hello, {}!", someone))?;
+    /// # Ok(())
+    /// # }
     /// ```
     ///
+    /// Control characters (`\n`, `\r`, `\t`) are sent as-is, unless enabled otherwise in the
+    /// frontend's print options (see `print_options`).
+    ///
     /// # Errors
     ///
     /// When given character requires overflowing current line, the behaviour is undefined.
-    fn print<T: Into<String>>(&mut self, str: T) -> UnitResult {
-        for ch in str.into().chars() {
-            self.print_char(ch as u8)?;
-        }
+    fn print<T: Into<String>>(&mut self, str: T) -> UnitResult where Self: Sized {
+        self.print_str(&str.into())
+    }
 
-        Ok(())
+    /// Prints a string at current cursor's position and moves the cursor - that's the same as
+    /// `print`, but callable on trait objects (`&mut dyn Hd44780`), too.
+    ///
+    /// Frontends customizing how the text is printed should override this method (instead of
+    /// `print`).
+    fn print_str(&mut self, str: &str) -> UnitResult {
+        let options = self.print_options();
+
+        print_with(self, str, options, |lcd| {
+            let y = (lcd.cursor().y + 1) % lcd.height();
+            lcd.move_at(y, 0)
+        })
+    }
+
+    /// Returns the options used by `print` (the default ones, unless the frontend allows to
+    /// change them).
+    fn print_options(&self) -> PrintOptions {
+        PrintOptions::default()
     }
 
     /// Prints a string at given position.
     ///
     /// # Example
     ///
-    /// ```rust
-    /// lcd.print_at(1, 0, "Hello World!");
-    /// lcd.print_at(2, 0, format!("Hello, {}!", someone));
+    /// ```rust,no_run
+    /// # use pwr_hd44780::{Hd44780, UnitResult};
+    /// # fn example(lcd: &mut impl Hd44780) -> UnitResult {
+    /// # let someone = "Anna";
+    /// lcd.print_at(1, 0, "Hello World!")?;
+    /// lcd.print_at(2, 0, format!("Hello, {}!", someone))?;
+    /// # Ok(())
+    /// # }
     /// ```
     ///
     /// # Errors
@@ -105,25 +402,164 @@ pub trait Hd44780 {
     /// 1. Returns an error when passed an invalid coordinates.
     ///
     /// 2. When given string requires overflowing current line, the behaviour is undefined.
-    fn print_at<T: Into<String>>(&mut self, y: usize, x: usize, str: T) -> UnitResult {
+    fn print_at<T: Into<String>>(&mut self, y: usize, x: usize, str: T) -> UnitResult where Self: Sized {
+        self.print_str_at(y, x, &str.into())
+    }
+
+    /// Prints a string at given position - that's the same as `print_at`, but callable on trait
+    /// objects (`&mut dyn Hd44780`), too.
+    fn print_str_at(&mut self, y: usize, x: usize, str: &str) -> UnitResult {
         self.move_at(y, x)?;
-        self.print(str)
+        self.print_str(str)
+    }
+
+    /// Clears given line (fills it with spaces) and moves the cursor at its beginning.
+    ///
+    /// Unlike `clear`, does not require any additional delays.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when passed an invalid line.
+    fn clear_line(&mut self, y: usize) -> UnitResult {
+        let width = self.width();
+
+        self.clear_region(Point::new(y, 0), Point::new(y, width - 1))
+    }
+
+    /// Clears given rectangular region (fills it with spaces) and moves the cursor at its top-left
+    /// corner. Both corners are inclusive.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // clear the right half of a 20x4 screen
+    /// lcd.clear_region(Point::new(0, 10), Point::new(3, 19));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when passed an invalid coordinates or when `from` is not the top-left
+    /// corner of the region.
+    fn clear_region(&mut self, from: Point, to: Point) -> UnitResult {
+        check_region(self, from, to)?;
+
+        for y in from.y..=to.y {
+            self.move_at(y, from.x)?;
+            self.print_char_repeated(b' ', to.x - from.x + 1)?;
+        }
+
+        self.move_at(from.y, from.x)
+    }
+
+    /// Reads the character displayed at given position straight from the LCD (as a ROM code) -
+    /// eg. to verify what has been actually written (see `Buffered::set_verify`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when given position lies outside the screen or when the LCD cannot be
+    /// read from (which is the default).
+    fn read_char_at(&mut self, _point: Point) -> Result<u8> {
+        Err(Error::ReadingNotSupported.into())
     }
 
+    /// Returns LCD's character ROM, used to translate printed strings.
+    fn rom(&self) -> Rom {
+        Rom::A00
+    }
+
+    /// Returns screen's height (number of lines).
+    fn height(&self) -> usize;
+
+    /// Returns screen's width (number of characters per line).
+    fn width(&self) -> usize;
+
+    /// Returns what the LCD is able to do (eg. whether it can be read from) - so that the
+    /// applications can adapt to the hardware at runtime, instead of checking the errors.
+    ///
+    /// By default, nothing is supported.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+    }
+}
+
+/// Capability: the LCD's backlight can be turned on and off.
+pub trait Backlight: Hd44780 {
     /// Enables / disables the backlight.
     fn set_backlight(&mut self, enabled: bool) -> UnitResult;
 
-    /// Enables / disables blinking the cursor.
-    /// `Blinking` means that the whole character box is blinking (a whole 5x8 or 5x10 box),
+    /// Changes the backlight's brightness (`0` - off, `255` - full brightness).
+    ///
+    /// LCDs unable to dim the backlight just turn it on for any non-zero brightness.
+    fn set_backlight_brightness(&mut self, brightness: u8) -> UnitResult {
+        self.set_backlight(brightness > 0)
+    }
+
+    /// Returns whether the LCD is able to dim the backlight (instead of just turning it on /
+    /// off, which is the default).
+    fn can_dim_backlight(&self) -> bool {
+        false
+    }
+}
+
+/// Capability: the cursor and the text can be shown and hidden (the HD44780's "display control"
+/// instruction).
+pub trait DisplayControl: Hd44780 {
+    /// Enables / disables the blinking block - the whole character box (5x8 or 5x10) at the
+    /// cursor's position blinks.
+    ///
+    /// It's independent of `set_cursor_visible` - when both are enabled, the underline stays lit
+    /// while the block blinks.
     fn set_cursor_blinking(&mut self, enabled: bool) -> UnitResult;
 
-    /// Enables / disables the cursor.
-    /// `Visible` means that only bottom of the character box is blinking (a single line).
+    /// Shows / hides the underline cursor - a steady (non-blinking) line at the bottom of the
+    /// character box at the cursor's position.
     fn set_cursor_visible(&mut self, enabled: bool) -> UnitResult;
 
+    /// Changes both the cursor's flags at once (see `CursorStyle`).
+    fn set_cursor_style(&mut self, style: CursorStyle) -> UnitResult {
+        self.set_cursor_visible(style.underline())?;
+        self.set_cursor_blinking(style.blinking())
+    }
+
     /// Shows / hides the text.
     fn set_text_visible(&mut self, enabled: bool) -> UnitResult;
 
+    /// Puts the display into standby: hides the text and turns the backlight off (on frontends
+    /// having one).
+    ///
+    /// Frontends tracking the LCD's state (eg. the direct one) remember the previous one, so that
+    /// `wake` is able to restore it.
+    fn sleep(&mut self) -> UnitResult {
+        self.set_text_visible(false)
+    }
+
+    /// Wakes the display up from standby (see `sleep`).
+    fn wake(&mut self) -> UnitResult {
+        self.set_text_visible(true)
+    }
+
+    /// Scrolls the whole display (all the lines at once) one character to the left - for simple
+    /// marquee effects.
+    ///
+    /// The direct frontend uses the hardware shift (see `Direct::shift_display_left`), while the
+    /// buffered one emulates it by rotating each line of its buffer - so the text leaving the
+    /// screen on the left comes back from the right either immediately (buffered) or after a
+    /// while (direct, where each line is a ring of 40 characters).
+    ///
+    /// By default, returns an error - the frontend does not support scrolling.
+    fn scroll_display_left(&mut self) -> UnitResult {
+        Err(Error::ScrollingNotSupported.into())
+    }
+
+    /// Scrolls the whole display (all the lines at once) one character to the right (see
+    /// `scroll_display_left`).
+    fn scroll_display_right(&mut self) -> UnitResult {
+        Err(Error::ScrollingNotSupported.into())
+    }
+}
+
+/// Capability: custom characters can be created.
+pub trait CustomChars: Hd44780 {
     /// Creates a custom character from given bitmap.
     ///
     /// Each array item in given bitmap represents a single line, of which only the last 5 bits are
@@ -136,7 +572,9 @@ pub trait Hd44780 {
     ///
     /// # Example
     ///
-    /// ```rust
+    /// ```rust,no_run
+    /// # use pwr_hd44780::{CustomChars, Hd44780, UnitResult};
+    /// # fn example(lcd: &mut (impl Hd44780 + CustomChars)) -> UnitResult {
     /// lcd.create_char(1, [
     ///   0b00000000,
     ///   0b10000000,
@@ -146,30 +584,577 @@ pub trait Hd44780 {
     ///   0b00001000,
     ///   0b00000100,
     ///   0b00000010,
-    /// ]);
+    /// ])?;
     ///
-    /// lcd.print_char(1);
+    /// lcd.print_char(1)?;
+    /// # Ok(())
+    /// # }
     /// ```
     ///
     /// # Errors
     ///
     /// Returns an error when passed an invalid index.
     fn create_char(&mut self, idx: u8, lines: [u8; 8]) -> UnitResult;
+}
 
-    /// Returns screen's height (number of lines).
-    fn height(&self) -> usize;
+impl<L: Hd44780 + ?Sized> Hd44780 for &mut L {
+    fn clear(&mut self) -> UnitResult {
+        (**self).clear()
+    }
 
-    /// Returns screen's width (number of characters per line).
-    fn width(&self) -> usize;
+    fn home(&mut self) -> UnitResult {
+        (**self).home()
+    }
+
+    fn move_at(&mut self, y: usize, x: usize) -> UnitResult {
+        (**self).move_at(y, x)
+    }
+
+    fn cursor(&self) -> Point {
+        (**self).cursor()
+    }
+
+    fn print_char(&mut self, ch: u8) -> UnitResult {
+        (**self).print_char(ch)
+    }
+
+    fn print_char_repeated(&mut self, ch: u8, count: usize) -> UnitResult {
+        (**self).print_char_repeated(ch, count)
+    }
+
+    fn print_char_at(&mut self, y: usize, x: usize, ch: u8) -> UnitResult {
+        (**self).print_char_at(y, x, ch)
+    }
+
+    fn print_str(&mut self, str: &str) -> UnitResult {
+        (**self).print_str(str)
+    }
+
+    fn print_options(&self) -> PrintOptions {
+        (**self).print_options()
+    }
+
+    fn print_str_at(&mut self, y: usize, x: usize, str: &str) -> UnitResult {
+        (**self).print_str_at(y, x, str)
+    }
+
+    fn clear_line(&mut self, y: usize) -> UnitResult {
+        (**self).clear_line(y)
+    }
+
+    fn clear_region(&mut self, from: Point, to: Point) -> UnitResult {
+        (**self).clear_region(from, to)
+    }
+
+    fn read_char_at(&mut self, point: Point) -> Result<u8> {
+        (**self).read_char_at(point)
+    }
+
+    fn rom(&self) -> Rom {
+        (**self).rom()
+    }
+
+    fn height(&self) -> usize {
+        (**self).height()
+    }
+
+    fn width(&self) -> usize {
+        (**self).width()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        (**self).capabilities()
+    }
 }
 
-#[derive(Copy, Clone, PartialEq)]
+impl<L: Hd44780 + ?Sized> Hd44780 for Box<L> {
+    fn clear(&mut self) -> UnitResult {
+        (**self).clear()
+    }
+
+    fn home(&mut self) -> UnitResult {
+        (**self).home()
+    }
+
+    fn move_at(&mut self, y: usize, x: usize) -> UnitResult {
+        (**self).move_at(y, x)
+    }
+
+    fn cursor(&self) -> Point {
+        (**self).cursor()
+    }
+
+    fn print_char(&mut self, ch: u8) -> UnitResult {
+        (**self).print_char(ch)
+    }
+
+    fn print_char_repeated(&mut self, ch: u8, count: usize) -> UnitResult {
+        (**self).print_char_repeated(ch, count)
+    }
+
+    fn print_char_at(&mut self, y: usize, x: usize, ch: u8) -> UnitResult {
+        (**self).print_char_at(y, x, ch)
+    }
+
+    fn print_str(&mut self, str: &str) -> UnitResult {
+        (**self).print_str(str)
+    }
+
+    fn print_options(&self) -> PrintOptions {
+        (**self).print_options()
+    }
+
+    fn print_str_at(&mut self, y: usize, x: usize, str: &str) -> UnitResult {
+        (**self).print_str_at(y, x, str)
+    }
+
+    fn clear_line(&mut self, y: usize) -> UnitResult {
+        (**self).clear_line(y)
+    }
+
+    fn clear_region(&mut self, from: Point, to: Point) -> UnitResult {
+        (**self).clear_region(from, to)
+    }
+
+    fn read_char_at(&mut self, point: Point) -> Result<u8> {
+        (**self).read_char_at(point)
+    }
+
+    fn rom(&self) -> Rom {
+        (**self).rom()
+    }
+
+    fn height(&self) -> usize {
+        (**self).height()
+    }
+
+    fn width(&self) -> usize {
+        (**self).width()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        (**self).capabilities()
+    }
+}
+
+impl<L: Backlight + ?Sized> Backlight for &mut L {
+    fn set_backlight(&mut self, enabled: bool) -> UnitResult {
+        (**self).set_backlight(enabled)
+    }
+
+    fn set_backlight_brightness(&mut self, brightness: u8) -> UnitResult {
+        (**self).set_backlight_brightness(brightness)
+    }
+
+    fn can_dim_backlight(&self) -> bool {
+        (**self).can_dim_backlight()
+    }
+}
+
+impl<L: Backlight + ?Sized> Backlight for Box<L> {
+    fn set_backlight(&mut self, enabled: bool) -> UnitResult {
+        (**self).set_backlight(enabled)
+    }
+
+    fn set_backlight_brightness(&mut self, brightness: u8) -> UnitResult {
+        (**self).set_backlight_brightness(brightness)
+    }
+
+    fn can_dim_backlight(&self) -> bool {
+        (**self).can_dim_backlight()
+    }
+}
+
+impl<L: DisplayControl + ?Sized> DisplayControl for &mut L {
+    fn set_cursor_blinking(&mut self, enabled: bool) -> UnitResult {
+        (**self).set_cursor_blinking(enabled)
+    }
+
+    fn set_cursor_visible(&mut self, enabled: bool) -> UnitResult {
+        (**self).set_cursor_visible(enabled)
+    }
+
+    fn set_cursor_style(&mut self, style: CursorStyle) -> UnitResult {
+        (**self).set_cursor_style(style)
+    }
+
+    fn set_text_visible(&mut self, enabled: bool) -> UnitResult {
+        (**self).set_text_visible(enabled)
+    }
+
+    fn sleep(&mut self) -> UnitResult {
+        (**self).sleep()
+    }
+
+    fn wake(&mut self) -> UnitResult {
+        (**self).wake()
+    }
+
+    fn scroll_display_left(&mut self) -> UnitResult {
+        (**self).scroll_display_left()
+    }
+
+    fn scroll_display_right(&mut self) -> UnitResult {
+        (**self).scroll_display_right()
+    }
+}
+
+impl<L: DisplayControl + ?Sized> DisplayControl for Box<L> {
+    fn set_cursor_blinking(&mut self, enabled: bool) -> UnitResult {
+        (**self).set_cursor_blinking(enabled)
+    }
+
+    fn set_cursor_visible(&mut self, enabled: bool) -> UnitResult {
+        (**self).set_cursor_visible(enabled)
+    }
+
+    fn set_cursor_style(&mut self, style: CursorStyle) -> UnitResult {
+        (**self).set_cursor_style(style)
+    }
+
+    fn set_text_visible(&mut self, enabled: bool) -> UnitResult {
+        (**self).set_text_visible(enabled)
+    }
+
+    fn sleep(&mut self) -> UnitResult {
+        (**self).sleep()
+    }
+
+    fn wake(&mut self) -> UnitResult {
+        (**self).wake()
+    }
+
+    fn scroll_display_left(&mut self) -> UnitResult {
+        (**self).scroll_display_left()
+    }
+
+    fn scroll_display_right(&mut self) -> UnitResult {
+        (**self).scroll_display_right()
+    }
+}
+
+impl<L: CustomChars + ?Sized> CustomChars for &mut L {
+    fn create_char(&mut self, idx: u8, lines: [u8; 8]) -> UnitResult {
+        (**self).create_char(idx, lines)
+    }
+}
+
+impl<L: CustomChars + ?Sized> CustomChars for Box<L> {
+    fn create_char(&mut self, idx: u8, lines: [u8; 8]) -> UnitResult {
+        (**self).create_char(idx, lines)
+    }
+}
+
+/// A position on the screen.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Point {
+    // line
+    pub y: usize,
+
+    // column
+    pub x: usize,
+}
+
+impl Point {
+    pub fn new(y: usize, x: usize) -> Point {
+        Point { y, x }
+    }
+
+    /// Returns the point moved by given number of lines & columns - or `None`, when it would end
+    /// up above / to the left of the screen.
+    pub fn offset(self, dy: isize, dx: isize) -> Option<Point> {
+        Some(Point::new(self.y.checked_add_signed(dy)?, self.x.checked_add_signed(dx)?))
+    }
+
+    /// Returns the point moved by given number of lines & columns - or `None`, when it would end
+    /// up outside a screen of given size.
+    pub fn offset_within(self, dy: isize, dx: isize, width: usize, height: usize) -> Option<Point> {
+        self.offset(dy, dx).filter(|point| point.is_within(width, height))
+    }
+
+    /// Returns the point moved by given number of cells in the reading order (just like the
+    /// cursor moves when printing), wrapping onto the next line after the last column and back
+    /// onto the first line after the last one; negative numbers move backwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics when given an empty screen.
+    pub fn wrapping_offset(self, cells: isize, width: usize, height: usize) -> Point {
+        let len = (width * height) as isize;
+        let idx = (self.index(width) as isize + cells).rem_euclid(len);
+
+        Point::from_index(idx as usize, width)
+    }
+
+    /// Returns whether the point lies within a screen of given size.
+    pub fn is_within(self, width: usize, height: usize) -> bool {
+        self.y < height && self.x < width
+    }
+
+    /// Returns the point's index in the reading order on a screen of given width (`0` for the
+    /// top-left corner, `width` for the beginning of the second line and so on).
+    pub fn index(self, width: usize) -> usize {
+        self.y * width + self.x
+    }
+
+    /// Returns the point lying at given index in the reading order (see `index`).
+    ///
+    /// # Panics
+    ///
+    /// Panics when given a zero width.
+    pub fn from_index(idx: usize, width: usize) -> Point {
+        Point::new(idx / width, idx % width)
+    }
+
+    /// Returns an iterator over all the points of given region (`from` being its top-left corner
+    /// and `to` - its bottom-right one, both inclusive), line by line.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// for point in Point::region(Point::new(0, 10), Point::new(3, 19)) {
+    ///     lcd.print_char_at(point.y, point.x, b'#')?;
+    /// }
+    /// ```
+    pub fn region(from: Point, to: Point) -> Cells {
+        Cells {
+            next: Some(from).filter(|from| from.y <= to.y && from.x <= to.x),
+            from,
+            to,
+        }
+    }
+}
+
+/// An iterator over the points of a region (see `Point::region` and `Hd44780Ext::cells`).
+#[derive(Clone, Debug)]
+pub struct Cells {
+    from: Point,
+    to: Point,
+    next: Option<Point>,
+}
+
+impl Iterator for Cells {
+    type Item = Point;
+
+    fn next(&mut self) -> Option<Point> {
+        let point = self.next?;
+
+        self.next = if point.x < self.to.x {
+            Some(Point::new(point.y, point.x + 1))
+        } else if point.y < self.to.y {
+            Some(Point::new(point.y + 1, self.from.x))
+        } else {
+            None
+        };
+
+        Some(point)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.next.map_or(0, |next| {
+            let width = self.to.x - self.from.x + 1;
+
+            (self.to.y - next.y) * width + (self.to.x - next.x + 1)
+        });
+
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Cells {}
+
+/// How the hardware cursor looks (see `DisplayControl::set_cursor_style`).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CursorStyle {
+    /// No cursor.
+    Hidden,
+
+    /// A steady line at the bottom of the character box.
+    Underline,
+
+    /// The whole character box blinking.
+    Blinking,
+
+    /// A steady line at the bottom of the character box, with the whole box blinking.
+    BlinkingUnderline,
+}
+
+impl CursorStyle {
+    /// Returns whether the underline is shown (the `C` bit of the "display control" instruction).
+    pub fn underline(self) -> bool {
+        matches!(self, CursorStyle::Underline | CursorStyle::BlinkingUnderline)
+    }
+
+    /// Returns whether the character box blinks (the `B` bit of the "display control"
+    /// instruction).
+    pub fn blinking(self) -> bool {
+        matches!(self, CursorStyle::Blinking | CursorStyle::BlinkingUnderline)
+    }
+}
+
+/// Features supported by an LCD (see `Hd44780::capabilities`).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Whether the backlight can be turned on and off.
+    pub backlight: bool,
+
+    /// Whether the backlight's brightness can be changed (see `Backlight::set_backlight_brightness`).
+    pub dimmable_backlight: bool,
+
+    /// Whether the characters can be read back (see `Hd44780::read_char_at`).
+    pub readable: bool,
+
+    /// Number of custom characters which can be created (see `CustomChars::create_char`).
+    pub custom_chars: usize,
+
+    /// Whether the whole display can be scrolled (see `DisplayControl::scroll_display_left`).
+    pub scrolling: bool,
+}
+
+impl Capabilities {
+    /// Returns the capabilities supported by both of the LCDs - eg. for a frontend mirroring the
+    /// text onto both.
+    pub fn intersect(self, other: Capabilities) -> Capabilities {
+        Capabilities {
+            backlight: self.backlight && other.backlight,
+            dimmable_backlight: self.dimmable_backlight && other.dimmable_backlight,
+            readable: self.readable && other.readable,
+            custom_chars: self.custom_chars.min(other.custom_chars),
+            scrolling: self.scrolling && other.scrolling,
+        }
+    }
+}
+
+/// Options affecting how `print` treats the text.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PrintOptions {
+    /// Whether `\n` (next line), `\r` (beginning of the line) and `\t` (next tab stop) move the
+    /// cursor, instead of being sent to the LCD as they are.
+    pub control_chars: bool,
+
+    /// Distance between the tab stops.
+    pub tab_width: usize,
+
+    /// What to print in place of the characters not present in the ROM.
+    pub fallback: Fallback,
+
+    /// What to do with the control and the invisible characters - meant for printing untrusted
+    /// text (see `Sanitize`).
+    pub sanitize: Sanitize,
+}
+
+impl Default for PrintOptions {
+    fn default() -> PrintOptions {
+        PrintOptions {
+            control_chars: false,
+            tab_width: 4,
+            fallback: Fallback::default(),
+            sanitize: Sanitize::default(),
+        }
+    }
+}
+
+impl PrintOptions {
+    /// Returns the options meant for the untrusted text: the control and the invisible
+    /// characters are removed (see `Sanitize`).
+    pub fn untrusted() -> PrintOptions {
+        PrintOptions {
+            sanitize: Sanitize::Strip,
+            ..PrintOptions::default()
+        }
+    }
+}
+
+/// Prints given text, interpreting the control characters (when enabled); `new_line` moves the
+/// cursor at the beginning of the next line.
+pub(crate) fn print_with<L, F>(lcd: &mut L, str: &str, options: PrintOptions, mut new_line: F) -> UnitResult
+    where L: Hd44780 + ?Sized, F: FnMut(&mut L) -> UnitResult {
+    let rom = lcd.rom();
+
+    for ch in str.chars().filter_map(|ch| options.sanitize.sanitize_char(ch)) {
+        match ch {
+            '\n' if options.control_chars => {
+                new_line(lcd)?;
+            }
+
+            '\r' if options.control_chars => {
+                let y = lcd.cursor().y;
+                lcd.move_at(y, 0)?;
+            }
+
+            '\t' if options.control_chars => {
+                let x = lcd.cursor().x;
+                let tab_width = options.tab_width.max(1);
+                let stop = ((x / tab_width + 1) * tab_width).min(lcd.width());
+
+                for _ in x..stop {
+                    lcd.print_char(b' ')?;
+                }
+            }
+
+            ch => {
+                rom.encode_with(ch, options.fallback, |code| lcd.print_char(code))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks whether given region lies within the screen and whether its corners are ordered.
+pub(crate) fn check_region<L: Hd44780 + ?Sized>(lcd: &L, from: Point, to: Point) -> UnitResult {
+    if to.y >= lcd.height() || to.x >= lcd.width() {
+        return Err(
+            format!("Tried to access a region outside the screen (at y={}, x={}).", to.y, to.x).into()
+        );
+    }
+
+    if from.y > to.y || from.x > to.x {
+        return Err(
+            format!("Invalid region: (y={}, x={}) is not the top-left corner of (y={}, x={}).", from.y, from.x, to.y, to.x).into()
+        );
+    }
+
+    Ok(())
+}
+
+/// Direction in which the cursor moves after printing a character.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
+    LeftToRight,
+    RightToLeft,
+}
+
+/// Describes how the characters of a screen are laid out in the LCD's memory.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+pub enum Geometry {
+    /// Use `Split` for 16x1 displays (most of them are in fact 8x2 inside) and `Contiguous` for
+    /// all the other ones.
+    #[default]
+    Auto,
+
+    /// Each line occupies a contiguous range of addresses (see `Properties::row_addresses`).
+    Contiguous,
+
+    /// Single-line display driven as two lines: the left half of the screen lives at `0x00`, while
+    /// the right half lives at `0x40`.
+    Split,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Font {
     Font5x8,
     Font5x10,
 }
 
-#[derive(Copy, Clone)]
+/// Number of characters the LCD's display data RAM (DDRAM) is able to hold.
+pub const DDRAM_SIZE: usize = 80;
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Properties {
     // number of lines
     pub height: usize,
@@ -179,4 +1164,247 @@ pub struct Properties {
 
     // LCD's font
     pub font: Font,
-}
\ No newline at end of file
+
+    // LCD's character ROM
+    pub rom: Rom,
+
+    // DDRAM addresses of the lines' beginnings; when `None`, they're inferred from the dimensions
+    // (see `Properties::row_addresses`)
+    pub row_addresses: Option<[u8; 4]>,
+
+    // layout of the characters in the LCD's memory
+    pub geometry: Geometry,
+
+    // LCD's controller
+    pub controller: Controller,
+}
+
+impl Properties {
+    /// Creates the default properties for a screen of given size: the `A00` ROM, the 5x10 font
+    /// for the 1-line displays and the 5x8 one for the rest.
+    pub fn new(width: usize, height: usize) -> Properties {
+        let mut properties = Properties {
+            height,
+            width,
+
+            font: Font::Font5x8,
+            rom: Rom::A00,
+            row_addresses: None,
+            geometry: Geometry::Auto,
+            controller: Controller::Hd44780,
+        };
+
+        if properties.controller_lines() == 1 {
+            properties.font = Font::Font5x10;
+        }
+
+        properties
+    }
+
+    /// Creates the default properties for a screen of given size (see `new`), checking whether
+    /// HD44780 is able to drive such a screen at all (see `validate`).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let properties = Properties::try_new(20, 4)?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidDimensions` when any of the dimensions is zero and
+    /// `Error::InvalidConfig` when the screen is too large.
+    pub fn try_new(width: usize, height: usize) -> ::core::result::Result<Properties, Error> {
+        let properties = Properties::new(width, height);
+
+        properties.validate()?;
+
+        Ok(properties)
+    }
+
+    /// Returns whether the screen is a single line split into two halves living at different
+    /// addresses (see `Geometry::Split`).
+    pub fn is_split(&self) -> bool {
+        match self.geometry {
+            Geometry::Auto => self.height == 1 && self.width == 16,
+            Geometry::Contiguous => false,
+            Geometry::Split => true,
+        }
+    }
+
+    /// Returns number of lines the LCD's controller has to be configured for - it differs from the
+    /// screen's height for the split displays.
+    pub fn controller_lines(&self) -> usize {
+        if self.is_split() { 2 } else { self.height }
+    }
+
+    /// Returns DDRAM address of the character at given position.
+    pub fn address_of(&self, y: usize, x: usize) -> u8 {
+        if self.is_split() && x >= self.width / 2 {
+            0x40 + (x - self.width / 2) as u8
+        } else {
+            self.row_addresses()[y] + x as u8
+        }
+    }
+
+    /// Returns position of the character living at given DDRAM address - the inverse of
+    /// `address_of`.
+    ///
+    /// Addresses past the end of a line map to positions outside the screen (eg. `x = 16` on a
+    /// 16x2 display).
+    pub fn position_of(&self, address: u8) -> Point {
+        if self.is_split() {
+            return if address >= 0x40 {
+                Point::new(0, self.width / 2 + (address - 0x40) as usize)
+            } else {
+                Point::new(0, address as usize)
+            };
+        }
+
+        let row_addresses = self.row_addresses();
+
+        // find the line starting closest before the address
+        let y = row_addresses
+            .iter()
+            .take(self.height)
+            .enumerate()
+            .filter(|&(_, &row_address)| row_address <= address)
+            .max_by_key(|&(_, &row_address)| row_address)
+            .map_or(0, |(y, _)| y);
+
+        Point::new(y, address.saturating_sub(row_addresses[y]) as usize)
+    }
+
+    /// Returns whether the LCD is driven in the 4-line mode of the controllers having one (eg.
+    /// `Controller::Ks0073`).
+    pub fn is_four_line_mode(&self) -> bool {
+        self.height == 4 && self.controller.profile().four_line_addresses.is_some()
+    }
+
+    /// Returns the DDRAM address following (`increment`) or preceding given one - just like the
+    /// LCD's address counter does after writing a character.
+    pub(crate) fn next_address(&self, address: u8, increment: bool) -> u8 {
+        if self.is_four_line_mode() {
+            // KS0073's 4-line mode: a single range of 128 addresses, the lines being 32 apart
+            if increment { (address + 1) % 0x80 } else { (address + 0x7F) % 0x80 }
+        } else if self.controller_lines() == 1 {
+            // 1-line mode: a single line of 80 characters
+            if increment { (address + 1) % 80 } else { (address + 79) % 80 }
+        } else {
+            // 2-line mode: two lines of 40 characters, at 0x00 and 0x40
+            match (increment, address) {
+                (true, 0x27) => 0x40,
+                (true, 0x67) => 0x00,
+                (true, _) => address + 1,
+                (false, 0x00) => 0x67,
+                (false, 0x40) => 0x27,
+                (false, _) => address - 1,
+            }
+        }
+    }
+
+    /// Returns DDRAM addresses of the lines' beginnings.
+    ///
+    /// Unless specified explicitly, the first two lines start at `0x00` and `0x40`, while the
+    /// third and the fourth one continue right after them (at `0x00 + width` and `0x40 + width`);
+    /// that's `[0x00, 0x40, 0x14, 0x54]` for 20x4 displays and `[0x00, 0x40, 0x10, 0x50]` for
+    /// 16x4 ones. In the 4-line mode, the controller's profile decides (see
+    /// `ControllerProfile::four_line_addresses`).
+    pub fn row_addresses(&self) -> [u8; 4] {
+        if let (true, Some(addresses)) = (self.height == 4, self.controller.profile().four_line_addresses) {
+            return self.row_addresses.unwrap_or(addresses);
+        }
+
+        self.row_addresses.unwrap_or_else(|| {
+            let width = self.width.min(0x40) as u8;
+
+            [0x00, 0x40, width, 0x40 + width]
+        })
+    }
+
+    /// Checks whether the properties describe a display HD44780 is able to drive.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when:
+    /// - any of the dimensions is zero,
+    /// - the screen has more than 4 lines,
+    /// - the screen has more than one line, each wider than 40 characters,
+    /// - the screen has more characters than the DDRAM is able to hold,
+    /// - any of the lines does not fit in the DDRAM's address space,
+    /// - the split geometry is used on a multi-line screen,
+    /// - the 5x10 font is used on a multi-line or split screen (HD44780 supports it only in the
+    ///   1-line mode) or with a controller not supporting it at all (see
+    ///   `ControllerProfile::font_5x10`),
+    /// - the screen is wider than 20 characters in the 4-line mode.
+    pub fn validate(&self) -> ::core::result::Result<(), Error> {
+        if self.height == 0 || self.width == 0 {
+            return Err(Error::InvalidDimensions {
+                width: self.width,
+                height: self.height,
+            });
+        }
+
+        if self.height > 4 {
+            return Err(Error::invalid_config(
+                "properties.height",
+                format!("at most 4 lines are supported (got {})", self.height),
+            ));
+        }
+
+        if self.height > 1 && self.width > DDRAM_SIZE / 2 {
+            return Err(Error::invalid_config(
+                "properties.width",
+                format!("multi-line screens support at most {} characters per line (got {})", DDRAM_SIZE / 2, self.width),
+            ));
+        }
+
+        if self.width * self.height > DDRAM_SIZE {
+            return Err(Error::invalid_config(
+                "properties",
+                format!("{}x{} characters exceed the DDRAM size of {} characters", self.width, self.height, DDRAM_SIZE),
+            ));
+        }
+
+        for (y, &address) in self.row_addresses().iter().take(self.height).enumerate() {
+            if address as usize + self.width > 0x80 {
+                return Err(Error::invalid_config(
+                    "properties.row_addresses",
+                    format!("line {} (starting at {:#04x}) exceeds the DDRAM", y, address),
+                ));
+            }
+        }
+
+        if self.geometry == Geometry::Split && (self.height != 1 || self.width > 80) {
+            return Err(Error::invalid_config(
+                "properties.geometry",
+                "split geometry can be used only with 1-line screens of at most 80 characters",
+            ));
+        }
+
+        if self.font == Font::Font5x10 && self.controller_lines() > 1 {
+            return Err(Error::invalid_config(
+                "properties.font",
+                "5x10 font can be used only with 1-line screens (which are not split)",
+            ));
+        }
+
+        let profile = self.controller.profile();
+
+        if self.font == Font::Font5x10 && !profile.font_5x10 {
+            return Err(Error::invalid_config(
+                "properties.font",
+                format!("5x10 font is not supported by {}", profile.name),
+            ));
+        }
+
+        if self.is_four_line_mode() && self.width > 20 {
+            return Err(Error::invalid_config(
+                "properties.width",
+                format!("{}'s 4-line mode supports at most 20 characters per line (got {})", profile.name, self.width),
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+//! Provides a `log` crate's backend, showing the log records on the LCD.
+//!
+//! Records are appended to a region of the screen (a few consecutive lines), which scrolls up
+//! when it fills up - so the most recent records are always visible. Each record is prefixed with
+//! its level's first letter and wrapped to the screen's width:
+//!
+//! ```text
+//! I Connected to
+//!   the network
+//! W Low battery
+//! ```
+//!
+//! Requires the `log` feature.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let lcd = SharedLcd::new(lcd);
+//!
+//! // use the two bottom lines for warnings & errors
+//! LcdLogger::new(lcd.clone(), 2, 2, LevelFilter::Warn)?.init()?;
+//!
+//! log::warn!("Low battery");
+//! ```
+
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::string::String;
+use alloc::vec::Vec;
+use std::sync::Mutex;
+use log::{LevelFilter, Log, Metadata, Record, SetLoggerError};
+use super::{Error, Hd44780, Hd44780Ext, Result, UnitResult};
+use super::frontends::{Buffered, Shared};
+
+pub struct LcdLogger {
+    lcd: Shared<Buffered>,
+
+    /// first line of the region
+    y: usize,
+
+    /// number of lines in the region
+    height: usize,
+
+    level: LevelFilter,
+
+    /// lines currently displayed in the region (already wrapped)
+    lines: Mutex<VecDeque<String>>,
+}
+
+impl LcdLogger {
+    /// Creates a new logger, writing the records into `height` lines of the screen, starting at
+    /// line `y`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when the region does not fit on the screen.
+    pub fn new(lcd: Shared<Buffered>, y: usize, height: usize, level: LevelFilter) -> Result<LcdLogger> {
+        if height == 0 || y + height > lcd.height() {
+            return Err(Error::invalid_config(
+                "height",
+                format!("region of {} line(s) starting at line {} does not fit on a screen with {} line(s)", height, y, lcd.height()),
+            ).into());
+        }
+
+        Ok(LcdLogger {
+            lcd,
+            y,
+            height,
+            level,
+            lines: Mutex::new(VecDeque::with_capacity(height)),
+        })
+    }
+
+    /// Installs the logger as the global one (see `log::set_logger`), setting the maximum log
+    /// level accordingly.
+    pub fn init(self) -> ::std::result::Result<(), SetLoggerError> {
+        let level = self.level;
+
+        log::set_boxed_logger(Box::new(self))?;
+        log::set_max_level(level);
+
+        Ok(())
+    }
+
+    /// Appends given record to the region and redraws it.
+    fn append(&self, record: &Record) -> UnitResult {
+        let width = self.lcd.width();
+        let prefix = record.level().as_str().chars().next().unwrap_or(' ');
+        let message = format!("{} {}", prefix, record.args());
+
+        let mut lines = self.lines
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+
+        for line in wrap(&message, width) {
+            if lines.len() == self.height {
+                lines.pop_front();
+            }
+
+            lines.push_back(line);
+        }
+
+        let (first_y, height) = (self.y, self.height);
+
+        self.lcd.transaction(|lcd| {
+            for y in 0..height {
+                let line = lines
+                    .get(y)
+                    .map_or("", |line| line.as_str());
+
+                lcd.print_line(first_y + y, line)?;
+            }
+
+            lcd.render()?;
+
+            Ok(())
+        })
+    }
+}
+
+impl Log for LcdLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        // there's no one to report the error to - especially not the logger
+        let _ = self.append(record);
+    }
+
+    fn flush(&self) {}
+}
+
+/// Splits given message into lines of given width; the continuation lines are indented by two
+/// characters, so that they line up with the text following the level's letter.
+fn wrap(message: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut chars = message.chars().filter(|ch| !ch.is_control()).peekable();
+    let indent = if width > 2 { 2 } else { 0 };
+
+    while chars.peek().is_some() {
+        let mut line = String::new();
+
+        if !lines.is_empty() {
+            line.push_str(&"  "[..indent]);
+        }
+
+        while line.chars().count() < width {
+            match chars.next() {
+                Some(ch) => line.push(ch),
+                None => break,
+            }
+        }
+
+        lines.push(line);
+    }
+
+    lines
+}
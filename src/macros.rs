@@ -0,0 +1,57 @@
+//! Provides the formatting macros, printing straight onto the screen (see `Hd44780Ext`), and the
+//! `char_bitmap!` macro (see `CharBitmap`).
+
+/// Formats given arguments onto the LCD at given position (`(y, x)`), truncating the text at the
+/// end of the line; does not allocate.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// lcd_write!(lcd, (0, 1), "{:>4} rpm", rpm)?;
+/// ```
+#[macro_export]
+macro_rules! lcd_write {
+    ($lcd:expr, ($y:expr, $x:expr), $($arg:tt)*) => {{
+        use $crate::Hd44780Ext as _;
+
+        $lcd.write_fmt_at($y, $x, format_args!($($arg)*))
+    }};
+}
+
+/// Formats given arguments onto given line, replacing the whole line (the text is truncated or
+/// padded with spaces); does not allocate.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// lcd_writeln!(lcd, 1, "Temp: {:.1}°C", temperature)?;
+/// ```
+#[macro_export]
+macro_rules! lcd_writeln {
+    ($lcd:expr, $y:expr, $($arg:tt)*) => {{
+        use $crate::Hd44780Ext as _;
+
+        $lcd.write_line_fmt($y, format_args!($($arg)*))
+    }};
+}
+
+/// Builds a custom character's bitmap from an "ASCII art" at compile time (see `CharBitmap`) - a
+/// malformed art fails the build.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// lcd.create_char(0, char_bitmap![
+///     "  #  ",
+///     " ### ",
+///     "#####",
+/// ])?;
+/// ```
+#[macro_export]
+macro_rules! char_bitmap {
+    ($($line:expr),* $(,)?) => {{
+        const BITMAP: [u8; 8] = $crate::CharBitmap::from_art(&[$($line),*]);
+
+        BITMAP
+    }};
+}
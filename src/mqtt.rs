@@ -0,0 +1,207 @@
+//! Provides a bridge subscribing to the MQTT topics and showing what gets published on them on
+//! the LCD - so that a wall-mounted display can be driven straight from the home automation
+//! (eg. Home Assistant), without writing any glue code.
+//!
+//! Requires the `mqtt` feature.
+//!
+//! # Topics
+//!
+//! Relative to the prefix (`lcd` by default - see `set_prefix`):
+//!
+//! - `<prefix>/text` - replaces the whole screen (lines are separated with `\n`),
+//! - `<prefix>/line/<y>` - replaces given line,
+//! - `<prefix>/clear` - clears the screen (the payload is ignored),
+//! - `<prefix>/backlight` - `on` / `off` (`ON` / `OFF`, `true` / `false` and `1` / `0` work too),
+//! - `<prefix>/brightness` - backlight's brightness (`0..=255`),
+//! - `<prefix>/command` - any of the daemon's commands (see the `daemon` module), one per line.
+//!
+//! The bridge publishes `online` onto `<prefix>/status` (retained, with `offline` set as the last
+//! will - so that it can be used as Home Assistant's availability topic) and the errors (eg. an
+//! invalid payload) onto `<prefix>/error`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut bridge = MqttBridge::new(lcd, "homeassistant.local", 1883);
+//!
+//! bridge.set_prefix("hallway/lcd");
+//! bridge.set_credentials("lcd", "secret");
+//! bridge.run()?;
+//! ```
+//!
+//! ```text
+//! $ mosquitto_pub -h homeassistant.local -t hallway/lcd/line/0 -m "Washing done!"
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::time::Duration;
+use rumqttc::{Client, Event, LastWill, MqttOptions, Packet, QoS};
+use std::thread;
+use super::{Backlight, Hd44780, Hd44780Ext, UnitResult};
+use super::commands;
+use super::frontends::Buffered;
+
+pub struct MqttBridge {
+    lcd: Buffered,
+
+    host: String,
+    port: u16,
+    client_id: String,
+    credentials: Option<(String, String)>,
+
+    /// prefix of all the topics (without the trailing slash)
+    prefix: String,
+}
+
+/// Topics the bridge subscribes to (relative to the prefix).
+const TOPICS: [&str; 6] = ["text", "line/+", "clear", "backlight", "brightness", "command"];
+
+/// How long to wait before reconnecting to the broker.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+impl MqttBridge {
+    /// Creates a new bridge, taking over given LCD; the broker is connected to once `run` gets
+    /// called.
+    pub fn new<T: Into<String>>(lcd: Buffered, host: T, port: u16) -> MqttBridge {
+        MqttBridge {
+            lcd,
+            host: host.into(),
+            port,
+            client_id: "pwr-hd44780".to_string(),
+            credentials: None,
+            prefix: "lcd".to_string(),
+        }
+    }
+
+    /// Changes the prefix of the topics (`lcd` by default).
+    pub fn set_prefix<T: Into<String>>(&mut self, prefix: T) {
+        self.prefix = prefix
+            .into()
+            .trim_end_matches('/')
+            .to_string();
+    }
+
+    /// Changes the client's identifier (`pwr-hd44780` by default) - it has to be unique among the
+    /// clients connected to the broker.
+    pub fn set_client_id<T: Into<String>>(&mut self, client_id: T) {
+        self.client_id = client_id.into();
+    }
+
+    /// Makes the bridge log into the broker using given credentials.
+    pub fn set_credentials<U: Into<String>, P: Into<String>>(&mut self, username: U, password: P) {
+        self.credentials = Some((username.into(), password.into()));
+    }
+
+    /// Returns the LCD (eg. to draw the initial screen).
+    pub fn lcd(&mut self) -> &mut Buffered {
+        &mut self.lcd
+    }
+
+    /// Connects to the broker and shows the messages published on the topics; never returns,
+    /// unless the LCD fails - losing the connection to the broker makes the bridge reconnect.
+    pub fn run(&mut self) -> UnitResult {
+        let status_topic = self.topic("status");
+
+        let mut options = MqttOptions::new(self.client_id.clone(), self.host.clone(), self.port);
+
+        options.set_keep_alive(Duration::from_secs(30));
+        options.set_last_will(LastWill::new(status_topic.clone(), "offline", QoS::AtLeastOnce, true));
+
+        if let Some((username, password)) = &self.credentials {
+            options.set_credentials(username.clone(), password.clone());
+        }
+
+        let (client, mut connection) = Client::new(options, 16);
+
+        for event in connection.iter() {
+            match event {
+                // the subscriptions do not survive reconnecting, so they're (re)made here
+                Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                    for topic in TOPICS.iter() {
+                        client.subscribe(self.topic(topic), QoS::AtLeastOnce)?;
+                    }
+
+                    client.publish(status_topic.as_str(), QoS::AtLeastOnce, true, "online")?;
+                }
+
+                Ok(Event::Incoming(Packet::Publish(publish))) => {
+                    let payload = String::from_utf8_lossy(&publish.payload);
+
+                    if let Err(err) = self.handle(&publish.topic, &payload) {
+                        client.publish(self.topic("error"), QoS::AtMostOnce, false, err.to_string())?;
+                    }
+
+                    self.lcd.render()?;
+                }
+
+                Ok(_) => (),
+
+                // the connection gets re-established on the next iteration
+                Err(_) => thread::sleep(RECONNECT_DELAY),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Shows the message published on given topic.
+    fn handle(&mut self, topic: &str, payload: &str) -> UnitResult {
+        let topic = topic
+            .strip_prefix(self.prefix.as_str())
+            .and_then(|topic| topic.strip_prefix('/'))
+            .ok_or_else(|| format!("Unexpected topic: {}", topic))?;
+
+        match topic {
+            "text" => {
+                let lines: Vec<&str> = payload.lines().collect();
+
+                for y in 0..self.lcd.height() {
+                    self.lcd.print_line(y, lines.get(y).copied().unwrap_or(""))?;
+                }
+
+                Ok(())
+            }
+
+            "clear" => {
+                self.lcd.clear()
+            }
+
+            "backlight" => {
+                match payload.trim() {
+                    "on" | "ON" | "true" | "1" => self.lcd.set_backlight(true),
+                    "off" | "OFF" | "false" | "0" => self.lcd.set_backlight(false),
+                    payload => Err(format!("Expected `on` or `off`, got: {}", payload).into()),
+                }
+            }
+
+            "brightness" => {
+                let brightness = payload.trim().parse()?;
+
+                self.lcd.set_backlight_brightness(brightness)
+            }
+
+            "command" => {
+                for line in payload.lines().filter(|line| !line.trim().is_empty()) {
+                    commands::execute(&mut self.lcd, line)?;
+                }
+
+                Ok(())
+            }
+
+            topic => {
+                let y = topic
+                    .strip_prefix("line/")
+                    .ok_or_else(|| format!("Unknown topic: {}", topic))?
+                    .parse()?;
+
+                self.lcd.print_line(y, payload)
+            }
+        }
+    }
+
+    /// Returns the full name of given topic.
+    fn topic(&self, topic: &str) -> String {
+        format!("{}/{}", self.prefix, topic)
+    }
+}
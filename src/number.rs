@@ -0,0 +1,208 @@
+//! Formats numbers into fixed-width regions of the screen - right-aligned, with a fixed number of
+//! decimals, thousands separators and a unit suffix - straight onto the LCD, without allocating.
+//!
+//! The region always gets filled as a whole (padded with spaces), so there are no leftovers of
+//! the longer values; a value not fitting in the region is shown as `#`s (just like spreadsheets
+//! do), instead of spilling into the neighbouring text.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! const POWER: NumberFormat = NumberFormat::new(9).decimals(1).thousands(',').unit("W");
+//!
+//! // `  1,234.5W`
+//! lcd.print_number_at(0, 0, 1234.5, &POWER)?;
+//!
+//! // `P:  1,234.5W`
+//! lcd_write!(lcd, (1, 0), "P:{}", POWER.display(1234.5))?;
+//! ```
+
+use core::fmt::{self, Write};
+use super::Align;
+
+/// Describes how a number is formatted (see the module's docs).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct NumberFormat {
+    width: usize,
+    decimals: usize,
+    separator: Option<char>,
+    unit: &'static str,
+    align: Align,
+}
+
+/// Maximum length of a formatted number (without the separators and the unit); longer ones are
+/// treated as not fitting in the region.
+const MAX_LEN: usize = 64;
+
+impl NumberFormat {
+    /// Creates a format of given width (in characters, including the unit), with the numbers
+    /// right-aligned and without any decimals.
+    pub const fn new(width: usize) -> NumberFormat {
+        NumberFormat {
+            width,
+            decimals: 0,
+            separator: None,
+            unit: "",
+            align: Align::Right,
+        }
+    }
+
+    /// Makes the numbers have given number of decimals (rounding them).
+    pub const fn decimals(mut self, decimals: usize) -> NumberFormat {
+        self.decimals = decimals;
+        self
+    }
+
+    /// Makes the thousands get separated with given character (eg. `,` or `'`).
+    pub const fn thousands(mut self, separator: char) -> NumberFormat {
+        self.separator = Some(separator);
+        self
+    }
+
+    /// Makes the numbers get followed by given unit (eg. `"C"` or `" rpm"`).
+    pub const fn unit(mut self, unit: &'static str) -> NumberFormat {
+        self.unit = unit;
+        self
+    }
+
+    /// Changes how the numbers are aligned inside the region.
+    pub const fn align(mut self, align: Align) -> NumberFormat {
+        self.align = align;
+        self
+    }
+
+    /// Returns the region's width.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns given number formatted (padded to the region's width) as something displayable -
+    /// eg. for the `lcd_write!` macro.
+    pub fn display(&self, value: f64) -> impl fmt::Display + '_ {
+        Formatted {
+            format: self,
+            value,
+        }
+    }
+
+    /// Formats given number (padded to the region's width) into given writer.
+    pub fn write<W: Write>(&self, writer: &mut W, value: f64) -> fmt::Result {
+        let mut digits = Digits::default();
+
+        if write!(digits, "{:.*}", self.decimals, value).is_err() {
+            return self.write_overflow(writer);
+        }
+
+        let number = digits.as_str();
+        let (sign, number) = match number.strip_prefix('-') {
+            Some(number) => ("-", number),
+            None => ("", number),
+        };
+
+        let (integer, fraction) = match number.find('.') {
+            Some(dot) => number.split_at(dot),
+            None => (number, ""),
+        };
+
+        // non-finite numbers (`NaN`, `inf`) do not get separated
+        let separators = match self.separator {
+            Some(_) if integer.bytes().all(|ch| ch.is_ascii_digit()) => (integer.len() - 1) / 3,
+            _ => 0,
+        };
+
+        let len = sign.len() + integer.len() + separators + fraction.len() + self.unit.chars().count();
+
+        if len > self.width {
+            return self.write_overflow(writer);
+        }
+
+        let padding = self.width - len;
+
+        let left = match self.align {
+            Align::Left => 0,
+            Align::Right => padding,
+            Align::Center => padding / 2,
+        };
+
+        pad(writer, left)?;
+        writer.write_str(sign)?;
+
+        for (idx, ch) in integer.chars().enumerate() {
+            if separators > 0 && idx > 0 && (integer.len() - idx) % 3 == 0 {
+                writer.write_char(self.separator.unwrap_or(','))?;
+            }
+
+            writer.write_char(ch)?;
+        }
+
+        writer.write_str(fraction)?;
+        writer.write_str(self.unit)?;
+
+        pad(writer, padding - left)
+    }
+
+    fn write_overflow<W: Write>(&self, writer: &mut W) -> fmt::Result {
+        for _ in 0..self.width {
+            writer.write_char('#')?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Adapts a number to `fmt::Display`, so that it can be written through the LCD's writer.
+struct Formatted<'a> {
+    format: &'a NumberFormat,
+    value: f64,
+}
+
+impl fmt::Display for Formatted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.format.write(f, self.value)
+    }
+}
+
+/// A stack buffer the number is formatted into, before being split into the parts.
+struct Digits {
+    buffer: [u8; MAX_LEN],
+    len: usize,
+}
+
+impl Default for Digits {
+    fn default() -> Digits {
+        Digits {
+            buffer: [0; MAX_LEN],
+            len: 0,
+        }
+    }
+}
+
+impl Digits {
+    fn as_str(&self) -> &str {
+        // only whole strings get written in, so the buffer is always a valid UTF-8
+        core::str::from_utf8(&self.buffer[..self.len]).unwrap_or_default()
+    }
+}
+
+impl Write for Digits {
+    fn write_str(&mut self, str: &str) -> fmt::Result {
+        let end = self.len + str.len();
+
+        if end > MAX_LEN {
+            return Err(fmt::Error);
+        }
+
+        self.buffer[self.len..end].copy_from_slice(str.as_bytes());
+        self.len = end;
+
+        Ok(())
+    }
+}
+
+fn pad<W: Write>(writer: &mut W, len: usize) -> fmt::Result {
+    for _ in 0..len {
+        writer.write_char(' ')?;
+    }
+
+    Ok(())
+}
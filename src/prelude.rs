@@ -0,0 +1,14 @@
+//! Re-exports the most commonly used types and traits, so that a single import is enough:
+//!
+//! ```rust,ignore
+//! use pwr_hd44780::prelude::*;
+//! ```
+
+pub use super::{Backlight, Capabilities, CursorStyle, CustomChars, Direction, DisplayControl, Error, Fallback, Font, Geometry, Hd44780, Hd44780Ext, Point, PrintOptions, Properties, RgbBacklight, Rom, Sanitize, UnitResult};
+pub use super::{BufferedLcd, DirectLcd, DropAction, FrameCapture, InMemoryLcd, NullBus, NumberFormat, ResilientLcd, RetryPolicy, TeeLcd, TiledLcd};
+#[cfg(feature = "std")]
+pub use super::{LcdHandle, LcdWorker, LoggingBus, Pages, RenderHandle, RenderLoop, SharedLcd};
+#[cfg(feature = "i2cdev")]
+pub use super::{Backpack, I2CBus, I2C8Bus, Mcp23008Bus};
+#[cfg(feature = "raspberrypi")]
+pub use super::Gpio4Bus;
@@ -0,0 +1,247 @@
+//! Renders the screen's contents into a PNG image - for the documentation or for debugging a
+//! deployment remotely.
+//!
+//! Requires the `png` feature.
+//!
+//! The characters are drawn using an embedded 5x8 font, covering the ASCII part of the `A00` ROM
+//! (`0x20..0x7F`); the custom characters are drawn using their bitmaps and all the other
+//! characters are drawn as an empty box.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! lcd.render_png(File::create("screen.png")?)?;
+//!
+//! // or, for any other frontend
+//! PngPreview::default().render(&lines, &custom_chars, File::create("screen.png")?)?;
+//! ```
+
+use alloc::vec::Vec;
+use std::io::Write;
+use super::UnitResult;
+
+/// Colors and size of the rendered image.
+#[derive(Copy, Clone, Debug)]
+pub struct PngPreview {
+    /// size of a single LCD's pixel, in the image's pixels
+    pub scale: u32,
+
+    /// color of the background (the gaps between the characters)
+    pub background: [u8; 3],
+
+    /// color of the unlit pixels
+    pub pixel_off: [u8; 3],
+
+    /// color of the lit pixels
+    pub pixel_on: [u8; 3],
+}
+
+/// Size of a character, in pixels.
+const CHAR_WIDTH: usize = 5;
+const CHAR_HEIGHT: usize = 8;
+
+/// Gap between the characters and around the screen, in pixels.
+const GAP: usize = 1;
+const MARGIN: usize = 3;
+
+impl Default for PngPreview {
+    /// Returns the classic white-on-blue look.
+    fn default() -> PngPreview {
+        PngPreview {
+            scale: 4,
+            background: [0x10, 0x20, 0xC0],
+            pixel_off: [0x18, 0x30, 0xE0],
+            pixel_on: [0xF0, 0xF0, 0xFF],
+        }
+    }
+}
+
+impl PngPreview {
+    /// Renders given lines (as ROM codes) into a PNG image; `custom_chars` are the bitmaps of the
+    /// custom characters (codes `0..7`, repeated at `8..15`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the image could not be written.
+    pub fn render<L: AsRef<[u8]>, W: Write>(&self, lines: &[L], custom_chars: &[Option<[u8; 8]>; 8], writer: W) -> UnitResult {
+        let height = lines.len();
+        let width = lines.iter().map(|line| line.as_ref().len()).max().unwrap_or(0);
+
+        let pixels_x = 2 * MARGIN + width * (CHAR_WIDTH + GAP) - GAP.min(width);
+        let pixels_y = 2 * MARGIN + height * (CHAR_HEIGHT + GAP) - GAP.min(height);
+
+        // `None` for the background, `Some(lit)` for the characters' pixels
+        let mut screen = vec![vec![None; pixels_x]; pixels_y];
+
+        for (y, line) in lines.iter().enumerate() {
+            for (x, &code) in line.as_ref().iter().enumerate() {
+                let bitmap = match code {
+                    0..=15 => custom_chars[code as usize % 8].unwrap_or([0; 8]),
+                    code => glyph(code),
+                };
+
+                let (top, left) = (MARGIN + y * (CHAR_HEIGHT + GAP), MARGIN + x * (CHAR_WIDTH + GAP));
+
+                for (row, bits) in bitmap.iter().enumerate() {
+                    for col in 0..CHAR_WIDTH {
+                        screen[top + row][left + col] = Some(bits & (1 << (CHAR_WIDTH - 1 - col)) != 0);
+                    }
+                }
+            }
+        }
+
+        let scale = self.scale.max(1) as usize;
+        let mut data = Vec::with_capacity(pixels_x * pixels_y * scale * scale * 3);
+
+        for row in &screen {
+            let mut line = Vec::with_capacity(pixels_x * scale * 3);
+
+            for pixel in row {
+                let color = match pixel {
+                    None => self.background,
+                    Some(false) => self.pixel_off,
+                    Some(true) => self.pixel_on,
+                };
+
+                for _ in 0..scale {
+                    line.extend_from_slice(&color);
+                }
+            }
+
+            for _ in 0..scale {
+                data.extend_from_slice(&line);
+            }
+        }
+
+        let mut encoder = png::Encoder::new(writer, (pixels_x * scale) as u32, (pixels_y * scale) as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        encoder
+            .write_header()?
+            .write_image_data(&data)?;
+
+        Ok(())
+    }
+}
+
+/// Returns bitmap of given (non-custom) character.
+fn glyph(code: u8) -> [u8; 8] {
+    let columns = match code {
+        0x20..=0x7F => FONT[(code - 0x20) as usize],
+        _ => return [0b11111, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b11111, 0],
+    };
+
+    let mut bitmap = [0u8; 8];
+
+    for (col, bits) in columns.iter().enumerate() {
+        for (row, line) in bitmap.iter_mut().enumerate().take(7) {
+            if bits & (1 << row) != 0 {
+                *line |= 1 << (CHAR_WIDTH - 1 - col);
+            }
+        }
+    }
+
+    bitmap
+}
+
+/// The `A00` ROM's characters `0x20..0x7F`, column by column (the least significant bit being
+/// the top pixel).
+const FONT: [[u8; 5]; 96] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00], // ' '
+    [0x00, 0x00, 0x5F, 0x00, 0x00], // !
+    [0x00, 0x07, 0x00, 0x07, 0x00], // "
+    [0x14, 0x7F, 0x14, 0x7F, 0x14], // #
+    [0x24, 0x2A, 0x7F, 0x2A, 0x12], // $
+    [0x23, 0x13, 0x08, 0x64, 0x62], // %
+    [0x36, 0x49, 0x55, 0x22, 0x50], // &
+    [0x00, 0x05, 0x03, 0x00, 0x00], // '
+    [0x00, 0x1C, 0x22, 0x41, 0x00], // (
+    [0x00, 0x41, 0x22, 0x1C, 0x00], // )
+    [0x14, 0x08, 0x3E, 0x08, 0x14], // *
+    [0x08, 0x08, 0x3E, 0x08, 0x08], // +
+    [0x00, 0x50, 0x30, 0x00, 0x00], // ,
+    [0x08, 0x08, 0x08, 0x08, 0x08], // -
+    [0x00, 0x60, 0x60, 0x00, 0x00], // .
+    [0x20, 0x10, 0x08, 0x04, 0x02], // /
+    [0x3E, 0x51, 0x49, 0x45, 0x3E], // 0
+    [0x00, 0x42, 0x7F, 0x40, 0x00], // 1
+    [0x42, 0x61, 0x51, 0x49, 0x46], // 2
+    [0x21, 0x41, 0x45, 0x4B, 0x31], // 3
+    [0x18, 0x14, 0x12, 0x7F, 0x10], // 4
+    [0x27, 0x45, 0x45, 0x45, 0x39], // 5
+    [0x3C, 0x4A, 0x49, 0x49, 0x30], // 6
+    [0x01, 0x71, 0x09, 0x05, 0x03], // 7
+    [0x36, 0x49, 0x49, 0x49, 0x36], // 8
+    [0x06, 0x49, 0x49, 0x29, 0x1E], // 9
+    [0x00, 0x36, 0x36, 0x00, 0x00], // :
+    [0x00, 0x56, 0x36, 0x00, 0x00], // ;
+    [0x08, 0x14, 0x22, 0x41, 0x00], // <
+    [0x14, 0x14, 0x14, 0x14, 0x14], // =
+    [0x00, 0x41, 0x22, 0x14, 0x08], // >
+    [0x02, 0x01, 0x51, 0x09, 0x06], // ?
+    [0x32, 0x49, 0x79, 0x41, 0x3E], // @
+    [0x7E, 0x11, 0x11, 0x11, 0x7E], // A
+    [0x7F, 0x49, 0x49, 0x49, 0x36], // B
+    [0x3E, 0x41, 0x41, 0x41, 0x22], // C
+    [0x7F, 0x41, 0x41, 0x22, 0x1C], // D
+    [0x7F, 0x49, 0x49, 0x49, 0x41], // E
+    [0x7F, 0x09, 0x09, 0x01, 0x01], // F
+    [0x3E, 0x41, 0x41, 0x51, 0x32], // G
+    [0x7F, 0x08, 0x08, 0x08, 0x7F], // H
+    [0x00, 0x41, 0x7F, 0x41, 0x00], // I
+    [0x20, 0x40, 0x41, 0x3F, 0x01], // J
+    [0x7F, 0x08, 0x14, 0x22, 0x41], // K
+    [0x7F, 0x40, 0x40, 0x40, 0x40], // L
+    [0x7F, 0x02, 0x04, 0x02, 0x7F], // M
+    [0x7F, 0x04, 0x08, 0x10, 0x7F], // N
+    [0x3E, 0x41, 0x41, 0x41, 0x3E], // O
+    [0x7F, 0x09, 0x09, 0x09, 0x06], // P
+    [0x3E, 0x41, 0x51, 0x21, 0x5E], // Q
+    [0x7F, 0x09, 0x19, 0x29, 0x46], // R
+    [0x46, 0x49, 0x49, 0x49, 0x31], // S
+    [0x01, 0x01, 0x7F, 0x01, 0x01], // T
+    [0x3F, 0x40, 0x40, 0x40, 0x3F], // U
+    [0x1F, 0x20, 0x40, 0x20, 0x1F], // V
+    [0x7F, 0x20, 0x18, 0x20, 0x7F], // W
+    [0x63, 0x14, 0x08, 0x14, 0x63], // X
+    [0x03, 0x04, 0x78, 0x04, 0x03], // Y
+    [0x61, 0x51, 0x49, 0x45, 0x43], // Z
+    [0x00, 0x7F, 0x41, 0x41, 0x00], // [
+    [0x15, 0x16, 0x7C, 0x16, 0x15], // ¥
+    [0x00, 0x41, 0x41, 0x7F, 0x00], // ]
+    [0x04, 0x02, 0x01, 0x02, 0x04], // ^
+    [0x40, 0x40, 0x40, 0x40, 0x40], // _
+    [0x00, 0x01, 0x02, 0x04, 0x00], // `
+    [0x20, 0x54, 0x54, 0x54, 0x78], // a
+    [0x7F, 0x48, 0x44, 0x44, 0x38], // b
+    [0x38, 0x44, 0x44, 0x44, 0x20], // c
+    [0x38, 0x44, 0x44, 0x48, 0x7F], // d
+    [0x38, 0x54, 0x54, 0x54, 0x18], // e
+    [0x08, 0x7E, 0x09, 0x01, 0x02], // f
+    [0x0C, 0x52, 0x52, 0x52, 0x3E], // g
+    [0x7F, 0x08, 0x04, 0x04, 0x78], // h
+    [0x00, 0x44, 0x7D, 0x40, 0x00], // i
+    [0x20, 0x40, 0x44, 0x3D, 0x00], // j
+    [0x7F, 0x10, 0x28, 0x44, 0x00], // k
+    [0x00, 0x41, 0x7F, 0x40, 0x00], // l
+    [0x7C, 0x04, 0x18, 0x04, 0x78], // m
+    [0x7C, 0x08, 0x04, 0x04, 0x78], // n
+    [0x38, 0x44, 0x44, 0x44, 0x38], // o
+    [0x7C, 0x14, 0x14, 0x14, 0x08], // p
+    [0x08, 0x14, 0x14, 0x18, 0x7C], // q
+    [0x7C, 0x08, 0x04, 0x04, 0x08], // r
+    [0x48, 0x54, 0x54, 0x54, 0x20], // s
+    [0x04, 0x3F, 0x44, 0x40, 0x20], // t
+    [0x3C, 0x40, 0x40, 0x20, 0x7C], // u
+    [0x1C, 0x20, 0x40, 0x20, 0x1C], // v
+    [0x3C, 0x40, 0x30, 0x40, 0x3C], // w
+    [0x44, 0x28, 0x10, 0x28, 0x44], // x
+    [0x0C, 0x50, 0x50, 0x50, 0x3C], // y
+    [0x44, 0x64, 0x54, 0x4C, 0x44], // z
+    [0x00, 0x08, 0x36, 0x41, 0x00], // {
+    [0x00, 0x00, 0x7F, 0x00, 0x00], // |
+    [0x00, 0x41, 0x36, 0x08, 0x00], // }
+    [0x08, 0x08, 0x2A, 0x1C, 0x08], // →
+    [0x08, 0x1C, 0x2A, 0x08, 0x08], // ←
+];
@@ -0,0 +1,587 @@
+//! Drives an LCD attached to another machine - the `RemoteBus` sends the bus operations over TCP
+//! or a serial port to a `RemoteAgent`, which executes them on the real bus.
+//!
+//! Any frontend works on top of the `RemoteBus`, so the application does not have to know the
+//! display is not connected locally:
+//!
+//! ```rust,ignore
+//! // on the machine with the LCD (eg. a Raspberry Pi)
+//! RemoteAgent::new(I2CBus::new("/dev/i2c-1", 0x27)?).listen("0.0.0.0:4444")?;
+//!
+//! // anywhere else
+//! let bus = RemoteBus::connect("raspberrypi.local:4444")?;
+//! let mut lcd = DirectLcd::new(Box::new(bus), 20, 4)?;
+//!
+//! lcd.print("Hello World!")?;
+//! ```
+//!
+//! Serial ports are opened as regular files, so they have to be configured beforehand (eg. using
+//! `stty -F /dev/ttyUSB0 115200 raw -echo`):
+//!
+//! ```rust,ignore
+//! let port = RemoteAgent::open_serial("/dev/ttyAMA0")?;
+//! RemoteAgent::new(bus).serve(port.try_clone()?, port)?;
+//!
+//! let bus = RemoteBus::open_serial("/dev/ttyUSB0")?;
+//! ```
+//!
+//! Requires the `remote` feature.
+//!
+//! # Protocol
+//!
+//! Each request is a single byte identifying the bus' operation, followed by its arguments; each
+//! request, except for the delays, is answered with a status byte (`0` - ok,
+//! `1` - error, followed by the error's message prefixed with its length as `u16`) and, for the
+//! successful ones, the returned value.
+//!
+//! All the numbers are little-endian.
+//!
+//! # Caveats
+//!
+//! 1. Each transfer waits for the agent's response, so the network's latency adds to each byte -
+//!    use `DirectLcd::batch` (or the buffered frontend, which batches the renders) to send many
+//!    bytes in a single round trip.
+//!
+//! 2. The agent serves one client at a time - the other ones wait until the current one
+//!    disconnects.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::path::Path;
+use super::{Result, UnitResult};
+use super::buses::{Bus, Timings};
+
+/// A bus sending the operations to a `RemoteAgent`.
+pub struct RemoteBus<S: Read + Write = TcpStream> {
+    reader: BufReader<S>,
+    writer: BufWriter<S>,
+
+    /// agent's bus width (4 / 8)
+    width: usize,
+
+    can_read: bool,
+    can_dim_backlight: bool,
+
+    /// agent's bus timings
+    timings: Timings,
+}
+
+/// Executes the operations sent by a `RemoteBus` on given bus.
+pub struct RemoteAgent<B: Bus> {
+    bus: B,
+}
+
+/// Operations sent from the `RemoteBus` to the `RemoteAgent`.
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Request {
+    /// returns the bus' width (`u8`), capabilities (`u8`: `1` - reading, `2` - dimming) and
+    /// timings (`u32` each, in the order of `Timings`' fields)
+    Hello = 0x00,
+
+    Initialize = 0x01,
+    InitializeWarm = 0x02,
+    Resync = 0x03,
+
+    /// `enabled: u8`
+    SetBacklight = 0x04,
+
+    /// `brightness: u8`
+    SetBacklightBrightness = 0x05,
+
+    /// `level: u8`
+    SetContrast = 0x06,
+
+    /// `as_data: u8`; returns the byte read (`u8`)
+    ReadByte = 0x07,
+
+    /// `count: u16`, followed by `count` times `value: u8, as_data: u8`
+    WriteBytes = 0x08,
+
+    /// `us: u32`; not answered
+    Delay = 0x09,
+
+    /// returns `0` - disconnected, `1` - connected, `2` - unknown
+    Probe = 0x0A,
+}
+
+impl Request {
+    fn parse(value: u8) -> Option<Request> {
+        const REQUESTS: [Request; 11] = [
+            Request::Hello,
+            Request::Initialize,
+            Request::InitializeWarm,
+            Request::Resync,
+            Request::SetBacklight,
+            Request::SetBacklightBrightness,
+            Request::SetContrast,
+            Request::ReadByte,
+            Request::WriteBytes,
+            Request::Delay,
+            Request::Probe,
+        ];
+
+        REQUESTS
+            .iter()
+            .copied()
+            .find(|&request| request as u8 == value)
+    }
+
+    /// Reads the request's arguments (see the variants' docs).
+    fn read_args<R: Read>(self, reader: &mut R) -> io::Result<Vec<u8>> {
+        let len = match self {
+            Request::Hello
+            | Request::Initialize
+            | Request::InitializeWarm
+            | Request::Resync
+            | Request::Probe => 0,
+
+            Request::SetBacklight
+            | Request::SetBacklightBrightness
+            | Request::SetContrast
+            | Request::ReadByte => 1,
+
+            Request::WriteBytes => {
+                let mut count = [0; 2];
+                reader.read_exact(&mut count)?;
+
+                2 * u16::from_le_bytes(count) as usize
+            }
+
+            Request::Delay => 4,
+        };
+
+        let mut args = vec![0; len];
+        reader.read_exact(&mut args)?;
+
+        Ok(args)
+    }
+}
+
+const CAN_READ: u8 = 0x01;
+const CAN_DIM_BACKLIGHT: u8 = 0x02;
+
+const STATUS_OK: u8 = 0x00;
+const STATUS_ERROR: u8 = 0x01;
+
+/// Length of the encoded `Timings` (see `Request::Hello`).
+const TIMINGS_LEN: usize = 8 * 4;
+
+impl RemoteBus<TcpStream> {
+    /// Connects to the agent listening at given address (see `RemoteAgent::listen`).
+    pub fn connect<A: ToSocketAddrs>(address: A) -> Result<RemoteBus<TcpStream>> {
+        let stream = TcpStream::connect(address)?;
+
+        // each request waits for its response anyway
+        stream.set_nodelay(true)?;
+
+        RemoteBus::new(stream.try_clone()?, stream)
+    }
+}
+
+impl RemoteBus<File> {
+    /// Opens given serial port, with the agent on the other end (see `RemoteAgent::serve`).
+    pub fn open_serial<P: AsRef<Path>>(path: P) -> Result<RemoteBus<File>> {
+        let port = open_serial(path.as_ref())?;
+
+        RemoteBus::new(port.try_clone()?, port)
+    }
+}
+
+impl<S: Read + Write> RemoteBus<S> {
+    /// Creates a bus talking to the agent through given streams (the reading and the writing end
+    /// of the same connection), asking the agent about its bus.
+    pub fn new(reader: S, writer: S) -> Result<RemoteBus<S>> {
+        let mut bus = RemoteBus {
+            reader: BufReader::new(reader),
+            writer: BufWriter::new(writer),
+            width: 4,
+            can_read: false,
+            can_dim_backlight: false,
+            timings: Timings::default(),
+        };
+
+        bus.request(Request::Hello, &[])?;
+
+        let [width, capabilities] = bus.receive::<2>()?;
+        let timings = bus.receive::<TIMINGS_LEN>()?;
+
+        bus.width = width as usize;
+        bus.can_read = capabilities & CAN_READ != 0;
+        bus.can_dim_backlight = capabilities & CAN_DIM_BACKLIGHT != 0;
+        bus.timings = decode_timings(timings);
+
+        Ok(bus)
+    }
+
+    /// Sends given request and waits for its status.
+    fn request(&mut self, request: Request, args: &[u8]) -> UnitResult {
+        self.send(request, args)?;
+
+        let [status] = self.receive::<1>()?;
+
+        match status {
+            STATUS_OK => Ok(()),
+
+            STATUS_ERROR => {
+                let len = u16::from_le_bytes(self.receive::<2>()?);
+                let mut message = vec![0; len as usize];
+
+                self.reader.read_exact(&mut message)?;
+
+                Err(format!("Remote bus has failed: {}", String::from_utf8_lossy(&message)).into())
+            }
+
+            status => {
+                Err(format!("Remote agent has responded with an unknown status: {:#04x}", status).into())
+            }
+        }
+    }
+
+    fn send(&mut self, request: Request, args: &[u8]) -> UnitResult {
+        self.writer.write_all(&[request as u8])?;
+        self.writer.write_all(args)?;
+        self.writer.flush()?;
+
+        Ok(())
+    }
+
+    fn receive<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut buf = [0; N];
+
+        self.reader.read_exact(&mut buf)?;
+
+        Ok(buf)
+    }
+}
+
+impl<S: Read + Write + Send> Bus for RemoteBus<S> {
+    fn initialize(&mut self) -> UnitResult {
+        self.request(Request::Initialize, &[])
+    }
+
+    fn initialize_warm(&mut self) -> UnitResult {
+        self.request(Request::InitializeWarm, &[])
+    }
+
+    fn resync(&mut self) -> UnitResult {
+        self.request(Request::Resync, &[])
+    }
+
+    fn set_backlight(&mut self, enabled: bool) -> UnitResult {
+        self.request(Request::SetBacklight, &[enabled as u8])
+    }
+
+    fn set_backlight_brightness(&mut self, brightness: u8) -> UnitResult {
+        self.request(Request::SetBacklightBrightness, &[brightness])
+    }
+
+    fn can_dim_backlight(&self) -> bool {
+        self.can_dim_backlight
+    }
+
+    fn set_contrast(&mut self, level: u8) -> UnitResult {
+        self.request(Request::SetContrast, &[level])
+    }
+
+    fn read_byte(&mut self, as_data: bool) -> Result<u8> {
+        self.request(Request::ReadByte, &[as_data as u8])?;
+
+        let [value] = self.receive::<1>()?;
+
+        Ok(value)
+    }
+
+    fn can_read(&self) -> bool {
+        self.can_read
+    }
+
+    fn write_byte(&mut self, value: u8, as_data: bool) -> UnitResult {
+        self.write_bytes(&[(value, as_data)])
+    }
+
+    fn write_bytes(&mut self, bytes: &[(u8, bool)]) -> UnitResult {
+        for chunk in bytes.chunks(u16::MAX as usize) {
+            let mut args = Vec::with_capacity(2 + 2 * chunk.len());
+
+            args.extend_from_slice(&(chunk.len() as u16).to_le_bytes());
+
+            for &(value, as_data) in chunk {
+                args.push(value);
+                args.push(as_data as u8);
+            }
+
+            self.request(Request::WriteBytes, &args)?;
+        }
+
+        Ok(())
+    }
+
+    /// Sends the whole run in a single request.
+    fn write_data_repeated(&mut self, value: u8, count: usize) -> UnitResult {
+        self.write_bytes(&vec![(value, true); count])
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the agent's bus timings, as reported when connecting.
+    fn timings(&self) -> Timings {
+        self.timings
+    }
+
+    /// The agent waits (so that the delay happens right next to the LCD); a failure to send the
+    /// request is reported by the next one.
+    fn delay_us(&mut self, us: u32) {
+        let _ = self.send(Request::Delay, &us.to_le_bytes());
+    }
+
+    fn probe(&mut self) -> Option<bool> {
+        self.request(Request::Probe, &[]).ok()?;
+
+        match self.receive::<1>().ok()? {
+            [0] => Some(false),
+            [1] => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl<B: Bus> RemoteAgent<B> {
+    /// Creates an agent executing the operations on given bus.
+    pub fn new(bus: B) -> RemoteAgent<B> {
+        RemoteAgent { bus }
+    }
+
+    /// Opens given serial port, for passing it into `serve` (see the module's docs).
+    pub fn open_serial<P: AsRef<Path>>(path: P) -> Result<File> {
+        Ok(open_serial(path.as_ref())?)
+    }
+
+    /// Listens on given address and serves the clients, one at a time; never returns, unless
+    /// listening fails.
+    pub fn listen<A: ToSocketAddrs>(&mut self, address: A) -> UnitResult {
+        let listener = TcpListener::bind(address)?;
+
+        for stream in listener.incoming() {
+            let stream = stream?;
+
+            stream.set_nodelay(true)?;
+
+            // a client disconnecting abruptly must not bring the agent down
+            let _ = self.serve(stream.try_clone()?, stream);
+        }
+
+        Ok(())
+    }
+
+    /// Serves the client using given reading and writing end of the same connection (eg. a serial
+    /// port - see `open_serial`), until the connection gets closed.
+    pub fn serve<R: Read, W: Write>(&mut self, reader: R, writer: W) -> UnitResult {
+        let mut reader = BufReader::new(reader);
+        let mut writer = BufWriter::new(writer);
+
+        loop {
+            let mut request = [0];
+
+            match reader.read_exact(&mut request) {
+                Ok(()) => (),
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+                Err(err) => return Err(err.into()),
+            }
+
+            // there's no telling how long the unknown request's arguments are, so there's no way
+            // to carry on
+            let request = match Request::parse(request[0]) {
+                Some(request) => request,
+
+                None => {
+                    let err = format!("Unknown request: {:#04x}", request[0]);
+
+                    respond_error(&mut writer, &err)?;
+                    return Err(err.into());
+                }
+            };
+
+            // a connection failing in the middle of the arguments leaves the stream out of sync (the
+            // arguments left would be taken for the requests), so there's no way to carry on either
+            let args = request.read_args(&mut reader)?;
+
+            match self.execute(request, &args) {
+                Ok(None) => (),
+
+                Ok(Some(value)) => {
+                    writer.write_all(&[STATUS_OK])?;
+                    writer.write_all(&value)?;
+                    writer.flush()?;
+                }
+
+                Err(err) => {
+                    respond_error(&mut writer, &err.to_string())?;
+                }
+            }
+        }
+    }
+
+    /// Executes given request, returning its response's value (or `None` for the requests not
+    /// being answered).
+    fn execute(&mut self, request: Request, args: &[u8]) -> Result<Option<Vec<u8>>> {
+        let value = match request {
+            Request::Hello => {
+                let mut capabilities = 0;
+
+                if self.bus.can_read() {
+                    capabilities |= CAN_READ;
+                }
+
+                if self.bus.can_dim_backlight() {
+                    capabilities |= CAN_DIM_BACKLIGHT;
+                }
+
+                let mut value = vec![self.bus.width() as u8, capabilities];
+
+                value.extend_from_slice(&encode_timings(&self.bus.timings()));
+                value
+            }
+
+            Request::Initialize => {
+                self.bus.initialize()?;
+                vec![]
+            }
+
+            Request::InitializeWarm => {
+                self.bus.initialize_warm()?;
+                vec![]
+            }
+
+            Request::Resync => {
+                self.bus.resync()?;
+                vec![]
+            }
+
+            Request::SetBacklight => {
+                let enabled = args[0] != 0;
+
+                self.bus.set_backlight(enabled)?;
+                vec![]
+            }
+
+            Request::SetBacklightBrightness => {
+                let brightness = args[0];
+
+                self.bus.set_backlight_brightness(brightness)?;
+                vec![]
+            }
+
+            Request::SetContrast => {
+                let level = args[0];
+
+                self.bus.set_contrast(level)?;
+                vec![]
+            }
+
+            Request::ReadByte => {
+                let as_data = args[0] != 0;
+
+                vec![self.bus.read_byte(as_data)?]
+            }
+
+            Request::WriteBytes => {
+                let bytes: Vec<(u8, bool)> = args
+                    .chunks(2)
+                    .map(|pair| (pair[0], pair[1] != 0))
+                    .collect();
+
+                self.bus.write_bytes(&bytes)?;
+                vec![]
+            }
+
+            Request::Delay => {
+                self.bus.delay_us(u32::from_le_bytes([args[0], args[1], args[2], args[3]]));
+
+                return Ok(None);
+            }
+
+            Request::Probe => {
+                vec![match self.bus.probe() {
+                    Some(false) => 0,
+                    Some(true) => 1,
+                    None => 2,
+                }]
+            }
+        };
+
+        Ok(Some(value))
+    }
+
+    /// Returns the bus.
+    pub fn into_inner(self) -> B {
+        self.bus
+    }
+}
+
+fn respond_error<W: Write>(writer: &mut W, message: &str) -> UnitResult {
+    let message = &message.as_bytes()[..message.len().min(u16::MAX as usize)];
+
+    writer.write_all(&[STATUS_ERROR])?;
+    writer.write_all(&(message.len() as u16).to_le_bytes())?;
+    writer.write_all(message)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+fn open_serial(path: &Path) -> io::Result<File> {
+    OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)
+}
+
+/// Encodes given timings for the `Hello` response.
+fn encode_timings(timings: &Timings) -> [u8; TIMINGS_LEN] {
+    let fields = [
+        timings.setup_ns,
+        timings.enable_pulse_ns,
+        timings.settle_us,
+        timings.clear_home_us,
+        timings.power_on_us,
+        timings.init_first_us,
+        timings.init_us,
+        timings.lock_wait_ms,
+    ];
+
+    let mut bytes = [0; TIMINGS_LEN];
+
+    for (chunk, field) in bytes.chunks_exact_mut(4).zip(fields.iter()) {
+        chunk.copy_from_slice(&field.to_le_bytes());
+    }
+
+    bytes
+}
+
+/// Decodes the timings sent by `encode_timings`.
+fn decode_timings(bytes: [u8; TIMINGS_LEN]) -> Timings {
+    let mut fields = [0; 8];
+
+    for (field, chunk) in fields.iter_mut().zip(bytes.chunks_exact(4)) {
+        *field = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+
+    let [setup_ns, enable_pulse_ns, settle_us, clear_home_us, power_on_us, init_first_us, init_us, lock_wait_ms] = fields;
+
+    Timings {
+        setup_ns,
+        enable_pulse_ns,
+        settle_us,
+        clear_home_us,
+        power_on_us,
+        init_first_us,
+        init_us,
+        lock_wait_ms,
+    }
+}
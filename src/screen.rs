@@ -0,0 +1,272 @@
+//! Provides the screen's contents (the characters & the custom characters) detached from any LCD -
+//! so that a whole screen can be built up front (eg. from the sensors' readings) and swapped into
+//! the buffered frontend in one go (see `Buffered::replace_screen`).
+//!
+//! Screens are plain values: they can be composed (see `Screen::blit`), compared (see
+//! `Screen::diff`), (de)serialized (with the `serde` feature - eg. to send them over the network)
+//! and rendered onto any LCD (see `Screen::render`). The pages (see `Pages`) are screens, too.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut screen = Screen::new(20, 4);
+//!
+//! screen.print_line(0, format!("Temperature: {:>5.1}C", temperature))?;
+//! screen.print_line(1, format!("Humidity:    {:>5.1}%", humidity))?;
+//!
+//! // only the characters that differ from what's on the LCD get sent
+//! lcd.replace_screen(&screen)?;
+//! lcd.render()?;
+//! ```
+//!
+//! ```rust,ignore
+//! // a status bar shared by all the screens
+//! let mut status = Screen::new(20, 1);
+//! status.print_at(0, 0, "12:45       WiFi OK")?;
+//!
+//! let mut screen = Screen::new(20, 4);
+//! screen.blit(Point::new(3, 0), &status);
+//! screen.render(&mut lcd)?;
+//! ```
+
+use alloc::vec::Vec;
+use super::{Capabilities, CustomChars, Error, Hd44780, Point, PrintOptions, Result, Rom, UnitResult};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Screen {
+    /// characters, as the ROM codes
+    lines: Vec<Vec<u8>>,
+
+    /// custom characters created on the screen (see `CustomChars::create_char`)
+    chars: [Option<[u8; 8]>; 8],
+
+    #[cfg_attr(feature = "serde", serde(skip))]
+    cursor: Point,
+
+    rom: Rom,
+
+    #[cfg_attr(feature = "serde", serde(skip))]
+    print_options: PrintOptions,
+}
+
+impl Screen {
+    /// Creates a blank screen of given size, using the `A00` ROM.
+    pub fn new(width: usize, height: usize) -> Screen {
+        Screen::with_rom(width, height, Rom::A00)
+    }
+
+    /// Creates a blank screen of given size, using given ROM.
+    pub fn with_rom(width: usize, height: usize, rom: Rom) -> Screen {
+        Screen {
+            lines: vec![vec![b' '; width]; height],
+            chars: [None; 8],
+            cursor: Point::new(0, 0),
+            rom,
+            print_options: PrintOptions::default(),
+        }
+    }
+
+    /// Creates a screen with given contents (each line has to be of the same length).
+    pub(crate) fn from_parts(lines: Vec<Vec<u8>>, chars: [Option<[u8; 8]>; 8], rom: Rom) -> Screen {
+        Screen {
+            lines,
+            chars,
+            cursor: Point::new(0, 0),
+            rom,
+            print_options: PrintOptions::default(),
+        }
+    }
+
+    /// Changes the options used when printing strings (see `PrintOptions`).
+    pub fn set_print_options(&mut self, options: PrintOptions) {
+        self.print_options = options;
+    }
+
+    /// Returns character at given position (as a ROM code), or `None` if the position lies
+    /// outside the screen.
+    pub fn char_at(&self, point: Point) -> Option<u8> {
+        self.lines
+            .get(point.y)
+            .and_then(|line| line.get(point.x))
+            .cloned()
+    }
+
+    /// Returns given line (as ROM codes), or `None` if the line lies outside the screen.
+    pub fn line(&self, y: usize) -> Option<&[u8]> {
+        self.lines
+            .get(y)
+            .map(|line| line.as_slice())
+    }
+
+    /// Returns all the lines (as ROM codes).
+    pub fn lines(&self) -> &[Vec<u8>] {
+        &self.lines
+    }
+
+    /// Returns given custom character, or `None` if it has not been created on this screen.
+    pub fn custom_char(&self, idx: u8) -> Option<[u8; 8]> {
+        self.chars
+            .get(idx as usize)
+            .cloned()
+            .flatten()
+    }
+
+    /// Returns all the custom characters (`None` for the ones not created on this screen).
+    pub fn custom_chars(&self) -> &[Option<[u8; 8]>; 8] {
+        &self.chars
+    }
+
+    /// Returns the characters of `other` which differ from this screen's ones (eg. what has to be
+    /// sent to turn this screen into the other one), along with their positions.
+    ///
+    /// Characters lying outside this screen are always considered different.
+    pub fn diff(&self, other: &Screen) -> Vec<(Point, u8)> {
+        let mut changes = Vec::new();
+
+        for (y, line) in other.lines.iter().enumerate() {
+            for (x, &ch) in line.iter().enumerate() {
+                let point = Point::new(y, x);
+
+                if self.char_at(point) != Some(ch) {
+                    changes.push((point, ch));
+                }
+            }
+        }
+
+        changes
+    }
+
+    /// Copies the characters of `other` onto this screen, with the other screen's top-left corner
+    /// at given position; characters falling outside this screen are skipped.
+    ///
+    /// The custom characters are not copied - the screens being composed should agree on them.
+    pub fn blit(&mut self, at: Point, other: &Screen) {
+        for (line, other) in self.lines.iter_mut().skip(at.y).zip(&other.lines) {
+            for (ch, &other) in line.iter_mut().skip(at.x).zip(other) {
+                *ch = other;
+            }
+        }
+    }
+
+    /// Renders the screen onto given LCD: uploads the custom characters created on the screen
+    /// and prints all the characters (the buffered frontends have to be rendered afterwards).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the screens' sizes differ.
+    pub fn render<L: CustomChars>(&self, lcd: &mut L) -> UnitResult {
+        if lcd.width() != self.width() || lcd.height() != self.height() {
+            return Err(format!(
+                "Expected a {}x{} screen, got {}x{}.", lcd.width(), lcd.height(), self.width(), self.height(),
+            ).into());
+        }
+
+        for (idx, lines) in self.chars.iter().enumerate() {
+            if let Some(lines) = lines {
+                lcd.create_char(idx as u8, *lines)?;
+            }
+        }
+
+        for (y, line) in self.lines.iter().enumerate() {
+            lcd.move_at(y, 0)?;
+
+            for &ch in line {
+                lcd.print_char(ch)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Hd44780 for Screen {
+    fn clear(&mut self) -> UnitResult {
+        for line in &mut self.lines {
+            for ch in line {
+                *ch = b' ';
+            }
+        }
+
+        self.cursor = Point::new(0, 0);
+
+        Ok(())
+    }
+
+    fn home(&mut self) -> UnitResult {
+        self.cursor = Point::new(0, 0);
+
+        Ok(())
+    }
+
+    fn move_at(&mut self, y: usize, x: usize) -> UnitResult {
+        if y >= self.height() || x >= self.width() {
+            return Err(
+                format!("Tried to move the cursor outside the screen (at y={}, x={}).", y, x).into()
+            );
+        }
+
+        self.cursor = Point::new(y, x);
+
+        Ok(())
+    }
+
+    fn cursor(&self) -> Point {
+        self.cursor
+    }
+
+    fn print_char(&mut self, ch: u8) -> UnitResult {
+        let Point { y, x } = self.cursor;
+
+        self.lines[y][x] = ch;
+
+        self.cursor.x += 1;
+
+        if self.cursor.x >= self.width() {
+            self.cursor = Point::new((y + 1) % self.height(), 0);
+        }
+
+        Ok(())
+    }
+
+    fn print_options(&self) -> PrintOptions {
+        self.print_options
+    }
+
+    fn read_char_at(&mut self, point: Point) -> Result<u8> {
+        self.char_at(point)
+            .ok_or_else(|| format!("Tried to read a character outside the screen (at y={}, x={}).", point.y, point.x).into())
+    }
+
+    fn rom(&self) -> Rom {
+        self.rom
+    }
+
+    fn height(&self) -> usize {
+        self.lines.len()
+    }
+
+    fn width(&self) -> usize {
+        self.lines
+            .first()
+            .map_or(0, |line| line.len())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            readable: true,
+            custom_chars: 8,
+            ..Capabilities::default()
+        }
+    }
+}
+
+impl CustomChars for Screen {
+    fn create_char(&mut self, idx: u8, lines: [u8; 8]) -> UnitResult {
+        Error::check_char_idx(idx, 7)?;
+
+        self.chars[idx as usize] = Some(lines);
+
+        Ok(())
+    }
+}
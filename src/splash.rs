@@ -0,0 +1,151 @@
+//! Shows a boot message (optionally with a logo made of custom characters) while the application
+//! initializes, keeping it on the screen for at least given time - so that it does not just flash
+//! by on a fast boot.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let splash = Splash::new("ACME Thermostat\nv1.2.0", Duration::from_secs(2))
+//!     .with_logo(&[FLAME_TOP, FLAME_BOTTOM], 0)?
+//!     .show(&mut lcd)?;
+//!
+//! let sensors = Sensors::connect()?; // ... whatever takes a while
+//!
+//! // waits for what's left of the two seconds and clears the screen
+//! splash.finish(&mut lcd)?;
+//! ```
+//!
+//! The splash can be also shown straight from the config (see `Config::splash`).
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::{thread, time::Instant};
+use super::{CustomChars, Result, UnitResult};
+#[cfg(feature = "std")]
+use super::Hd44780;
+use super::char_bank::SLOTS;
+use super::layout::{pad, Align};
+
+pub struct Splash {
+    /// lines of the message, centered on the screen
+    text: String,
+
+    /// how long the message stays on the screen at least
+    duration: Duration,
+
+    /// glyphs of the logo, shown in front of the message's first line
+    logo: Vec<[u8; 8]>,
+
+    /// custom character the logo's first glyph gets uploaded into
+    first_slot: u8,
+}
+
+/// A splash being shown (see `Splash::show`).
+#[cfg(feature = "std")]
+pub struct ShownSplash {
+    until: Instant,
+}
+
+impl Splash {
+    /// Creates a splash showing given message (lines are separated with `\n`) for at least
+    /// `duration`.
+    pub fn new<T: Into<String>>(text: T, duration: Duration) -> Splash {
+        Splash {
+            text: text.into(),
+            duration,
+            logo: Vec::new(),
+            first_slot: 0,
+        }
+    }
+
+    /// Shows given glyphs (uploaded into the custom characters, starting from `first_slot`) in
+    /// front of the message's first line.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the glyphs do not fit in the CGRAM.
+    pub fn with_logo(mut self, glyphs: &[[u8; 8]], first_slot: u8) -> Result<Splash> {
+        if first_slot as usize + glyphs.len() > SLOTS {
+            return Err(format!(
+                "Logo of {} characters does not fit in the CGRAM when starting at slot {}.", glyphs.len(), first_slot,
+            ).into());
+        }
+
+        self.logo = glyphs.to_vec();
+        self.first_slot = first_slot;
+
+        Ok(self)
+    }
+
+    /// Returns how long the splash stays on the screen at least.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Draws the splash, replacing the whole screen (the buffered frontends have to be rendered
+    /// afterwards).
+    pub fn draw<L: CustomChars>(&self, lcd: &mut L) -> UnitResult {
+        lcd.clear()?;
+
+        for (idx, glyph) in self.logo.iter().enumerate() {
+            lcd.create_char(self.first_slot + idx as u8, *glyph)?;
+        }
+
+        let width = lcd.width();
+
+        for (y, line) in self.text.lines().take(lcd.height()).enumerate() {
+            if y > 0 || self.logo.is_empty() {
+                lcd.print_str_at(y, 0, &pad(line, width, Align::Center))?;
+                continue;
+            }
+
+            let logo = self.logo.len().min(width);
+            let text: String = line.chars().take(width.saturating_sub(logo + 1)).collect();
+
+            let used = if text.is_empty() { logo } else { logo + 1 + text.chars().count() };
+
+            lcd.move_at(0, (width - used) / 2)?;
+
+            for idx in 0..logo {
+                lcd.print_char(self.first_slot + idx as u8)?;
+            }
+
+            if !text.is_empty() {
+                lcd.print_str(" ")?;
+                lcd.print_str(&text)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Draws the splash and starts counting its duration down (see `ShownSplash::finish`).
+    #[cfg(feature = "std")]
+    pub fn show<L: CustomChars>(&self, lcd: &mut L) -> Result<ShownSplash> {
+        self.draw(lcd)?;
+
+        Ok(ShownSplash {
+            until: Instant::now() + self.duration,
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl ShownSplash {
+    /// Returns whether the splash has been shown for long enough.
+    pub fn is_over(&self) -> bool {
+        Instant::now() >= self.until
+    }
+
+    /// Waits until the splash has been shown for long enough and clears the screen, handing it
+    /// over to the application.
+    pub fn finish<L: Hd44780>(self, lcd: &mut L) -> UnitResult {
+        if let Some(remaining) = self.until.checked_duration_since(Instant::now()) {
+            thread::sleep(remaining);
+        }
+
+        lcd.clear()
+    }
+}
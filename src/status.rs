@@ -0,0 +1,256 @@
+//! Shows the machine's status - the hostname, the IP address, the load, the memory & disk usage
+//! and the uptime - refreshed on a schedule; the "Pi status display" everyone ends up writing.
+//!
+//! The statistics are read from `/proc` (and `statvfs`), so only Linux is supported.
+//!
+//! Requires the `sysinfo` feature.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! // raspberrypi
+//! // 192.168.1.23
+//! // Load 0.42  Mem  37%
+//! // Disk  61%  Up 3d 4h
+//! let mut status = StatusScreen::new(Duration::from_secs(5));
+//! let started = Instant::now();
+//!
+//! loop {
+//!     status.tick(&mut lcd, started.elapsed())?;
+//!     lcd.render()?;
+//!
+//!     thread::sleep(Duration::from_millis(100));
+//! }
+//! ```
+//!
+//! On the screens having less than four lines, the lines are shown page by page - each refresh
+//! shows the next page.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::time::Duration;
+use std::ffi::CString;
+use std::fs;
+use std::net::{IpAddr, UdpSocket};
+use super::{Hd44780, Hd44780Ext, Result, UnitResult};
+
+pub struct StatusScreen {
+    /// how often the statistics get refreshed
+    interval: Duration,
+
+    /// filesystem the disk usage is shown for
+    mount_point: String,
+
+    /// when the screen has been refreshed last (`None` before the first refresh)
+    refreshed_at: Option<Duration>,
+
+    /// page shown on the screens having less than four lines
+    page: usize,
+}
+
+/// A snapshot of the machine's statistics (see `SystemStatus::read`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct SystemStatus {
+    pub hostname: String,
+
+    /// address of the interface the default route goes through (`None` when offline)
+    pub ip: Option<IpAddr>,
+
+    /// 1-minute load average
+    pub load: f32,
+
+    /// percentage of the memory in use (excluding the caches)
+    pub memory_used: u8,
+
+    /// percentage of the disk space in use
+    pub disk_used: u8,
+
+    pub uptime: Duration,
+}
+
+impl StatusScreen {
+    /// Creates a status screen refreshed every `interval`, showing the disk usage of the root
+    /// filesystem.
+    pub fn new(interval: Duration) -> StatusScreen {
+        StatusScreen {
+            interval,
+            mount_point: "/".to_string(),
+            refreshed_at: None,
+            page: 0,
+        }
+    }
+
+    /// Changes the filesystem the disk usage is shown for (`/` by default).
+    pub fn set_mount_point<T: Into<String>>(&mut self, mount_point: T) {
+        self.mount_point = mount_point.into();
+    }
+
+    /// Refreshes the screen if `interval` has elapsed since the last refresh; `now` is the time
+    /// elapsed since any fixed point (eg. `Instant::now() - started`).
+    ///
+    /// Returns whether the screen has been refreshed.
+    pub fn tick<L: Hd44780>(&mut self, lcd: &mut L, now: Duration) -> Result<bool> {
+        if let Some(refreshed_at) = self.refreshed_at {
+            if now.saturating_sub(refreshed_at) < self.interval {
+                return Ok(false);
+            }
+        }
+
+        self.refreshed_at = Some(now);
+        self.draw(lcd)?;
+
+        Ok(true)
+    }
+
+    /// Reads the statistics and draws them, replacing the whole screen.
+    pub fn draw<L: Hd44780>(&mut self, lcd: &mut L) -> UnitResult {
+        let status = SystemStatus::read(&self.mount_point)?;
+        let lines = status.lines(lcd.width());
+        let height = lcd.height();
+
+        let pages = lines.len().div_ceil(height);
+        let first = (self.page % pages) * height;
+
+        for y in 0..height {
+            lcd.print_line(y, lines.get(first + y).map_or("", |line| line.as_str()))?;
+        }
+
+        self.page = (self.page + 1) % pages;
+
+        Ok(())
+    }
+}
+
+impl SystemStatus {
+    /// Reads the statistics, with the disk usage of given filesystem.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when `/proc` is not available (eg. on a non-Linux system) or when given
+    /// filesystem does not exist.
+    pub fn read(mount_point: &str) -> Result<SystemStatus> {
+        Ok(SystemStatus {
+            hostname: fs::read_to_string("/proc/sys/kernel/hostname")?.trim().to_string(),
+            ip: local_ip(),
+            load: read_load()?,
+            memory_used: read_memory_used()?,
+            disk_used: read_disk_used(mount_point)?,
+            uptime: read_uptime()?,
+        })
+    }
+
+    /// Returns the lines shown by the `StatusScreen` on a screen of given width (the screens
+    /// narrower than 20 characters get the abbreviated labels).
+    pub fn lines(&self, width: usize) -> Vec<String> {
+        let ip = match self.ip {
+            Some(ip) => ip.to_string(),
+            None => "No network".to_string(),
+        };
+
+        let uptime = format_uptime(self.uptime);
+
+        let (usage, disk) = if width >= 20 {
+            (
+                format!("Load {:.2}  Mem {:>3}%", self.load, self.memory_used),
+                format!("Disk {:>3}%  Up {}", self.disk_used, uptime),
+            )
+        } else {
+            (
+                format!("Ld {:.2} Mem{:>3}%", self.load, self.memory_used),
+                format!("Dsk{:>3}% Up {}", self.disk_used, uptime),
+            )
+        };
+
+        vec![self.hostname.clone(), ip, usage, disk]
+    }
+}
+
+/// Returns the address of the interface the default route goes through - connecting a UDP socket
+/// does not send anything, it only picks the interface.
+fn local_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+
+    socket.connect("192.0.2.1:9").ok()?;
+
+    Some(socket.local_addr().ok()?.ip())
+}
+
+fn read_load() -> Result<f32> {
+    let loadavg = fs::read_to_string("/proc/loadavg")?;
+
+    let load = loadavg
+        .split_whitespace()
+        .next()
+        .ok_or("Unexpected format of /proc/loadavg")?;
+
+    Ok(load.parse()?)
+}
+
+fn read_memory_used() -> Result<u8> {
+    let meminfo = fs::read_to_string("/proc/meminfo")?;
+
+    let field = |name: &str| -> Result<u64> {
+        let value = meminfo
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+            .and_then(|value| value.split_whitespace().next())
+            .ok_or_else(|| format!("Missing {} in /proc/meminfo", name))?;
+
+        Ok(value.parse()?)
+    };
+
+    let total = field("MemTotal")?;
+    let available = field("MemAvailable")?;
+
+    Ok(percentage(total.saturating_sub(available), total))
+}
+
+fn read_disk_used(mount_point: &str) -> Result<u8> {
+    let path = CString::new(mount_point)?;
+
+    // SAFETY: `path` is a valid C string and `stat` is a properly sized buffer
+    let stat = unsafe {
+        let mut stat: libc::statvfs = core::mem::zeroed();
+
+        if libc::statvfs(path.as_ptr(), &mut stat) != 0 {
+            return Err(format!("Cannot stat {}: {}", mount_point, std::io::Error::last_os_error()).into());
+        }
+
+        stat
+    };
+
+    let total = stat.f_blocks as u64;
+    let available = stat.f_bavail as u64;
+
+    Ok(percentage(total.saturating_sub(available), total))
+}
+
+fn read_uptime() -> Result<Duration> {
+    let uptime = fs::read_to_string("/proc/uptime")?;
+
+    let secs: f64 = uptime
+        .split_whitespace()
+        .next()
+        .ok_or("Unexpected format of /proc/uptime")?
+        .parse()?;
+
+    Ok(Duration::from_secs(secs as u64))
+}
+
+fn percentage(used: u64, total: u64) -> u8 {
+    (used * 100).checked_div(total).unwrap_or(0) as u8
+}
+
+/// Formats uptime as eg. `3d 4h`, `4h 12m` or `12m`.
+fn format_uptime(uptime: Duration) -> String {
+    let minutes = uptime.as_secs() / 60;
+    let (days, hours, minutes) = (minutes / (24 * 60), minutes / 60 % 24, minutes % 60);
+
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
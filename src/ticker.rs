@@ -0,0 +1,102 @@
+//! Paces the rendering & the animations, so that they advance on a fixed cadence (eg. 10 times per
+//! second) - no matter how often the application's loop spins or modifies the screen.
+//!
+//! Rendering on each change can saturate the bus (a slow I2C one especially) when the changes come
+//! faster than they can be sent; ticking the widgets on each iteration of a loop makes them go as
+//! fast as the loop does. The ticker tells when the next frame is due instead:
+//!
+//! ```rust,ignore
+//! let mut ticker = Ticker::from_hz(10)?;
+//! let started = Instant::now();
+//!
+//! loop {
+//!     // ... react to the sensors, buttons etc., modifying the buffer as often as needed
+//!
+//!     if ticker.tick(started.elapsed()) {
+//!         marquee.tick(&mut lcd)?;
+//!         spinner.tick(&mut lcd)?;
+//!         lcd.render()?;
+//!     }
+//!
+//!     thread::sleep(ticker.until_next(started.elapsed()).min(Duration::from_millis(10)));
+//! }
+//! ```
+//!
+//! The ticks keep their phase - a late tick does not delay the following ones, and the ticks
+//! missed altogether (eg. while the application was busy) are skipped instead of being fired in a
+//! burst.
+
+use core::time::Duration;
+use super::{Error, Result};
+
+#[derive(Clone, Debug)]
+pub struct Ticker {
+    period: Duration,
+
+    /// when the next tick is due (`None` before the first one)
+    next: Option<Duration>,
+}
+
+impl Ticker {
+    /// Creates a ticker firing every `period`; the first tick is due straight away.
+    pub fn new(period: Duration) -> Ticker {
+        Ticker {
+            period,
+            next: None,
+        }
+    }
+
+    /// Creates a ticker firing `hz` times per second.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when `hz` is zero.
+    pub fn from_hz(hz: u32) -> Result<Ticker> {
+        if hz == 0 {
+            return Err(Error::invalid_config("hz", "rate must be greater than zero").into());
+        }
+
+        Ok(Ticker::new(Duration::from_secs(1) / hz))
+    }
+
+    /// Returns the interval between the ticks.
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Returns whether a tick is due at given moment (the time elapsed since any fixed point, eg.
+    /// `Instant::now() - started`) and, if so, schedules the next one.
+    pub fn tick(&mut self, now: Duration) -> bool {
+        match self.next {
+            Some(next) if now < next => false,
+
+            Some(next) => {
+                let mut next = next + self.period;
+
+                // skip the missed ticks, keeping the phase
+                if next <= now && !self.period.is_zero() {
+                    let behind = (now - next).as_nanos() % self.period.as_nanos();
+                    next = now + self.period - Duration::from_nanos(behind as u64);
+                }
+
+                self.next = Some(next);
+                true
+            }
+
+            None => {
+                self.next = Some(now + self.period);
+                true
+            }
+        }
+    }
+
+    /// Returns how long it is until the next tick (zero when it's already due).
+    pub fn until_next(&self, now: Duration) -> Duration {
+        self.next.map_or(Duration::from_secs(0), |next| next.saturating_sub(now))
+    }
+
+    /// Makes the next tick due straight away, starting the cadence anew.
+    pub fn reset(&mut self) {
+        self.next = None;
+    }
+}
@@ -0,0 +1,181 @@
+//! Provides a tiny HTTP server showing what's on the screen and allowing to change it - so that a
+//! display deployed in the field can be debugged without walking up to it.
+//!
+//! Requires the `web` feature (and the `png` one for the rendered preview).
+//!
+//! # Endpoints
+//!
+//! - `GET /` (or `GET /text`) - the screen's contents, as text (lines are separated with `\n`),
+//! - `GET /screen.png` - the screen's contents, rendered into an image (see `PngPreview`),
+//! - `POST /text` - replaces the whole screen with the request's body (lines separated with `\n`),
+//! - `POST /line/<y>` - replaces given line with the request's body,
+//! - `POST /clear` - clears the screen.
+//!
+//! The changes are rendered straight away; the invalid requests are answered with `400 Bad
+//! Request` and the error's message.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let lcd = SharedLcd::new(lcd);
+//! let preview = WebPreview::bind(lcd.clone(), "0.0.0.0:8080")?;
+//!
+//! thread::spawn(move || preview.run().unwrap());
+//!
+//! // ... keep on drawing through `lcd`
+//! ```
+//!
+//! ```text
+//! $ curl http://raspberrypi.local:8080/
+//! $ curl -d 'Hello from afar!' http://raspberrypi.local:8080/line/1
+//! ```
+//!
+//! There's no authentication whatsoever - bind the server to a trusted interface only.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use std::io::{Cursor, Read};
+use std::net::{SocketAddr, ToSocketAddrs};
+use tiny_http::{Header, Method, Request, Response, Server};
+use super::{Hd44780, Hd44780Ext, Result, UnitResult};
+use super::frontends::{decode_char, Buffered, Shared};
+
+pub struct WebPreview {
+    lcd: Shared<Buffered>,
+    server: Server,
+}
+
+/// Largest accepted request's body, in bytes.
+const MAX_BODY: u64 = 4096;
+
+impl WebPreview {
+    /// Starts listening at given address; the requests are served once `run` gets called.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the address cannot be bound to.
+    pub fn bind<A: ToSocketAddrs>(lcd: Shared<Buffered>, address: A) -> Result<WebPreview> {
+        Ok(WebPreview {
+            lcd,
+            server: Server::http(address).map_err(|err| format!("Cannot start the server: {}", err))?,
+        })
+    }
+
+    /// Returns the address the server listens at (eg. to find out the port chosen by the system,
+    /// after binding to port `0`).
+    pub fn address(&self) -> Option<SocketAddr> {
+        self.server
+            .server_addr()
+            .to_ip()
+    }
+
+    /// Serves the requests; never returns, unless the server fails.
+    pub fn run(&self) -> UnitResult {
+        loop {
+            let request = self.server.recv()?;
+
+            // a client going away is not the server's problem
+            let _ = self.handle(request);
+        }
+    }
+
+    fn handle(&self, mut request: Request) -> UnitResult {
+        let url = request.url().to_string();
+
+        let response = match (request.method(), url.as_str()) {
+            (Method::Get, "/") | (Method::Get, "/text") => {
+                text(self.snapshot(), 200)
+            }
+
+            #[cfg(feature = "png")]
+            (Method::Get, "/screen.png") => {
+                let mut image = Vec::new();
+
+                match self.lcd.transaction(|lcd| lcd.render_png(&mut image)) {
+                    Ok(()) => with_content_type(Response::from_data(image), "image/png"),
+                    Err(err) => text(err.to_string(), 500),
+                }
+            }
+
+            (Method::Post, url) if url == "/text" || url == "/clear" || url.starts_with("/line/") => {
+                let mut body = String::new();
+
+                request
+                    .as_reader()
+                    .take(MAX_BODY)
+                    .read_to_string(&mut body)?;
+
+                match self.update(url, &body) {
+                    Ok(()) => text(self.snapshot(), 200),
+                    Err(err) => text(err.to_string(), 400),
+                }
+            }
+
+            _ => text("Not found".to_string(), 404),
+        };
+
+        request.respond(response)?;
+
+        Ok(())
+    }
+
+    /// Applies given `POST` request and renders the screen.
+    fn update(&self, url: &str, body: &str) -> UnitResult {
+        self.lcd.transaction(|lcd| {
+            match url {
+                "/text" => {
+                    let lines: Vec<&str> = body.lines().collect();
+
+                    for y in 0..lcd.height() {
+                        lcd.print_line(y, lines.get(y).copied().unwrap_or(""))?;
+                    }
+                }
+
+                "/clear" => {
+                    lcd.clear()?;
+                }
+
+                url => {
+                    let y = url
+                        .trim_start_matches("/line/")
+                        .parse()
+                        .map_err(|_| format!("Invalid line: {}", url))?;
+
+                    lcd.print_line(y, body.trim_end_matches(&['\r', '\n'][..]))?;
+                }
+            }
+
+            lcd.render()?;
+
+            Ok(())
+        })
+    }
+
+    /// Returns the screen's contents, as text (see `FrameCapture::snapshot`).
+    fn snapshot(&self) -> String {
+        self.lcd.transaction(|lcd| {
+            let rom = lcd.rom();
+
+            let lines: Vec<String> = (0..lcd.height())
+                .filter_map(|y| lcd.line(y))
+                .map(|line| line.iter().map(|&code| decode_char(rom, code)).collect())
+                .collect();
+
+            lines.join("\n")
+        })
+    }
+}
+
+fn text(body: String, status: u16) -> Response<Cursor<Vec<u8>>> {
+    with_content_type(
+        Response::from_string(body).with_status_code(status),
+        "text/plain; charset=utf-8",
+    )
+}
+
+fn with_content_type<R: Read>(response: Response<R>, value: &'static str) -> Response<R> {
+    match Header::from_bytes("Content-Type", value) {
+        Ok(header) => response.with_header(header),
+        Err(()) => response,
+    }
+}
@@ -0,0 +1,248 @@
+//! Plays many simple animations (blinking icons, waves, walking characters...) at once, driven by a
+//! single `tick` call.
+//!
+//! Each animation is a sequence of frames - either texts drawn at given position, or custom
+//! characters (glyphs) uploaded into the animation's slot - each one shown for given duration.
+//! The animator draws only the animations whose frame has changed since the previous tick, so it's
+//! cheap to call it often.
+//!
+//! Since there's no clock in `no_std`, the current time has to be provided by the caller - as a
+//! duration since any fixed point (eg. `Instant::now() - started`).
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut animator = Animator::new();
+//!
+//! // a blinking "!" in the top-right corner
+//! animator.add(
+//!     Animation::new(0, 15)
+//!         .frame(AnimationFrame::Text("!".into()), Duration::from_millis(500))
+//!         .frame(AnimationFrame::Text(" ".into()), Duration::from_millis(500))
+//! )?;
+//!
+//! // a heart beating in custom character #3
+//! animator.add(
+//!     Animation::new(1, 15)
+//!         .slot(3)
+//!         .frame(AnimationFrame::Glyph(SMALL_HEART), Duration::from_millis(600))
+//!         .frame(AnimationFrame::Glyph(BIG_HEART), Duration::from_millis(200))
+//! )?;
+//!
+//! let started = Instant::now();
+//!
+//! loop {
+//!     animator.tick(&mut lcd, started.elapsed())?;
+//!     lcd.render()?;
+//!
+//!     thread::sleep(Duration::from_millis(20));
+//! }
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::time::Duration;
+use super::super::{CustomChars, Error, Result, UnitResult};
+
+#[derive(Default)]
+pub struct Animator {
+    animations: Vec<(usize, Animation)>,
+    next_id: usize,
+}
+
+pub struct Animation {
+    y: usize,
+    x: usize,
+    frames: Vec<(AnimationFrame, Duration)>,
+    slot: Option<u8>,
+    looped: bool,
+
+    /// index of the frame being shown and the moment it's been shown at (`None` before the first
+    /// tick)
+    current: Option<(usize, Duration)>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnimationFrame {
+    /// text drawn at the animation's position
+    Text(String),
+
+    /// custom character uploaded into the animation's slot (and drawn at its position)
+    Glyph([u8; 8]),
+}
+
+impl Animator {
+    pub fn new() -> Animator {
+        Animator::default()
+    }
+
+    /// Adds given animation (which starts playing during the next tick) and returns its id.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` when the animation has no frames, has a frame lasting zero
+    /// time, contains glyphs but no slot, or its slot is already used by another animation.
+    pub fn add(&mut self, animation: Animation) -> Result<usize> {
+        if animation.frames.is_empty() {
+            return Err(Error::invalid_config("frames", "animation must have at least one frame").into());
+        }
+
+        if animation.frames.iter().any(|(_, duration)| *duration == Duration::from_secs(0)) {
+            return Err(Error::invalid_config("frames", "frames must last longer than zero").into());
+        }
+
+        let has_glyphs = animation.frames
+            .iter()
+            .any(|(frame, _)| matches!(frame, AnimationFrame::Glyph(_)));
+
+        match animation.slot {
+            None if has_glyphs => {
+                return Err(Error::invalid_config("slot", "animation containing glyphs requires a slot").into());
+            }
+
+            Some(slot) => {
+                super::check_slots("Animation", slot, 1)?;
+
+                if self.animations.iter().any(|(_, other)| other.slot == Some(slot)) {
+                    return Err(Error::invalid_config("slot", format!("slot {} is already used by another animation", slot)).into());
+                }
+            }
+
+            None => {}
+        }
+
+        let id = self.next_id;
+
+        self.next_id += 1;
+        self.animations.push((id, animation));
+
+        Ok(id)
+    }
+
+    /// Stops given animation (leaving its last frame on the screen); returns whether it existed.
+    pub fn remove(&mut self, id: usize) -> bool {
+        let len = self.animations.len();
+
+        self.animations.retain(|(other, _)| *other != id);
+        self.animations.len() != len
+    }
+
+    /// Returns whether given animation has finished playing (the looped ones never do).
+    pub fn is_finished(&self, id: usize) -> bool {
+        self.animations
+            .iter()
+            .find(|(other, _)| *other == id)
+            .is_none_or(|(_, animation)| animation.is_finished())
+    }
+
+    /// Advances all the animations to given moment and draws the frames that have changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when an animation lies outside the screen.
+    pub fn tick<L: CustomChars>(&mut self, lcd: &mut L, now: Duration) -> UnitResult {
+        for (_, animation) in &mut self.animations {
+            if animation.advance(now) {
+                animation.draw(lcd)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Animation {
+    /// Creates an empty, looped animation at (`y`, `x`).
+    pub fn new(y: usize, x: usize) -> Animation {
+        Animation {
+            y,
+            x,
+            frames: Vec::new(),
+            slot: None,
+            looped: true,
+            current: None,
+        }
+    }
+
+    /// Appends a frame, shown for given duration.
+    pub fn frame(mut self, frame: AnimationFrame, duration: Duration) -> Animation {
+        self.frames.push((frame, duration));
+        self
+    }
+
+    /// Sets the custom character the glyphs are uploaded into (required when there are any).
+    pub fn slot(mut self, slot: u8) -> Animation {
+        self.slot = Some(slot);
+        self
+    }
+
+    /// Makes the animation stop at its last frame, instead of starting over.
+    pub fn once(mut self) -> Animation {
+        self.looped = false;
+        self
+    }
+
+    /// Returns whether the animation has reached its last frame for good.
+    fn is_finished(&self) -> bool {
+        !self.looped && self.current.is_some_and(|(frame, _)| frame + 1 == self.frames.len())
+    }
+
+    /// Advances the animation to given moment; returns whether the frame has changed.
+    fn advance(&mut self, now: Duration) -> bool {
+        let (mut frame, mut shown_at) = match self.current {
+            Some(current) => current,
+
+            None => {
+                self.current = Some((0, now));
+                return true;
+            }
+        };
+
+        let previous = frame;
+
+        // skip the whole cycles at once, in case the ticks are rare
+        if self.looped {
+            let cycle: Duration = self.frames.iter().map(|(_, duration)| *duration).sum();
+            let cycles = (now.saturating_sub(shown_at).as_nanos() / cycle.as_nanos()) as u32;
+
+            if cycles > 1 {
+                shown_at += cycle * (cycles - 1);
+            }
+        }
+
+        while !self.is_finished() {
+            let shown_until = shown_at + self.frames[frame].1;
+
+            if now < shown_until {
+                break;
+            }
+
+            frame = (frame + 1) % self.frames.len();
+            shown_at = shown_until;
+            self.current = Some((frame, shown_at));
+        }
+
+        frame != previous
+    }
+
+    fn draw<L: CustomChars>(&self, lcd: &mut L) -> UnitResult {
+        let frame = match self.current {
+            Some((frame, _)) => &self.frames[frame].0,
+            None => return Ok(()),
+        };
+
+        match frame {
+            AnimationFrame::Text(text) => {
+                lcd.print_at(self.y, self.x, text.as_str())
+            }
+
+            AnimationFrame::Glyph(lines) => {
+                // checked by `Animator::add`
+                let slot = self.slot.unwrap_or_default();
+
+                lcd.create_char(slot, *lines)?;
+                lcd.print_char_at(self.y, self.x, slot)
+            }
+        }
+    }
+}
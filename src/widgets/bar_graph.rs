@@ -0,0 +1,104 @@
+//! Draws a horizontal bar graph (a gauge) with a sub-character resolution.
+//!
+//! Each character is split into 5 columns, so a 10-characters wide graph is able to show 50
+//! distinct levels. The partially filled characters are drawn using five custom characters, which
+//! have to be uploaded using the `load` method before drawing anything.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! // 16-characters wide graph at (1, 0)
+//! let graph = BarGraph::new(1, 0, 16);
+//!
+//! graph.load(&mut lcd)?;
+//! graph.draw(&mut lcd, 0.75)?;
+//!
+//! lcd.render()?;
+//! ```
+
+use super::super::{CustomChars, Hd44780, Result, UnitResult};
+use super::check_slots;
+
+pub struct BarGraph {
+    y: usize,
+    x: usize,
+    width: usize,
+    first_slot: u8,
+}
+
+/// Number of columns a single character consists of.
+const COLUMNS: usize = 5;
+
+impl BarGraph {
+    /// Number of custom characters required to draw the graph.
+    pub const SLOTS: u8 = COLUMNS as u8;
+
+    /// Creates a bar graph occupying custom characters `<0, 4>`.
+    pub fn new(y: usize, x: usize, width: usize) -> BarGraph {
+        BarGraph {
+            y,
+            x,
+            width,
+            first_slot: 0,
+        }
+    }
+
+    /// Creates a bar graph occupying custom characters `<first_slot, first_slot + 4>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the slots would not fit in the CGRAM.
+    pub fn with_first_slot(y: usize, x: usize, width: usize, first_slot: u8) -> Result<BarGraph> {
+        check_slots("Bar graph", first_slot, BarGraph::SLOTS)?;
+
+        Ok(
+            BarGraph {
+                y,
+                x,
+                width,
+                first_slot,
+            }
+        )
+    }
+
+    /// Uploads the custom characters required to draw the graph.
+    pub fn load<L: CustomChars>(&self, lcd: &mut L) -> UnitResult {
+        for columns in 1..=COLUMNS {
+            let line = (0b11111 << (COLUMNS - columns)) & 0b11111;
+
+            lcd.create_char(self.first_slot + columns as u8 - 1, [line; 8])?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns number of distinct levels the graph is able to show.
+    pub fn resolution(&self) -> usize {
+        self.width * COLUMNS
+    }
+
+    /// Draws the graph filled in given fraction (from range `<0.0, 1.0>`; values outside of it
+    /// are clamped).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the graph does not fit on the screen.
+    pub fn draw<L: Hd44780>(&self, lcd: &mut L, fraction: f32) -> UnitResult {
+        let fraction = fraction.clamp(0.0, 1.0);
+        let filled = (fraction * self.resolution() as f32 + 0.5) as usize;
+
+        // the full cells, the partially filled one (if any) and the empty ones
+        let full = filled / COLUMNS;
+        let partial = filled % COLUMNS;
+        let empty = self.width - full - (partial > 0) as usize;
+
+        lcd.move_at(self.y, self.x)?;
+        lcd.print_char_repeated(self.first_slot + COLUMNS as u8 - 1, full)?;
+
+        if partial > 0 {
+            lcd.print_char(self.first_slot + partial as u8 - 1)?;
+        }
+
+        lcd.print_char_repeated(b' ', empty)
+    }
+}
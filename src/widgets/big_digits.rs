@@ -0,0 +1,185 @@
+//! Draws big, clock-style numerals spanning two or four lines.
+//!
+//! Digits are built out of four custom characters (top bar, bottom bar, both bars and a full
+//! block), which have to be uploaded using the `load` method before printing anything.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let digits = BigDigits::new(BigDigitsSize::TwoLines);
+//!
+//! digits.load(&mut lcd)?;
+//! digits.print_big_number(&mut lcd, 0, 0, 1234)?;
+//!
+//! lcd.render()?;
+//! ```
+
+use alloc::string::ToString;
+use super::super::{CustomChars, Hd44780, Result, UnitResult};
+use super::check_slots;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum BigDigitsSize {
+    TwoLines,
+    FourLines,
+}
+
+pub struct BigDigits {
+    size: BigDigitsSize,
+    first_slot: u8,
+}
+
+// indices of the custom characters, relative to the first slot
+const TOP: u8 = 0;
+const BOTTOM: u8 = 1;
+const BOTH: u8 = 2;
+const FULL: u8 = 3;
+
+const GLYPHS: [[u8; 8]; 4] = [
+    // top bar
+    [0b11111, 0b11111, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000],
+
+    // bottom bar
+    [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b11111, 0b11111],
+
+    // both bars
+    [0b11111, 0b11111, 0b00000, 0b00000, 0b00000, 0b00000, 0b11111, 0b11111],
+
+    // full block
+    [0b11111, 0b11111, 0b11111, 0b11111, 0b11111, 0b11111, 0b11111, 0b11111],
+];
+
+// `T` - top bar, `D` - bottom bar, `B` - both bars, `F` - full block
+const TWO_LINES: [[&str; 2]; 10] = [
+    ["FTF", "FDF"],
+    ["TF ", "DFD"],
+    ["BBF", "FDD"],
+    ["BBF", "DDF"],
+    ["FDF", "  F"],
+    ["FBB", "DDF"],
+    ["FBB", "FDF"],
+    ["TTF", "  F"],
+    ["FBF", "FDF"],
+    ["FBF", "DDF"],
+];
+
+const FOUR_LINES: [[&str; 4]; 10] = [
+    ["FTF", "F F", "F F", "FDF"],
+    ["TF ", " F ", " F ", "DFD"],
+    ["TTF", "DDF", "F  ", "FDD"],
+    ["TTF", "DDF", "  F", "DDF"],
+    ["F F", "FDF", "  F", "  F"],
+    ["FTT", "FDD", "  F", "DDF"],
+    ["FTT", "FDD", "F F", "FDF"],
+    ["TTF", "  F", "  F", "  F"],
+    ["FTF", "FDF", "F F", "FDF"],
+    ["FTF", "FDF", "  F", "DDF"],
+];
+
+impl BigDigits {
+    /// Number of custom characters required to draw the digits.
+    pub const SLOTS: u8 = 4;
+
+    /// Creates big digits occupying custom characters `<0, 3>`.
+    pub fn new(size: BigDigitsSize) -> BigDigits {
+        BigDigits {
+            size,
+            first_slot: 0,
+        }
+    }
+
+    /// Creates big digits occupying custom characters `<first_slot, first_slot + 3>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the slots would not fit in the CGRAM.
+    pub fn with_first_slot(size: BigDigitsSize, first_slot: u8) -> Result<BigDigits> {
+        check_slots("Big digits", first_slot, BigDigits::SLOTS)?;
+
+        Ok(
+            BigDigits {
+                size,
+                first_slot,
+            }
+        )
+    }
+
+    /// Uploads the custom characters required to draw the digits.
+    pub fn load<L: CustomChars>(&self, lcd: &mut L) -> UnitResult {
+        for (idx, glyph) in GLYPHS.iter().enumerate() {
+            lcd.create_char(self.first_slot + idx as u8, *glyph)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns width of a single digit (in characters).
+    pub fn digit_width(&self) -> usize {
+        3
+    }
+
+    /// Returns height of a single digit (in lines).
+    pub fn digit_height(&self) -> usize {
+        match self.size {
+            BigDigitsSize::TwoLines => 2,
+            BigDigitsSize::FourLines => 4,
+        }
+    }
+
+    /// Prints a single digit with its top-left corner at given position.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when given digit is not from range `<0, 9>` or when it does not fit on the
+    /// screen.
+    pub fn print_big_digit<L: Hd44780>(&self, lcd: &mut L, y: usize, x: usize, digit: u8) -> UnitResult {
+        if digit > 9 {
+            return Err(format!("Tried to print a big digit out of range: {}.", digit).into());
+        }
+
+        let rows: &[&str] = match self.size {
+            BigDigitsSize::TwoLines => &TWO_LINES[digit as usize],
+            BigDigitsSize::FourLines => &FOUR_LINES[digit as usize],
+        };
+
+        for (dy, row) in rows.iter().enumerate() {
+            lcd.move_at(y + dy, x)?;
+
+            for segment in row.bytes() {
+                lcd.print_char(self.segment_char(segment))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Prints a non-negative number with its top-left corner at given position; digits are
+    /// separated with a single blank column.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// digits.print_big_number(&mut lcd, 0, 0, 42)?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the number does not fit on the screen.
+    pub fn print_big_number<L: Hd44780>(&self, lcd: &mut L, y: usize, x: usize, value: u64) -> UnitResult {
+        for (idx, digit) in value.to_string().bytes().enumerate() {
+            self.print_big_digit(lcd, y, x + idx * (self.digit_width() + 1), digit - b'0')?;
+        }
+
+        Ok(())
+    }
+
+    fn segment_char(&self, segment: u8) -> u8 {
+        match segment {
+            b'T' => self.first_slot + TOP,
+            b'D' => self.first_slot + BOTTOM,
+            b'B' => self.first_slot + BOTH,
+            b'F' => self.first_slot + FULL,
+            _ => b' ',
+        }
+    }
+}
@@ -0,0 +1,158 @@
+//! Draws a clock (or an uptime counter) formatted according to a `strftime`-like pattern,
+//! optionally blinking the colons once per second.
+//!
+//! The clock is given the time explicitly (as a `Duration`), so the same widget can show both the
+//! time of day (eg. the time since midnight) and the time elapsed since some event; supported
+//! specifiers are:
+//!
+//! - `%H` - hours (`00`-`23`),
+//! - `%I` - hours in the 12-hour format (`01`-`12`),
+//! - `%p` - `AM` / `PM`,
+//! - `%M` - minutes (`00`-`59`),
+//! - `%S` - seconds (`00`-`59`),
+//! - `%d` - days (not padded; for the uptime counters),
+//! - `%h` - hours, without wrapping around after a day (`00`-`...`; for the uptime counters),
+//! - `%%` - the `%` itself.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut clock = Clock::new(0, 6, "%H:%M:%S");
+//!
+//! loop {
+//!     let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+//!
+//!     clock.draw(&mut lcd, now)?;
+//!     thread::sleep(clock.until_next_change(now));
+//! }
+//! ```
+
+use alloc::string::String;
+use core::fmt::Write;
+use core::time::Duration;
+use super::super::{Hd44780, UnitResult};
+
+pub struct Clock {
+    y: usize,
+    x: usize,
+    pattern: String,
+    blinking_colons: bool,
+
+    /// what's been drawn the last time (`None` when unknown)
+    drawn: Option<String>,
+}
+
+const MINUTE: u64 = 60;
+const HOUR: u64 = 60 * MINUTE;
+const DAY: u64 = 24 * HOUR;
+
+impl Clock {
+    /// Creates a clock drawn at line `y`, starting at column `x`, formatted according to given
+    /// pattern (see the module's docs).
+    pub fn new<T: Into<String>>(y: usize, x: usize, pattern: T) -> Clock {
+        Clock {
+            y,
+            x,
+            pattern: pattern.into(),
+            blinking_colons: false,
+            drawn: None,
+        }
+    }
+
+    /// Enables or disables blinking the colons - they are hidden during every odd second.
+    pub fn set_blinking_colons(&mut self, enabled: bool) {
+        self.blinking_colons = enabled;
+        self.drawn = None;
+    }
+
+    /// Returns the clock's text for given time.
+    ///
+    /// Time longer than a day wraps around for all the specifiers but `%d` and `%h` - so the time
+    /// elapsed since the UNIX epoch yields the (UTC) time of day.
+    pub fn format(&self, time: Duration) -> String {
+        let secs = time.as_secs();
+        let hours = (secs % DAY) / HOUR;
+        let colons_hidden = self.blinking_colons && secs % 2 == 1;
+
+        let mut text = String::with_capacity(self.pattern.len());
+        let mut chars = self.pattern.chars();
+
+        while let Some(ch) = chars.next() {
+            // writing into a `String` never fails
+            let _ = match ch {
+                '%' => match chars.next() {
+                    Some('H') => write!(text, "{:02}", hours),
+                    Some('I') => write!(text, "{:02}", (hours + 11) % 12 + 1),
+                    Some('p') => write!(text, "{}", if hours < 12 { "AM" } else { "PM" }),
+                    Some('M') => write!(text, "{:02}", (secs % HOUR) / MINUTE),
+                    Some('S') => write!(text, "{:02}", secs % MINUTE),
+                    Some('d') => write!(text, "{}", secs / DAY),
+                    Some('h') => write!(text, "{:02}", secs / HOUR),
+                    Some('%') => write!(text, "%"),
+                    Some(ch) => write!(text, "%{}", ch),
+                    None => write!(text, "%"),
+                },
+
+                ':' if colons_hidden => write!(text, " "),
+
+                ch => write!(text, "{}", ch),
+            };
+        }
+
+        text
+    }
+
+    /// Returns time remaining until the clock's text changes - eg. for a clock without seconds,
+    /// the time until the next full minute - so that the application can sleep until then.
+    pub fn until_next_change(&self, time: Duration) -> Duration {
+        let period = self.period();
+        let elapsed = Duration::new(time.as_secs() % period, time.subsec_nanos());
+
+        Duration::from_secs(period) - elapsed
+    }
+
+    /// Draws the clock for given time - unless it's the same text as the one drawn the last time.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the clock does not fit on the screen.
+    pub fn draw<L: Hd44780 + ?Sized>(&mut self, lcd: &mut L, time: Duration) -> UnitResult {
+        let text = self.format(time);
+
+        if self.drawn.as_ref() == Some(&text) {
+            return Ok(());
+        }
+
+        if self.y >= lcd.height() || self.x + text.chars().count() > lcd.width() {
+            return Err(
+                format!("Clock does not fit on the screen (at y={}, x={}).", self.y, self.x).into()
+            );
+        }
+
+        lcd.print_str_at(self.y, self.x, &text)?;
+        self.drawn = Some(text);
+
+        Ok(())
+    }
+
+    /// Makes the next `draw` draw the clock even if its text has not changed (eg. after the
+    /// screen has been cleared).
+    pub fn invalidate(&mut self) {
+        self.drawn = None;
+    }
+
+    /// Returns number of seconds between the changes of the clock's text.
+    fn period(&self) -> u64 {
+        let has = |specifier: &str| self.pattern.contains(specifier);
+
+        if self.blinking_colons || has("%S") {
+            1
+        } else if has("%M") {
+            MINUTE
+        } else if has("%H") || has("%I") || has("%h") || has("%p") {
+            HOUR
+        } else {
+            DAY
+        }
+    }
+}
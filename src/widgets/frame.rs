@@ -0,0 +1,213 @@
+//! Draws frames around parts of the screen - optionally with a title in the top border and
+//! separators splitting the frame into panes - so that the dashboards look structured without
+//! placing the lines by hand.
+//!
+//! The borders are drawn using the ROM's `-` and `|`, with the corners drawn as `+` - or, after
+//! uploading six custom characters (see `with_first_slot` and `load`), as proper box-drawing
+//! corners and tees (see `glyphs::BOX_TOP_LEFT` and the others).
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! // ┌─ Status ─────────┐
+//! // │Temp:       23.4C │
+//! // ├──────────────────┤
+//! // └──────────────────┘
+//! let mut frame = Frame::with_first_slot(Point::new(0, 0), Point::new(3, 19), 0)?;
+//!
+//! frame.set_title("Status");
+//! frame.load(&mut lcd)?;
+//! frame.draw(&mut lcd)?;
+//! frame.draw_separator(&mut lcd, 2)?;
+//!
+//! let (from, _) = frame.interior().unwrap();
+//! lcd.print_at(from.y, from.x, "Temp:       23.4C")?;
+//! ```
+
+use alloc::string::String;
+use super::super::{check_region, CustomChars, Hd44780, Point, Result, UnitResult};
+use super::super::glyphs;
+
+pub struct Frame {
+    /// frame's top-left corner
+    from: Point,
+
+    /// frame's bottom-right corner
+    to: Point,
+
+    /// first of the custom characters drawing the corners and tees (`None` when drawn using `+`)
+    first_slot: Option<u8>,
+
+    title: String,
+}
+
+/// Custom characters uploaded by `Frame::load`, in the order of their slots.
+const GLYPHS: [glyphs::Glyph; 6] = [
+    glyphs::BOX_TOP_LEFT,
+    glyphs::BOX_TOP_RIGHT,
+    glyphs::BOX_BOTTOM_LEFT,
+    glyphs::BOX_BOTTOM_RIGHT,
+    glyphs::BOX_TEE_LEFT,
+    glyphs::BOX_TEE_RIGHT,
+];
+
+/// Indices of the characters in `GLYPHS`.
+const TOP_LEFT: u8 = 0;
+const TOP_RIGHT: u8 = 1;
+const BOTTOM_LEFT: u8 = 2;
+const BOTTOM_RIGHT: u8 = 3;
+const TEE_LEFT: u8 = 4;
+const TEE_RIGHT: u8 = 5;
+
+impl Frame {
+    /// Number of custom characters required to draw the box-drawing corners.
+    pub const SLOTS: u8 = GLYPHS.len() as u8;
+
+    /// Creates a frame spanning from the top-left corner to the bottom-right one (inclusive),
+    /// with the corners drawn as `+`.
+    pub fn new(from: Point, to: Point) -> Frame {
+        Frame {
+            from,
+            to,
+            first_slot: None,
+            title: String::new(),
+        }
+    }
+
+    /// Creates a frame with the box-drawing corners, using custom characters
+    /// `<first_slot, first_slot + 5>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the slots would not fit in the CGRAM.
+    pub fn with_first_slot(from: Point, to: Point, first_slot: u8) -> Result<Frame> {
+        super::check_slots("Frame", first_slot, Frame::SLOTS)?;
+
+        Ok(
+            Frame {
+                first_slot: Some(first_slot),
+                ..Frame::new(from, to)
+            }
+        )
+    }
+
+    /// Changes the title drawn in the top border (an empty one disables it); titles longer than
+    /// the border get cut.
+    pub fn set_title<T: Into<String>>(&mut self, title: T) {
+        self.title = title.into();
+    }
+
+    /// Uploads the custom characters drawing the corners (does nothing for the frames created
+    /// using `new`).
+    pub fn load<L: CustomChars>(&self, lcd: &mut L) -> UnitResult {
+        if let Some(first_slot) = self.first_slot {
+            for (idx, glyph) in GLYPHS.iter().enumerate() {
+                lcd.create_char(first_slot + idx as u8, *glyph)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the part of the screen inside the frame, as its top-left and bottom-right corners
+    /// (or `None` when the frame is too small to have any).
+    pub fn interior(&self) -> Option<(Point, Point)> {
+        if self.to.y < self.from.y + 2 || self.to.x < self.from.x + 2 {
+            return None;
+        }
+
+        Some((
+            Point::new(self.from.y + 1, self.from.x + 1),
+            Point::new(self.to.y - 1, self.to.x - 1),
+        ))
+    }
+
+    /// Draws the frame (leaving its interior untouched).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the frame does not fit on the screen or is smaller than 2x2.
+    pub fn draw<L: Hd44780>(&self, lcd: &mut L) -> UnitResult {
+        self.check(lcd)?;
+
+        let (from, to) = (self.from, self.to);
+
+        self.draw_line(lcd, from.y, self.corner(lcd, TOP_LEFT), self.corner(lcd, TOP_RIGHT))?;
+        self.draw_line(lcd, to.y, self.corner(lcd, BOTTOM_LEFT), self.corner(lcd, BOTTOM_RIGHT))?;
+
+        let vertical = lcd.rom().encode('|');
+
+        for y in (from.y + 1)..to.y {
+            lcd.print_char_at(y, from.x, vertical)?;
+            lcd.print_char_at(y, to.x, vertical)?;
+        }
+
+        self.draw_title(lcd)
+    }
+
+    /// Draws a separator - a horizontal line joined with the frame's sides - across line `y` of
+    /// the screen.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the frame does not fit on the screen or when given line is not
+    /// inside the frame.
+    pub fn draw_separator<L: Hd44780>(&self, lcd: &mut L, y: usize) -> UnitResult {
+        self.check(lcd)?;
+
+        if y <= self.from.y || y >= self.to.y {
+            return Err(
+                format!("Separator must lie inside the frame (got y={}, while the frame spans y={}..={}).", y, self.from.y, self.to.y).into()
+            );
+        }
+
+        self.draw_line(lcd, y, self.corner(lcd, TEE_LEFT), self.corner(lcd, TEE_RIGHT))
+    }
+
+    /// Draws a horizontal line across the frame, ending with given characters.
+    fn draw_line<L: Hd44780>(&self, lcd: &mut L, y: usize, left: u8, right: u8) -> UnitResult {
+        let horizontal = lcd.rom().encode('-');
+
+        lcd.print_char_at(y, self.from.x, left)?;
+
+        for x in (self.from.x + 1)..self.to.x {
+            lcd.print_char_at(y, x, horizontal)?;
+        }
+
+        lcd.print_char_at(y, self.to.x, right)
+    }
+
+    /// Draws the title into the top border, surrounded by spaces and one character away from
+    /// the corner (so that it's clear where the border starts).
+    fn draw_title<L: Hd44780>(&self, lcd: &mut L) -> UnitResult {
+        // corners, the gap after the left one and the spaces around the title
+        let available = (self.to.x - self.from.x + 1).saturating_sub(5);
+
+        if self.title.is_empty() || available == 0 {
+            return Ok(());
+        }
+
+        let title: String = self.title.chars().take(available).collect();
+
+        lcd.print_str_at(self.from.y, self.from.x + 2, &format!(" {} ", title))
+    }
+
+    /// Returns code of given corner / tee.
+    fn corner<L: Hd44780>(&self, lcd: &L, idx: u8) -> u8 {
+        match self.first_slot {
+            Some(first_slot) => first_slot + idx,
+            None => lcd.rom().encode('+'),
+        }
+    }
+
+    /// Checks whether the frame fits on the screen and is at least 2x2.
+    fn check<L: Hd44780>(&self, lcd: &L) -> UnitResult {
+        check_region(lcd, self.from, self.to)?;
+
+        if self.to.y == self.from.y || self.to.x == self.from.x {
+            return Err("Frame must be at least 2 lines high and 2 characters wide.".into());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,252 @@
+//! Collects a value typed on a keypad - a single-line text field with a cursor drawn in software,
+//! the insert & overwrite modes and an optional mask (for the PINs and passwords).
+//!
+//! Values longer than the field scroll horizontally, so that the cursor always stays visible.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! // PIN: ****_
+//! lcd.print_at(1, 0, "PIN: ")?;
+//!
+//! let mut pin = InputField::new(1, 5, 6);
+//!
+//! pin.set_mask(Some('*'));
+//! pin.set_max_len(Some(6));
+//!
+//! loop {
+//!     match keypad.read()? {
+//!         Some('#') => break,
+//!         Some('*') => pin.backspace(),
+//!         Some(digit) => { pin.insert(digit); }
+//!         None => (),
+//!     }
+//!
+//!     pin.tick(&mut lcd, started.elapsed())?;
+//!     lcd.render()?;
+//! }
+//!
+//! check_pin(&pin.value())?;
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::time::Duration;
+use super::super::{Hd44780, UnitResult};
+
+pub struct InputField {
+    y: usize,
+    x: usize,
+    width: usize,
+
+    value: Vec<char>,
+
+    /// position of the cursor within the value (`value.len()` when past its end)
+    cursor: usize,
+
+    /// position of the first visible character
+    offset: usize,
+
+    max_len: Option<usize>,
+    overwrite: bool,
+    mask: Option<char>,
+
+    /// character the cursor is drawn with (`None` hides it)
+    cursor_glyph: Option<char>,
+
+    /// how long the cursor stays visible / hidden when blinking (`None` disables blinking)
+    blink_period: Option<Duration>,
+}
+
+impl InputField {
+    /// Creates an empty field occupying `width` characters of line `y`, starting at column `x`.
+    ///
+    /// By default the characters get inserted, there's no limit on the value's length and the
+    /// cursor is an underscore blinking every 500 ms.
+    pub fn new(y: usize, x: usize, width: usize) -> InputField {
+        InputField {
+            y,
+            x,
+            width,
+            value: Vec::new(),
+            cursor: 0,
+            offset: 0,
+            max_len: None,
+            overwrite: false,
+            mask: None,
+            cursor_glyph: Some('_'),
+            blink_period: Some(Duration::from_millis(500)),
+        }
+    }
+
+    /// Returns the value typed so far.
+    pub fn value(&self) -> String {
+        self.value.iter().collect()
+    }
+
+    /// Replaces the value (truncating it to the maximum length) and moves the cursor past its end.
+    pub fn set_value<T: Into<String>>(&mut self, value: T) {
+        self.value = value.into().chars().collect();
+
+        if let Some(max_len) = self.max_len {
+            self.value.truncate(max_len);
+        }
+
+        self.end();
+    }
+
+    /// Empties the field.
+    pub fn clear(&mut self) {
+        self.set_value("");
+    }
+
+    /// Limits the value's length (`None` - the default - means no limit).
+    pub fn set_max_len(&mut self, max_len: Option<usize>) {
+        self.max_len = max_len;
+
+        if let Some(max_len) = max_len {
+            self.value.truncate(max_len);
+            self.move_to(self.cursor.min(self.value.len()));
+        }
+    }
+
+    /// Switches between the overwrite mode (when enabled, typed characters replace the ones under
+    /// the cursor) and the insert one (the default).
+    pub fn set_overwrite(&mut self, enabled: bool) {
+        self.overwrite = enabled;
+    }
+
+    /// Returns whether the field is in the overwrite mode.
+    pub fn is_overwrite(&self) -> bool {
+        self.overwrite
+    }
+
+    /// Makes the field show given character in place of each of the value's ones (eg. `*` for the
+    /// PINs); `None` shows the value as it is.
+    pub fn set_mask(&mut self, mask: Option<char>) {
+        self.mask = mask;
+    }
+
+    /// Changes the character the cursor is drawn with (`_` by default); `None` hides the cursor
+    /// (eg. when the field is not being edited).
+    pub fn set_cursor(&mut self, glyph: Option<char>) {
+        self.cursor_glyph = glyph;
+    }
+
+    /// Changes how long the cursor stays visible & hidden when blinking (500 ms by default);
+    /// `None` makes it steady.
+    pub fn set_blink_period(&mut self, period: Option<Duration>) {
+        self.blink_period = period;
+    }
+
+    /// Returns the cursor's position within the value.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Types given character at the cursor's position and moves the cursor right.
+    ///
+    /// Returns `false` (leaving the value intact) when the value has already reached its maximum
+    /// length.
+    pub fn insert(&mut self, ch: char) -> bool {
+        if self.overwrite && self.cursor < self.value.len() {
+            self.value[self.cursor] = ch;
+        } else if self.max_len.is_none_or(|max_len| self.value.len() < max_len) {
+            self.value.insert(self.cursor, ch);
+        } else {
+            return false;
+        }
+
+        self.move_to(self.cursor + 1);
+
+        true
+    }
+
+    /// Removes the character before the cursor.
+    pub fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.value.remove(self.cursor - 1);
+            self.move_to(self.cursor - 1);
+        }
+    }
+
+    /// Removes the character under the cursor.
+    pub fn delete(&mut self) {
+        if self.cursor < self.value.len() {
+            self.value.remove(self.cursor);
+        }
+    }
+
+    /// Moves the cursor one character left.
+    pub fn move_left(&mut self) {
+        self.move_to(self.cursor.saturating_sub(1));
+    }
+
+    /// Moves the cursor one character right (up to the position past the value's end).
+    pub fn move_right(&mut self) {
+        self.move_to((self.cursor + 1).min(self.value.len()));
+    }
+
+    /// Moves the cursor at the value's beginning.
+    pub fn home(&mut self) {
+        self.move_to(0);
+    }
+
+    /// Moves the cursor past the value's end.
+    pub fn end(&mut self) {
+        self.move_to(self.value.len());
+    }
+
+    /// Draws the field with the cursor blinking according to given moment (the time elapsed
+    /// since any fixed point, eg. `Instant::now() - started`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the field does not fit on the screen.
+    pub fn tick<L: Hd44780>(&self, lcd: &mut L, now: Duration) -> UnitResult {
+        let visible = match self.blink_period {
+            Some(period) if !period.is_zero() => (now.as_nanos() / period.as_nanos()).is_multiple_of(2),
+            _ => true,
+        };
+
+        self.draw_with(lcd, visible)
+    }
+
+    /// Draws the field with the cursor visible.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the field does not fit on the screen.
+    pub fn draw<L: Hd44780>(&self, lcd: &mut L) -> UnitResult {
+        self.draw_with(lcd, true)
+    }
+
+    fn draw_with<L: Hd44780>(&self, lcd: &mut L, cursor_visible: bool) -> UnitResult {
+        let rom = lcd.rom();
+
+        lcd.move_at(self.y, self.x)?;
+
+        for idx in self.offset..self.offset + self.width {
+            let ch = match (self.cursor_glyph, self.value.get(idx)) {
+                (Some(glyph), _) if cursor_visible && idx == self.cursor => glyph,
+                (_, Some(&ch)) => self.mask.unwrap_or(ch),
+                (_, None) => ' ',
+            };
+
+            lcd.print_char(rom.encode(ch))?;
+        }
+
+        Ok(())
+    }
+
+    /// Moves the cursor at given position, scrolling the field so that it stays visible.
+    fn move_to(&mut self, cursor: usize) {
+        self.cursor = cursor;
+
+        if self.cursor < self.offset {
+            self.offset = self.cursor;
+        } else if self.width > 0 && self.cursor >= self.offset + self.width {
+            self.offset = self.cursor + 1 - self.width;
+        }
+    }
+}
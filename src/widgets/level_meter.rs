@@ -0,0 +1,116 @@
+//! Draws a vertical level meter (eg. an audio VU meter or a signal strength indicator).
+//!
+//! The meter is a single column spanning one or more lines; each character is split into 4 levels
+//! (two pixel lines each), so a 4-lines tall meter is able to show 16 distinct levels. The
+//! partially filled characters are drawn using four custom characters, which have to be uploaded
+//! using the `load` method before drawing anything.
+//!
+//! Many meters can share the same custom characters - only one of them has to be loaded.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! // two 4-lines tall meters (left & right channel) in the first two columns
+//! let left = LevelMeter::new(0, 0, 4);
+//! let right = LevelMeter::new(0, 1, 4);
+//!
+//! left.load(&mut lcd)?;
+//!
+//! left.draw(&mut lcd, 0.8)?;
+//! right.draw(&mut lcd, 0.6)?;
+//!
+//! lcd.render()?;
+//! ```
+
+use super::super::{CustomChars, Hd44780, Result, UnitResult};
+use super::check_slots;
+
+pub struct LevelMeter {
+    y: usize,
+    x: usize,
+    height: usize,
+    first_slot: u8,
+}
+
+/// Number of levels a single character consists of.
+const LEVELS: usize = 4;
+
+impl LevelMeter {
+    /// Number of custom characters required to draw the meter.
+    pub const SLOTS: u8 = LEVELS as u8;
+
+    /// Creates a level meter with its top at (`y`, `x`), spanning `height` lines and occupying
+    /// custom characters `<0, 3>`.
+    pub fn new(y: usize, x: usize, height: usize) -> LevelMeter {
+        LevelMeter {
+            y,
+            x,
+            height,
+            first_slot: 0,
+        }
+    }
+
+    /// Creates a level meter occupying custom characters `<first_slot, first_slot + 3>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the slots would not fit in the CGRAM.
+    pub fn with_first_slot(y: usize, x: usize, height: usize, first_slot: u8) -> Result<LevelMeter> {
+        check_slots("Level meter", first_slot, LevelMeter::SLOTS)?;
+
+        Ok(
+            LevelMeter {
+                y,
+                x,
+                height,
+                first_slot,
+            }
+        )
+    }
+
+    /// Uploads the custom characters required to draw the meter.
+    pub fn load<L: CustomChars>(&self, lcd: &mut L) -> UnitResult {
+        for level in 1..=LEVELS {
+            let mut lines = [0u8; 8];
+
+            for line in lines.iter_mut().skip(8 - 2 * level) {
+                *line = 0b11111;
+            }
+
+            lcd.create_char(self.first_slot + level as u8 - 1, lines)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns number of distinct levels the meter is able to show.
+    pub fn resolution(&self) -> usize {
+        self.height * LEVELS
+    }
+
+    /// Draws the meter filled in given fraction (from range `<0.0, 1.0>`; values outside of it
+    /// are clamped).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the meter does not fit on the screen.
+    pub fn draw<L: Hd44780>(&self, lcd: &mut L, fraction: f32) -> UnitResult {
+        let fraction = fraction.clamp(0.0, 1.0);
+        let filled = (fraction * self.resolution() as f32 + 0.5) as usize;
+
+        // cells are numbered from the bottom
+        for cell in 0..self.height {
+            let levels = filled.saturating_sub(cell * LEVELS).min(LEVELS);
+
+            let ch = if levels == 0 {
+                b' '
+            } else {
+                self.first_slot + levels as u8 - 1
+            };
+
+            lcd.print_char_at(self.y + self.height - 1 - cell, self.x, ch)?;
+        }
+
+        Ok(())
+    }
+}
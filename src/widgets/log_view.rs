@@ -0,0 +1,163 @@
+//! Shows the most recent messages in a region of the screen, just like `tail -f` would - new
+//! messages appear at the bottom, pushing the older ones up and eventually out.
+//!
+//! Messages are kept in a ring buffer (of `height` messages by default), so that the view can be
+//! redrawn at any time (eg. after switching back from another screen). Messages longer than the
+//! region's width are either truncated or wrapped into many lines (see `set_wrap`).
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut log = LogView::new(1, 0, 20, 3);
+//!
+//! log.push("Connecting...");
+//! log.push("Connected to 192.168.1.10");
+//!
+//! log.draw(&mut lcd)?;
+//! lcd.render()?;
+//! ```
+
+use alloc::collections::VecDeque;
+use alloc::string::String;
+use alloc::vec::Vec;
+use super::super::{Hd44780, UnitResult};
+use super::super::layout::{pad, Align};
+
+pub struct LogView {
+    y: usize,
+    x: usize,
+    width: usize,
+    height: usize,
+
+    messages: VecDeque<String>,
+    capacity: usize,
+    wrap: bool,
+
+    /// whether the messages have changed since the previous draw
+    dirty: bool,
+}
+
+impl LogView {
+    /// Creates a view occupying `height` lines (starting at line `y`), each `width` characters
+    /// long (starting at column `x`).
+    pub fn new(y: usize, x: usize, width: usize, height: usize) -> LogView {
+        LogView {
+            y,
+            x,
+            width,
+            height,
+            messages: VecDeque::with_capacity(height),
+            capacity: height,
+            wrap: false,
+            dirty: true,
+        }
+    }
+
+    /// Changes number of messages kept in the buffer (the view's height by default) - the oldest
+    /// ones are forgotten first.
+    pub fn set_capacity(&mut self, messages: usize) {
+        self.capacity = messages.max(1);
+
+        while self.messages.len() > self.capacity {
+            self.messages.pop_front();
+        }
+
+        self.dirty = true;
+    }
+
+    /// Enables or disables wrapping the messages longer than the view's width (when disabled,
+    /// they are truncated).
+    pub fn set_wrap(&mut self, enabled: bool) {
+        self.wrap = enabled;
+        self.dirty = true;
+    }
+
+    /// Appends a message; each line of a multi-line message becomes a separate message.
+    pub fn push<T: Into<String>>(&mut self, message: T) {
+        for line in message.into().lines() {
+            if self.messages.len() == self.capacity {
+                self.messages.pop_front();
+            }
+
+            self.messages.push_back(String::from(line));
+        }
+
+        self.dirty = true;
+    }
+
+    /// Forgets all the messages (the view gets blank during the next draw).
+    pub fn clear(&mut self) {
+        self.messages.clear();
+        self.dirty = true;
+    }
+
+    /// Returns the messages, from the oldest one.
+    pub fn messages(&self) -> impl Iterator<Item = &str> {
+        self.messages.iter().map(|message| message.as_str())
+    }
+
+    /// Draws the most recent messages - unless nothing has changed since the previous draw.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the view does not fit on the screen.
+    pub fn draw<L: Hd44780 + ?Sized>(&mut self, lcd: &mut L) -> UnitResult {
+        if !self.dirty {
+            return Ok(());
+        }
+
+        if self.y + self.height > lcd.height() || self.x + self.width > lcd.width() {
+            return Err(
+                format!("Log view does not fit on the screen (at y={}, x={}).", self.y, self.x).into()
+            );
+        }
+
+        let rows = self.rows();
+        let blank = pad("", self.width, Align::Left);
+
+        for dy in 0..self.height {
+            let row = rows.get(dy).unwrap_or(&blank);
+
+            lcd.print_str_at(self.y + dy, self.x, row)?;
+        }
+
+        self.dirty = false;
+
+        Ok(())
+    }
+
+    /// Makes the next `draw` redraw the view even if nothing has changed (eg. after the screen
+    /// has been cleared).
+    pub fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Returns the rows to be drawn (already padded to the view's width), the oldest one first.
+    fn rows(&self) -> Vec<String> {
+        let mut rows = VecDeque::with_capacity(self.height);
+
+        // the newest messages are needed only, so it's the simplest to go backwards
+        for message in self.messages.iter().rev() {
+            let chars: Vec<char> = message.chars().collect();
+
+            let mut message_rows: Vec<String> = if self.wrap && chars.len() > self.width {
+                chars
+                    .chunks(self.width.max(1))
+                    .map(|chunk| pad(&chunk.iter().collect::<String>(), self.width, Align::Left))
+                    .collect()
+            } else {
+                vec![pad(message, self.width, Align::Left)]
+            };
+
+            while let Some(row) = message_rows.pop() {
+                if rows.len() == self.height {
+                    return rows.into();
+                }
+
+                rows.push_front(row);
+            }
+        }
+
+        rows.into()
+    }
+}
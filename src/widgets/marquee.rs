@@ -0,0 +1,93 @@
+//! Scrolls a text that does not fit in given region of a line, one character per `tick`.
+//!
+//! Texts short enough to fit in the region are drawn as-is, without scrolling.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut marquee = Marquee::new(0, 0, 16, "Never Gonna Give You Up - Rick Astley");
+//!
+//! loop {
+//!     marquee.tick(&mut lcd)?;
+//!     lcd.render()?;
+//!
+//!     thread::sleep(time::Duration::from_millis(300));
+//! }
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use super::super::{Hd44780, UnitResult};
+
+pub struct Marquee {
+    y: usize,
+    x: usize,
+    width: usize,
+    text: Vec<char>,
+    gap: usize,
+    offset: usize,
+}
+
+impl Marquee {
+    /// Creates a marquee occupying `width` characters of line `y`, starting at column `x`.
+    pub fn new<T: Into<String>>(y: usize, x: usize, width: usize, text: T) -> Marquee {
+        Marquee {
+            y,
+            x,
+            width,
+            text: text.into().chars().collect(),
+            gap: 3,
+            offset: 0,
+        }
+    }
+
+    /// Changes the text and starts scrolling it from the beginning.
+    pub fn set_text<T: Into<String>>(&mut self, text: T) {
+        self.text = text.into().chars().collect();
+        self.offset = 0;
+    }
+
+    /// Changes number of spaces separating the end of the text from its beginning (3 by default).
+    pub fn set_gap(&mut self, gap: usize) {
+        self.gap = gap;
+        self.offset = 0;
+    }
+
+    /// Returns whether the text is too long to fit in the region (and thus is scrolled).
+    pub fn is_scrolling(&self) -> bool {
+        self.text.len() > self.width
+    }
+
+    /// Moves the text one character to the left and draws it.
+    pub fn tick<L: Hd44780>(&mut self, lcd: &mut L) -> UnitResult {
+        if self.is_scrolling() {
+            self.offset = (self.offset + 1) % (self.text.len() + self.gap);
+        }
+
+        self.draw(lcd)
+    }
+
+    /// Draws the text without moving it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the region does not fit on the screen.
+    pub fn draw<L: Hd44780>(&self, lcd: &mut L) -> UnitResult {
+        let rom = lcd.rom();
+        let period = self.text.len() + self.gap;
+
+        lcd.move_at(self.y, self.x)?;
+
+        for cell in 0..self.width {
+            let ch = if self.is_scrolling() {
+                self.text.get((self.offset + cell) % period)
+            } else {
+                self.text.get(cell)
+            };
+
+            lcd.print_char(ch.map_or(b' ', |&ch| rom.encode(ch)))?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+pub use self::animator::{Animation, AnimationFrame, Animator};
+pub use self::bar_graph::BarGraph;
+pub use self::big_digits::{BigDigits, BigDigitsSize};
+pub use self::clock::Clock;
+pub use self::frame::Frame;
+pub use self::input_field::InputField;
+pub use self::level_meter::LevelMeter;
+pub use self::log_view::LogView;
+pub use self::marquee::Marquee;
+pub use self::progress_bar::ProgressBar;
+pub use self::sparkline::Sparkline;
+pub use self::spinner::{Spinner, SpinnerStyle};
+use super::Result;
+use super::char_bank::SLOTS;
+
+mod animator;
+mod bar_graph;
+mod big_digits;
+mod clock;
+mod frame;
+mod input_field;
+mod level_meter;
+mod log_view;
+mod marquee;
+mod progress_bar;
+mod sparkline;
+mod spinner;
+
+/// Checks whether `count` custom characters starting at `first_slot` fit in the CGRAM.
+fn check_slots(widget: &str, first_slot: u8, count: u8) -> Result<()> {
+    if first_slot > SLOTS as u8 - count {
+        return Err(
+            format!("{} requires {} custom characters - first slot must be in range <0, {}>.", widget, count, SLOTS as u8 - count).into()
+        );
+    }
+
+    Ok(())
+}
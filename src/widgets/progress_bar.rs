@@ -0,0 +1,148 @@
+//! Draws a progress bar - a bar graph (see `BarGraph`) with an optional frame around it and an
+//! optional percentage label next to it.
+//!
+//! Just like the bar graph, it has a sub-character resolution and requires five custom characters
+//! to be uploaded (using the `load` method) before drawing anything. The whole bar is drawn each
+//! time, so it's best used with the buffered frontend - which sends only the cells that have
+//! actually changed.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! // [#######   ]  70%
+//! let mut bar = ProgressBar::new(1, 0, 16);
+//!
+//! bar.set_frame(Some(('[', ']')));
+//! bar.set_label(true);
+//! bar.load(&mut lcd)?;
+//!
+//! for step in 0..=100 {
+//!     bar.draw(&mut lcd, step as f32 / 100.0)?;
+//!     lcd.render()?;
+//! }
+//! ```
+
+use super::super::{CustomChars, Hd44780, Result, UnitResult};
+use super::BarGraph;
+
+pub struct ProgressBar {
+    y: usize,
+    x: usize,
+    width: usize,
+    first_slot: u8,
+    frame: Option<(char, char)>,
+    label: bool,
+}
+
+/// Width of the percentage label (` 100%`).
+const LABEL_WIDTH: usize = 5;
+
+impl ProgressBar {
+    /// Number of custom characters required to draw the bar.
+    pub const SLOTS: u8 = BarGraph::SLOTS;
+
+    /// Creates a progress bar occupying `width` characters of line `y` (including the frame and
+    /// the label), starting at column `x` and using custom characters `<0, 4>`.
+    ///
+    /// By default there's neither a frame nor a label.
+    pub fn new(y: usize, x: usize, width: usize) -> ProgressBar {
+        ProgressBar {
+            y,
+            x,
+            width,
+            first_slot: 0,
+            frame: None,
+            label: false,
+        }
+    }
+
+    /// Creates a progress bar using custom characters `<first_slot, first_slot + 4>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the slots would not fit in the CGRAM.
+    pub fn with_first_slot(y: usize, x: usize, width: usize, first_slot: u8) -> Result<ProgressBar> {
+        super::check_slots("Progress bar", first_slot, ProgressBar::SLOTS)?;
+
+        Ok(
+            ProgressBar {
+                first_slot,
+                ..ProgressBar::new(y, x, width)
+            }
+        )
+    }
+
+    /// Changes the characters drawn at the bar's both ends (eg. `[` and `]`); `None` disables the
+    /// frame.
+    pub fn set_frame(&mut self, frame: Option<(char, char)>) {
+        self.frame = frame;
+    }
+
+    /// Enables or disables the percentage label, drawn right after the bar.
+    pub fn set_label(&mut self, enabled: bool) {
+        self.label = enabled;
+    }
+
+    /// Uploads the custom characters required to draw the bar.
+    pub fn load<L: CustomChars>(&self, lcd: &mut L) -> UnitResult {
+        self.graph(0)?.load(lcd)
+    }
+
+    /// Returns number of distinct levels the bar is able to show.
+    pub fn resolution(&self) -> usize {
+        BarGraph::new(self.y, self.x, self.bar_width()).resolution()
+    }
+
+    /// Draws the bar filled in given fraction (from range `<0.0, 1.0>`; values outside of it are
+    /// clamped).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the bar does not fit on the screen.
+    pub fn draw<L: Hd44780>(&self, lcd: &mut L, fraction: f32) -> UnitResult {
+        let fraction = fraction.clamp(0.0, 1.0);
+        let rom = lcd.rom();
+        let mut x = self.x;
+
+        if let Some((left, _)) = self.frame {
+            lcd.print_char_at(self.y, x, rom.encode(left))?;
+            x += 1;
+        }
+
+        self.graph(x - self.x)?.draw(lcd, fraction)?;
+        x += self.bar_width();
+
+        if let Some((_, right)) = self.frame {
+            lcd.print_char_at(self.y, x, rom.encode(right))?;
+            x += 1;
+        }
+
+        if self.label {
+            let label = format!("{:>4}%", (fraction * 100.0 + 0.5) as u32);
+
+            lcd.print_at(self.y, x, label)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns number of characters left for the bar itself.
+    fn bar_width(&self) -> usize {
+        let mut width = self.width;
+
+        if self.frame.is_some() {
+            width = width.saturating_sub(2);
+        }
+
+        if self.label {
+            width = width.saturating_sub(LABEL_WIDTH);
+        }
+
+        width
+    }
+
+    /// Returns the bar graph drawing the bar itself, `offset` characters from the beginning.
+    fn graph(&self, offset: usize) -> Result<BarGraph> {
+        BarGraph::with_first_slot(self.y, self.x + offset, self.bar_width(), self.first_slot)
+    }
+}
@@ -0,0 +1,191 @@
+//! Draws a sparkline - a rolling series of samples (eg. temperature, CPU load or signal strength)
+//! shown as a row of tiny bars, one per character, for an at-a-glance trend.
+//!
+//! The newest sample is drawn in the rightmost character; once the sparkline is full, the oldest
+//! samples fall off its left side. The bars are scaled automatically between the lowest and the
+//! highest sample shown (or within a fixed range - see `set_range`) and drawn using seven custom
+//! characters, which have to be uploaded using the `load` method before drawing anything.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! // "Temp ▁▂▂▃▅▆▇▆▅▄  21.5C"
+//! let mut sparkline = Sparkline::new(0, 5, 10);
+//!
+//! sparkline.load(&mut lcd)?;
+//!
+//! loop {
+//!     let temperature = sensor.read()?;
+//!
+//!     sparkline.push(temperature);
+//!     sparkline.draw(&mut lcd)?;
+//!
+//!     lcd.print_at(0, 16, format!("{:>4.1}C", temperature))?;
+//!     lcd.render()?;
+//!
+//!     thread::sleep(Duration::from_secs(60));
+//! }
+//! ```
+
+use alloc::collections::VecDeque;
+use super::super::{CustomChars, Hd44780, Result, UnitResult};
+use super::check_slots;
+
+pub struct Sparkline {
+    y: usize,
+    x: usize,
+    width: usize,
+    first_slot: u8,
+
+    /// samples, from the oldest one
+    samples: VecDeque<f32>,
+
+    /// fixed range of the samples (`None` scales them automatically)
+    range: Option<(f32, f32)>,
+}
+
+/// Number of distinct bars' heights (a bar is at least one pixel line tall, so that the lowest
+/// samples do not disappear).
+const LEVELS: usize = 7;
+
+impl Sparkline {
+    /// Number of custom characters required to draw the sparkline.
+    pub const SLOTS: u8 = LEVELS as u8;
+
+    /// Creates an empty sparkline showing the last `width` samples, occupying custom characters
+    /// `<0, 6>`.
+    pub fn new(y: usize, x: usize, width: usize) -> Sparkline {
+        Sparkline {
+            y,
+            x,
+            width,
+            first_slot: 0,
+            samples: VecDeque::with_capacity(width),
+            range: None,
+        }
+    }
+
+    /// Creates a sparkline occupying custom characters `<first_slot, first_slot + 6>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the slots would not fit in the CGRAM.
+    pub fn with_first_slot(y: usize, x: usize, width: usize, first_slot: u8) -> Result<Sparkline> {
+        check_slots("Sparkline", first_slot, Sparkline::SLOTS)?;
+
+        Ok(
+            Sparkline {
+                first_slot,
+                ..Sparkline::new(y, x, width)
+            }
+        )
+    }
+
+    /// Uploads the custom characters required to draw the sparkline.
+    pub fn load<L: CustomChars>(&self, lcd: &mut L) -> UnitResult {
+        for level in 1..=LEVELS {
+            let mut lines = [0u8; 8];
+
+            // the last pixel line is left empty, so that the bars do not merge with the next row
+            for line in lines.iter_mut().take(LEVELS).skip(LEVELS - level) {
+                *line = 0b11111;
+            }
+
+            lcd.create_char(self.first_slot + level as u8 - 1, lines)?;
+        }
+
+        Ok(())
+    }
+
+    /// Appends a sample, dropping the oldest one when the sparkline is full.
+    ///
+    /// Non-finite samples (eg. a failed sensor's reading passed as `NaN`) are drawn as gaps.
+    pub fn push(&mut self, sample: f32) {
+        if self.width == 0 {
+            return;
+        }
+
+        if self.samples.len() >= self.width {
+            self.samples.pop_front();
+        }
+
+        self.samples.push_back(sample);
+    }
+
+    /// Removes all the samples.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Returns the samples shown, from the oldest one.
+    pub fn samples(&self) -> impl Iterator<Item = f32> + '_ {
+        self.samples.iter().copied()
+    }
+
+    /// Scales the bars within given range (samples outside of it are clamped), instead of
+    /// between the lowest and the highest sample shown - `None` (the default) restores the
+    /// automatic scaling.
+    ///
+    /// The fixed range comes in handy for the series with a known scale (eg. a CPU load in
+    /// percents), where the automatic one would blow tiny fluctuations out of proportion.
+    pub fn set_range(&mut self, range: Option<(f32, f32)>) {
+        self.range = range;
+    }
+
+    /// Returns the range the bars are currently scaled within, or `None` when there are no
+    /// (finite) samples to scale.
+    pub fn range(&self) -> Option<(f32, f32)> {
+        if self.range.is_some() {
+            return self.range;
+        }
+
+        self.samples
+            .iter()
+            .filter(|sample| sample.is_finite())
+            .fold(None, |range, &sample| match range {
+                Some((min, max)) => Some((sample.min(min), sample.max(max))),
+                None => Some((sample, sample)),
+            })
+    }
+
+    /// Draws the sparkline; the characters without a sample are left blank.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the sparkline does not fit on the screen.
+    pub fn draw<L: Hd44780>(&self, lcd: &mut L) -> UnitResult {
+        let range = self.range();
+
+        lcd.move_at(self.y, self.x)?;
+
+        for _ in self.samples.len()..self.width {
+            lcd.print_char(b' ')?;
+        }
+
+        for &sample in &self.samples {
+            match (range, sample.is_finite()) {
+                (Some((min, max)), true) => {
+                    lcd.print_char(self.first_slot + level(sample, min, max) as u8)?;
+                }
+
+                _ => {
+                    lcd.print_char(b' ')?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns height of the bar representing given sample, from range `<0, LEVELS - 1>` (a flat
+/// series is drawn at half the height).
+fn level(sample: f32, min: f32, max: f32) -> usize {
+    if max <= min {
+        return LEVELS / 2;
+    }
+
+    let fraction = ((sample - min) / (max - min)).clamp(0.0, 1.0);
+
+    (fraction * (LEVELS - 1) as f32 + 0.5) as usize
+}
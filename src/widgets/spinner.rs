@@ -0,0 +1,149 @@
+//! Draws a single-character activity indicator ("working..."), advanced one frame per `tick`.
+//!
+//! The `Line` and `Dots` styles are drawn using a single custom character, which is re-uploaded on
+//! each frame (the backslash required by the classic `|/-\` spinner is not present in any of the
+//! character ROMs); the `Chars` style uses only the ROM's characters.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut spinner = Spinner::new(0, 15, SpinnerStyle::Dots);
+//!
+//! while !job.is_finished() {
+//!     spinner.tick(&mut lcd)?;
+//!     lcd.render()?;
+//!
+//!     thread::sleep(time::Duration::from_millis(100));
+//! }
+//!
+//! spinner.clear(&mut lcd)?;
+//! ```
+
+use alloc::vec::Vec;
+use super::super::{CustomChars, Hd44780, Result, UnitResult};
+
+pub struct Spinner {
+    y: usize,
+    x: usize,
+    style: SpinnerStyle,
+    slot: u8,
+    frame: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SpinnerStyle {
+    /// `|`, `/`, `-`, `\` (uses a custom character)
+    Line,
+
+    /// three dots circling around a braille-like 2x3 grid (uses a custom character)
+    Dots,
+
+    /// given ROM's characters, shown one after another (eg. `.oOo`)
+    Chars(Vec<char>),
+}
+
+/// Bitmaps of the `Line` style's frames.
+const LINE: [[u8; 8]; 4] = [
+    [0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00000],
+    [0b00001, 0b00010, 0b00010, 0b00100, 0b01000, 0b01000, 0b10000, 0b00000],
+    [0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000, 0b00000],
+    [0b10000, 0b01000, 0b01000, 0b00100, 0b00010, 0b00010, 0b00001, 0b00000],
+];
+
+/// Positions (line, column mask) of the `Dots` style's dots, clockwise from the top-left one.
+const DOTS: [(usize, u8); 6] = [
+    (1, 0b01000),
+    (1, 0b00010),
+    (3, 0b00010),
+    (5, 0b00010),
+    (5, 0b01000),
+    (3, 0b01000),
+];
+
+impl Spinner {
+    /// Creates a spinner at (`y`, `x`), occupying custom character `0` (when the style needs one).
+    pub fn new(y: usize, x: usize, style: SpinnerStyle) -> Spinner {
+        Spinner {
+            y,
+            x,
+            style,
+            slot: 0,
+            frame: 0,
+        }
+    }
+
+    /// Creates a spinner occupying given custom character (when the style needs one).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the slot lies outside the CGRAM.
+    pub fn with_slot(y: usize, x: usize, style: SpinnerStyle, slot: u8) -> Result<Spinner> {
+        super::check_slots("Spinner", slot, 1)?;
+
+        Ok(
+            Spinner {
+                slot,
+                ..Spinner::new(y, x, style)
+            }
+        )
+    }
+
+    /// Returns number of frames the animation consists of.
+    pub fn frames(&self) -> usize {
+        match &self.style {
+            SpinnerStyle::Line => LINE.len(),
+            SpinnerStyle::Dots => DOTS.len(),
+            SpinnerStyle::Chars(chars) => chars.len().max(1),
+        }
+    }
+
+    /// Moves to the next frame and draws it.
+    pub fn tick<L: CustomChars>(&mut self, lcd: &mut L) -> UnitResult {
+        self.frame = (self.frame + 1) % self.frames();
+        self.draw(lcd)
+    }
+
+    /// Draws the current frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the spinner lies outside the screen.
+    pub fn draw<L: CustomChars>(&self, lcd: &mut L) -> UnitResult {
+        let ch = match &self.style {
+            SpinnerStyle::Line => {
+                lcd.create_char(self.slot, LINE[self.frame])?;
+                self.slot
+            }
+
+            SpinnerStyle::Dots => {
+                lcd.create_char(self.slot, self.dots())?;
+                self.slot
+            }
+
+            SpinnerStyle::Chars(chars) => {
+                chars
+                    .get(self.frame)
+                    .map_or(b' ', |&ch| lcd.rom().encode(ch))
+            }
+        };
+
+        lcd.print_char_at(self.y, self.x, ch)
+    }
+
+    /// Replaces the spinner with a space (eg. after the work's been done).
+    pub fn clear<L: Hd44780>(&self, lcd: &mut L) -> UnitResult {
+        lcd.print_char_at(self.y, self.x, b' ')
+    }
+
+    /// Returns bitmap of the current `Dots` frame.
+    fn dots(&self) -> [u8; 8] {
+        let mut lines = [0u8; 8];
+
+        for dot in 0..3 {
+            let (line, mask) = DOTS[(self.frame + dot) % DOTS.len()];
+            lines[line] |= mask;
+        }
+
+        lines
+    }
+}
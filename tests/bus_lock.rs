@@ -0,0 +1,43 @@
+//! Checks the advisory locking of the LCDs (see the `buses::lock` module) - a second lock on the
+//! same LCD conflicts with the first one, even within a single process.
+
+use pwr_hd44780::Error;
+use pwr_hd44780::buses::BusLock;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn fails_fast_when_locked() {
+    let name = "test-fails-fast";
+    let lock = BusLock::acquire(name, Duration::ZERO).unwrap();
+    let err = BusLock::acquire(name, Duration::ZERO).unwrap_err();
+
+    match err.downcast_ref::<Error>() {
+        Some(Error::BusLocked { lock: path, pid }) => {
+            assert_eq!(Path::new(path), lock.path());
+            assert_eq!(*pid, Some(std::process::id()));
+        }
+
+        _ => panic!("unexpected error: {}", err),
+    }
+
+    drop(lock);
+
+    assert!(BusLock::acquire(name, Duration::ZERO).is_ok());
+}
+
+#[test]
+fn waits_for_release() {
+    let name = "test-waits";
+    let lock = BusLock::acquire(name, Duration::ZERO).unwrap();
+
+    let releaser = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        drop(lock);
+    });
+
+    assert!(BusLock::acquire(name, Duration::from_secs(5)).is_ok());
+
+    releaser.join().unwrap();
+}
@@ -0,0 +1,86 @@
+//! Checks polling the busy flag (see `DirectLcd::set_busy_timeout`) against a simulated HD44780
+//! that can be wedged on demand.
+
+use pwr_hd44780::buses::Bus;
+use pwr_hd44780::prelude::*;
+use pwr_hd44780::SimulatedController;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// Forwards everything to the simulated controller, reporting it busy forever once wedged.
+struct WedgingBus {
+    sim: SimulatedController,
+    wedged: Arc<AtomicBool>,
+}
+
+impl Bus for WedgingBus {
+    fn initialize(&mut self) -> UnitResult {
+        self.sim.initialize()
+    }
+
+    fn set_backlight(&mut self, enabled: bool) -> UnitResult {
+        self.sim.set_backlight(enabled)
+    }
+
+    fn read_byte(&mut self, as_data: bool) -> pwr_hd44780::Result<u8> {
+        let value = self.sim.read_byte(as_data)?;
+
+        if !as_data && self.wedged.load(Ordering::SeqCst) {
+            return Ok(value | 0x80);
+        }
+
+        Ok(value)
+    }
+
+    fn can_read(&self) -> bool {
+        true
+    }
+
+    fn write_byte(&mut self, value: u8, as_data: bool) -> UnitResult {
+        self.sim.write_byte(value, as_data)
+    }
+
+    fn width(&self) -> usize {
+        self.sim.width()
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        self.sim.delay_us(us)
+    }
+}
+
+#[test]
+fn times_out_on_wedged_controller() {
+    let sim = SimulatedController::new(16, 2);
+    let wedged = Arc::new(AtomicBool::new(false));
+    let bus = WedgingBus { sim: sim.clone(), wedged: wedged.clone() };
+    let mut lcd = DirectLcd::new(Box::new(bus), 16, 2).unwrap();
+
+    lcd.set_busy_timeout(Some(Duration::from_millis(1))).unwrap();
+    lcd.print_at(0, 0, "Hello").unwrap();
+    lcd.home().unwrap();
+
+    assert_eq!(sim.lines()[0], "Hello           ");
+
+    wedged.store(true, Ordering::SeqCst);
+
+    let err = lcd.clear().unwrap_err();
+
+    match err.downcast_ref::<Error>() {
+        Some(Error::Timeout { after, .. }) => assert!(*after >= Duration::from_millis(1)),
+        _ => panic!("expected a timeout, got: {}", err),
+    }
+
+    // the fixed delays alone do not notice anything
+    lcd.set_busy_timeout(None).unwrap();
+    lcd.clear().unwrap();
+}
+
+#[test]
+fn requires_readable_bus() {
+    let mut lcd = DirectLcd::new(Box::new(NullBus::new()), 16, 2).unwrap();
+
+    assert!(lcd.set_busy_timeout(Some(Duration::from_millis(1))).is_err());
+    assert!(lcd.set_busy_timeout(None).is_ok());
+}
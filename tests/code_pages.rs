@@ -0,0 +1,64 @@
+//! Checks the code pages generated from `codepages/` against the test vectors taken from the
+//! datasheets.
+
+use pwr_hd44780::{CodePage, Rom};
+
+/// Characters (along with their codes) each of the code pages must contain.
+const VECTORS: &[(CodePage, &[(char, u8)])] = &[
+    (CodePage::A00, &[
+        ('A', 0x41), ('}', 0x7D), ('¥', 0x5C), ('→', 0x7E), ('ｱ', 0xB1), ('ア', 0xB1), ('°', 0xDF),
+        ('µ', 0xE4), ('μ', 0xE4), ('π', 0xF7), ('█', 0xFF),
+    ]),
+    (CodePage::A02, &[
+        ('~', 0x7E), ('▶', 0x10), ('Б', 0x80), ('Э', 0x8F), ('Я', 0xAD), ('А', 0x41), ('é', 0xE9),
+        ('ÿ', 0xFF), ('ω', 0xB8),
+    ]),
+    (CodePage::Cyrillic, &[
+        ('z', 0x7A), ('Б', 0xA0), ('я', 0xC7), ('Щ', 0xE2), ('щ', 0xE6), ('Ь', 0x62), ('у', 0x79),
+    ]),
+];
+
+/// Characters each of the code pages must not contain.
+const MISSING: &[(CodePage, &[char])] = &[
+    (CodePage::A00, &['\\', '~', 'ガ', 'é']),
+    (CodePage::A02, &['¨', '¬', 'ア']),
+    (CodePage::Cyrillic, &['é', '¥']),
+];
+
+#[test]
+fn matches_vectors() {
+    for &(page, vectors) in VECTORS {
+        for &(ch, code) in vectors {
+            assert_eq!(page.translate(ch), Some(code), "{:?}: {}", page, ch);
+        }
+    }
+
+    for &(page, chars) in MISSING {
+        for &ch in chars {
+            assert_eq!(page.translate(ch), None, "{:?}: {}", page, ch);
+        }
+    }
+}
+
+#[test]
+fn decodes_what_it_translates() {
+    for &page in &CodePage::ALL {
+        assert!(page.chars().count() > 95);
+
+        for code in 0..=255 {
+            if let Some(ch) = page.decode(code) {
+                assert_eq!(page.translate(ch), Some(code), "{:?}: {:#04x}", page, code);
+            }
+        }
+
+        for (ch, code) in page.chars() {
+            assert!(page.decode(code).is_some(), "{:?}: {}", page, ch);
+        }
+    }
+
+    // custom characters
+    assert_eq!(CodePage::A00.decode(0x00), None);
+
+    assert_eq!(Rom::Cyrillic.code_page(), CodePage::Cyrillic);
+    assert_eq!(Rom::Cyrillic.decode(0xE6), Some('щ'));
+}
@@ -0,0 +1,156 @@
+//! Checks that every `Command` encodes into the bit pattern given by the datasheets (the HD44780U's
+//! instruction table, plus the KS0073's and WS0010's extensions).
+
+use proptest::prelude::*;
+use pwr_hd44780::Command;
+
+proptest! {
+    #[test]
+    fn set_entry_mode(enable_shift: bool, increment_counter: bool) {
+        // 0 0 0 0 0 1 I/D S
+        let expected = 0b0000_0100
+            | (increment_counter as u8) << 1
+            | enable_shift as u8;
+
+        prop_assert_eq!(Command::SetEntryMode { enable_shift, increment_counter }.encode(), expected);
+    }
+
+    #[test]
+    fn set_display_flags(cursor_blinking: bool, cursor_visible: bool, text_visible: bool) {
+        // 0 0 0 0 1 D C B
+        let expected = 0b0000_1000
+            | (text_visible as u8) << 2
+            | (cursor_visible as u8) << 1
+            | cursor_blinking as u8;
+
+        prop_assert_eq!(Command::SetDisplayFlags { cursor_blinking, cursor_visible, text_visible }.encode(), expected);
+    }
+
+    #[test]
+    fn shift(display: bool, right: bool) {
+        // 0 0 0 1 S/C R/L - -
+        let expected = 0b0001_0000
+            | (display as u8) << 3
+            | (right as u8) << 2;
+
+        prop_assert_eq!(Command::Shift { display, right }.encode(), expected);
+    }
+
+    #[test]
+    fn set_functions(font_5x10: bool, height in 1usize..=4, eight_bit_bus: bool) {
+        // 0 0 1 DL N F - -
+        let expected = 0b0010_0000
+            | (eight_bit_bus as u8) << 4
+            | ((height >= 2) as u8) << 3
+            | (font_5x10 as u8) << 2;
+
+        prop_assert_eq!(Command::SetFunctions { font_5x10, height, eight_bit_bus }.encode(), expected);
+    }
+
+    #[test]
+    fn set_cgram_address(address in 0u8..0x40) {
+        // 0 1 ACG ACG ACG ACG ACG ACG
+        prop_assert_eq!(Command::SetCGRamAddress { address }.encode(), 0b0100_0000 | address);
+    }
+
+    #[test]
+    fn set_ddram_address(address in 0u8..0x80) {
+        // 1 ADD ADD ADD ADD ADD ADD ADD
+        prop_assert_eq!(Command::SetDDRamAddress { address }.encode(), 0b1000_0000 | address);
+    }
+
+    #[test]
+    fn set_functions_extended(height in 1usize..=4, eight_bit_bus: bool) {
+        // 0 0 1 DL N RE(1) - -
+        let expected = 0b0010_0100
+            | (eight_bit_bus as u8) << 4
+            | ((height >= 2) as u8) << 3;
+
+        prop_assert_eq!(Command::SetFunctionsExtended { height, eight_bit_bus }.encode(), expected);
+    }
+
+    #[test]
+    fn set_extended_functions(wide_font: bool, inverted_cursor: bool, four_lines: bool) {
+        // 0 0 0 0 1 FW B/W NW
+        let expected = 0b0000_1000
+            | (wide_font as u8) << 2
+            | (inverted_cursor as u8) << 1
+            | four_lines as u8;
+
+        prop_assert_eq!(Command::SetExtendedFunctions { wide_font, inverted_cursor, four_lines }.encode(), expected);
+    }
+
+    #[test]
+    fn set_oled_functions(height in 1usize..=4, eight_bit_bus: bool, font_table in 0u8..4) {
+        // 0 0 1 DL N F(0) FT1 FT0
+        let expected = 0b0010_0000
+            | (eight_bit_bus as u8) << 4
+            | ((height >= 2) as u8) << 3
+            | font_table;
+
+        prop_assert_eq!(Command::SetOledFunctions { height, eight_bit_bus, font_table }.encode(), expected);
+    }
+
+    #[test]
+    fn set_mode_power(graphics: bool, power: bool) {
+        // 0 0 0 1 G/C PWR 1 1
+        let expected = 0b0001_0011
+            | (graphics as u8) << 3
+            | (power as u8) << 2;
+
+        prop_assert_eq!(Command::SetModePower { graphics, power }.encode(), expected);
+    }
+
+    /// The instruction is identified by its highest bit set, so no flag can leak into the opcode.
+    #[test]
+    fn opcode_is_the_highest_bit(command in regular_command()) {
+        let opcode = match command {
+            Command::Clear => 0x01,
+            Command::Home => 0x02,
+            Command::SetEntryMode { .. } => 0x04,
+            Command::SetDisplayFlags { .. } => 0x08,
+            Command::Shift { .. } => 0x10,
+            Command::SetFunctions { .. } => 0x20,
+            Command::SetCGRamAddress { .. } => 0x40,
+            Command::SetDDRamAddress { .. } => 0x80,
+            _ => unreachable!(),
+        };
+
+        let encoded = command.encode();
+
+        prop_assert_eq!(1 << (7 - encoded.leading_zeros()), opcode, "{:?} encoded as {:#010b}", command, encoded);
+    }
+}
+
+#[test]
+fn clear_and_home() {
+    assert_eq!(Command::Clear.encode(), 0b0000_0001);
+    assert_eq!(Command::Home.encode(), 0b0000_0010);
+}
+
+/// Generates any of the HD44780U's own commands (ie. excluding the clones' extensions).
+fn regular_command() -> impl Strategy<Value = Command> {
+    prop_oneof![
+        Just(Command::Clear),
+        Just(Command::Home),
+
+        (any::<bool>(), any::<bool>()).prop_map(|(enable_shift, increment_counter)| {
+            Command::SetEntryMode { enable_shift, increment_counter }
+        }),
+
+        (any::<bool>(), any::<bool>(), any::<bool>()).prop_map(|(cursor_blinking, cursor_visible, text_visible)| {
+            Command::SetDisplayFlags { cursor_blinking, cursor_visible, text_visible }
+        }),
+
+        (any::<bool>(), any::<bool>()).prop_map(|(display, right)| {
+            Command::Shift { display, right }
+        }),
+
+        (any::<bool>(), 1usize..=4, any::<bool>()).prop_map(|(font_5x10, height, eight_bit_bus)| {
+            Command::SetFunctions { font_5x10, height, eight_bit_bus }
+        }),
+
+        (0u8..0x40).prop_map(|address| Command::SetCGRamAddress { address }),
+        (0u8..0x80).prop_map(|address| Command::SetDDRamAddress { address }),
+    ]
+}
@@ -0,0 +1,74 @@
+//! Checks how a `Daemon` treats whatever it finds at the path it is asked to listen on.
+
+use pwr_hd44780::prelude::*;
+use pwr_hd44780::{Daemon, SimulatedController};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::time::Duration;
+use std::{process, thread};
+
+fn daemon() -> Daemon {
+    let sim = SimulatedController::new(16, 2);
+
+    Daemon::new(BufferedLcd::from_lcd(DirectLcd::new(Box::new(sim), 16, 2).unwrap()))
+}
+
+fn path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("pwr-hd44780-{}-{}.sock", name, process::id()))
+}
+
+#[test]
+fn keeps_regular_files() {
+    let path = path("file");
+
+    fs::write(&path, "precious").unwrap();
+
+    assert!(daemon().serve(&path).is_err());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "precious");
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn keeps_sockets_in_use() {
+    let path = path("in-use");
+    let _listener = UnixListener::bind(&path).unwrap();
+
+    assert!(daemon().serve(&path).is_err());
+    assert!(path.exists());
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn replaces_stale_sockets() {
+    let path = path("stale");
+
+    // a socket no one listens on anymore
+    drop(UnixListener::bind(&path).unwrap());
+
+    let daemon = daemon();
+    let serve_path = path.clone();
+
+    thread::spawn(move || {
+        let _ = daemon.serve(serve_path);
+    });
+
+    let stream = loop {
+        match UnixStream::connect(&path) {
+            Ok(stream) => break stream,
+            Err(_) => thread::sleep(Duration::from_millis(10)),
+        }
+    };
+
+    let mut response = String::new();
+
+    writeln!(&stream, "clear").unwrap();
+    BufReader::new(&stream).read_line(&mut response).unwrap();
+
+    assert_eq!(response, "ok\n");
+
+    fs::remove_file(&path).unwrap();
+}
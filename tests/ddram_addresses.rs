@@ -0,0 +1,107 @@
+//! Checks the DDRAM addresses computed by `Properties` against the lines' map, for all the
+//! geometries `Properties::validate` accepts.
+
+use proptest::prelude::*;
+use pwr_hd44780::{Controller, Font, Geometry, Point, Properties};
+
+proptest! {
+    /// Every character lives at its line's address plus its column (or, for the split displays,
+    /// at the address of the half it belongs to).
+    #[test]
+    fn address_matches_row_map(properties in valid_properties()) {
+        let row_addresses = properties.row_addresses();
+        let half = properties.width / 2;
+
+        for (y, x) in positions(&properties) {
+            let expected = if properties.is_split() {
+                if x < half { x as u8 } else { 0x40 + (x - half) as u8 }
+            } else {
+                row_addresses[y] + x as u8
+            };
+
+            prop_assert_eq!(properties.address_of(y, x), expected, "at y={}, x={}", y, x);
+        }
+    }
+
+    /// All characters fit in the DDRAM, without any two of them sharing an address.
+    #[test]
+    fn addresses_are_unique(properties in valid_properties()) {
+        let mut used = [false; 0x80];
+
+        for (y, x) in positions(&properties) {
+            let address = properties.address_of(y, x) as usize;
+
+            prop_assert!(address < 0x80, "address {:#04x} (at y={}, x={}) exceeds the DDRAM", address, y, x);
+            prop_assert!(!used[address], "address {:#04x} (at y={}, x={}) is used twice", address, y, x);
+
+            used[address] = true;
+        }
+    }
+
+    /// `position_of` is the inverse of `address_of`.
+    #[test]
+    fn position_of_inverts_address_of(properties in valid_properties()) {
+        for (y, x) in positions(&properties) {
+            let address = properties.address_of(y, x);
+
+            prop_assert_eq!(properties.position_of(address), Point::new(y, x), "at address {:#04x}", address);
+        }
+    }
+}
+
+#[test]
+fn well_known_row_maps() {
+    let row_addresses = |width, height, controller| {
+        let mut properties = Properties::new(width, height);
+        properties.controller = controller;
+        properties.row_addresses()
+    };
+
+    assert_eq!(row_addresses(16, 2, Controller::Hd44780)[..2], [0x00, 0x40]);
+    assert_eq!(row_addresses(16, 4, Controller::Hd44780), [0x00, 0x40, 0x10, 0x50]);
+    assert_eq!(row_addresses(20, 4, Controller::Hd44780), [0x00, 0x40, 0x14, 0x54]);
+    assert_eq!(row_addresses(20, 4, Controller::Ks0073), [0x00, 0x20, 0x40, 0x60]);
+}
+
+/// Generates properties of any screen `Properties::validate` accepts, driven by any of the
+/// controllers.
+fn valid_properties() -> impl Strategy<Value = Properties> {
+    let controller = prop_oneof![
+        Just(Controller::Hd44780),
+        Just(Controller::Splc780d),
+        Just(Controller::St7066u),
+        Just(Controller::Ks0073),
+        Just(Controller::Ws0010),
+    ];
+
+    let geometry = prop_oneof![
+        Just(Geometry::Auto),
+        Just(Geometry::Contiguous),
+        Just(Geometry::Split),
+    ];
+
+    (1usize..=4)
+        .prop_flat_map(|height| (1..=80 / height, Just(height)))
+        .prop_flat_map(move |(width, height)| (Just(width), Just(height), controller.clone(), geometry.clone()))
+        .prop_map(|(width, height, controller, geometry)| {
+            let mut properties = Properties::new(width, height);
+
+            properties.controller = controller;
+            properties.geometry = geometry;
+
+            // the 5x10 font (picked for the 1-line screens) depends on the geometry & controller
+            if properties.controller_lines() > 1 || !controller.profile().font_5x10 {
+                properties.font = Font::Font5x8;
+            }
+
+            properties
+        })
+        .prop_filter("properties must be valid", |properties| properties.validate().is_ok())
+}
+
+/// Returns all the positions on the screen.
+fn positions(properties: &Properties) -> impl Iterator<Item = (usize, usize)> {
+    let width = properties.width;
+
+    (0..properties.height).flat_map(move |y| (0..width).map(move |x| (y, x)))
+}
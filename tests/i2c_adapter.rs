@@ -0,0 +1,21 @@
+//! Checks the I2C adapters' discovery (on whatever machine the tests run - most likely one without
+//! any I2C buses at all).
+
+use pwr_hd44780::I2CAdapter;
+use std::path::Path;
+
+#[test]
+fn points_at_device() {
+    let adapter = I2CAdapter::new(11);
+
+    assert_eq!(adapter.index(), 11);
+    assert_eq!(adapter.path(), Path::new("/dev/i2c-11"));
+}
+
+#[test]
+fn lists_adapters_in_order() {
+    let adapters = I2CAdapter::list().unwrap();
+
+    assert!(adapters.windows(2).all(|pair| pair[0].index() < pair[1].index()));
+    assert!(adapters.iter().all(|adapter| adapter.path().exists()));
+}
@@ -0,0 +1,155 @@
+//! Checks the debouncing & repeating of the buttons and the decoding of the rotary encoders, fed
+//! by the fake sources.
+
+use pwr_hd44780::{Button, ButtonEvent, Buttons, Navigate, RotaryEncoder};
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+
+fn buttons() -> (Rc<Cell<u8>>, Buttons<impl FnMut() -> pwr_hd44780::Result<u8>>) {
+    let state = Rc::new(Cell::new(0));
+    let source = state.clone();
+
+    (state, Buttons::new(move || Ok(source.get())))
+}
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn reports_presses_and_releases() {
+    let (state, mut buttons) = buttons();
+
+    state.set(Button::Up.mask());
+
+    assert_eq!(buttons.poll(ms(0)).unwrap(), []);
+    assert_eq!(buttons.poll(ms(25)).unwrap(), [ButtonEvent::Pressed(Button::Up)]);
+    assert_eq!(buttons.poll(ms(30)).unwrap(), []);
+    assert!(buttons.is_pressed(Button::Up));
+
+    state.set(0);
+
+    assert_eq!(buttons.poll(ms(100)).unwrap(), []);
+    assert_eq!(buttons.poll(ms(120)).unwrap(), [ButtonEvent::Released(Button::Up)]);
+    assert!(!buttons.is_pressed(Button::Up));
+}
+
+#[test]
+fn ignores_bounces() {
+    let (state, mut buttons) = buttons();
+
+    for (now, pressed) in [(0, true), (5, false), (10, true), (15, false), (20, true)].iter() {
+        state.set(if *pressed { Button::Select.mask() } else { 0 });
+
+        assert_eq!(buttons.poll(ms(*now)).unwrap(), []);
+    }
+
+    assert_eq!(buttons.poll(ms(35)).unwrap(), []);
+    assert_eq!(buttons.poll(ms(40)).unwrap(), [ButtonEvent::Pressed(Button::Select)]);
+}
+
+#[test]
+fn repeats_held_buttons() {
+    let (state, mut buttons) = buttons();
+
+    buttons.set_repeat(ms(500), ms(100));
+    state.set(Button::Down.mask() | Button::Left.mask());
+
+    buttons.poll(ms(0)).unwrap();
+
+    assert_eq!(buttons.poll(ms(20)).unwrap(), [
+        ButtonEvent::Pressed(Button::Left),
+        ButtonEvent::Pressed(Button::Down),
+    ]);
+
+    assert_eq!(buttons.poll(ms(400)).unwrap(), []);
+
+    state.set(Button::Down.mask());
+
+    assert_eq!(buttons.poll(ms(510)).unwrap(), []);
+
+    assert_eq!(buttons.poll(ms(530)).unwrap(), [
+        ButtonEvent::Released(Button::Left),
+        ButtonEvent::Repeated(Button::Down),
+    ]);
+
+    assert_eq!(buttons.poll(ms(600)).unwrap(), []);
+    assert_eq!(buttons.poll(ms(630)).unwrap(), [ButtonEvent::Repeated(Button::Down)]);
+}
+
+/// Levels of the encoder's pins during a single clockwise detent.
+const CLOCKWISE: [(bool, bool); 4] = [(false, true), (false, false), (true, false), (true, true)];
+
+#[derive(Default)]
+struct Cursor {
+    position: i32,
+    selected: bool,
+}
+
+impl Navigate for Cursor {
+    fn next(&mut self) {
+        self.position += 1;
+    }
+
+    fn prev(&mut self) {
+        self.position -= 1;
+    }
+
+    fn select(&mut self) {
+        self.selected = true;
+    }
+}
+
+fn encoder(levels: Vec<(bool, bool)>) -> RotaryEncoder<impl FnMut() -> pwr_hd44780::Result<(bool, bool)>> {
+    let mut levels = levels.into_iter();
+
+    RotaryEncoder::new(move || Ok(levels.next().unwrap_or((true, true))))
+}
+
+#[test]
+fn decodes_detents() {
+    let mut levels = vec![(true, true)];
+
+    levels.extend(CLOCKWISE.iter().chain(CLOCKWISE.iter()));
+    levels.extend(CLOCKWISE.iter().rev().skip(1).chain(Some(&(true, true))));
+
+    let mut encoder = encoder(levels.clone());
+    let detents: Vec<i32> = (0..levels.len()).map(|_| encoder.poll().unwrap()).collect();
+
+    assert_eq!(detents, [0, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, -1]);
+}
+
+#[test]
+fn ignores_incomplete_detents() {
+    let mut encoder = encoder(vec![(true, true), (false, true), (false, false), (false, true), (true, true)]);
+    let detents: i32 = (0..5).map(|_| encoder.poll().unwrap()).sum();
+
+    assert_eq!(detents, 0);
+}
+
+#[test]
+fn navigates() {
+    let mut levels = vec![(true, true)];
+
+    for _ in 0..3 {
+        levels.extend(CLOCKWISE.iter());
+    }
+
+    let mut encoder = encoder(levels.clone());
+    let mut cursor = Cursor::default();
+
+    for _ in 0..levels.len() {
+        encoder.navigate(&mut cursor).unwrap();
+    }
+
+    assert_eq!(cursor.position, 3);
+
+    cursor.handle(ButtonEvent::Pressed(Button::Up));
+    cursor.handle(ButtonEvent::Repeated(Button::Up));
+    cursor.handle(ButtonEvent::Released(Button::Up));
+    cursor.handle(ButtonEvent::Pressed(Button::Select));
+
+    assert_eq!(cursor.position, 1);
+    assert!(cursor.selected);
+}
@@ -0,0 +1,91 @@
+//! Checks editing & drawing of the `InputField`, on an in-memory LCD.
+
+use pwr_hd44780::prelude::*;
+use pwr_hd44780::widgets::InputField;
+use std::time::Duration;
+
+fn drawn(field: &InputField) -> String {
+    let mut lcd = FrameCapture::new(8, 1);
+
+    field.draw(&mut lcd).unwrap();
+    lcd.snapshot()
+}
+
+#[test]
+fn inserts_and_overwrites() {
+    let mut field = InputField::new(0, 0, 8);
+
+    for ch in "1234".chars() {
+        field.insert(ch);
+    }
+
+    field.move_left();
+    field.move_left();
+    field.insert('x');
+
+    assert_eq!(field.value(), "12x34");
+    assert_eq!(drawn(&field), "12x_4   ");
+
+    field.set_overwrite(true);
+    field.insert('y');
+    field.insert('z');
+    field.insert('!');
+
+    assert_eq!(field.value(), "12xyz!");
+
+    field.home();
+    field.delete();
+    field.end();
+    field.backspace();
+
+    assert_eq!(field.value(), "2xyz");
+    assert_eq!(drawn(&field), "2xyz_   ");
+}
+
+#[test]
+fn masks_value() {
+    let mut field = InputField::new(0, 2, 4);
+
+    field.set_mask(Some('*'));
+    field.set_max_len(Some(4));
+
+    for ch in "12345".chars() {
+        field.insert(ch);
+    }
+
+    assert_eq!(field.value(), "1234");
+    assert!(!field.insert('6'));
+    assert_eq!(drawn(&field), "  ***_  ");
+}
+
+#[test]
+fn scrolls_to_cursor() {
+    let mut field = InputField::new(0, 0, 4);
+
+    field.set_value("abcdefgh");
+
+    assert_eq!(drawn(&field), "fgh_    ");
+
+    field.home();
+
+    assert_eq!(drawn(&field), "_bcd    ");
+}
+
+#[test]
+fn blinks_cursor() {
+    let mut field = InputField::new(0, 0, 4);
+    let mut lcd = FrameCapture::new(4, 1);
+
+    field.set_value("ab");
+    field.move_left();
+
+    field.tick(&mut lcd, Duration::from_millis(100)).unwrap();
+    assert_eq!(lcd.snapshot(), "a_  ");
+
+    field.tick(&mut lcd, Duration::from_millis(600)).unwrap();
+    assert_eq!(lcd.snapshot(), "ab  ");
+
+    field.set_cursor(None);
+    field.tick(&mut lcd, Duration::from_millis(100)).unwrap();
+    assert_eq!(lcd.snapshot(), "ab  ");
+}
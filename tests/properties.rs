@@ -0,0 +1,66 @@
+//! Checks which screens `Properties` accept.
+
+use pwr_hd44780::prelude::*;
+use pwr_hd44780::SimulatedController;
+
+#[test]
+fn accepts_common_screens() {
+    for &(width, height) in &[(8, 1), (16, 1), (40, 1), (80, 1), (8, 2), (16, 2), (20, 2), (40, 2), (16, 4), (20, 4)] {
+        assert!(Properties::try_new(width, height).is_ok(), "{}x{}", width, height);
+    }
+}
+
+#[test]
+fn rejects_zero_dimensions() {
+    for &(width, height) in &[(0, 2), (16, 0), (0, 0)] {
+        match Properties::try_new(width, height) {
+            Err(Error::InvalidDimensions { .. }) => (),
+            other => panic!("{}x{}: unexpected {:?}", width, height, other),
+        }
+    }
+}
+
+#[test]
+fn rejects_oversized_screens() {
+    for &(width, height) in &[(41, 2), (81, 1), (20, 5), (30, 3), (40, 4)] {
+        match Properties::try_new(width, height) {
+            Err(Error::InvalidConfig { .. }) => (),
+            other => panic!("{}x{}: unexpected {:?}", width, height, other),
+        }
+    }
+}
+
+#[test]
+fn rejects_5x10_font_on_multi_line_screens() {
+    let mut properties = Properties::new(16, 2);
+
+    properties.font = Font::Font5x10;
+
+    assert!(properties.validate().is_err());
+
+    properties.width = 20;
+    properties.height = 1;
+
+    assert!(properties.validate().is_ok());
+}
+
+#[test]
+fn rejects_invalid_properties_before_touching_the_bus() {
+    let sim = SimulatedController::new(16, 2);
+
+    assert!(DirectLcd::new(Box::new(sim.clone()), 41, 2).is_err());
+    assert!(DirectLcd::new(Box::new(sim.clone()), 16, 0).is_err());
+    assert!(!sim.registers().text_visible);
+}
+
+#[test]
+fn rejects_5x10_chars_without_5x10_font() {
+    let sim = SimulatedController::new(16, 2);
+    let mut lcd = DirectLcd::new(Box::new(sim), 16, 2).unwrap();
+    let err = lcd.create_char_5x10(0, [0; 10]).unwrap_err();
+
+    match err.downcast_ref::<Error>() {
+        Some(Error::InvalidConfig { field: "properties.font", .. }) => (),
+        other => panic!("unexpected {:?}", other),
+    }
+}
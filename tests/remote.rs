@@ -0,0 +1,108 @@
+//! Drives a simulated HD44780 (see `SimulatedController`) through a `RemoteAgent` listening on
+//! the loopback interface.
+
+use pwr_hd44780::prelude::*;
+use pwr_hd44780::buses::{Bus, Timings};
+use pwr_hd44780::{NullBus, RemoteAgent, RemoteBus, SimulatedController};
+use std::io::{self, Read};
+use std::net::TcpListener;
+use std::thread;
+
+/// Spawns an agent serving a single client, returning the address it listens at.
+fn agent<B: Bus + Send + 'static>(bus: B) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let mut agent = RemoteAgent::new(bus);
+
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+
+        agent.serve(stream.try_clone().unwrap(), stream).unwrap();
+    });
+
+    address
+}
+
+#[test]
+fn prints_remotely() {
+    let sim = SimulatedController::new(20, 4);
+    let bus = RemoteBus::connect(agent(sim.clone())).unwrap();
+    let mut lcd = BufferedLcd::from_lcd(DirectLcd::new(Box::new(bus), 20, 4).unwrap());
+
+    lcd.print_at(0, 0, "Hello").unwrap();
+    lcd.print_at(3, 15, "World").unwrap();
+    lcd.render().unwrap();
+
+    assert_eq!(sim.lines(), [
+        "Hello               ",
+        "                    ",
+        "                    ",
+        "               World",
+    ]);
+}
+
+#[test]
+fn reads_remotely() {
+    let sim = SimulatedController::new(16, 2);
+    let bus = RemoteBus::connect(agent(sim.clone())).unwrap();
+    let mut lcd = DirectLcd::new(Box::new(bus), 16, 2).unwrap();
+
+    lcd.print_at(1, 3, "abc").unwrap();
+
+    assert!(lcd.capabilities().readable);
+    assert_eq!(lcd.read_char_at(Point::new(1, 4)).unwrap(), b'b');
+}
+
+#[test]
+fn reports_remote_errors() {
+    let sim = SimulatedController::new(16, 2);
+    let bus = RemoteBus::connect(agent(sim.clone())).unwrap();
+    let mut lcd = DirectLcd::new(Box::new(bus), 16, 2).unwrap();
+
+    let err = lcd.set_contrast(128).unwrap_err();
+
+    assert!(err.to_string().contains("contrast"), "{}", err);
+
+    // the connection stays usable
+    lcd.print_at(0, 0, "Still here").unwrap();
+
+    assert_eq!(sim.lines()[0], "Still here      ");
+}
+
+/// Fails every read, as if the connection has broken.
+struct BrokenReader;
+
+impl Read for BrokenReader {
+    fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+        Err(io::Error::new(io::ErrorKind::ConnectionReset, "connection reset"))
+    }
+}
+
+#[test]
+fn stops_on_broken_arguments() {
+    let mut agent = RemoteAgent::new(SimulatedController::new(16, 2));
+    let mut response = Vec::new();
+
+    // `SetBacklight`, whose argument never comes
+    let reader = [0x04].chain(BrokenReader);
+
+    assert!(agent.serve(reader, &mut response).is_err());
+    assert!(response.is_empty());
+}
+
+#[test]
+fn forwards_agent_timings() {
+    let timings = Timings {
+        settle_us: 50,
+        clear_home_us: 2000,
+        ..Timings::default()
+    };
+
+    let mut null = NullBus::new();
+
+    null.set_timings(timings);
+
+    let bus = RemoteBus::connect(agent(null)).unwrap();
+
+    assert_eq!(bus.timings(), timings);
+}
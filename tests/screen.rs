@@ -0,0 +1,73 @@
+//! Checks composing, diffing & rendering of the `Screen`.
+
+use pwr_hd44780::prelude::*;
+use pwr_hd44780::{Pages, Point, Screen};
+
+const HEART: [u8; 8] = [0, 0b01010, 0b11111, 0b11111, 0b01110, 0b00100, 0, 0];
+
+#[test]
+fn composes_and_diffs() {
+    let mut status = Screen::new(8, 1);
+    status.print_line(0, "12:45 ok").unwrap();
+
+    let mut screen = Screen::new(6, 2);
+    screen.print_line(0, "Temp").unwrap();
+
+    let before = screen.clone();
+
+    // clipped to the screen
+    screen.blit(Point::new(1, 2), &status);
+
+    assert_eq!(screen.line(0), Some(&b"Temp  "[..]));
+    assert_eq!(screen.line(1), Some(&b"  12:4"[..]));
+
+    assert_eq!(
+        before.diff(&screen),
+        [(Point::new(1, 2), b'1'), (Point::new(1, 3), b'2'), (Point::new(1, 4), b':'), (Point::new(1, 5), b'4')],
+    );
+
+    assert!(screen.diff(&screen).is_empty());
+}
+
+#[test]
+fn renders_onto_any_lcd() {
+    let mut screen = Screen::new(4, 2);
+
+    screen.create_char(3, HEART).unwrap();
+    screen.print_line(1, "I").unwrap();
+    screen.move_at(1, 2).unwrap();
+    screen.print_char(3).unwrap();
+
+    let mut lcd = FrameCapture::new(4, 2);
+
+    screen.render(&mut lcd).unwrap();
+
+    assert_eq!(lcd.line(1), Some(&[b'I', b' ', 3, b' '][..]));
+    assert_eq!(lcd.custom_char(3), Some(HEART));
+
+    assert!(screen.render(&mut FrameCapture::new(16, 2)).is_err());
+    assert!(screen.create_char(8, HEART).is_err());
+}
+
+#[test]
+fn backs_pages() {
+    let mut pages = Pages::new(BufferedLcd::from_lcd(DirectLcd::new(Box::new(NullBus::new()), 4, 1).unwrap()));
+    let mut screen = Screen::new(4, 1);
+
+    screen.print_line(0, "cpu").unwrap();
+    screen.create_char(0, HEART).unwrap();
+
+    let page = pages.add("cpu").unwrap();
+
+    page.set_screen(screen.clone()).unwrap();
+
+    assert_eq!(page.screen(), &screen);
+    assert!(page.set_screen(Screen::new(8, 1)).is_err());
+
+    pages.render().unwrap();
+
+    let shown = pages.lcd_mut().screen();
+
+    assert_eq!(shown.line(0), Some(&b"cpu "[..]));
+    assert_eq!(shown.custom_char(0), Some(HEART));
+}
@@ -0,0 +1,710 @@
+//! End-to-end tests of the frontends talking to a simulated HD44780 (see `SimulatedController`) -
+//! checking what a real display would show, instead of what the frontends think it shows.
+
+use pwr_hd44780::prelude::*;
+use pwr_hd44780::{FixedBufferedLcd, QueuedLcd, Recovery, Screen, SimulatedController};
+use pwr_hd44780::widgets::BarGraph;
+use pwr_hd44780::buses::{Bus, RawNibbleBus};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn direct(width: usize, height: usize) -> (SimulatedController, DirectLcd) {
+    let sim = SimulatedController::new(width, height);
+    let lcd = DirectLcd::new(Box::new(sim.clone()), width, height).unwrap();
+
+    (sim, lcd)
+}
+
+#[test]
+fn initializes_4_bit_bus() {
+    let (sim, _lcd) = direct(20, 4);
+    let registers = sim.registers();
+
+    assert!(!registers.eight_bit);
+    assert!(registers.two_lines);
+    assert!(registers.text_visible);
+    assert!(!registers.cursor_visible);
+    assert!(registers.increment);
+    assert!(!sim.is_mid_byte());
+}
+
+#[test]
+fn initializes_8_bit_bus() {
+    let mut sim = SimulatedController::new(16, 2);
+    sim.set_width(8);
+
+    let _lcd = DirectLcd::new(Box::new(sim.clone()), 16, 2).unwrap();
+
+    assert!(sim.registers().eight_bit);
+    assert!(sim.registers().two_lines);
+}
+
+#[test]
+fn prints_on_all_lines() {
+    let (sim, mut lcd) = direct(20, 4);
+
+    for (y, text) in ["Line #1", "Line #2", "Line #3", "Line #4"].iter().enumerate() {
+        lcd.print_at(y, 0, *text).unwrap();
+    }
+
+    assert_eq!(sim.lines(), [
+        "Line #1             ",
+        "Line #2             ",
+        "Line #3             ",
+        "Line #4             ",
+    ]);
+}
+
+#[test]
+fn prints_on_split_display() {
+    let (sim, mut lcd) = direct(16, 1);
+
+    lcd.print_at(0, 0, "Hello World!").unwrap();
+
+    assert_eq!(sim.snapshot(), "Hello World!    ");
+    assert_eq!(sim.ddram(0x40), b'r');
+}
+
+#[test]
+fn moves_cursor() {
+    let (sim, mut lcd) = direct(20, 4);
+
+    lcd.move_at(2, 5).unwrap();
+
+    assert_eq!(sim.cursor(), Some(Point::new(2, 5)));
+
+    lcd.print("abc").unwrap();
+
+    assert_eq!(sim.cursor(), Some(Point::new(2, 8)));
+}
+
+#[test]
+fn creates_custom_chars() {
+    let (sim, mut lcd) = direct(16, 2);
+    let heart = [0x00, 0x0A, 0x1F, 0x1F, 0x0E, 0x04, 0x00, 0x00];
+
+    lcd.create_char(3, heart).unwrap();
+    lcd.print_at(0, 0, "I ").unwrap();
+    lcd.print_char(3).unwrap();
+
+    assert_eq!(sim.custom_char(3), heart);
+    assert_eq!(sim.lines()[0], "I ③             ");
+}
+
+#[test]
+fn reuploads_custom_chars_of_failed_batch() {
+    let (sim, mut lcd) = direct(16, 2);
+    let heart = [0x00, 0x0A, 0x1F, 0x1F, 0x0E, 0x04, 0x00, 0x00];
+
+    let result = lcd.batch(|lcd| {
+        lcd.create_char(3, heart)?;
+        Err("aborted".into())
+    });
+
+    assert!(result.is_err());
+    assert_eq!(lcd.custom_chars()[3], None);
+
+    // nothing has been sent, so the character must not be taken for an already loaded one
+    lcd.create_char(3, heart).unwrap();
+
+    assert_eq!(sim.custom_char(3), heart);
+}
+
+/// Forwards everything to the simulated controller, failing the batches while `failing` is set.
+struct FlakyBus {
+    sim: SimulatedController,
+    failing: Arc<AtomicBool>,
+}
+
+impl Bus for FlakyBus {
+    fn initialize(&mut self) -> UnitResult {
+        self.sim.initialize()
+    }
+
+    fn set_backlight(&mut self, enabled: bool) -> UnitResult {
+        self.sim.set_backlight(enabled)
+    }
+
+    fn write_byte(&mut self, value: u8, as_data: bool) -> UnitResult {
+        self.sim.write_byte(value, as_data)
+    }
+
+    fn write_bytes(&mut self, bytes: &[(u8, bool)]) -> UnitResult {
+        if self.failing.load(Ordering::SeqCst) {
+            return Err("bus has failed".into());
+        }
+
+        self.sim.write_bytes(bytes)
+    }
+
+    fn width(&self) -> usize {
+        self.sim.width()
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        self.sim.delay_us(us)
+    }
+}
+
+#[test]
+fn reuploads_custom_chars_of_unsent_batch() {
+    let sim = SimulatedController::new(16, 2);
+    let failing = Arc::new(AtomicBool::new(true));
+    let bus = FlakyBus { sim: sim.clone(), failing: failing.clone() };
+    let mut lcd = DirectLcd::new(Box::new(bus), 16, 2).unwrap();
+    let heart = [0x00, 0x0A, 0x1F, 0x1F, 0x0E, 0x04, 0x00, 0x00];
+
+    assert!(lcd.batch(|lcd| lcd.create_char(3, heart)).is_err());
+    assert_eq!(lcd.custom_chars()[3], None);
+
+    failing.store(false, Ordering::SeqCst);
+    lcd.create_char(3, heart).unwrap();
+
+    assert_eq!(sim.custom_char(3), heart);
+}
+
+#[test]
+fn shifts_display() {
+    let (sim, mut lcd) = direct(16, 2);
+
+    lcd.print_at(0, 0, "Hello").unwrap();
+    lcd.shift_display_right().unwrap();
+    lcd.shift_display_right().unwrap();
+
+    assert_eq!(sim.lines()[0], "  Hello         ");
+
+    lcd.shift_display_left().unwrap();
+
+    assert_eq!(sim.lines()[0], " Hello          ");
+}
+
+#[test]
+fn prints_right_to_left() {
+    let (sim, mut lcd) = direct(16, 2);
+
+    lcd.set_entry_mode(Direction::RightToLeft, false).unwrap();
+    lcd.print_at(0, 5, "abc").unwrap();
+
+    assert_eq!(sim.lines()[0], "   cba          ");
+}
+
+#[test]
+fn hides_text() {
+    let (sim, mut lcd) = direct(16, 2);
+
+    lcd.print_at(0, 0, "Hello").unwrap();
+    lcd.set_text_visible(false).unwrap();
+
+    assert_eq!(sim.lines()[0], "                ");
+
+    lcd.set_text_visible(true).unwrap();
+
+    assert_eq!(sim.lines()[0], "Hello           ");
+}
+
+#[test]
+fn reads_chars_back() {
+    let (_sim, mut lcd) = direct(20, 4);
+
+    lcd.print_at(3, 10, "xyz").unwrap();
+
+    assert_eq!(lcd.read_char_at(Point::new(3, 11)).unwrap(), b'y');
+}
+
+#[test]
+fn recovers_from_desync() {
+    let (mut sim, mut lcd) = direct(16, 2);
+
+    lcd.print_at(0, 0, "Hello").unwrap();
+
+    // a transfer interrupted after the first nibble
+    sim.write_raw_nibble(0x04, true).unwrap();
+
+    assert!(sim.is_mid_byte());
+
+    lcd.resync().unwrap();
+    lcd.print_at(1, 0, "World").unwrap();
+
+    assert!(!sim.is_mid_byte());
+    assert_eq!(sim.lines(), ["Hello           ", "World           "]);
+}
+
+#[test]
+fn switches_backlight_along_with_raw_nibbles() {
+    let (mut sim, mut lcd) = direct(16, 2);
+
+    lcd.move_at(0, 0).unwrap();
+
+    // `A`, with the backlight going off after the first nibble
+    sim.write_raw_nibble_ex(0x04, true, true).unwrap();
+    assert!(sim.backlight());
+
+    sim.write_raw_nibble_ex(0x01, true, false).unwrap();
+    assert!(!sim.backlight());
+
+    assert_eq!(sim.lines()[0], "A               ");
+}
+
+#[test]
+fn renders_buffer() {
+    let sim = SimulatedController::new(20, 4);
+    let mut lcd = BufferedLcd::from_lcd(DirectLcd::new(Box::new(sim.clone()), 20, 4).unwrap());
+
+    lcd.print_at(0, 0, "Temperature:   23.4C").unwrap();
+    lcd.print_at(3, 0, "Uptime:     12:34:56").unwrap();
+    lcd.render().unwrap();
+
+    lcd.print_at(0, 15, "23.5").unwrap();
+    lcd.print_at(3, 18, "57").unwrap();
+    lcd.render().unwrap();
+
+    assert_eq!(sim.lines(), [
+        "Temperature:   23.5C",
+        "                    ",
+        "                    ",
+        "Uptime:     12:34:57",
+    ]);
+}
+
+#[test]
+fn replaces_whole_screen() {
+    let sim = SimulatedController::new(16, 2);
+    let mut lcd = BufferedLcd::from_lcd(DirectLcd::new(Box::new(sim.clone()), 16, 2).unwrap());
+
+    lcd.set_lines(&["Temp: 23.4C", "Hum:  45%"]).unwrap();
+    lcd.render().unwrap();
+
+    lcd.set_lines(&["Temp: 23.5C"]).unwrap();
+
+    assert_eq!(lcd.render().unwrap().cells, 1 + 7);
+    assert_eq!(sim.lines(), ["Temp: 23.5C     ", "                "]);
+
+    let mut screen = Screen::new(16, 2);
+
+    screen.print_line(1, "Humidity: 46%").unwrap();
+    lcd.replace_screen(&screen).unwrap();
+    lcd.render().unwrap();
+
+    assert_eq!(sim.lines(), ["                ", "Humidity: 46%   "]);
+    assert!(lcd.set_lines(&["a", "b", "c"]).is_err());
+    assert!(lcd.replace_screen(&Screen::new(20, 4)).is_err());
+}
+
+#[test]
+fn renders_overlays() {
+    let sim = SimulatedController::new(16, 2);
+    let mut lcd = BufferedLcd::from_lcd(DirectLcd::new(Box::new(sim.clone()), 16, 2).unwrap());
+
+    lcd.print_at(0, 0, "Alarm 07:30").unwrap();
+    lcd.set_overlay(Point::new(0, 5), Some(3)).unwrap();
+    lcd.render().unwrap();
+
+    assert_eq!(sim.line(0).unwrap()[4..7], [b'm', 3, b'0']);
+
+    // the text beneath changes, but the overlay stays on top of it
+    lcd.print_at(0, 0, "Alarm:07:45").unwrap();
+
+    assert_eq!(lcd.char_at(Point::new(0, 5)), Some(b':'));
+    assert_eq!(lcd.render().unwrap().cells, 2);
+    assert_eq!(sim.line(0).unwrap()[5], 3);
+
+    // and reappears once the overlay is gone
+    lcd.set_overlay(Point::new(0, 5), None).unwrap();
+
+    assert_eq!(lcd.render().unwrap().cells, 1);
+    assert_eq!(sim.lines()[0], "Alarm:07:45     ");
+
+    assert!(lcd.set_overlay(Point::new(2, 0), Some(0)).is_err());
+    assert!(lcd.set_overlay(Point::new(0, 0), Some(8)).is_err());
+}
+
+/// Forwards everything to the simulated controller, remembering the runs it has been asked to
+/// send at once.
+struct RunsBus {
+    sim: SimulatedController,
+    runs: Arc<Mutex<Vec<(u8, usize)>>>,
+}
+
+impl Bus for RunsBus {
+    fn initialize(&mut self) -> UnitResult {
+        self.sim.initialize()
+    }
+
+    fn set_backlight(&mut self, enabled: bool) -> UnitResult {
+        self.sim.set_backlight(enabled)
+    }
+
+    fn write_byte(&mut self, value: u8, as_data: bool) -> UnitResult {
+        self.sim.write_byte(value, as_data)
+    }
+
+    fn write_data_repeated(&mut self, value: u8, count: usize) -> UnitResult {
+        self.runs.lock().unwrap().push((value, count));
+        self.sim.write_data_repeated(value, count)
+    }
+
+    fn width(&self) -> usize {
+        self.sim.width()
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        self.sim.delay_us(us)
+    }
+}
+
+#[test]
+fn coalesces_repeated_chars() {
+    let sim = SimulatedController::new(16, 2);
+    let runs = Arc::new(Mutex::new(Vec::new()));
+    let bus = RunsBus { sim: sim.clone(), runs: runs.clone() };
+    let mut lcd = BufferedLcd::from_lcd(DirectLcd::new(Box::new(bus), 16, 2).unwrap());
+
+    lcd.print_at(0, 0, "Loading: ").unwrap();
+    lcd.print_at(1, 0, "=====>").unwrap();
+    lcd.render().unwrap();
+
+    assert!(runs.lock().unwrap().contains(&(b'=', 5)));
+
+    runs.lock().unwrap().clear();
+
+    // only the characters that differ get sent - the trailing spaces are already in place
+    lcd.clear_line(1).unwrap();
+    lcd.print_at(0, 9, "done").unwrap();
+    lcd.render().unwrap();
+
+    assert!(runs.lock().unwrap().contains(&(b' ', 6)));
+    assert_eq!(sim.lines(), ["Loading: done   ", "                "]);
+}
+
+#[test]
+fn coalesces_repeated_chars_through_shared_lcd() {
+    let sim = SimulatedController::new(16, 2);
+    let runs = Arc::new(Mutex::new(Vec::new()));
+    let bus = RunsBus { sim: sim.clone(), runs: runs.clone() };
+    let shared = SharedLcd::new(DirectLcd::new(Box::new(bus), 16, 2).unwrap());
+    let mut lcd = BufferedLcd::from_lcd(shared);
+
+    lcd.print_at(1, 0, "=====>").unwrap();
+    lcd.render().unwrap();
+
+    assert!(runs.lock().unwrap().contains(&(b'=', 5)));
+    assert_eq!(sim.lines()[1], "=====>          ");
+}
+
+#[test]
+fn draws_bar_graph_in_runs() {
+    let sim = SimulatedController::new(16, 2);
+    let runs = Arc::new(Mutex::new(Vec::new()));
+    let bus = RunsBus { sim: sim.clone(), runs: runs.clone() };
+    let mut lcd = DirectLcd::new(Box::new(bus), 16, 2).unwrap();
+    let graph = BarGraph::new(1, 0, 16);
+
+    graph.load(&mut lcd).unwrap();
+    graph.draw(&mut lcd, 0.5).unwrap();
+
+    assert_eq!(*runs.lock().unwrap(), [(4, 8), (b' ', 8)]);
+
+    runs.lock().unwrap().clear();
+    lcd.clear_region(Point::new(0, 4), Point::new(1, 11)).unwrap();
+
+    assert_eq!(*runs.lock().unwrap(), [(b' ', 8), (b' ', 8)]);
+    assert_eq!(sim.lines()[1], "④④④④            ");
+}
+
+#[test]
+fn reports_fewer_custom_chars_for_5x10_font() {
+    let (_sim, lcd) = direct(20, 1);
+
+    assert_eq!(lcd.capabilities().custom_chars, 4);
+
+    let render_loop = RenderLoop::new(BufferedLcd::from_lcd(lcd), 50).unwrap();
+
+    assert_eq!(render_loop.handle().capabilities().custom_chars, 4);
+    assert!(render_loop.stop().is_ok());
+}
+
+#[test]
+fn scrolls_regions_lazily() {
+    let sim = SimulatedController::new(20, 4);
+    let mut lcd = BufferedLcd::from_lcd(DirectLcd::new(Box::new(sim.clone()), 20, 4).unwrap());
+
+    lcd.print_at(0, 0, "== Build status ==").unwrap();
+
+    {
+        let mut log = lcd.region(Point::new(1, 0), Point::new(3, 19)).unwrap();
+
+        log.println("Fetching...").unwrap();
+        log.println("Compiling...").unwrap();
+        log.println("Testing...").unwrap();
+    }
+
+    lcd.render().unwrap();
+
+    assert_eq!(sim.lines(), [
+        "== Build status ==  ",
+        "Fetching...         ",
+        "Compiling...        ",
+        "Testing...          ",
+    ]);
+
+    // the same region, created again - the pending scroll is remembered
+    lcd.region(Point::new(1, 0), Point::new(3, 19)).unwrap().println("Done!").unwrap();
+    lcd.render().unwrap();
+
+    assert_eq!(sim.lines(), [
+        "== Build status ==  ",
+        "Compiling...        ",
+        "Testing...          ",
+        "Done!               ",
+    ]);
+}
+
+#[test]
+fn renders_fixed_buffer() {
+    let sim = SimulatedController::new(16, 2);
+    let runs = Arc::new(Mutex::new(Vec::new()));
+    let bus = RunsBus { sim: sim.clone(), runs: runs.clone() };
+    let mut lcd: FixedBufferedLcd<_, 16, 2> = FixedBufferedLcd::new(DirectLcd::new(Box::new(bus), 16, 2).unwrap()).unwrap();
+
+    lcd.print_at_const::<0, 0>("Loading").unwrap();
+    lcd.print_at_const::<1, 0>("=====>").unwrap();
+    lcd.render().unwrap();
+
+    assert_eq!(sim.lines(), ["Loading         ", "=====>          "]);
+    assert!(runs.lock().unwrap().contains(&(b'=', 5)));
+
+    // only the characters that differ get sent
+    runs.lock().unwrap().clear();
+    lcd.print_at_const::<1, 5>("=====>").unwrap();
+    lcd.render().unwrap();
+
+    assert_eq!(*runs.lock().unwrap(), [(b'=', 5), (b'>', 1)]);
+    assert_eq!(sim.lines()[1], "==========>     ");
+
+    assert!(FixedBufferedLcd::<_, 20, 4>::new(DirectLcd::new(Box::new(sim), 16, 2).unwrap()).is_err());
+}
+
+/// Forwards everything to the simulated controller, failing the next `failures` writes.
+struct FailingBus {
+    sim: SimulatedController,
+    failures: Arc<AtomicUsize>,
+}
+
+impl Bus for FailingBus {
+    fn initialize(&mut self) -> UnitResult {
+        self.sim.initialize()
+    }
+
+    fn set_backlight(&mut self, enabled: bool) -> UnitResult {
+        self.sim.set_backlight(enabled)
+    }
+
+    fn write_byte(&mut self, value: u8, as_data: bool) -> UnitResult {
+        let failing = self.failures
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |failures| failures.checked_sub(1))
+            .is_ok();
+
+        if failing {
+            return Err("bus has failed".into());
+        }
+
+        self.sim.write_byte(value, as_data)
+    }
+
+    fn width(&self) -> usize {
+        self.sim.width()
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        self.sim.delay_us(us)
+    }
+}
+
+fn failing(width: usize, height: usize) -> (SimulatedController, Arc<AtomicUsize>, DirectLcd) {
+    let sim = SimulatedController::new(width, height);
+    let failures = Arc::new(AtomicUsize::new(0));
+    let bus = FailingBus { sim: sim.clone(), failures: failures.clone() };
+    let lcd = DirectLcd::new(Box::new(bus), width, height).unwrap();
+
+    (sim, failures, lcd)
+}
+
+#[test]
+fn recovers_resilient_lcd() {
+    let (sim, failures, lcd) = failing(16, 2);
+    let heart = [0x00, 0x0A, 0x1F, 0x1F, 0x0E, 0x04, 0x00, 0x00];
+    let recoveries = Arc::new(Mutex::new(Vec::new()));
+    let mut lcd = ResilientLcd::new(BufferedLcd::from_lcd(lcd));
+
+    lcd.set_failure_threshold(2);
+
+    lcd.on_recovery({
+        let recoveries = recoveries.clone();
+
+        move |recovery| {
+            if let Recovery::Reinitialized { failures, .. } = recovery {
+                recoveries.lock().unwrap().push(failures);
+            }
+        }
+    });
+
+    lcd.create_char(0, heart).unwrap();
+    lcd.print_char_at(0, 0, 0).unwrap();
+    lcd.print_at(0, 2, "Hello").unwrap();
+    lcd.render().unwrap();
+
+    // the LCD browns out and the bus fails for a while
+    sim.power_cycle();
+    failures.store(2, Ordering::SeqCst);
+    lcd.print_at(1, 0, "World").unwrap();
+
+    // the first failure is passed through, the second one reinitializes the LCD
+    assert!(lcd.render().is_err());
+    assert!(lcd.render().is_ok());
+
+    assert_eq!(*recoveries.lock().unwrap(), [2]);
+    assert_eq!(sim.custom_char(0), heart);
+    assert_eq!(sim.lines(), ["⓪ Hello         ", "World           "]);
+}
+
+#[test]
+fn keeps_queue_after_failure() {
+    let (sim, failures, lcd) = failing(16, 2);
+    let mut lcd = QueuedLcd::new(lcd);
+
+    lcd.print_at(0, 0, "Hi!").unwrap();
+
+    assert_eq!(lcd.pending(), 4);
+    assert!(!lcd.flush_count(1).unwrap());
+
+    // the failed operation gets dropped, the rest stays queued
+    failures.store(1, Ordering::SeqCst);
+
+    assert!(lcd.flush_count(2).is_err());
+    assert_eq!(lcd.pending(), 2);
+
+    lcd.flush().unwrap();
+
+    assert!(lcd.is_flushed());
+    assert_eq!(sim.lines()[0], "i!              ");
+}
+
+#[test]
+fn spreads_tiled_screen_over_lcds() {
+    let (top_sim, top_failures, top) = failing(16, 2);
+    let (bottom_sim, _, bottom) = failing(16, 2);
+    let mut lcd = TiledLcd::vertical(vec![top, bottom]).unwrap();
+    let heart = [0x00, 0x0A, 0x1F, 0x1F, 0x0E, 0x04, 0x00, 0x00];
+
+    assert_eq!((lcd.height(), lcd.width()), (4, 16));
+
+    // the text overflowing the top tile continues on the bottom one
+    lcd.print_at(1, 14, "abcd").unwrap();
+
+    assert_eq!(top_sim.lines()[1], "              ab");
+    assert_eq!(bottom_sim.lines()[0], "cd              ");
+
+    // a failing tile does not keep the others from being updated
+    top_failures.store(usize::MAX, Ordering::SeqCst);
+
+    assert!(lcd.create_char(1, heart).is_err());
+    assert_eq!(bottom_sim.custom_char(1), heart);
+}
+
+#[test]
+fn mirrors_onto_both_lcds_despite_failures() {
+    let (primary_sim, primary_failures, primary) = failing(16, 2);
+    let (secondary_sim, secondary) = direct(16, 2);
+    let mut lcd = TeeLcd::new(primary, secondary);
+
+    lcd.print_at(0, 0, "Hello").unwrap();
+
+    assert_eq!(primary_sim.lines()[0], "Hello           ");
+    assert_eq!(secondary_sim.lines()[0], "Hello           ");
+
+    // a broken primary LCD does not stop the secondary one
+    primary_failures.store(usize::MAX, Ordering::SeqCst);
+
+    assert!(lcd.print_at(1, 0, "World").is_err());
+    assert_eq!(primary_sim.lines()[1], "                ");
+    assert_eq!(secondary_sim.lines()[1], "World           ");
+}
+
+/// Waits (up to a few seconds) for given condition to become true - for the tests of the
+/// frontends doing their work in background threads.
+fn wait_until<F: FnMut() -> bool>(mut condition: F) {
+    let started = Instant::now();
+
+    while !condition() {
+        assert!(started.elapsed() < Duration::from_secs(5), "timed out waiting for the condition");
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn stops_render_loop_on_failure() {
+    let (sim, failures, lcd) = failing(16, 2);
+    let render_loop = RenderLoop::new(BufferedLcd::from_lcd(lcd), 100).unwrap();
+    let mut handle = render_loop.handle();
+
+    handle.print_at(0, 0, "Hello").unwrap();
+    wait_until(|| sim.lines()[0] == "Hello           ");
+
+    // the failure is not reported by the handle, but stops the loop
+    failures.store(usize::MAX, Ordering::SeqCst);
+    handle.print_at(1, 0, "World").unwrap();
+
+    wait_until(|| !render_loop.is_running());
+    assert!(render_loop.stop().is_err());
+}
+
+#[test]
+fn pauses_render_loop_in_low_power_mode() {
+    let (sim, lcd) = direct(16, 2);
+    let render_loop = RenderLoop::new(BufferedLcd::from_lcd(lcd), 100).unwrap();
+    let mut handle = render_loop.handle();
+
+    handle.enter_low_power().unwrap();
+    wait_until(|| !sim.registers().text_visible);
+
+    // the changes made in the low-power mode wait for it to end
+    handle.print_at(0, 0, "Hello").unwrap();
+    thread::sleep(Duration::from_millis(50));
+
+    assert_eq!(sim.lines()[0], "                ");
+
+    handle.exit_low_power().unwrap();
+    wait_until(|| sim.lines()[0] == "Hello           ");
+
+    assert!(sim.registers().text_visible);
+    assert!(render_loop.stop().is_ok());
+}
+
+#[test]
+fn stops_lcd_worker_on_failure() {
+    let (sim, failures, lcd) = failing(16, 2);
+    let worker = LcdWorker::spawn(lcd);
+    let handle = worker.handle();
+
+    handle.print_at(0, 0, "Hello").unwrap();
+    handle.call(|_| Ok(())).unwrap();
+
+    assert_eq!(sim.lines()[0], "Hello           ");
+
+    // the failures of the jobs sent through `call` are returned to the caller...
+    failures.store(1, Ordering::SeqCst);
+
+    assert!(handle.call(|lcd| lcd.print_str_at(1, 0, "World")).is_err());
+    assert!(worker.is_running());
+
+    // ... while the other ones stop the worker
+    failures.store(usize::MAX, Ordering::SeqCst);
+    handle.print_at(1, 0, "World").unwrap();
+
+    wait_until(|| !worker.is_running());
+
+    assert!(handle.print_at(0, 0, "Again").is_err());
+    assert!(worker.stop().is_err());
+}
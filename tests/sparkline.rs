@@ -0,0 +1,67 @@
+//! Checks scaling & drawing of the `Sparkline`, on an in-memory LCD.
+
+use pwr_hd44780::prelude::*;
+use pwr_hd44780::widgets::Sparkline;
+
+fn drawn(sparkline: &Sparkline) -> Vec<u8> {
+    let mut lcd = FrameCapture::new(4, 1);
+
+    sparkline.draw(&mut lcd).unwrap();
+    lcd.line(0).unwrap().to_vec()
+}
+
+#[test]
+fn scales_automatically() {
+    let mut sparkline = Sparkline::new(0, 0, 4);
+
+    assert_eq!(drawn(&sparkline), b"    ");
+
+    sparkline.push(20.0);
+    sparkline.push(25.0);
+    sparkline.push(30.0);
+
+    assert_eq!(sparkline.range(), Some((20.0, 30.0)));
+    assert_eq!(drawn(&sparkline), [b' ', 0, 3, 6]);
+
+    // the oldest samples fall off
+    sparkline.push(f32::NAN);
+    sparkline.push(10.0);
+
+    assert_eq!(sparkline.samples().count(), 4);
+    assert_eq!(sparkline.range(), Some((10.0, 30.0)));
+    assert_eq!(drawn(&sparkline), [5, 6, b' ', 0]);
+}
+
+#[test]
+fn scales_within_fixed_range() {
+    let mut sparkline = Sparkline::with_first_slot(0, 0, 4, 1).unwrap();
+
+    sparkline.set_range(Some((0.0, 100.0)));
+
+    for &sample in &[-10.0, 50.0, 100.0, 150.0] {
+        sparkline.push(sample);
+    }
+
+    assert_eq!(drawn(&sparkline), [1, 4, 7, 7]);
+
+    // a flat series is drawn at half the height
+    sparkline.set_range(None);
+    sparkline.clear();
+    sparkline.push(5.0);
+
+    assert_eq!(drawn(&sparkline), [b' ', b' ', b' ', 4]);
+
+    assert!(Sparkline::with_first_slot(0, 0, 4, 2).is_err());
+}
+
+#[test]
+fn loads_bars() {
+    let mut lcd = FrameCapture::new(4, 1);
+
+    Sparkline::new(0, 0, 4)
+        .load(&mut lcd)
+        .unwrap();
+
+    assert_eq!(lcd.custom_char(0), Some([0, 0, 0, 0, 0, 0, 0b11111, 0]));
+    assert_eq!(lcd.custom_char(6), Some([0b11111, 0b11111, 0b11111, 0b11111, 0b11111, 0b11111, 0b11111, 0]));
+}
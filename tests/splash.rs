@@ -0,0 +1,49 @@
+//! Checks how the `Splash` lays the message & the logo out.
+
+use pwr_hd44780::prelude::*;
+use pwr_hd44780::Splash;
+use std::time::{Duration, Instant};
+
+const LOGO: [u8; 8] = [0x04, 0x0E, 0x1F, 0x1F, 0x0E, 0x04, 0x00, 0x00];
+
+#[test]
+fn centers_message() {
+    let mut lcd = FrameCapture::new(16, 2);
+
+    Splash::new("ACME\nv1.2.0", Duration::from_secs(0)).draw(&mut lcd).unwrap();
+
+    assert_eq!(lcd.snapshot(), "      ACME      \n     v1.2.0     ");
+}
+
+#[test]
+fn shows_logo() {
+    let mut lcd = FrameCapture::new(16, 2);
+
+    Splash::new("ACME\nBooting...", Duration::from_secs(0))
+        .with_logo(&[LOGO, LOGO], 2)
+        .unwrap()
+        .draw(&mut lcd)
+        .unwrap();
+
+    assert_eq!(lcd.snapshot(), "    ②③ ACME     \n   Booting...   ");
+    assert_eq!(lcd.custom_char(3), Some(LOGO));
+}
+
+#[test]
+fn rejects_oversized_logo() {
+    assert!(Splash::new("", Duration::from_secs(0)).with_logo(&[LOGO; 3], 6).is_err());
+}
+
+#[test]
+fn stays_for_given_duration() {
+    let mut lcd = FrameCapture::new(16, 2);
+    let started = Instant::now();
+    let splash = Splash::new("ACME", Duration::from_millis(50)).show(&mut lcd).unwrap();
+
+    assert!(!splash.is_over());
+
+    splash.finish(&mut lcd).unwrap();
+
+    assert!(started.elapsed() >= Duration::from_millis(50));
+    assert_eq!(lcd.snapshot(), "                \n                ");
+}
@@ -0,0 +1,61 @@
+//! Checks the cadence of the `Ticker`.
+
+use pwr_hd44780::Ticker;
+use std::time::Duration;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn ticks_on_cadence() {
+    let mut ticker = Ticker::from_hz(10).unwrap();
+
+    let ticks: Vec<u64> = (0..=350)
+        .step_by(10)
+        .filter(|&now| ticker.tick(ms(now)))
+        .collect();
+
+    assert_eq!(ticks, [0, 100, 200, 300]);
+    assert_eq!(ticker.until_next(ms(350)), ms(50));
+}
+
+#[test]
+fn keeps_phase_when_late() {
+    let mut ticker = Ticker::new(ms(100));
+
+    assert!(ticker.tick(ms(0)));
+    assert!(ticker.tick(ms(130)));
+    assert!(!ticker.tick(ms(190)));
+    assert!(ticker.tick(ms(200)));
+}
+
+#[test]
+fn skips_missed_ticks() {
+    let mut ticker = Ticker::new(ms(100));
+
+    assert!(ticker.tick(ms(0)));
+    assert!(ticker.tick(ms(550)));
+    assert!(!ticker.tick(ms(590)));
+    assert_eq!(ticker.until_next(ms(590)), ms(10));
+    assert!(ticker.tick(ms(600)));
+}
+
+#[test]
+fn resets() {
+    let mut ticker = Ticker::new(ms(100));
+
+    assert!(ticker.tick(ms(0)));
+    assert!(!ticker.tick(ms(10)));
+
+    ticker.reset();
+
+    assert!(ticker.tick(ms(20)));
+    assert!(!ticker.tick(ms(110)));
+    assert!(ticker.tick(ms(120)));
+}
+
+#[test]
+fn rejects_zero_rate() {
+    assert!(Ticker::from_hz(0).is_err());
+}
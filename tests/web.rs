@@ -0,0 +1,69 @@
+//! Talks to a `WebPreview` serving a simulated HD44780 (see `SimulatedController`), over plain
+//! HTTP on the loopback interface.
+
+use pwr_hd44780::prelude::*;
+use pwr_hd44780::{SimulatedController, WebPreview};
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::thread;
+
+/// Spawns a server showing a 16x2 LCD, returning the simulator & the server's address.
+fn server() -> (SimulatedController, SocketAddr) {
+    let sim = SimulatedController::new(16, 2);
+    let lcd = BufferedLcd::from_lcd(DirectLcd::new(Box::new(sim.clone()), 16, 2).unwrap());
+    let preview = WebPreview::bind(SharedLcd::new(lcd), "127.0.0.1:0").unwrap();
+    let address = preview.address().unwrap();
+
+    thread::spawn(move || preview.run().unwrap());
+
+    (sim, address)
+}
+
+/// Sends given request, returning the response's status code and body.
+fn request(address: SocketAddr, method: &str, path: &str, body: &str) -> (u16, String) {
+    let mut stream = TcpStream::connect(address).unwrap();
+
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\nHost: lcd\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{}",
+        method, path, body.len(), body,
+    ).unwrap();
+
+    let mut response = String::new();
+
+    stream.read_to_string(&mut response).unwrap();
+
+    let status = response[9..12].parse().unwrap();
+    let body = response.split("\r\n\r\n").nth(1).unwrap_or("").to_string();
+
+    (status, body)
+}
+
+#[test]
+fn shows_and_updates_lines() {
+    let (sim, address) = server();
+
+    let (status, body) = request(address, "POST", "/line/1", "Hello from afar");
+
+    assert_eq!(status, 200);
+    assert_eq!(body, "                \nHello from afar ");
+    assert_eq!(sim.lines()[1], "Hello from afar ");
+
+    request(address, "POST", "/text", "First\nSecond\n");
+
+    assert_eq!(request(address, "GET", "/", ""), (200, "First           \nSecond          ".to_string()));
+    assert_eq!(sim.lines(), ["First           ", "Second          "]);
+
+    request(address, "POST", "/clear", "");
+
+    assert_eq!(sim.lines(), ["                ", "                "]);
+}
+
+#[test]
+fn rejects_invalid_requests() {
+    let (_sim, address) = server();
+
+    assert_eq!(request(address, "POST", "/line/5", "Nope").0, 400);
+    assert_eq!(request(address, "POST", "/line/x", "Nope").0, 400);
+    assert_eq!(request(address, "GET", "/nope", "").0, 404);
+}